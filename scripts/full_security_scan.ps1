@@ -299,5 +299,7 @@ $report.summary = @{
     total = $report.sections.Count
 }
 
-# Output JSON (une seule ligne)
-$report | ConvertTo-Json -Depth 10 -Compress
+# Output JSON entre deux marqueurs (lus par l'agent)
+[Console]::Out.WriteLine("---MDIAG-JSON-START---")
+$report | ConvertTo-Json -Depth 10
+[Console]::Out.WriteLine("---MDIAG-JSON-END---")
@@ -0,0 +1,208 @@
+// ============================================
+// MICRODIAG AGENT - Self Update
+// Release check against Supabase + verified installer launch
+// ============================================
+
+use crate::config::*;
+use crate::hashing;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AgentUpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+    /// Set by the release (`mandatory`) or when this build is older than its `min_version`
+    pub mandatory: bool,
+    pub notes: Option<String>,
+    pub download_url: Option<String>,
+    pub sha256: Option<String>,
+}
+
+// ============================================
+// VERSION HELPERS
+// ============================================
+
+fn parse_version(version: &str) -> Vec<u32> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split(|c: char| c == '.' || c == '-' || c == '+')
+        .take(3)
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// True only when `latest` is strictly newer than `current` (never downgrade)
+pub fn is_newer_version(latest: &str, current: &str) -> bool {
+    let mut latest = parse_version(latest);
+    let mut current = parse_version(current);
+    latest.resize(3, 0);
+    current.resize(3, 0);
+    latest > current
+}
+
+// ============================================
+// RELEASE CHECK
+// ============================================
+
+pub async fn check_agent_update() -> Result<AgentUpdateInfo, String> {
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/rest/v1/agent_releases?select=version,notes,download_url,sha256,mandatory,min_version&order=created_at.desc&limit=1",
+            SUPABASE_URL
+        ))
+        .header("Authorization", format!("Bearer {}", SUPABASE_ANON_KEY))
+        .header("apikey", SUPABASE_ANON_KEY)
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("API error: {}", response.status()));
+    }
+
+    let releases: Vec<serde_json::Value> = response
+        .json()
+        .await
+        .map_err(|e| format!("JSON error: {}", e))?;
+
+    let latest = releases.first().ok_or_else(|| "Aucune version publiee".to_string())?;
+    let latest_version = latest["version"].as_str().unwrap_or_default().to_string();
+    let update_available = is_newer_version(&latest_version, AGENT_VERSION);
+    let below_minimum = latest["min_version"].as_str()
+        .map(|min| is_newer_version(min, AGENT_VERSION))
+        .unwrap_or(false);
+    let mandatory = update_available && (latest["mandatory"].as_bool().unwrap_or(false) || below_minimum);
+
+    Ok(AgentUpdateInfo {
+        current_version: AGENT_VERSION.to_string(),
+        update_available,
+        mandatory,
+        latest_version,
+        notes: latest["notes"].as_str().map(|s| s.to_string()),
+        download_url: latest["download_url"].as_str().map(|s| s.to_string()),
+        sha256: latest["sha256"].as_str().map(|s| s.to_string()),
+    })
+}
+
+// ============================================
+// DOWNLOAD + VERIFY + LAUNCH
+// ============================================
+
+async fn download_to_file(url: &str, path: &Path) -> Result<(), String> {
+    let client = reqwest::Client::new();
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Erreur telechargement: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Erreur telechargement: {}", response.status()));
+    }
+
+    // Stream to disk chunk by chunk, installers can be large
+    let mut file = std::fs::File::create(path).map_err(|e| format!("Erreur ecriture: {}", e))?;
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Erreur telechargement: {}", e))? {
+        file.write_all(&chunk).map_err(|e| format!("Erreur ecriture: {}", e))?;
+    }
+    file.flush().map_err(|e| format!("Erreur ecriture: {}", e))?;
+    Ok(())
+}
+
+/// Downloads the latest release, verifies its checksum and launches the installer.
+/// Returns the path of the launched installer; the caller is expected to exit.
+pub async fn apply_agent_update() -> Result<String, String> {
+    let info = check_agent_update().await?;
+
+    if !info.update_available {
+        return Err(format!(
+            "Aucune mise a jour: version {} deja installee (derniere: {})",
+            info.current_version, info.latest_version
+        ));
+    }
+
+    let url = info.download_url.ok_or_else(|| "URL de telechargement manquante".to_string())?;
+    let expected = info.sha256.ok_or_else(|| "Checksum manquant, mise a jour refusee".to_string())?;
+
+    let ext = if url.to_lowercase().ends_with(".msi") { "msi" } else { "exe" };
+    let installer = std::env::temp_dir().join(format!("microdiag_update_{}.{}", info.latest_version, ext));
+
+    download_to_file(&url, &installer).await?;
+
+    let actual = hashing::hash_file_sha256(&installer)?;
+    if !hashing::digest_matches(&actual, &expected) {
+        let _ = std::fs::remove_file(&installer);
+        return Err("Fichier corrompu: checksum invalide".to_string());
+    }
+
+    launch_installer(&installer)?;
+    Ok(installer.to_string_lossy().to_string())
+}
+
+#[cfg(windows)]
+fn launch_installer(path: &Path) -> Result<(), String> {
+    use std::process::Command;
+
+    let is_msi = path.extension().map(|e| e.eq_ignore_ascii_case("msi")).unwrap_or(false);
+    let result = if is_msi {
+        Command::new("msiexec").arg("/i").arg(path).arg("/passive").spawn()
+    } else {
+        Command::new(path).spawn()
+    };
+
+    result.map(|_| ()).map_err(|e| format!("Erreur lancement installeur: {}", e))
+}
+
+#[cfg(not(windows))]
+fn launch_installer(_path: &Path) -> Result<(), String> {
+    Err("Mise a jour automatique disponible uniquement sur Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_prefixed_and_prerelease_versions() {
+        assert_eq!(parse_version("v1.4.2"), vec![1, 4, 2]);
+        assert_eq!(parse_version(" 2.0.0-beta.1 "), vec![2, 0, 0]);
+        assert_eq!(parse_version("1.7"), vec![1, 7]);
+        assert_eq!(parse_version("1.x.3"), vec![1, 0, 3]);
+    }
+
+    #[test]
+    fn only_a_strictly_newer_release_is_an_update() {
+        assert!(is_newer_version("1.4.3", "1.4.2"));
+        assert!(is_newer_version("1.10.0", "1.9.9"));
+        assert!(is_newer_version("v2.0.0", "1.9.9"));
+
+        // Equal, including across segment counts, prefixes and suffixes
+        assert!(!is_newer_version("1.4.2", "1.4.2"));
+        assert!(!is_newer_version("1.4", "1.4.0"));
+        assert!(!is_newer_version("v1.4.2", "1.4.2"));
+        assert!(!is_newer_version("1.4.2-rc1", "1.4.2"));
+
+        // Never a downgrade
+        assert!(!is_newer_version("1.4.1", "1.4.2"));
+        assert!(!is_newer_version("1.3", "1.4.2"));
+    }
+
+    #[test]
+    fn installer_checksum_is_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let installer = dir.path().join("microdiag_update_1.4.3.exe");
+        std::fs::write(&installer, b"installer payload").unwrap();
+        let actual = hashing::hash_file_sha256(&installer).unwrap();
+
+        assert!(hashing::digest_matches(&actual, &actual.to_uppercase()));
+        assert!(hashing::digest_matches(&actual, &format!(" {} ", actual)));
+        assert!(!hashing::digest_matches(&actual, &hashing::hash_bytes_sha256(b"tampered payload")));
+        assert!(!hashing::digest_matches(&actual, ""));
+    }
+}
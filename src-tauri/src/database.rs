@@ -4,7 +4,7 @@
 
 use rusqlite::{Connection, Result as SqlResult, params};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 // ============================================
@@ -18,29 +18,278 @@ pub fn get_db_path() -> PathBuf {
     path
 }
 
+/// Last known-good copy, refreshed on every healthy startup
+fn get_backup_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("db.bak")
+}
+
+/// SQLCipher key, itself protected by DPAPI
+fn get_key_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("key")
+}
+
+// ============================================
+// ENCRYPTION AT REST (SQLCipher)
+// ============================================
+
+/// Plain SQLite files start with this header, SQLCipher ones look random
+fn is_plaintext_database(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map(|_| &header == b"SQLite format 3\0")
+        .unwrap_or(false)
+}
+
+fn open_connection(path: &Path, key: Option<&str>) -> SqlResult<Connection> {
+    let conn = Connection::open(path)?;
+    if let Some(key) = key {
+        // Hex key only, safe to inline; must be the first statement on the connection
+        conn.execute_batch(&format!("PRAGMA key = '{}';", key))?;
+    }
+    Ok(conn)
+}
+
+/// Copies the whole database into `target` encrypted with `key` (user_version included)
+fn export_encrypted(conn: &Connection, target: &Path, key: &str) -> SqlResult<()> {
+    let _ = std::fs::remove_file(target);
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    conn.execute("ATTACH DATABASE ?1 AS encrypted KEY ?2", params![target.to_string_lossy(), key])?;
+    let result = conn
+        .query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))
+        .and_then(|_| conn.execute_batch(&format!("PRAGMA encrypted.user_version = {};", version)));
+    conn.execute_batch("DETACH DATABASE encrypted;")?;
+    result
+}
+
+/// One-time migration of a plaintext database (and its backup) to SQLCipher
+fn encrypt_existing_database(path: &Path, key: &str) -> SqlResult<()> {
+    let encrypted = path.with_extension("db.encrypting");
+    {
+        let conn = Connection::open(path)?;
+        export_encrypted(&conn, &encrypted, key)?;
+    }
+    std::fs::remove_file(path)
+        .and_then(|_| std::fs::rename(&encrypted, path))
+        .map_err(|e| open_error(format!("Remplacement de la base impossible: {}", e)))?;
+    // The plaintext backup would leak the same data; a fresh one is written after opening
+    let _ = std::fs::remove_file(get_backup_path(path));
+    Ok(())
+}
+
+fn open_error(message: String) -> rusqlite::Error {
+    rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN), Some(message))
+}
+
+/// Non-empty file without the plaintext header: encrypted, or damaged beyond recognition
+fn is_foreign_database(path: &Path) -> bool {
+    let non_empty = std::fs::metadata(path).map(|m| m.len() > 0).unwrap_or(false);
+    non_empty && !is_plaintext_database(path)
+}
+
+/// Why the existing database could not be opened
+enum OpenFailure {
+    /// Encrypted with a key this Windows user can't read (password reset, profile moved,
+    /// file copied from another user or machine): set aside, never discarded
+    Locked(String),
+    Sql(rusqlite::Error),
+}
+
+impl From<rusqlite::Error> for OpenFailure {
+    fn from(e: rusqlite::Error) -> Self {
+        OpenFailure::Sql(e)
+    }
+}
+
+/// Renames the database, its backup and its key to "*.<timestamp>.locked" so a fresh
+/// database can start; the files can still be opened later with the right Windows account
+fn quarantine_locked_database(path: &Path) -> SqlResult<PathBuf> {
+    let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let locked = path.with_extension(format!("db.{}.locked", stamp));
+    std::fs::rename(path, &locked)
+        .map_err(|e| open_error(format!("Impossible de mettre de cote {:?}: {}", path, e)))?;
+
+    let key_path = get_key_path(path);
+    if key_path.exists() {
+        let _ = std::fs::rename(&key_path, key_path.with_extension(format!("key.{}.locked", stamp)));
+    }
+    // Encrypted with the same key, restoring it later would hit the same wall
+    let backup = get_backup_path(path);
+    if backup.exists() {
+        let _ = std::fs::rename(&backup, path.with_extension(format!("db.bak.{}.locked", stamp)));
+    }
+    Ok(locked)
+}
+
+/// Loads the database key, or creates one (migrating an existing plaintext file).
+/// Ok(None) means "no encryption": non-Windows builds, or the key could not be stored.
+/// A key file that exists but can't be read means the database is locked: opening
+/// without it would treat the encrypted database as corrupt and discard it.
+fn load_or_create_key(path: &Path) -> Result<Option<String>, OpenFailure> {
+    if !crate::secure_store::is_supported() {
+        return Ok(None);
+    }
+
+    let key_path = get_key_path(path);
+    if key_path.exists() {
+        return match crate::secure_store::read_protected(&key_path) {
+            Ok(key) => Ok(Some(key)),
+            Err(e) if path.exists() => Err(OpenFailure::Locked(format!("Cle de la base illisible: {}", e))),
+            // Nothing encrypted with it, a new key can replace it
+            Err(e) => {
+                println!("[DB] Unreadable key without a database, replacing it: {}", e);
+                let _ = std::fs::remove_file(&key_path);
+                load_or_create_key(path)
+            }
+        };
+    }
+
+    if is_foreign_database(path) {
+        return Err(OpenFailure::Locked("Base chiffree sans cle".to_string()));
+    }
+
+    let key: String = {
+        use rand::Rng;
+        let bytes: [u8; 32] = rand::thread_rng().gen();
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    };
+
+    // Key first: a database encrypted with a key that was never saved would be lost
+    if let Err(e) = crate::secure_store::write_protected(&key_path, &key) {
+        println!("[DB] Could not store database key, staying in plaintext: {}", e);
+        return Ok(None);
+    }
+
+    if path.exists() && is_plaintext_database(path) {
+        if let Err(e) = encrypt_existing_database(path, &key) {
+            println!("[DB] Encryption migration failed, staying in plaintext: {}", e);
+            let _ = std::fs::remove_file(&key_path);
+            return Ok(None);
+        }
+        println!("[DB] Existing database migrated to encrypted storage");
+    }
+    Ok(Some(key))
+}
+
 // ============================================
 // DATABASE STATE
 // ============================================
 pub struct Database {
     pub conn: Mutex<Connection>,
+    path: PathBuf,
+    key: Option<String>,
+    /// Previous database set aside at startup because it could not be decrypted
+    locked_copy: Option<PathBuf>,
+}
+
+/// Shown by the UI so a reset database is never a silent surprise
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DbStorageStatus {
+    pub encrypted: bool,
+    pub locked_copy: Option<String>,
 }
 
 impl Database {
     pub fn new() -> SqlResult<Self> {
-        let path = get_db_path();
+        Self::open_at(get_db_path())
+    }
+
+    /// A locked database is quarantined and replaced by a fresh one, so the agent
+    /// still starts after a password reset or with a file from another account
+    fn open_at(path: PathBuf) -> SqlResult<Self> {
         println!("[DB] Opening database at: {:?}", path);
-        let conn = Connection::open(&path)?;
 
-        let db = Database {
-            conn: Mutex::new(conn),
+        let reason = match Self::try_open(&path) {
+            Ok(db) => return Ok(db),
+            Err(OpenFailure::Sql(e)) => return Err(e),
+            Err(OpenFailure::Locked(reason)) => reason,
+        };
+
+        let locked = quarantine_locked_database(&path)?;
+        println!("[DB] {}, moved to {:?}, starting with an empty database", reason, locked);
+        match Self::try_open(&path) {
+            Ok(mut db) => {
+                db.locked_copy = Some(locked);
+                Ok(db)
+            }
+            Err(OpenFailure::Sql(e)) => Err(e),
+            Err(OpenFailure::Locked(reason)) => Err(open_error(reason)),
+        }
+    }
+
+    fn try_open(path: &Path) -> Result<Self, OpenFailure> {
+        let key = load_or_create_key(path)?;
+        let mut db = Database {
+            conn: Mutex::new(open_connection(path, key.as_deref())?),
+            path: path.to_path_buf(),
+            key,
+            locked_copy: None,
         };
 
+        let healthy = matches!(db.integrity_check(), Ok(ref errors) if errors.is_empty());
+        if !healthy {
+            // Unreadable and not plaintext: encrypted with another key, recovery would discard it
+            if !db.is_readable() && is_foreign_database(path) {
+                // Release the file handle first, Windows won't rename an open file
+                drop(db);
+                return Err(OpenFailure::Locked("Base chiffree illisible avec cette cle".to_string()));
+            }
+            println!("[DB] Integrity check failed, restoring from backup");
+            // Release the file handle first, Windows won't rename an open file
+            db.conn = Mutex::new(Connection::open_in_memory()?);
+            db.conn = Mutex::new(Self::recover(path, db.key.as_deref())?);
+        }
+
         db.init_schema()?;
+        if let Err(e) = db.backup_to(&get_backup_path(path)) {
+            println!("[DB] Backup failed: {}", e);
+        }
         Ok(db)
     }
 
-    fn init_schema(&self) -> SqlResult<()> {
+    pub fn storage_status(&self) -> DbStorageStatus {
+        DbStorageStatus {
+            encrypted: self.key.is_some(),
+            locked_copy: self.locked_copy.as_ref().map(|p| p.to_string_lossy().to_string()),
+        }
+    }
+
+    /// The key (or lack of one) opens the file, whatever its integrity
+    fn is_readable(&self) -> bool {
         let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0)).is_ok()
+    }
+
+    /// Move the corrupt file aside and reopen from the backup (or start empty)
+    fn recover(path: &Path, key: Option<&str>) -> SqlResult<Connection> {
+        let corrupt = path.with_extension("db.corrupt");
+        let _ = std::fs::remove_file(&corrupt);
+        if let Err(e) = std::fs::rename(path, &corrupt) {
+            println!("[DB] Could not move corrupt database aside: {}", e);
+            let _ = std::fs::remove_file(path);
+        }
+
+        let backup = get_backup_path(path);
+        if backup.exists() && std::fs::copy(&backup, path).is_ok() {
+            let conn = open_connection(path, key)?;
+            let ok = conn.query_row("PRAGMA integrity_check", [], |row| row.get::<_, String>(0))
+                .unwrap_or_default();
+            if ok == "ok" {
+                println!("[DB] Database restored from backup");
+                return Ok(conn);
+            }
+            drop(conn);
+            let _ = std::fs::remove_file(path);
+        }
+
+        println!("[DB] No usable backup, starting with an empty database");
+        open_connection(path, key)
+    }
+
+    fn init_schema(&self) -> SqlResult<()> {
+        let mut conn = self.conn.lock().unwrap();
 
         // Scripts table - local cache of Supabase scripts
         conn.execute(
@@ -124,20 +373,127 @@ impl Database {
             [],
         )?;
 
+        // Script preferences - user favorites/order, kept apart from scripts so re-sync doesn't wipe them
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS script_prefs (
+                id TEXT PRIMARY KEY,
+                favorite INTEGER DEFAULT 0,
+                sort_order INTEGER DEFAULT 0
+            )",
+            [],
+        )?;
+
+        // SMART history - per-drive attribute snapshots for trend detection
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS smart_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                serial TEXT NOT NULL,
+                model TEXT,
+                timestamp TEXT DEFAULT CURRENT_TIMESTAMP,
+                reallocated_sectors INTEGER,
+                pending_sectors INTEGER,
+                temperature_c INTEGER,
+                power_on_hours INTEGER,
+                percentage_used INTEGER
+            )",
+            [],
+        )?;
+
+        // Temperature history - sensor readings sampled on each heartbeat
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS temperature_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT DEFAULT CURRENT_TIMESTAMP,
+                sensor_name TEXT NOT NULL,
+                sensor_type TEXT NOT NULL,
+                value REAL NOT NULL
+            )",
+            [],
+        )?;
+
         // Create indexes for performance
         conn.execute("CREATE INDEX IF NOT EXISTS idx_scripts_category ON scripts(category)", [])?;
         conn.execute("CREATE INDEX IF NOT EXISTS idx_scripts_active ON scripts(is_active)", [])?;
         conn.execute("CREATE INDEX IF NOT EXISTS idx_metrics_synced ON metrics_history(synced)", [])?;
         conn.execute("CREATE INDEX IF NOT EXISTS idx_sync_queue_table ON sync_queue(table_name)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_smart_history_serial ON smart_history(serial, timestamp)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_temperature_history_type ON temperature_history(sensor_type, timestamp)", [])?;
 
-        println!("[DB] Schema initialized");
+        let version = run_migrations(&mut conn)?;
+        println!("[DB] Schema initialized (version {})", version);
         Ok(())
     }
 }
 
+// ============================================
+// SCHEMA MIGRATIONS
+// ============================================
+
+/// Changes applied on top of the base schema above; migration N leaves `user_version` at N.
+/// Append only: a shipped migration must never be edited or reordered.
+const MIGRATIONS: &[&str] = &[
+    // 1 - sync queue exponential backoff
+    "ALTER TABLE sync_queue ADD COLUMN next_retry_at TEXT;",
+    // 2 - chat full-text search, external content index over chat_history
+    "CREATE VIRTUAL TABLE IF NOT EXISTS chat_history_fts USING fts5(
+        content,
+        content='chat_history',
+        content_rowid='id',
+        tokenize='unicode61 remove_diacritics 2'
+    );
+    INSERT INTO chat_history_fts(chat_history_fts) VALUES('rebuild');",
+    // 3 - health score history from scheduled and manual diagnostics
+    "CREATE TABLE IF NOT EXISTS diagnostic_history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp TEXT DEFAULT CURRENT_TIMESTAMP,
+        source TEXT NOT NULL,
+        overall_score INTEGER NOT NULL,
+        temperature_score INTEGER NOT NULL,
+        process_score INTEGER NOT NULL,
+        storage_score INTEGER NOT NULL,
+        network_score INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_diagnostic_history_timestamp ON diagnostic_history(timestamp);",
+    // 4 - audit trail of every script run on this machine
+    "CREATE TABLE IF NOT EXISTS script_executions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        script_id TEXT NOT NULL,
+        source TEXT NOT NULL,
+        started_at TEXT NOT NULL,
+        finished_at TEXT NOT NULL,
+        exit_code INTEGER,
+        stdout_tail TEXT,
+        stderr_tail TEXT
+    );
+    CREATE INDEX IF NOT EXISTS idx_script_executions_started ON script_executions(started_at);",
+    // 5 - scripts written on this machine, never overwritten by the Supabase sync
+    "ALTER TABLE scripts ADD COLUMN is_local INTEGER NOT NULL DEFAULT 0;",
+    // 6 - SMART uncorrectable sector count (attribute 198) next to reallocated/pending
+    "ALTER TABLE smart_history ADD COLUMN uncorrectable_sectors INTEGER;",
+];
+
+/// Applies every migration above the current `user_version`, each in its own transaction
+fn run_migrations(conn: &mut Connection) -> SqlResult<usize> {
+    let current: usize = conn.query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))? as usize;
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(current) {
+        let version = index + 1;
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", version as i64)?;
+        tx.commit()?;
+        println!("[DB] Migration {} applied", version);
+    }
+
+    Ok(MIGRATIONS.len().max(current))
+}
+
 // ============================================
 // SCRIPT MODELS
 // ============================================
+/// Languages run_script knows how to launch
+pub const SCRIPT_LANGUAGES: &[&str] = &["powershell", "python", "batch"];
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LocalScript {
     pub id: String,
@@ -152,6 +508,12 @@ pub struct LocalScript {
     pub requires_admin: bool,
     pub estimated_time: Option<String>,
     pub success_message: Option<String>,
+    #[serde(default)]
+    pub is_favorite: bool,
+    #[serde(default)]
+    pub sort_order: i32,
+    #[serde(default)]
+    pub is_local: bool,     // Authored in the UI rather than synced from Supabase
 }
 
 // ============================================
@@ -161,9 +523,11 @@ impl Database {
     pub fn get_all_scripts(&self) -> SqlResult<Vec<LocalScript>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, slug, name, description, category, language, code, icon,
-                    is_active, requires_admin, estimated_time, success_message
-             FROM scripts WHERE is_active = 1 ORDER BY category, name"
+            "SELECT s.id, s.slug, s.name, s.description, s.category, s.language, s.code, s.icon,
+                    s.is_active, s.requires_admin, s.estimated_time, s.success_message,
+                    COALESCE(p.favorite, 0), COALESCE(p.sort_order, 0), s.is_local
+             FROM scripts s LEFT JOIN script_prefs p ON p.id = s.id
+             WHERE s.is_active = 1 ORDER BY s.category, s.name"
         )?;
 
         let scripts = stmt.query_map([], |row| {
@@ -180,6 +544,9 @@ impl Database {
                 requires_admin: row.get::<_, i32>(9)? == 1,
                 estimated_time: row.get(10)?,
                 success_message: row.get(11)?,
+                is_favorite: row.get::<_, i32>(12)? == 1,
+                sort_order: row.get(13)?,
+                is_local: row.get::<_, i32>(14)? == 1,
             })
         })?;
 
@@ -189,9 +556,11 @@ impl Database {
     pub fn get_scripts_by_category(&self, category: &str) -> SqlResult<Vec<LocalScript>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, slug, name, description, category, language, code, icon,
-                    is_active, requires_admin, estimated_time, success_message
-             FROM scripts WHERE is_active = 1 AND category = ?1 ORDER BY name"
+            "SELECT s.id, s.slug, s.name, s.description, s.category, s.language, s.code, s.icon,
+                    s.is_active, s.requires_admin, s.estimated_time, s.success_message,
+                    COALESCE(p.favorite, 0), COALESCE(p.sort_order, 0), s.is_local
+             FROM scripts s LEFT JOIN script_prefs p ON p.id = s.id
+             WHERE s.is_active = 1 AND s.category = ?1 ORDER BY s.name"
         )?;
 
         let scripts = stmt.query_map([category], |row| {
@@ -208,6 +577,9 @@ impl Database {
                 requires_admin: row.get::<_, i32>(9)? == 1,
                 estimated_time: row.get(10)?,
                 success_message: row.get(11)?,
+                is_favorite: row.get::<_, i32>(12)? == 1,
+                sort_order: row.get(13)?,
+                is_local: row.get::<_, i32>(14)? == 1,
             })
         })?;
 
@@ -219,8 +591,8 @@ impl Database {
         conn.execute(
             "INSERT OR REPLACE INTO scripts
              (id, slug, name, description, category, language, code, icon,
-              is_active, requires_admin, estimated_time, success_message, synced_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, CURRENT_TIMESTAMP)",
+              is_active, requires_admin, estimated_time, success_message, is_local, synced_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, CURRENT_TIMESTAMP)",
             params![
                 script.id,
                 script.slug,
@@ -234,17 +606,119 @@ impl Database {
                 script.requires_admin as i32,
                 script.estimated_time,
                 script.success_message,
+                script.is_local as i32,
             ],
         )?;
         Ok(())
     }
 
+    /// A local script already uses this id or slug, the sync must leave it alone
+    pub fn is_local_script(&self, id: &str, slug: &str) -> SqlResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT COUNT(*) FROM scripts WHERE is_local = 1 AND (id = ?1 OR slug = ?2)",
+            params![id, slug],
+            |row| row.get::<_, i64>(0),
+        ).map(|count| count > 0)
+    }
+
+    /// True when `id` belongs to a script synced from Supabase
+    pub fn is_synced_script(&self, id: &str) -> SqlResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT COUNT(*) FROM scripts WHERE is_local = 0 AND id = ?1",
+            [id],
+            |row| row.get::<_, i64>(0),
+        ).map(|count| count > 0)
+    }
+
+    /// Id of the script using `slug`, if any
+    pub fn script_id_for_slug(&self, slug: &str) -> SqlResult<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row("SELECT id FROM scripts WHERE slug = ?1", [slug], |row| row.get(0));
+
+        match result {
+            Ok(id) => Ok(Some(id)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Synced scripts can't be deleted, the next sync would bring them back
+    pub fn delete_local_script(&self, id: &str) -> SqlResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute("DELETE FROM scripts WHERE id = ?1 AND is_local = 1", [id])?;
+        if removed > 0 {
+            conn.execute("DELETE FROM script_prefs WHERE id = ?1", [id])?;
+        }
+        Ok(removed)
+    }
+
     pub fn get_scripts_count(&self) -> SqlResult<i32> {
         let conn = self.conn.lock().unwrap();
         conn.query_row("SELECT COUNT(*) FROM scripts WHERE is_active = 1", [], |row| row.get(0))
     }
 }
 
+// ============================================
+// SCRIPT PREFERENCES (favorites & ordering)
+// ============================================
+impl Database {
+    pub fn set_script_favorite(&self, id: &str, favorite: bool) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO script_prefs (id, favorite) VALUES (?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET favorite = excluded.favorite",
+            params![id, favorite as i32],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_script_order(&self, id: &str, sort_order: i32) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO script_prefs (id, sort_order) VALUES (?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET sort_order = excluded.sort_order",
+            params![id, sort_order],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_favorite_scripts(&self) -> SqlResult<Vec<LocalScript>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT s.id, s.slug, s.name, s.description, s.category, s.language, s.code, s.icon,
+                    s.is_active, s.requires_admin, s.estimated_time, s.success_message,
+                    p.favorite, p.sort_order, s.is_local
+             FROM scripts s INNER JOIN script_prefs p ON p.id = s.id
+             WHERE s.is_active = 1 AND p.favorite = 1
+             ORDER BY p.sort_order, s.name"
+        )?;
+
+        let scripts = stmt.query_map([], |row| {
+            Ok(LocalScript {
+                id: row.get(0)?,
+                slug: row.get(1)?,
+                name: row.get(2)?,
+                description: row.get(3)?,
+                category: row.get(4)?,
+                language: row.get(5)?,
+                code: row.get(6)?,
+                icon: row.get(7)?,
+                is_active: row.get::<_, i32>(8)? == 1,
+                requires_admin: row.get::<_, i32>(9)? == 1,
+                estimated_time: row.get(10)?,
+                success_message: row.get(11)?,
+                is_favorite: row.get::<_, i32>(12)? == 1,
+                sort_order: row.get(13)?,
+                is_local: row.get::<_, i32>(14)? == 1,
+            })
+        })?;
+
+        scripts.collect()
+    }
+}
+
 // ============================================
 // METRICS OPERATIONS
 // ============================================
@@ -260,6 +734,31 @@ pub struct LocalMetrics {
     pub synced: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HealthTrend {
+    pub values: Vec<f32>,
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+    pub current: Option<i32>,
+    pub sample_count: usize,
+}
+
+/// Bucketed averaging: splits the series into `points` equal buckets (sparse data is returned as-is)
+fn downsample_average(values: &[i32], points: usize) -> Vec<f32> {
+    if points == 0 || values.len() <= points {
+        return values.iter().map(|v| *v as f32).collect();
+    }
+
+    (0..points)
+        .map(|i| {
+            let start = i * values.len() / points;
+            let end = ((i + 1) * values.len() / points).max(start + 1);
+            let bucket = &values[start..end];
+            bucket.iter().map(|v| *v as f32).sum::<f32>() / bucket.len() as f32
+        })
+        .collect()
+}
+
 impl Database {
     pub fn save_metrics(&self, metrics: &LocalMetrics) -> SqlResult<i64> {
         let conn = self.conn.lock().unwrap();
@@ -331,6 +830,23 @@ impl Database {
         Ok(())
     }
 
+    /// Health scores over the stored window, downsampled to `points` for a sparkline
+    pub fn get_health_trend(&self, points: usize) -> SqlResult<HealthTrend> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT health_score FROM metrics_history WHERE health_score IS NOT NULL ORDER BY timestamp ASC, id ASC"
+        )?;
+        let scores: Vec<i32> = stmt.query_map([], |row| row.get(0))?.collect::<SqlResult<_>>()?;
+
+        Ok(HealthTrend {
+            values: downsample_average(&scores, points),
+            min: scores.iter().min().copied(),
+            max: scores.iter().max().copied(),
+            current: scores.last().copied(),
+            sample_count: scores.len(),
+        })
+    }
+
     // Cleanup old metrics (keep last 7 days)
     pub fn cleanup_old_metrics(&self) -> SqlResult<usize> {
         let conn = self.conn.lock().unwrap();
@@ -341,6 +857,331 @@ impl Database {
     }
 }
 
+// ============================================
+// SMART HISTORY OPERATIONS
+// ============================================
+/// Max snapshots kept per drive (heartbeat every 5 min => ~1 week at full rate)
+const SMART_HISTORY_MAX_PER_DRIVE: i64 = 2000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SmartHistoryEntry {
+    pub id: Option<i64>,
+    pub serial: String,
+    pub model: Option<String>,
+    pub timestamp: String,
+    pub reallocated_sectors: Option<i64>,
+    pub pending_sectors: Option<i64>,
+    pub uncorrectable_sectors: Option<i64>,
+    pub temperature_c: Option<i64>,
+    pub power_on_hours: Option<i64>,
+    pub percentage_used: Option<i64>,
+}
+
+const SMART_HISTORY_COLUMNS: &str = "id, serial, model, timestamp, reallocated_sectors, pending_sectors,
+    uncorrectable_sectors, temperature_c, power_on_hours, percentage_used";
+
+fn smart_history_from_row(row: &rusqlite::Row) -> SqlResult<SmartHistoryEntry> {
+    Ok(SmartHistoryEntry {
+        id: Some(row.get(0)?),
+        serial: row.get(1)?,
+        model: row.get(2)?,
+        timestamp: row.get(3)?,
+        reallocated_sectors: row.get(4)?,
+        pending_sectors: row.get(5)?,
+        uncorrectable_sectors: row.get(6)?,
+        temperature_c: row.get(7)?,
+        power_on_hours: row.get(8)?,
+        percentage_used: row.get(9)?,
+    })
+}
+
+impl Database {
+    pub fn save_smart_snapshot(&self, entry: &SmartHistoryEntry) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO smart_history
+             (serial, model, reallocated_sectors, pending_sectors, uncorrectable_sectors, temperature_c, power_on_hours, percentage_used)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.serial,
+                entry.model,
+                entry.reallocated_sectors,
+                entry.pending_sectors,
+                entry.uncorrectable_sectors,
+                entry.temperature_c,
+                entry.power_on_hours,
+                entry.percentage_used,
+            ],
+        )?;
+
+        // Bound history per drive
+        conn.execute(
+            "DELETE FROM smart_history WHERE serial = ?1 AND id NOT IN (
+                SELECT id FROM smart_history WHERE serial = ?1 ORDER BY id DESC LIMIT ?2
+             )",
+            params![entry.serial, SMART_HISTORY_MAX_PER_DRIVE],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_smart_history(&self, serial: &str, days: i32) -> SqlResult<Vec<SmartHistoryEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM smart_history
+             WHERE serial = ?1 AND timestamp >= datetime('now', '-' || ?2 || ' days')
+             ORDER BY timestamp ASC",
+            SMART_HISTORY_COLUMNS
+        ))?;
+
+        let entries = stmt.query_map(params![serial, days], smart_history_from_row)?;
+        entries.collect()
+    }
+
+    /// Newest snapshot of a drive, the baseline for degradation alerts
+    pub fn get_last_smart_snapshot(&self, serial: &str) -> SqlResult<Option<SmartHistoryEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            &format!("SELECT {} FROM smart_history WHERE serial = ?1 ORDER BY id DESC LIMIT 1", SMART_HISTORY_COLUMNS),
+            [serial],
+            smart_history_from_row,
+        );
+
+        match result {
+            Ok(entry) => Ok(Some(entry)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn get_smart_serials(&self) -> SqlResult<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT DISTINCT serial FROM smart_history")?;
+        let serials = stmt.query_map([], |row| row.get(0))?;
+        serials.collect()
+    }
+}
+
+// ============================================
+// DIAGNOSTIC HISTORY OPERATIONS
+// ============================================
+/// Score drop from one run to the next that the dashboard flags as a regression
+const DIAGNOSTIC_REGRESSION_POINTS: i64 = 20;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiagnosticHistoryEntry {
+    pub id: Option<i64>,
+    pub timestamp: String,
+    pub source: String, // scheduled, manual
+    pub overall_score: i64,
+    pub temperature_score: i64,
+    pub process_score: i64,
+    pub storage_score: i64,
+    pub network_score: i64,
+    pub score_delta: Option<i64>, // vs the previous run, filled on read
+    pub is_regression: bool,
+}
+
+impl Database {
+    pub fn save_diagnostic_history(&self, entry: &DiagnosticHistoryEntry) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO diagnostic_history
+             (source, overall_score, temperature_score, process_score, storage_score, network_score)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                entry.source,
+                entry.overall_score,
+                entry.temperature_score,
+                entry.process_score,
+                entry.storage_score,
+                entry.network_score,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Runs of the last `days`, oldest first, with the delta to the previous run
+    pub fn get_diagnostic_history(&self, days: i32) -> SqlResult<Vec<DiagnosticHistoryEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, source, overall_score, temperature_score, process_score, storage_score, network_score
+             FROM diagnostic_history
+             WHERE timestamp >= datetime('now', '-' || ?1 || ' days')
+             ORDER BY timestamp ASC, id ASC"
+        )?;
+
+        let entries = stmt.query_map([days], |row| {
+            Ok(DiagnosticHistoryEntry {
+                id: Some(row.get(0)?),
+                timestamp: row.get(1)?,
+                source: row.get(2)?,
+                overall_score: row.get(3)?,
+                temperature_score: row.get(4)?,
+                process_score: row.get(5)?,
+                storage_score: row.get(6)?,
+                network_score: row.get(7)?,
+                score_delta: None,
+                is_regression: false,
+            })
+        })?;
+
+        let mut result: Vec<DiagnosticHistoryEntry> = entries.collect::<SqlResult<Vec<_>>>()?;
+        for i in 1..result.len() {
+            let delta = result[i].overall_score - result[i - 1].overall_score;
+            result[i].score_delta = Some(delta);
+            result[i].is_regression = delta <= -DIAGNOSTIC_REGRESSION_POINTS;
+        }
+        Ok(result)
+    }
+
+    /// Seconds since the last run with this source, None if it never ran
+    pub fn get_last_diagnostic_age_secs(&self, source: &str) -> SqlResult<Option<i64>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT CAST((julianday('now') - julianday(MAX(timestamp))) * 86400 AS INTEGER)
+             FROM diagnostic_history WHERE source = ?1",
+            [source],
+            |row| row.get(0),
+        )
+    }
+}
+
+// ============================================
+// SCRIPT EXECUTION AUDIT
+// ============================================
+/// Rows kept in the audit log, oldest dropped first
+const SCRIPT_EXECUTIONS_MAX_ROWS: i64 = 5000;
+/// Output kept per stream: the end of the output, where errors and summaries are
+const SCRIPT_OUTPUT_TAIL_CHARS: usize = 4000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScriptExecution {
+    pub id: Option<i64>,
+    pub script_id: String,
+    pub source: String, // local, remote
+    pub started_at: String,
+    pub finished_at: String,
+    pub exit_code: Option<i32>, // None when cancelled, timed out or failed to start
+    pub stdout_tail: String,
+    pub stderr_tail: String,
+}
+
+/// Last `max_chars` characters of `text`
+fn output_tail(text: &str, max_chars: usize) -> String {
+    let count = text.chars().count();
+    text.chars().skip(count.saturating_sub(max_chars)).collect()
+}
+
+impl Database {
+    pub fn save_script_execution(&self, entry: &ScriptExecution) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO script_executions
+             (script_id, source, started_at, finished_at, exit_code, stdout_tail, stderr_tail)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                entry.script_id,
+                entry.source,
+                entry.started_at,
+                entry.finished_at,
+                entry.exit_code,
+                output_tail(&entry.stdout_tail, SCRIPT_OUTPUT_TAIL_CHARS),
+                output_tail(&entry.stderr_tail, SCRIPT_OUTPUT_TAIL_CHARS),
+            ],
+        )?;
+
+        conn.execute(
+            "DELETE FROM script_executions WHERE id NOT IN (
+                SELECT id FROM script_executions ORDER BY id DESC LIMIT ?1
+             )",
+            [SCRIPT_EXECUTIONS_MAX_ROWS],
+        )?;
+        Ok(())
+    }
+
+    /// Most recent runs first
+    pub fn get_script_executions(&self, limit: i32) -> SqlResult<Vec<ScriptExecution>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, script_id, source, started_at, finished_at, exit_code, stdout_tail, stderr_tail
+             FROM script_executions ORDER BY id DESC LIMIT ?1"
+        )?;
+
+        let entries = stmt.query_map([limit], |row| {
+            Ok(ScriptExecution {
+                id: Some(row.get(0)?),
+                script_id: row.get(1)?,
+                source: row.get(2)?,
+                started_at: row.get(3)?,
+                finished_at: row.get(4)?,
+                exit_code: row.get(5)?,
+                stdout_tail: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+                stderr_tail: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+            })
+        })?;
+
+        entries.collect()
+    }
+}
+
+// ============================================
+// TEMPERATURE HISTORY OPERATIONS
+// ============================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemperatureReading {
+    pub id: Option<i64>,
+    pub timestamp: String,
+    pub sensor_name: String,
+    pub sensor_type: String,
+    pub value: f32,
+}
+
+impl Database {
+    pub fn save_temperature_readings(&self, readings: &[TemperatureReading]) -> SqlResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for reading in readings {
+            tx.execute(
+                "INSERT INTO temperature_history (sensor_name, sensor_type, value) VALUES (?1, ?2, ?3)",
+                params![reading.sensor_name, reading.sensor_type, reading.value],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Readings of the last `hours`, oldest first. An empty type returns every sensor.
+    pub fn get_temperature_history(&self, sensor_type: &str, hours: i32) -> SqlResult<Vec<TemperatureReading>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, sensor_name, sensor_type, value
+             FROM temperature_history
+             WHERE (?1 = '' OR sensor_type = ?1) AND timestamp >= datetime('now', '-' || ?2 || ' hours')
+             ORDER BY timestamp ASC"
+        )?;
+
+        let readings = stmt.query_map(params![sensor_type, hours], |row| {
+            Ok(TemperatureReading {
+                id: Some(row.get(0)?),
+                timestamp: row.get(1)?,
+                sensor_name: row.get(2)?,
+                sensor_type: row.get(3)?,
+                value: row.get(4)?,
+            })
+        })?;
+
+        readings.collect()
+    }
+
+    // Cleanup old temperatures (keep last 30 days)
+    pub fn cleanup_old_temperatures(&self) -> SqlResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM temperature_history WHERE timestamp < datetime('now', '-30 days')",
+            [],
+        )
+    }
+}
+
 // ============================================
 // CACHE OPERATIONS
 // ============================================
@@ -383,6 +1224,76 @@ impl Database {
     }
 }
 
+// ============================================
+// MAINTENANCE (VACUUM / INTEGRITY)
+// ============================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DbMaintenanceReport {
+    pub metrics_removed: usize,
+    pub cache_removed: usize,
+    pub size_before: u64,
+    pub size_after: u64,
+    pub reclaimed_bytes: u64,
+    pub integrity_ok: bool,
+    pub warnings: Vec<String>,
+}
+
+impl Database {
+    pub fn file_size(&self) -> u64 {
+        std::fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0)
+    }
+
+    pub fn vacuum(&self) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch("VACUUM")
+    }
+
+    /// Empty when the database is healthy, otherwise the reported problems
+    pub fn integrity_check(&self) -> SqlResult<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let messages: Vec<String> = rows.collect::<SqlResult<_>>()?;
+        Ok(messages.into_iter().filter(|m| m != "ok").collect())
+    }
+
+    fn backup_to(&self, target: &Path) -> SqlResult<()> {
+        let _ = std::fs::remove_file(target);
+        let conn = self.conn.lock().unwrap();
+        match &self.key {
+            // VACUUM INTO would not carry the SQLCipher key over
+            Some(key) => export_encrypted(&conn, target, key),
+            None => conn.execute("VACUUM INTO ?1", [target.to_string_lossy()]).map(|_| ()),
+        }
+    }
+
+    pub fn run_maintenance(&self) -> SqlResult<DbMaintenanceReport> {
+        let size_before = self.file_size();
+        let metrics_removed = self.cleanup_old_metrics()?;
+        let cache_removed = self.cleanup_expired_cache()?;
+        self.vacuum()?;
+        let size_after = self.file_size();
+
+        let warnings = self.integrity_check()?;
+        let integrity_ok = warnings.is_empty();
+        if integrity_ok {
+            if let Err(e) = self.backup_to(&get_backup_path(&self.path)) {
+                println!("[DB] Backup failed: {}", e);
+            }
+        }
+
+        Ok(DbMaintenanceReport {
+            metrics_removed,
+            cache_removed,
+            size_before,
+            size_after,
+            reclaimed_bytes: size_before.saturating_sub(size_after),
+            integrity_ok,
+            warnings,
+        })
+    }
+}
+
 // ============================================
 // SETTINGS OPERATIONS
 // ============================================
@@ -410,6 +1321,37 @@ impl Database {
             Err(e) => Err(e),
         }
     }
+
+    /// Every setting with its `updated_at` (UTC, "YYYY-MM-DD HH:MM:SS")
+    pub fn get_settings_with_timestamps(&self) -> SqlResult<Vec<SettingEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT key, value, updated_at FROM settings")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(SettingEntry {
+                key: row.get(0)?,
+                value: row.get(1)?,
+                updated_at: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Stores a value pulled from the cloud with its remote timestamp, so it doesn't look locally changed
+    pub fn set_setting_at(&self, key: &str, value: &str, updated_at: &str) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?1, ?2, ?3)",
+            params![key, value, updated_at],
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingEntry {
+    pub key: String,
+    pub value: String,
+    pub updated_at: String,
 }
 
 // ============================================
@@ -424,8 +1366,12 @@ pub struct SyncQueueItem {
     pub created_at: String,
     pub retry_count: i32,
     pub last_error: Option<String>,
+    pub next_retry_at: Option<String>,
 }
 
+/// Items are abandoned after this many failures (until reset_sync_queue_failures)
+pub const SYNC_MAX_RETRIES: i32 = 5;
+
 impl Database {
     pub fn add_to_sync_queue(&self, table_name: &str, operation: &str, data: &str) -> SqlResult<i64> {
         let conn = self.conn.lock().unwrap();
@@ -439,11 +1385,13 @@ impl Database {
     pub fn get_pending_sync_items(&self, limit: i32) -> SqlResult<Vec<SyncQueueItem>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, table_name, operation, data, created_at, retry_count, last_error
-             FROM sync_queue WHERE retry_count < 5 ORDER BY created_at ASC LIMIT ?1"
+            "SELECT id, table_name, operation, data, created_at, retry_count, last_error, next_retry_at
+             FROM sync_queue
+             WHERE retry_count < ?2 AND (next_retry_at IS NULL OR next_retry_at <= datetime('now'))
+             ORDER BY created_at ASC LIMIT ?1"
         )?;
 
-        let items = stmt.query_map([limit], |row| {
+        let items = stmt.query_map(params![limit, SYNC_MAX_RETRIES], |row| {
             Ok(SyncQueueItem {
                 id: row.get(0)?,
                 table_name: row.get(1)?,
@@ -452,6 +1400,7 @@ impl Database {
                 created_at: row.get(4)?,
                 retry_count: row.get(5)?,
                 last_error: row.get(6)?,
+                next_retry_at: row.get(7)?,
             })
         })?;
 
@@ -464,14 +1413,38 @@ impl Database {
         Ok(())
     }
 
+    /// Exponential backoff: the n-th failure waits 2^n minutes (2, 4, 8, 16...)
     pub fn mark_sync_failed(&self, id: i64, error: &str) -> SqlResult<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "UPDATE sync_queue SET retry_count = retry_count + 1, last_error = ?2 WHERE id = ?1",
+            "UPDATE sync_queue SET
+                retry_count = retry_count + 1,
+                last_error = ?2,
+                next_retry_at = datetime('now', '+' || (1 << (retry_count + 1)) || ' minutes')
+             WHERE id = ?1",
             params![id, error],
         )?;
         Ok(())
     }
+
+    /// (items still to send, items abandoned after SYNC_MAX_RETRIES)
+    pub fn count_sync_queue(&self) -> SqlResult<(usize, usize)> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT COALESCE(SUM(retry_count < ?1), 0), COALESCE(SUM(retry_count >= ?1), 0) FROM sync_queue",
+            [SYNC_MAX_RETRIES],
+            |row| Ok((row.get::<_, i64>(0)? as usize, row.get::<_, i64>(1)? as usize)),
+        )
+    }
+
+    /// Re-arms items that exhausted their retries; returns how many were reset
+    pub fn reset_sync_queue_failures(&self) -> SqlResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE sync_queue SET retry_count = 0, next_retry_at = NULL, last_error = NULL WHERE retry_count >= ?1",
+            [SYNC_MAX_RETRIES],
+        )
+    }
 }
 
 // ============================================
@@ -485,14 +1458,38 @@ pub struct ChatMessage {
     pub timestamp: Option<String>,
 }
 
+fn chat_message_from_row(row: &rusqlite::Row) -> SqlResult<ChatMessage> {
+    Ok(ChatMessage {
+        id: Some(row.get(0)?),
+        role: row.get(1)?,
+        content: row.get(2)?,
+        timestamp: Some(row.get(3)?),
+    })
+}
+
+/// Each word becomes a quoted prefix term so user input can't inject FTS5 syntax
+fn fts_query(query: &str) -> String {
+    query.split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 impl Database {
     pub fn add_chat_message(&self, role: &str, content: &str) -> SqlResult<i64> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
             "INSERT INTO chat_history (role, content) VALUES (?1, ?2)",
             params![role, content],
         )?;
-        Ok(conn.last_insert_rowid())
+        let id = tx.last_insert_rowid();
+        tx.execute(
+            "INSERT INTO chat_history_fts (rowid, content) VALUES (?1, ?2)",
+            params![id, content],
+        )?;
+        tx.commit()?;
+        Ok(id)
     }
 
     pub fn get_chat_history(&self, limit: i32) -> SqlResult<Vec<ChatMessage>> {
@@ -501,14 +1498,7 @@ impl Database {
             "SELECT id, role, content, timestamp FROM chat_history ORDER BY timestamp DESC LIMIT ?1"
         )?;
 
-        let messages = stmt.query_map([limit], |row| {
-            Ok(ChatMessage {
-                id: Some(row.get(0)?),
-                role: row.get(1)?,
-                content: row.get(2)?,
-                timestamp: Some(row.get(3)?),
-            })
-        })?;
+        let messages = stmt.query_map([limit], chat_message_from_row)?;
 
         // Reverse to get chronological order
         let mut result: Vec<ChatMessage> = messages.collect::<SqlResult<Vec<_>>>()?;
@@ -516,9 +1506,248 @@ impl Database {
         Ok(result)
     }
 
-    pub fn clear_chat_history(&self) -> SqlResult<()> {
+    /// `limit` messages older than `before_id`, chronological. `before_id <= 0` starts from the newest.
+    pub fn get_chat_history_page(&self, before_id: i64, limit: i32) -> SqlResult<Vec<ChatMessage>> {
+        let before_id = if before_id <= 0 { i64::MAX } else { before_id };
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM chat_history", [])?;
-        Ok(())
+        let mut stmt = conn.prepare(
+            "SELECT id, role, content, timestamp FROM chat_history WHERE id < ?1 ORDER BY id DESC LIMIT ?2"
+        )?;
+
+        let messages = stmt.query_map(params![before_id, limit], chat_message_from_row)?;
+
+        let mut result: Vec<ChatMessage> = messages.collect::<SqlResult<Vec<_>>>()?;
+        result.reverse();
+        Ok(result)
+    }
+
+    /// Best matches first (FTS5 bm25 rank), accents ignored
+    pub fn search_chat_history(&self, query: &str, limit: i32) -> SqlResult<Vec<ChatMessage>> {
+        let match_expr = fts_query(query);
+        if match_expr.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT c.id, c.role, c.content, c.timestamp
+             FROM chat_history_fts f
+             JOIN chat_history c ON c.id = f.rowid
+             WHERE chat_history_fts MATCH ?1
+             ORDER BY f.rank
+             LIMIT ?2"
+        )?;
+
+        let messages = stmt.query_map(params![match_expr, limit], chat_message_from_row)?;
+        messages.collect()
+    }
+
+    pub fn clear_chat_history(&self) -> SqlResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM chat_history", [])?;
+        tx.execute("INSERT INTO chat_history_fts (chat_history_fts) VALUES ('delete-all')", [])?;
+        tx.commit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_database() -> (tempfile::TempDir, Database) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::open_at(dir.path().join("microdiag.db")).unwrap();
+        (dir, db)
+    }
+
+    fn catalog_script(id: &str, name: &str) -> LocalScript {
+        LocalScript {
+            id: id.to_string(),
+            slug: id.to_string(),
+            name: name.to_string(),
+            description: None,
+            category: "maintenance".to_string(),
+            language: "powershell".to_string(),
+            code: "Write-Output ok".to_string(),
+            icon: None,
+            is_active: true,
+            requires_admin: false,
+            estimated_time: None,
+            success_message: None,
+            is_favorite: false,
+            sort_order: 0,
+            is_local: false,
+        }
+    }
+
+    #[test]
+    fn undecryptable_database_is_set_aside_not_fatal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("microdiag.db");
+        // Looks like a SQLCipher file this account has no key for
+        let foreign: Vec<u8> = (0..8192u32).map(|i| (i * 7 % 251) as u8).collect();
+        std::fs::write(&path, &foreign).unwrap();
+
+        let db = Database::open_at(path.clone()).unwrap();
+
+        let locked = db.storage_status().locked_copy.expect("locked copy reported");
+        assert!(locked.ends_with(".locked"));
+        assert_eq!(std::fs::read(&locked).unwrap(), foreign);
+        db.set_setting("language", "fr").unwrap();
+        assert_eq!(db.get_setting("language").unwrap().as_deref(), Some("fr"));
+    }
+
+    #[test]
+    fn favorite_survives_catalog_resync() {
+        let (_dir, db) = temp_database();
+        db.upsert_script(&catalog_script("clean-temp", "Nettoyage")).unwrap();
+        db.set_script_favorite("clean-temp", true).unwrap();
+        db.set_script_order("clean-temp", 3).unwrap();
+
+        // The sync replaces the whole row with the catalog version
+        db.upsert_script(&catalog_script("clean-temp", "Nettoyage v2")).unwrap();
+
+        let favorites = db.get_favorite_scripts().unwrap();
+        assert_eq!(favorites.len(), 1);
+        assert_eq!(favorites[0].name, "Nettoyage v2");
+        assert!(favorites[0].is_favorite);
+        assert_eq!(favorites[0].sort_order, 3);
+    }
+
+    #[test]
+    fn synced_scripts_are_told_apart_from_local_ones() {
+        let (_dir, db) = temp_database();
+        db.upsert_script(&catalog_script("clean-temp", "Nettoyage")).unwrap();
+        let mut local = catalog_script("my-script", "Mon script");
+        local.is_local = true;
+        db.upsert_script(&local).unwrap();
+
+        assert!(db.is_synced_script("clean-temp").unwrap());
+        assert!(!db.is_synced_script("my-script").unwrap());
+        assert!(!db.is_synced_script("unknown").unwrap());
+    }
+
+    #[test]
+    fn downsampling_1000_rows_to_10_points_spans_the_range() {
+        let values: Vec<i32> = (0..1000).collect();
+        let points = downsample_average(&values, 10);
+
+        assert_eq!(points.len(), 10);
+        assert_eq!(points[0], 49.5);
+        assert_eq!(points[9], 949.5);
+        assert!(points.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(downsample_average(&[70, 80], 10), vec![70.0, 80.0]);
+    }
+
+    #[test]
+    fn health_trend_reads_stored_scores() {
+        let (_dir, db) = temp_database();
+        for score in 0..1000 {
+            db.save_metrics(&LocalMetrics {
+                id: None,
+                timestamp: String::new(),
+                cpu_usage: 10.0,
+                memory_percent: 40.0,
+                disk_percent: 50.0,
+                health_score: score % 101,
+                health_status: "good".to_string(),
+                synced: false,
+            }).unwrap();
+        }
+
+        let trend = db.get_health_trend(10).unwrap();
+        assert_eq!(trend.values.len(), 10);
+        assert_eq!(trend.sample_count, 1000);
+        assert_eq!((trend.min, trend.max), (Some(0), Some(100)));
+        assert_eq!(trend.current, Some(999 % 101));
+    }
+
+    #[test]
+    fn maintenance_vacuum_shrinks_file_and_passes_integrity_check() {
+        let (_dir, db) = temp_database();
+        {
+            let conn = db.conn.lock().unwrap();
+            let payload = "x".repeat(4096);
+            for i in 0..500 {
+                conn.execute(
+                    "INSERT INTO device_cache (key, value, expires_at) VALUES (?1, ?2, datetime('now', '-1 hour'))",
+                    params![format!("blob-{}", i), payload],
+                ).unwrap();
+            }
+        }
+        let size_full = db.file_size();
+
+        let report = db.run_maintenance().unwrap();
+
+        assert_eq!(report.cache_removed, 500);
+        assert!(report.integrity_ok, "{:?}", report.warnings);
+        assert_eq!(report.size_before, size_full);
+        assert!(report.size_after < report.size_before);
+        assert_eq!(report.reclaimed_bytes, report.size_before - report.size_after);
+        assert!(db.integrity_check().unwrap().is_empty());
+    }
+
+    fn user_version(db: &Database) -> i64 {
+        db.conn.lock().unwrap().query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap()
+    }
+
+    fn has_column(db: &Database, table: &str, column: &str) -> bool {
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).unwrap();
+        let names: Vec<String> = stmt.query_map([], |row| row.get(1)).unwrap().collect::<SqlResult<_>>().unwrap();
+        names.iter().any(|n| n == column)
+    }
+
+    #[test]
+    fn migrations_bring_an_empty_database_to_the_latest_version() {
+        let (_dir, db) = temp_database();
+
+        assert_eq!(user_version(&db), MIGRATIONS.len() as i64);
+        assert!(has_column(&db, "sync_queue", "next_retry_at"));
+        assert!(has_column(&db, "scripts", "is_local"));
+        assert!(has_column(&db, "smart_history", "uncorrectable_sectors"));
+        assert!(has_column(&db, "diagnostic_history", "overall_score"));
+        assert!(has_column(&db, "script_executions", "stdout_tail"));
+    }
+
+    #[test]
+    fn migrations_upgrade_a_v0_database_and_keep_its_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("microdiag.db");
+        {
+            // Schema as shipped before the first migration
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE sync_queue (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT, table_name TEXT NOT NULL, operation TEXT NOT NULL,
+                    data TEXT NOT NULL, created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                    retry_count INTEGER DEFAULT 0, last_error TEXT
+                );
+                CREATE TABLE chat_history (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT, role TEXT NOT NULL, content TEXT NOT NULL,
+                    timestamp TEXT DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE TABLE smart_history (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT, serial TEXT NOT NULL, model TEXT,
+                    timestamp TEXT DEFAULT CURRENT_TIMESTAMP, reallocated_sectors INTEGER, pending_sectors INTEGER,
+                    temperature_c INTEGER, power_on_hours INTEGER, percentage_used INTEGER
+                );
+                INSERT INTO chat_history (role, content) VALUES ('user', 'Mon ecran reste noir au demarrage');
+                INSERT INTO sync_queue (table_name, operation, data) VALUES ('metrics', 'insert', '{}');",
+            ).unwrap();
+            let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+            assert_eq!(version, 0);
+        }
+
+        let db = Database::open_at(path).unwrap();
+
+        assert_eq!(user_version(&db), MIGRATIONS.len() as i64);
+        assert!(has_column(&db, "sync_queue", "next_retry_at"));
+        assert!(has_column(&db, "scripts", "is_local"));
+        assert!(has_column(&db, "smart_history", "uncorrectable_sectors"));
+        assert_eq!(db.count_sync_queue().unwrap().0, 1);
+        // Existing messages are indexed by the FTS rebuild
+        assert_eq!(db.search_chat_history("ecran noir", 10).unwrap().len(), 1);
     }
 }
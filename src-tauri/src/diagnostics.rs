@@ -3,12 +3,19 @@
 // Full System Analysis with User-Friendly Insights
 // ============================================
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use crate::database::SmartHistoryEntry;
+use crate::godmode::DriverInfo;
+use crate::i18n;
+use crate::metrics::{CoreInfo, ScoringConfig};
 use sysinfo::{System, Components, Networks, Process, Pid};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 #[cfg(windows)]
+use crate::powershell;
+#[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 // ============================================
@@ -22,11 +29,21 @@ pub struct PremiumDiagnostic {
     pub network: NetworkAnalysis,
     pub storage: StorageAnalysis,
     pub system_info: ExtendedSystemInfo,
+    pub cpu_cores: Vec<CoreInfo>,
     pub recommendations: Vec<Recommendation>,
     pub overall_score: u8,
     pub overall_status: String,
 }
 
+/// Dashboard snapshot from the cached system state, see run_quick_diagnostic
+#[derive(Serialize, Clone, Debug)]
+pub struct QuickDiagnostic {
+    pub scores: DiagnosticScores,
+    pub overall_status: String,
+    pub top_processes: Vec<ProcessInfo>,
+    pub drives: Vec<DriveAnalysis>,
+}
+
 #[derive(Serialize, Clone, Debug)]
 pub struct TemperatureInfo {
     pub cpu_temp: Option<f32>,
@@ -65,6 +82,8 @@ pub struct ProcessInfo {
     pub status: String,
     pub description: String, // User-friendly description
     pub category: String,    // browser, antivirus, system, game, etc.
+    pub exe_path: String,
+    pub suspicion_reasons: Vec<String>,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -80,11 +99,14 @@ pub struct NetworkAnalysis {
     pub is_connected: bool,
     pub latency_ms: Option<u32>,
     pub latency_status: String,
+    pub latency: LatencyResult,
     pub dns_status: String,
+    pub dns: DnsHealth,
     pub interfaces: Vec<NetworkInterface>,
     pub download_speed: Option<f64>,
     pub upload_speed: Option<f64>,
     pub public_ip: Option<String>,
+    pub isp: Option<String>,
     pub summary: String,
 }
 
@@ -92,12 +114,26 @@ pub struct NetworkAnalysis {
 pub struct NetworkInterface {
     pub name: String,
     pub ip: String,
+    pub ipv4: Vec<String>,
+    pub ipv6: Vec<String>,
     pub mac: String,
     pub received_mb: f64,
     pub transmitted_mb: f64,
     pub is_up: bool,
 }
 
+#[derive(Serialize, Clone, Debug)]
+pub struct NetworkConnection {
+    pub pid: u32,
+    pub process_name: String,
+    pub category: String,
+    pub protocol: String,      // TCP, UDP
+    pub local_addr: String,
+    pub remote_addr: String,   // empty for UDP endpoints
+    pub state: String,         // Established, Listen, TimeWait... (empty for UDP)
+    pub highlighted: bool,     // Established by a non-system process
+}
+
 #[derive(Serialize, Clone, Debug)]
 pub struct StorageAnalysis {
     pub drives: Vec<DriveAnalysis>,
@@ -292,8 +328,12 @@ fn get_process_description(name: &str, cpu: f32, mem_mb: f64) -> String {
 fn get_wmi_cpu_temp() -> Option<f32> {
     use std::process::Command;
 
+    if !powershell::is_available() {
+        return None;
+    }
+
     // Try MSAcpi_ThermalZoneTemperature first (works on most laptops)
-    let output = Command::new("powershell")
+    let output = Command::new(powershell::program())
         .args([
             "-NoProfile", "-Command",
             "Get-WmiObject MSAcpi_ThermalZoneTemperature -Namespace 'root/wmi' -ErrorAction SilentlyContinue | Select-Object -First 1 -ExpandProperty CurrentTemperature"
@@ -314,7 +354,7 @@ fn get_wmi_cpu_temp() -> Option<f32> {
     }
 
     // Fallback: Try Win32_TemperatureProbe
-    let output2 = Command::new("powershell")
+    let output2 = Command::new(powershell::program())
         .args([
             "-NoProfile", "-Command",
             "Get-CimInstance Win32_TemperatureProbe -ErrorAction SilentlyContinue | Where-Object { $_.CurrentReading -gt 0 } | Select-Object -First 1 -ExpandProperty CurrentReading"
@@ -335,12 +375,24 @@ fn get_wmi_cpu_temp() -> Option<f32> {
     None
 }
 
+/// Platform source when sysinfo has no CPU sensor, with the label to display
+#[cfg(windows)]
+fn fallback_cpu_temp() -> Option<(f32, &'static str)> {
+    get_wmi_cpu_temp().map(|t| (t, "CPU (WMI)"))
+}
+
+/// hwmon knows chips sysinfo labels can't tell apart (k10temp "Tctl"...)
 #[cfg(not(windows))]
-fn get_wmi_cpu_temp() -> Option<f32> {
-    None
+fn fallback_cpu_temp() -> Option<(f32, &'static str)> {
+    crate::godmode::get_all_temperatures().cpu_temp.map(|t| (t, "CPU"))
 }
 
 pub fn get_temperatures() -> TemperatureInfo {
+    read_temperatures(true)
+}
+
+/// sysinfo sensors, plus the slower WMI/hwmon probe when `platform_fallback` is set
+fn read_temperatures(platform_fallback: bool) -> TemperatureInfo {
     let components = Components::new_with_refreshed_list();
     let mut cpu_temp: Option<f32> = None;
     let mut gpu_temp: Option<f32> = None;
@@ -381,12 +433,12 @@ pub fn get_temperatures() -> TemperatureInfo {
         }
     }
 
-    // Fallback to WMI on Windows if sysinfo didn't find CPU temp
-    if cpu_temp.is_none() {
-        if let Some(wmi_temp) = get_wmi_cpu_temp() {
+    // Fallback to WMI on Windows (hwmon/SMC elsewhere) if sysinfo didn't find CPU temp
+    if cpu_temp.is_none() && platform_fallback {
+        if let Some((wmi_temp, label)) = fallback_cpu_temp() {
             cpu_temp = Some(wmi_temp);
             component_temps.push(ComponentTemp {
-                name: "CPU (WMI)".to_string(),
+                name: label.to_string(),
                 temp: wmi_temp,
                 max_temp: 100.0,
                 status: if wmi_temp < 50.0 { "excellent" }
@@ -415,11 +467,187 @@ pub fn get_temperatures() -> TemperatureInfo {
     }
 }
 
+// ============================================
+// SUSPICIOUS PROCESS HEURISTICS
+// ============================================
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SuspicionConfig {
+    pub cpu_threshold: f32,
+    pub memory_threshold_mb: f64,
+    pub name_patterns: Vec<String>,
+    pub trusted_publishers: Vec<String>,
+    pub check_signatures: bool, // Get-AuthenticodeSignature is slow, opt-in
+}
+
+impl Default for SuspicionConfig {
+    fn default() -> Self {
+        SuspicionConfig {
+            cpu_threshold: 50.0,
+            memory_threshold_mb: 2048.0,
+            name_patterns: vec![
+                "miner".into(), "cryptominer".into(), "xmrig".into(),
+                "keylog".into(), "trojan".into(), "rat.exe".into(),
+            ],
+            trusted_publishers: vec![
+                "Microsoft".into(), "Google".into(), "Mozilla".into(),
+                "Intel".into(), "NVIDIA".into(), "Advanced Micro Devices".into(),
+            ],
+            check_signatures: false,
+        }
+    }
+}
+
+impl SuspicionConfig {
+    pub const SETTINGS_KEY: &'static str = "suspicion_config";
+
+    /// Parse the JSON stored in settings, falling back to defaults
+    pub fn from_setting(value: Option<String>) -> Self {
+        value
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SignatureInfo {
+    pub is_signed: bool,
+    pub publisher: String,
+}
+
+fn normalize_path(path: &str) -> String {
+    path.to_lowercase().replace('/', "\\")
+}
+
+/// Temp folders: legitimate software is almost never run from there
+fn is_temp_location(path: &str) -> bool {
+    let path = normalize_path(path);
+    path.contains("\\appdata\\local\\temp\\") || path.contains("\\windows\\temp\\")
+}
+
+/// User-writable folders: many legitimate apps install here (Discord, Teams, Spotify...)
+fn is_user_writable_location(path: &str) -> bool {
+    let path = normalize_path(path);
+    path.contains("\\appdata\\roaming\\")
+        || path.contains("\\downloads\\")
+        || path.contains("\\users\\public\\")
+}
+
+fn is_risky_location(path: &str) -> bool {
+    is_temp_location(path) || is_user_writable_location(path)
+}
+
+fn has_suspicion_signal(process: &ProcessInfo, config: &SuspicionConfig) -> bool {
+    let name = process.name.to_lowercase();
+    config.name_patterns.iter().any(|p| !p.is_empty() && name.contains(&p.to_lowercase()))
+        || is_risky_location(&process.exe_path)
+        || (process.category == "other"
+            && (process.cpu_percent > config.cpu_threshold || process.memory_mb > config.memory_threshold_mb))
+}
+
+/// Returns the reasons a process looks suspicious (empty = clean).
+/// A single weak signal (e.g. high CPU) is not enough on its own.
+pub fn suspicion_reasons(
+    process: &ProcessInfo,
+    config: &SuspicionConfig,
+    signature: Option<&SignatureInfo>,
+) -> Vec<String> {
+    let name = process.name.to_lowercase();
+    let mut reasons = Vec::new();
+    let mut score = 0u8;
+
+    if config.name_patterns.iter().any(|p| !p.is_empty() && name.contains(&p.to_lowercase())) {
+        reasons.push("Nom associe a un logiciel malveillant".to_string());
+        score += 3;
+    }
+
+    let trusted = signature
+        .map(|sig| sig.is_signed && config.trusted_publishers.iter()
+            .any(|p| sig.publisher.to_lowercase().contains(&p.to_lowercase())))
+        .unwrap_or(false);
+    if trusted && score == 0 {
+        return Vec::new();
+    }
+
+    let unsigned = signature.map(|sig| !sig.is_signed).unwrap_or(false);
+    if !process.exe_path.is_empty() && is_temp_location(&process.exe_path) {
+        reasons.push("Execute depuis un dossier temporaire".to_string());
+        score += 2;
+    } else if !process.exe_path.is_empty() && is_user_writable_location(&process.exe_path) && unsigned {
+        // AppData/Downloads only counts next to a missing signature
+        reasons.push("Execute depuis AppData/Telechargements".to_string());
+        score += 1;
+    }
+    if unsigned {
+        reasons.push("Executable non signe".to_string());
+        score += 2;
+    }
+    if process.category == "other" {
+        if process.cpu_percent > config.cpu_threshold {
+            reasons.push(format!("CPU eleve ({:.0}%)", process.cpu_percent));
+            score += 1;
+        }
+        if process.memory_mb > config.memory_threshold_mb {
+            reasons.push(format!("Memoire elevee ({:.0} MB)", process.memory_mb));
+            score += 1;
+        }
+    }
+
+    if score >= 2 { reasons } else { Vec::new() }
+}
+
+#[cfg(windows)]
+pub(crate) fn get_signatures(paths: &[String]) -> std::collections::HashMap<String, SignatureInfo> {
+    use std::process::Command;
+
+    let mut result = std::collections::HashMap::new();
+    if paths.is_empty() || !powershell::is_available() {
+        return result;
+    }
+
+    let list = paths.iter()
+        .map(|p| format!("'{}'", p.replace('\'', "''")))
+        .collect::<Vec<_>>()
+        .join(",");
+    let ps_script = format!(r#"
+@({}) | ForEach-Object {{
+    $sig = Get-AuthenticodeSignature -FilePath $_ -ErrorAction SilentlyContinue
+    @{{ Path = $_; Signed = ($sig.Status -eq 'Valid'); Publisher = if ($sig.SignerCertificate) {{ $sig.SignerCertificate.Subject }} else {{ '' }} }}
+}} | ConvertTo-Json -Compress
+"#, list);
+
+    let output = Command::new(powershell::program())
+        .args(["-NoProfile", "-Command", &ps_script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+
+    if let Ok(out) = output {
+        let json = String::from_utf8_lossy(&out.stdout);
+        let value = serde_json::from_str::<serde_json::Value>(json.trim()).unwrap_or(serde_json::Value::Null);
+        for item in json_as_array(value) {
+            if let Some(path) = item.get("Path").and_then(|v| v.as_str()) {
+                result.insert(path.to_string(), SignatureInfo {
+                    is_signed: item.get("Signed").and_then(|v| v.as_bool()).unwrap_or(false),
+                    publisher: item.get("Publisher").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                });
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(not(windows))]
+pub(crate) fn get_signatures(_paths: &[String]) -> std::collections::HashMap<String, SignatureInfo> {
+    std::collections::HashMap::new()
+}
+
 // ============================================
 // PROCESS ANALYSIS
 // ============================================
 
-pub fn analyze_processes(sys: &System) -> ProcessAnalysis {
+pub fn analyze_processes(sys: &System, config: &SuspicionConfig) -> ProcessAnalysis {
     let processes: Vec<(&Pid, &Process)> = sys.processes().iter().collect();
     let total_count = processes.len();
     let total_memory = sys.total_memory() as f64;
@@ -442,6 +670,8 @@ pub fn analyze_processes(sys: &System) -> ProcessAnalysis {
             status: if cpu > 0.1 { "active" } else { "idle" }.to_string(),
             description,
             category: category.to_string(),
+            exe_path: proc.exe().map(|p| p.to_string_lossy().to_string()).unwrap_or_default(),
+            suspicion_reasons: Vec::new(),
         }
     }).collect();
 
@@ -453,18 +683,31 @@ pub fn analyze_processes(sys: &System) -> ProcessAnalysis {
     process_list.sort_by(|a, b| b.memory_mb.partial_cmp(&a.memory_mb).unwrap_or(std::cmp::Ordering::Equal));
     let top_memory: Vec<ProcessInfo> = process_list.iter().take(5).cloned().collect();
 
-    // Check for suspicious processes
+    // Check for suspicious processes (signatures are only fetched for processes showing a signal)
+    let signatures = if config.check_signatures {
+        let mut paths: Vec<String> = process_list.iter()
+            .filter(|p| has_suspicion_signal(p, config))
+            .map(|p| p.exe_path.clone())
+            .filter(|p| !p.is_empty())
+            .collect();
+        paths.sort();
+        paths.dedup();
+        get_signatures(&paths)
+    } else {
+        std::collections::HashMap::new()
+    };
+
     let suspicious: Vec<ProcessInfo> = process_list.iter()
-        .filter(|p| {
-            let name = p.name.to_lowercase();
-            // High resource usage from unknown processes
-            (p.cpu_percent > 50.0 && p.category == "other") ||
-            // Suspicious names
-            name.contains("miner") || name.contains("cryptominer") ||
-            name.contains("keylog") || name.contains("trojan")
+        .filter_map(|p| {
+            let reasons = suspicion_reasons(p, config, signatures.get(&p.exe_path));
+            if reasons.is_empty() {
+                return None;
+            }
+            let mut flagged = p.clone();
+            flagged.suspicion_reasons = reasons;
+            Some(flagged)
         })
         .take(5)
-        .cloned()
         .collect();
 
     let summary = if suspicious.is_empty() && top_cpu.get(0).map(|p| p.cpu_percent < 80.0).unwrap_or(true) {
@@ -485,11 +728,183 @@ pub fn analyze_processes(sys: &System) -> ProcessAnalysis {
     }
 }
 
+// ============================================
+// PROCESS CONTROL
+// ============================================
+
+/// Processes whose termination crashes or locks the session
+const PROTECTED_PROCESS_NAMES: &[&str] = &[
+    "system", "idle", "registry", "smss.exe", "csrss.exe", "wininit.exe", "winlogon.exe",
+    "services.exe", "lsass.exe", "lsaiso.exe", "svchost.exe", "dwm.exe", "memory compression",
+];
+
+/// Looks up a live process and refuses critical system ones (and the agent itself)
+pub fn controllable_process(sys: &mut System, pid: u32) -> Result<&Process, String> {
+    if pid == 0 || pid == 4 || pid == std::process::id() {
+        return Err(format!("Processus {} protege", pid));
+    }
+
+    let pid = Pid::from_u32(pid);
+    if !sys.refresh_process(pid) {
+        return Err(format!("Processus {} introuvable (deja termine ?)", pid));
+    }
+    let process = sys.process(pid).ok_or_else(|| format!("Processus {} introuvable (deja termine ?)", pid))?;
+
+    let name = process.name().to_lowercase();
+    if PROTECTED_PROCESS_NAMES.contains(&name.as_str()) {
+        return Err(format!("{} est un processus systeme protege", process.name()));
+    }
+    Ok(process)
+}
+
+pub fn kill_process(sys: &mut System, pid: u32) -> Result<(), String> {
+    let process = controllable_process(sys, pid)?;
+    let name = process.name().to_string();
+    if process.kill() {
+        Ok(())
+    } else {
+        Err(format!("Impossible de terminer {} (PID {}): acces refuse ?", name, pid))
+    }
+}
+
+#[cfg(windows)]
+mod process_api {
+    use std::ffi::c_void;
+
+    pub const PROCESS_SUSPEND_RESUME: u32 = 0x0800;
+    pub const PROCESS_SET_INFORMATION: u32 = 0x0200;
+    pub const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn OpenProcess(desired_access: u32, inherit_handle: i32, process_id: u32) -> *mut c_void;
+        pub fn CloseHandle(handle: *mut c_void) -> i32;
+        pub fn SetPriorityClass(process: *mut c_void, priority_class: u32) -> i32;
+        pub fn GetPriorityClass(process: *mut c_void) -> u32;
+    }
+
+    #[link(name = "ntdll")]
+    extern "system" {
+        pub fn NtSuspendProcess(process: *mut c_void) -> i32;
+        pub fn NtResumeProcess(process: *mut c_void) -> i32;
+    }
+
+    /// Process handle closed on drop
+    pub struct ProcessHandle(pub *mut c_void);
+
+    impl ProcessHandle {
+        pub fn open(pid: u32, access: u32) -> Result<Self, String> {
+            let handle = unsafe { OpenProcess(access, 0, pid) };
+            if handle.is_null() {
+                return Err(format!("Acces au processus {} refuse: {}", pid, std::io::Error::last_os_error()));
+            }
+            Ok(ProcessHandle(handle))
+        }
+    }
+
+    impl Drop for ProcessHandle {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+}
+
+/// Freezes every thread of the process (NtSuspendProcess), same guard list as kill
+#[cfg(windows)]
+pub fn suspend_process(sys: &mut System, pid: u32) -> Result<(), String> {
+    use process_api::*;
+
+    let name = controllable_process(sys, pid)?.name().to_string();
+    let handle = ProcessHandle::open(pid, PROCESS_SUSPEND_RESUME)?;
+    let status = unsafe { NtSuspendProcess(handle.0) };
+    if status != 0 {
+        return Err(format!("Impossible de suspendre {} (PID {}): NTSTATUS 0x{:08X}", name, pid, status));
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+pub fn resume_process(sys: &mut System, pid: u32) -> Result<(), String> {
+    use process_api::*;
+
+    let name = controllable_process(sys, pid)?.name().to_string();
+    let handle = ProcessHandle::open(pid, PROCESS_SUSPEND_RESUME)?;
+    let status = unsafe { NtResumeProcess(handle.0) };
+    if status != 0 {
+        return Err(format!("Impossible de reprendre {} (PID {}): NTSTATUS 0x{:08X}", name, pid, status));
+    }
+    Ok(())
+}
+
+/// Priority names accepted from the UI and their Windows priority class.
+/// Realtime (0x100) is left out on purpose: it can starve input and freeze the machine.
+#[cfg(windows)]
+const PRIORITY_CLASSES: &[(&str, u32)] = &[
+    ("idle", 0x0040),
+    ("below_normal", 0x4000),
+    ("normal", 0x0020),
+    ("above_normal", 0x8000),
+    ("high", 0x0080),
+];
+
+/// Applies the priority class and returns the one Windows reports afterwards
+#[cfg(windows)]
+pub fn set_process_priority(sys: &mut System, pid: u32, priority: &str) -> Result<String, String> {
+    use process_api::*;
+
+    let priority = priority.trim().to_lowercase();
+    if priority == "realtime" {
+        return Err("Priorite temps reel refusee: elle peut bloquer le systeme".to_string());
+    }
+    let class = PRIORITY_CLASSES.iter()
+        .find(|(name, _)| *name == priority)
+        .map(|(_, class)| *class)
+        .ok_or_else(|| format!("Priorite inconnue: {} (idle, below_normal, normal, above_normal ou high)", priority))?;
+
+    let name = controllable_process(sys, pid)?.name().to_string();
+    let handle = ProcessHandle::open(pid, PROCESS_SET_INFORMATION | PROCESS_QUERY_LIMITED_INFORMATION)?;
+    if unsafe { SetPriorityClass(handle.0, class) } == 0 {
+        return Err(format!("Impossible de changer la priorite de {} (PID {}): {}", name, pid, std::io::Error::last_os_error()));
+    }
+
+    let applied = unsafe { GetPriorityClass(handle.0) };
+    Ok(PRIORITY_CLASSES.iter()
+        .find(|(_, c)| *c == applied)
+        .map(|(n, _)| n.to_string())
+        .unwrap_or_else(|| if applied == 0x0100 { "realtime".to_string() } else { priority }))
+}
+
+#[cfg(not(windows))]
+pub fn set_process_priority(sys: &mut System, pid: u32, _priority: &str) -> Result<String, String> {
+    controllable_process(sys, pid)?;
+    Err("Priorite des processus disponible uniquement sur Windows".to_string())
+}
+
+/// SIGSTOP / SIGCONT elsewhere
+#[cfg(not(windows))]
+pub fn suspend_process(sys: &mut System, pid: u32) -> Result<(), String> {
+    let process = controllable_process(sys, pid)?;
+    match process.kill_with(sysinfo::Signal::Stop) {
+        Some(true) => Ok(()),
+        _ => Err(format!("Impossible de suspendre {} (PID {})", process.name(), pid)),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn resume_process(sys: &mut System, pid: u32) -> Result<(), String> {
+    let process = controllable_process(sys, pid)?;
+    match process.kill_with(sysinfo::Signal::Continue) {
+        Some(true) => Ok(()),
+        _ => Err(format!("Impossible de reprendre {} (PID {})", process.name(), pid)),
+    }
+}
+
 // ============================================
 // NETWORK ANALYSIS
 // ============================================
 
-pub fn analyze_network() -> NetworkAnalysis {
+/// Interfaces with their addresses, and whether any of them is connected
+fn scan_interfaces(addresses: &[AdapterAddresses]) -> (Vec<NetworkInterface>, bool) {
     let networks = Networks::new_with_refreshed_list();
     let mut interfaces: Vec<NetworkInterface> = Vec::new();
     let mut is_connected = false;
@@ -497,24 +912,43 @@ pub fn analyze_network() -> NetworkAnalysis {
     for (name, data) in networks.iter() {
         let received = data.total_received() as f64 / 1_048_576.0;
         let transmitted = data.total_transmitted() as f64 / 1_048_576.0;
-
-        // Check if interface has traffic (likely connected)
-        if received > 0.0 || transmitted > 0.0 {
+        let mac = data.mac_address().to_string();
+
+        let adapter = addresses.iter().find(|a| a.matches(name, &mac));
+        let (ipv4, ipv6) = adapter
+            .map(|a| (a.ipv4.clone(), a.ipv6.clone()))
+            .unwrap_or_default();
+        // Operational status when known, traffic counters otherwise
+        let is_up = adapter
+            .and_then(|a| a.is_up)
+            .unwrap_or(received > 0.0 || transmitted > 0.0);
+
+        let has_address = adapter.is_none() || !ipv4.is_empty() || !ipv6.is_empty();
+        if is_up && has_address {
             is_connected = true;
         }
 
         interfaces.push(NetworkInterface {
             name: name.to_string(),
-            ip: String::new(), // Would need additional API to get IP
-            mac: data.mac_address().to_string(),
+            ip: ipv4.first().or(ipv6.first()).cloned().unwrap_or_default(),
+            ipv4,
+            ipv6,
+            mac,
             received_mb: received,
             transmitted_mb: transmitted,
-            is_up: received > 0.0 || transmitted > 0.0,
+            is_up,
         });
     }
 
-    // Test latency (ping)
-    let latency = test_latency();
+    (interfaces, is_connected)
+}
+
+pub fn analyze_network(latency_config: &LatencyConfig) -> NetworkAnalysis {
+    let addresses = get_interface_addresses();
+    let (interfaces, is_connected) = scan_interfaces(&addresses);
+
+    let latency_result = measure_latency(&latency_config.host, latency_config.count);
+    let latency = latency_result.avg_ms;
     let latency_status = match latency {
         Some(ms) if ms < 30 => "Excellent".to_string(),
         Some(ms) if ms < 60 => "Bon".to_string(),
@@ -522,6 +956,19 @@ pub fn analyze_network() -> NetworkAnalysis {
         Some(ms) => format!("Eleve ({}ms)", ms),
         None => "Non disponible".to_string(),
     };
+    let latency_status = if latency.is_some() && latency_result.loss_percent > 0.0 {
+        format!("{} - {:.0}% de perte", latency_status, latency_result.loss_percent)
+    } else {
+        latency_status
+    };
+
+    let dns = if is_connected { check_dns(&addresses) } else { DnsHealth::untested() };
+    let dns_status = match dns.status.as_str() {
+        "ok" => "OK".to_string(),
+        "slow" => format!("Lent ({}ms)", dns.avg_ms.unwrap_or(0)),
+        "failing" => "Echec de resolution".to_string(),
+        _ => "Non teste".to_string(),
+    };
 
     let summary = if !is_connected {
         "Aucune connexion reseau detectee".to_string()
@@ -537,147 +984,960 @@ pub fn analyze_network() -> NetworkAnalysis {
         is_connected,
         latency_ms: latency,
         latency_status,
-        dns_status: "OK".to_string(),
+        latency: latency_result,
+        dns_status,
+        dns,
         interfaces,
         download_speed: None,
         upload_speed: None,
         public_ip: None,
+        isp: None,
         summary,
     }
 }
 
-#[cfg(windows)]
-fn test_latency() -> Option<u32> {
-    use std::process::Command;
-    use std::time::Instant;
+// ============================================
+// BANDWIDTH MONITOR
+// ============================================
 
-    let start = Instant::now();
-    let output = Command::new("ping")
-        .args(["-n", "1", "-w", "1000", "8.8.8.8"])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output()
-        .ok()?;
+/// Sampling window bounds: shorter is noise, longer blocks the command
+const BANDWIDTH_MIN_DURATION_MS: u64 = 250;
+const BANDWIDTH_MAX_DURATION_MS: u64 = 10_000;
 
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        // Parse "time=XXms" from ping output
-        if let Some(time_pos) = stdout.find("time=") {
-            let time_str = &stdout[time_pos + 5..];
-            if let Some(ms_pos) = time_str.find("ms") {
-                if let Ok(ms) = time_str[..ms_pos].trim().parse::<u32>() {
-                    return Some(ms);
-                }
-            }
-        }
-        // Fallback to measured time
-        Some(start.elapsed().as_millis() as u32)
-    } else {
-        None
-    }
+/// Current throughput of one interface, unlike the lifetime totals in NetworkInterface
+#[derive(Serialize, Clone, Debug)]
+pub struct BandwidthSample {
+    pub interface: String,
+    pub rx_mbps: f64,
+    pub tx_mbps: f64,
 }
 
-#[cfg(not(windows))]
-fn test_latency() -> Option<u32> {
-    use std::process::Command;
-    use std::time::Instant;
+fn rate_mbps(bytes: u64, elapsed_secs: f64) -> f64 {
+    let mbps = bytes as f64 * 8.0 / elapsed_secs / 1_000_000.0;
+    (mbps * 100.0).round() / 100.0
+}
 
-    let start = Instant::now();
-    let output = Command::new("ping")
-        .args(["-c", "1", "-W", "1", "8.8.8.8"])
-        .output()
-        .ok()?;
+/// Byte counters snapshotted twice `duration_ms` apart, busiest interface first
+pub fn measure_bandwidth(duration_ms: u64) -> Vec<BandwidthSample> {
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
 
-    if output.status.success() {
-        Some(start.elapsed().as_millis() as u32)
-    } else {
-        None
-    }
+    let duration_ms = duration_ms.clamp(BANDWIDTH_MIN_DURATION_MS, BANDWIDTH_MAX_DURATION_MS);
+    let mut networks = Networks::new_with_refreshed_list();
+    let before: HashMap<String, (u64, u64)> = networks
+        .iter()
+        .map(|(name, data)| (name.to_string(), (data.total_received(), data.total_transmitted())))
+        .collect();
+    let started = Instant::now();
+
+    std::thread::sleep(Duration::from_millis(duration_ms));
+    networks.refresh();
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let mut samples: Vec<BandwidthSample> = networks
+        .iter()
+        .filter_map(|(name, data)| {
+            // Interfaces that appeared mid-sample have no baseline
+            let (rx, tx) = before.get(name.as_str())?;
+            Some(BandwidthSample {
+                interface: name.to_string(),
+                rx_mbps: rate_mbps(data.total_received().saturating_sub(*rx), elapsed),
+                tx_mbps: rate_mbps(data.total_transmitted().saturating_sub(*tx), elapsed),
+            })
+        })
+        .collect();
+
+    samples.sort_by(|a, b| (b.rx_mbps + b.tx_mbps).total_cmp(&(a.rx_mbps + a.tx_mbps)));
+    samples
 }
 
 // ============================================
-// STORAGE ANALYSIS
+// CONNECTIONS PER PROCESS
 // ============================================
 
-pub fn analyze_storage() -> StorageAnalysis {
-    use sysinfo::Disks;
+/// "[::1]:443" style for IPv6, "1.2.3.4:443" otherwise
+#[cfg(windows)]
+fn socket_addr(address: &str, port: u64) -> String {
+    if address.contains(':') {
+        format!("[{}]:{}", address, port)
+    } else {
+        format!("{}:{}", address, port)
+    }
+}
 
-    let disks = Disks::new_with_refreshed_list();
-    let mut drives: Vec<DriveAnalysis> = Vec::new();
-    let mut total_space: f64 = 0.0;
-    let mut used_space: f64 = 0.0;
+#[cfg(windows)]
+fn parse_connections(json: &str) -> Vec<NetworkConnection> {
+    let value: serde_json::Value = match serde_json::from_str(json.trim()) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    // ConvertTo-Json emits a bare object when there is a single row
+    let rows = match value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Null => Vec::new(),
+        single => vec![single],
+    };
 
-    for disk in disks.iter() {
-        let total = disk.total_space() as f64 / 1_073_741_824.0;
-        let available = disk.available_space() as f64 / 1_073_741_824.0;
-        let used = total - available;
-        let percent = if total > 0.0 { (used / total * 100.0) as f32 } else { 0.0 };
+    rows.iter()
+        .map(|row| {
+            let protocol = row["Proto"].as_str().unwrap_or("TCP").to_string();
+            let remote_addr = match row["RemoteAddress"].as_str() {
+                Some(address) if protocol == "TCP" => socket_addr(address, row["RemotePort"].as_u64().unwrap_or(0)),
+                _ => String::new(),
+            };
+            NetworkConnection {
+                pid: row["Pid"].as_u64().unwrap_or(0) as u32,
+                process_name: String::new(),
+                category: String::new(),
+                local_addr: socket_addr(
+                    row["LocalAddress"].as_str().unwrap_or_default(),
+                    row["LocalPort"].as_u64().unwrap_or(0),
+                ),
+                remote_addr,
+                state: row["State"].as_str().unwrap_or_default().to_string(),
+                protocol,
+                highlighted: false,
+            }
+        })
+        .collect()
+}
 
-        total_space += total;
-        used_space += used;
+/// TCP connections and UDP endpoints with their owning PID, names are filled by
+/// `resolve_connection_processes` so the process list lock is not held during the query
+#[cfg(windows)]
+pub fn list_network_connections() -> Vec<NetworkConnection> {
+    use std::process::Command;
 
-        let health = if percent > 95.0 {
-            "critical"
-        } else if percent > 85.0 {
-            "warning"
-        } else {
-            "good"
-        }.to_string();
+    // State is cast to its enum name so the output does not depend on the UI language
+    let script = r#"
+[Console]::OutputEncoding = [System.Text.Encoding]::UTF8
+$tcp = Get-NetTCPConnection -ErrorAction SilentlyContinue | ForEach-Object {
+    @{ Proto='TCP'; LocalAddress=$_.LocalAddress; LocalPort=[int]$_.LocalPort; RemoteAddress=$_.RemoteAddress; RemotePort=[int]$_.RemotePort; State=[string]$_.State; Pid=[int]$_.OwningProcess }
+}
+$udp = Get-NetUDPEndpoint -ErrorAction SilentlyContinue | ForEach-Object {
+    @{ Proto='UDP'; LocalAddress=$_.LocalAddress; LocalPort=[int]$_.LocalPort; State=''; Pid=[int]$_.OwningProcess }
+}
+@($tcp) + @($udp) | ConvertTo-Json -Compress
+"#;
 
-        let drive_type = match disk.kind() {
-            sysinfo::DiskKind::SSD => "SSD",
-            sysinfo::DiskKind::HDD => "HDD",
-            _ => "Unknown",
-        }.to_string();
+    let output = match powershell::require().and_then(|ps| {
+        Command::new(ps)
+            .args(["-NoProfile", "-Command", script])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| e.to_string())
+    }) {
+        Ok(output) => output,
+        Err(e) => {
+            println!("[Network] Connection listing failed: {}", e);
+            return Vec::new();
+        }
+    };
 
-        drives.push(DriveAnalysis {
-            letter: disk.mount_point().to_string_lossy().to_string(),
-            name: disk.name().to_string_lossy().to_string(),
-            total_gb: total,
-            used_gb: used,
-            free_gb: available,
-            percent,
-            health,
-            smart_status: "OK".to_string(),
-            drive_type,
-            read_speed: None,
-            write_speed: None,
-        });
+    parse_connections(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Names each connection after its process.
+/// Established connections of non-system processes come first and are highlighted.
+#[cfg(windows)]
+pub fn resolve_connection_processes(sys: &mut System, mut connections: Vec<NetworkConnection>) -> Vec<NetworkConnection> {
+    // Fresh process list so short-lived connections still resolve to a name
+    sys.refresh_processes();
+
+    for connection in connections.iter_mut() {
+        let name = match connection.pid {
+            0 => "System Idle".to_string(),
+            pid => sys.process(Pid::from_u32(pid))
+                .map(|p| p.name().to_string())
+                .unwrap_or_else(|| format!("PID {}", pid)),
+        };
+        let (category, _) = categorize_process(&name);
+        // PID 4 is the kernel ("System"), which carries SMB and HTTP.sys traffic
+        let is_system = category == "system" || connection.pid <= 4;
+        connection.highlighted = !is_system && connection.state == "Established";
+        connection.category = category.to_string();
+        connection.process_name = name;
     }
 
-    let free_space = total_space - used_space;
-    let summary = if free_space < 10.0 {
-        "Espace disque critique! Liberez de l'espace".to_string()
-    } else if free_space < 50.0 {
-        format!("{:.0} GB libres - Pensez a faire du menage", free_space)
-    } else {
-        format!("{:.0} GB libres - Espace suffisant", free_space)
-    };
+    connections.sort_by(|a, b| {
+        b.highlighted.cmp(&a.highlighted)
+            .then_with(|| a.process_name.to_lowercase().cmp(&b.process_name.to_lowercase()))
+    });
+    connections
+}
 
-    StorageAnalysis {
-        drives,
-        total_space_gb: total_space,
-        used_space_gb: used_space,
-        free_space_gb: free_space,
-        largest_files: Vec::new(), // Would require file system scan
-        temp_files_mb: 0.0,
-        recycle_bin_mb: 0.0,
-        summary,
-    }
+#[cfg(not(windows))]
+pub fn list_network_connections() -> Vec<NetworkConnection> {
+    Vec::new()
+}
+
+#[cfg(not(windows))]
+pub fn resolve_connection_processes(_sys: &mut System, connections: Vec<NetworkConnection>) -> Vec<NetworkConnection> {
+    connections
 }
 
 // ============================================
-// EXTENDED SYSTEM INFO
+// PUBLIC IP / ISP
 // ============================================
 
-pub fn get_extended_system_info(sys: &System) -> ExtendedSystemInfo {
-    let cpus = sys.cpus();
-    let cpu_name = cpus.first().map(|c| c.brand().to_string()).unwrap_or_default();
-    let cpu_cores = sys.physical_core_count().unwrap_or(0);
-    let cpu_threads = cpus.len();
-    let cpu_freq = cpus.first().map(|c| c.frequency()).unwrap_or(0);
-
+#[derive(Serialize, Clone, Debug)]
+pub struct PublicIpInfo {
+    pub ip: String,
+    pub isp: Option<String>,
+    pub asn: Option<String>,
+    pub country: Option<String>,
+}
+
+/// Public IP and ISP with a short timeout; None when offline so callers never block
+pub async fn fetch_public_ip_info() -> Option<PublicIpInfo> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(3))
+        .build()
+        .ok()?;
+
+    let ip = client.get("https://api.ipify.org").send().await.ok()?
+        .text().await.ok()?
+        .trim().to_string();
+    if ip.is_empty() {
+        return None;
+    }
+
+    // "org" looks like "AS3215 Orange S.A.", split it into ASN and ISP name
+    let geo: Option<serde_json::Value> = match client.get(format!("https://ipinfo.io/{}/json", ip)).send().await {
+        Ok(resp) if resp.status().is_success() => resp.json().await.ok(),
+        _ => None,
+    };
+    let org = geo.as_ref().and_then(|g| g["org"].as_str()).unwrap_or_default();
+    let (asn, isp) = match org.split_once(' ') {
+        Some((asn, name)) if asn.starts_with("AS") => (Some(asn.to_string()), Some(name.to_string())),
+        _ if !org.is_empty() => (None, Some(org.to_string())),
+        _ => (None, None),
+    };
+
+    Some(PublicIpInfo {
+        ip,
+        isp,
+        asn,
+        country: geo.as_ref().and_then(|g| g["country"].as_str()).map(|s| s.to_string()),
+    })
+}
+
+pub fn apply_public_ip_info(network: &mut NetworkAnalysis, info: Option<PublicIpInfo>) {
+    if let Some(info) = info {
+        network.public_ip = Some(info.ip);
+        network.isp = info.isp;
+    }
+}
+
+/// Addresses and link state of one adapter, matched to sysinfo by MAC (or name)
+struct AdapterAddresses {
+    name: String,
+    mac: String,
+    ipv4: Vec<String>,
+    ipv6: Vec<String>,
+    is_up: Option<bool>,
+    dns_servers: Vec<String>,
+}
+
+impl AdapterAddresses {
+    fn matches(&self, name: &str, mac: &str) -> bool {
+        let mac = mac.to_uppercase().replace('-', ":");
+        let zero_mac = mac.is_empty() || mac == "00:00:00:00:00:00";
+        if !zero_mac && !self.mac.is_empty() {
+            return self.mac == mac;
+        }
+        self.name.eq_ignore_ascii_case(name)
+    }
+}
+
+#[cfg(windows)]
+fn get_interface_addresses() -> Vec<AdapterAddresses> {
+    use std::collections::HashMap;
+    use wmi::{COMLibrary, Variant, WMIConnection};
+
+    let wmi_con = match COMLibrary::new().ok().and_then(|com| WMIConnection::new(com).ok()) {
+        Some(con) => con,
+        None => return Vec::new(),
+    };
+
+    let text = |row: &HashMap<String, Variant>, key: &str| match row.get(key) {
+        Some(Variant::String(s)) => s.clone(),
+        _ => String::new(),
+    };
+
+    // NetConnectionStatus 2 = Connected
+    let adapters: Vec<HashMap<String, Variant>> = wmi_con
+        .raw_query("SELECT Index, NetConnectionID, MACAddress, NetConnectionStatus FROM Win32_NetworkAdapter WHERE MACAddress IS NOT NULL")
+        .unwrap_or_default();
+    let configs: Vec<HashMap<String, Variant>> = wmi_con
+        .raw_query("SELECT Index, IPAddress, DNSServerSearchOrder FROM Win32_NetworkAdapterConfiguration WHERE IPEnabled = TRUE")
+        .unwrap_or_default();
+
+    let index_of = |row: &HashMap<String, Variant>| match row.get("Index") {
+        Some(Variant::UI4(i)) => Some(*i as u64),
+        Some(Variant::I4(i)) => Some(*i as u64),
+        _ => None,
+    };
+
+    let strings = |row: Option<&HashMap<String, Variant>>, key: &str| match row.and_then(|r| r.get(key)) {
+        Some(Variant::Array(values)) => values.iter().filter_map(|v| match v {
+            Variant::String(s) => Some(s.clone()),
+            _ => None,
+        }).collect(),
+        _ => Vec::new(),
+    };
+
+    adapters.iter().map(|adapter| {
+        let config = configs.iter().find(|c| index_of(c).is_some() && index_of(c) == index_of(adapter));
+        let ips: Vec<String> = strings(config, "IPAddress");
+        let is_up = match adapter.get("NetConnectionStatus") {
+            Some(Variant::UI2(status)) => Some(*status == 2),
+            Some(Variant::UI4(status)) => Some(*status == 2),
+            _ => None,
+        };
+
+        AdapterAddresses {
+            name: text(adapter, "NetConnectionID"),
+            mac: text(adapter, "MACAddress").to_uppercase().replace('-', ":"),
+            ipv4: ips.iter().filter(|ip| !ip.contains(':')).cloned().collect(),
+            ipv6: ips.iter().filter(|ip| ip.contains(':')).cloned().collect(),
+            is_up,
+            dns_servers: strings(config, "DNSServerSearchOrder"),
+        }
+    }).collect()
+}
+
+#[cfg(not(windows))]
+fn get_interface_addresses() -> Vec<AdapterAddresses> {
+    let mut adapters: Vec<AdapterAddresses> = Vec::new();
+    // One system-wide resolver list here, shared by every adapter
+    let dns_servers: Vec<String> = std::fs::read_to_string("/etc/resolv.conf")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("nameserver"))
+        .map(|server| server.trim().to_string())
+        .filter(|server| !server.is_empty())
+        .collect();
+
+    for iface in if_addrs::get_if_addrs().unwrap_or_default() {
+        let index = match adapters.iter().position(|a| a.name == iface.name) {
+            Some(i) => i,
+            None => {
+                // Link state from sysfs where available (Linux), unknown elsewhere
+                let operstate = std::fs::read_to_string(format!("/sys/class/net/{}/operstate", iface.name)).ok();
+                adapters.push(AdapterAddresses {
+                    name: iface.name.clone(),
+                    mac: String::new(),
+                    ipv4: Vec::new(),
+                    ipv6: Vec::new(),
+                    is_up: operstate.map(|s| s.trim() == "up"),
+                    dns_servers: dns_servers.clone(),
+                });
+                adapters.len() - 1
+            }
+        };
+        match iface.ip() {
+            std::net::IpAddr::V4(ip) => adapters[index].ipv4.push(ip.to_string()),
+            std::net::IpAddr::V6(ip) => adapters[index].ipv6.push(ip.to_string()),
+        }
+    }
+
+    adapters
+}
+
+// ============================================
+// DNS HEALTH
+// ============================================
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DnsHealth {
+    pub status: String, // ok, slow, failing, untested
+    pub resolver_ip: Option<String>,
+    pub avg_ms: Option<u32>,
+}
+
+impl DnsHealth {
+    fn untested() -> Self {
+        DnsHealth { status: "untested".to_string(), resolver_ip: None, avg_ms: None }
+    }
+}
+
+const DNS_TEST_HOSTS: &[&str] = &["www.google.com", "www.microsoft.com", "www.cloudflare.com"];
+const DNS_SLOW_MS: u32 = 200;
+const DNS_QUERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Minimal recursive A query for `host`
+fn dns_query_packet(id: u16, host: &str) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(18 + host.len());
+    packet.extend_from_slice(&id.to_be_bytes());
+    // Flags: recursion desired; 1 question, no answer/authority/additional records
+    packet.extend_from_slice(&[0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    for label in host.split('.') {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    // Root label, QTYPE A, QCLASS IN
+    packet.extend_from_slice(&[0x00, 0x00, 0x01, 0x00, 0x01]);
+    packet
+}
+
+/// Round trip of one query sent straight to the resolver, so the OS cache can't hide a slow server
+fn time_dns_query(resolver: std::net::IpAddr, host: &str, id: u16) -> Option<u32> {
+    use std::net::UdpSocket;
+    use std::time::Instant;
+
+    let bind = if resolver.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+    let socket = UdpSocket::bind(bind).ok()?;
+    socket.set_read_timeout(Some(DNS_QUERY_TIMEOUT)).ok()?;
+
+    let start = Instant::now();
+    socket.send_to(&dns_query_packet(id, host), (resolver, 53)).ok()?;
+
+    let mut buffer = [0u8; 512];
+    while start.elapsed() < DNS_QUERY_TIMEOUT {
+        let (len, _) = socket.recv_from(&mut buffer).ok()?;
+        if len < 12 || buffer[0..2] != id.to_be_bytes() {
+            continue;
+        }
+        // RCODE in the low bits of the flags, then the answer count
+        let rcode = buffer[3] & 0x0F;
+        let answers = u16::from_be_bytes([buffer[6], buffer[7]]);
+        return if rcode == 0 && answers > 0 { Some(start.elapsed().as_millis() as u32) } else { None };
+    }
+    None
+}
+
+/// Resolution through the system resolver, when the configured server isn't known
+fn time_system_lookup(host: &str) -> Option<u32> {
+    use std::net::ToSocketAddrs;
+    use std::time::Instant;
+
+    let start = Instant::now();
+    let mut addrs = (host, 443).to_socket_addrs().ok()?;
+    addrs.next().map(|_| start.elapsed().as_millis() as u32)
+}
+
+/// Resolves a few well-known hosts and rates the configured resolver
+fn check_dns(adapters: &[AdapterAddresses]) -> DnsHealth {
+    let resolver: Option<std::net::IpAddr> = adapters.iter()
+        .filter(|a| a.is_up != Some(false))
+        .flat_map(|a| a.dns_servers.iter())
+        .find_map(|server| server.parse().ok());
+
+    let base_id = (std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0) & 0xFFFF) as u16;
+
+    let timings: Vec<Option<u32>> = DNS_TEST_HOSTS.iter().enumerate()
+        .map(|(i, host)| match resolver {
+            Some(ip) => time_dns_query(ip, host, base_id.wrapping_add(i as u16)),
+            None => time_system_lookup(host),
+        })
+        .collect();
+
+    let succeeded: Vec<u32> = timings.iter().flatten().copied().collect();
+    let avg_ms = if succeeded.is_empty() {
+        None
+    } else {
+        Some(succeeded.iter().sum::<u32>() / succeeded.len() as u32)
+    };
+
+    // Most lookups failing is a resolver problem; a single miss can be the host itself
+    let status = match avg_ms {
+        _ if succeeded.len() * 2 < timings.len() => "failing",
+        Some(ms) if ms > DNS_SLOW_MS => "slow",
+        _ => "ok",
+    };
+
+    DnsHealth {
+        status: status.to_string(),
+        resolver_ip: resolver.map(|ip| ip.to_string()),
+        avg_ms,
+    }
+}
+
+// ============================================
+// LATENCY
+// ============================================
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LatencyConfig {
+    pub host: String,
+    pub count: u32,
+}
+
+impl Default for LatencyConfig {
+    fn default() -> Self {
+        LatencyConfig {
+            host: "1.1.1.1".into(),
+            count: 4,
+        }
+    }
+}
+
+impl LatencyConfig {
+    pub const SETTINGS_KEY: &'static str = "latency_config";
+
+    /// Parse the JSON stored in settings, falling back to defaults
+    pub fn from_setting(value: Option<String>) -> Self {
+        value
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct LatencyResult {
+    pub host: String,
+    pub method: String,     // icmp, tcp (ICMP filtered), none
+    pub sent: u32,
+    pub received: u32,
+    pub min_ms: Option<u32>,
+    pub avg_ms: Option<u32>,
+    pub max_ms: Option<u32>,
+    pub jitter_ms: Option<u32>,
+    pub loss_percent: f32,
+}
+
+const LATENCY_MAX_COUNT: u32 = 20;
+const LATENCY_TIMEOUT_MS: u64 = 1000;
+/// Handshake timing on HTTPS when ICMP is filtered
+const LATENCY_TCP_PORT: u16 = 443;
+
+impl LatencyResult {
+    fn from_samples(host: &str, method: &str, sent: u32, samples: &[u32]) -> Self {
+        let received = samples.len() as u32;
+        let avg_ms = if samples.is_empty() {
+            None
+        } else {
+            Some(samples.iter().sum::<u32>() / received)
+        };
+
+        LatencyResult {
+            host: host.to_string(),
+            method: method.to_string(),
+            sent,
+            received,
+            min_ms: samples.iter().min().copied(),
+            avg_ms,
+            max_ms: samples.iter().max().copied(),
+            jitter_ms: avg_ms.map(|_| jitter_from_samples(samples)),
+            loss_percent: sent.saturating_sub(received) as f32 * 100.0 / sent.max(1) as f32,
+        }
+    }
+}
+
+/// Reply times in ping output: "time=12ms", "temps<1ms", "time=11.8 ms"
+fn parse_ping_times(output: &str) -> Vec<u32> {
+    let re = match regex::Regex::new(r"(?i)\b(?:time|temps|zeit|tiempo|tempo)[=<]\s*(\d+(?:[.,]\d+)?)\s*ms") {
+        Ok(re) => re,
+        Err(_) => return Vec::new(),
+    };
+    re.captures_iter(output)
+        .filter_map(|caps| caps[1].replace(',', ".").parse::<f64>().ok())
+        .map(|ms| ms.round() as u32)
+        .collect()
+}
+
+fn ping_samples(host: &str, count: u32) -> Vec<u32> {
+    use std::process::Command;
+
+    let mut command = Command::new("ping");
+    #[cfg(windows)]
+    command
+        .args(["-n", &count.to_string(), "-w", &LATENCY_TIMEOUT_MS.to_string(), host])
+        .creation_flags(CREATE_NO_WINDOW);
+    #[cfg(not(windows))]
+    command.args(["-c", &count.to_string(), "-W", &(LATENCY_TIMEOUT_MS / 1000).to_string(), host]);
+
+    // Partial loss still exits 0 on Windows but not everywhere, read the replies either way
+    match command.output() {
+        Ok(output) => parse_ping_times(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn tcp_connect_samples(host: &str, count: u32) -> Vec<u32> {
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    // Resolve once so DNS time is not counted in the samples
+    let addr = match (host, LATENCY_TCP_PORT).to_socket_addrs().ok().and_then(|mut addrs| addrs.next()) {
+        Some(addr) => addr,
+        None => return Vec::new(),
+    };
+    let timeout = std::time::Duration::from_millis(LATENCY_TIMEOUT_MS);
+
+    (0..count)
+        .filter_map(|_| {
+            let start = std::time::Instant::now();
+            TcpStream::connect_timeout(&addr, timeout)
+                .ok()
+                .map(|_| start.elapsed().as_millis() as u32)
+        })
+        .collect()
+}
+
+/// `count` ICMP echoes to `host`, or TCP handshakes on port 443 when every echo is lost
+pub fn measure_latency(host: &str, count: u32) -> LatencyResult {
+    let host = host.trim();
+    let count = count.clamp(1, LATENCY_MAX_COUNT);
+
+    // The host ends up on the ping command line: addresses and DNS names only
+    let valid = !host.is_empty()
+        && !host.starts_with('-')
+        && host.chars().all(|c| c.is_ascii_alphanumeric() || ".-:".contains(c));
+    if !valid {
+        return LatencyResult::from_samples(host, "none", count, &[]);
+    }
+
+    let samples = ping_samples(host, count);
+    if !samples.is_empty() {
+        return LatencyResult::from_samples(host, "icmp", count, &samples);
+    }
+
+    let samples = tcp_connect_samples(host, count);
+    let method = if samples.is_empty() { "none" } else { "tcp" };
+    LatencyResult::from_samples(host, method, count, &samples)
+}
+
+// ============================================
+// STORAGE ANALYSIS
+// ============================================
+
+/// Fullness of each mounted volume, with approximate throughput when `probe_speed` is set
+fn analyze_drives(disks: &sysinfo::Disks, probe_speed: bool) -> Vec<DriveAnalysis> {
+    let _busy = probe_speed.then(HeavyTaskGuard::start);
+
+    disks.iter().map(|disk| {
+        let total = disk.total_space() as f64 / 1_073_741_824.0;
+        let available = disk.available_space() as f64 / 1_073_741_824.0;
+        let used = total - available;
+        let percent = if total > 0.0 { (used / total * 100.0) as f32 } else { 0.0 };
+
+        let health = if percent > 95.0 {
+            "critical"
+        } else if percent > 85.0 {
+            "warning"
+        } else {
+            "good"
+        }.to_string();
+
+        let drive_type = match disk.kind() {
+            sysinfo::DiskKind::SSD => "SSD",
+            sysinfo::DiskKind::HDD => "HDD",
+            _ => "Unknown",
+        }.to_string();
+
+        let (read_speed, write_speed) = if probe_speed && speed_probe_allowed(disk) {
+            probe_drive_speed(&speed_probe_dir(disks, disk.mount_point()))
+        } else {
+            (None, None)
+        };
+
+        DriveAnalysis {
+            letter: disk.mount_point().to_string_lossy().to_string(),
+            name: disk.name().to_string_lossy().to_string(),
+            total_gb: total,
+            used_gb: used,
+            free_gb: available,
+            percent,
+            health,
+            smart_status: "OK".to_string(),
+            drive_type,
+            read_speed,
+            write_speed,
+        }
+    }).collect()
+}
+
+/// `probe_speed` writes a few MB on each local drive, keep it for on-demand views
+pub fn analyze_storage(probe_speed: bool) -> StorageAnalysis {
+    use sysinfo::Disks;
+
+    let disks = Disks::new_with_refreshed_list();
+    let drives = analyze_drives(&disks, probe_speed);
+    let total_space: f64 = drives.iter().map(|d| d.total_gb).sum();
+    let used_space: f64 = drives.iter().map(|d| d.used_gb).sum();
+
+    let free_space = total_space - used_space;
+    let summary = if free_space < 10.0 {
+        "Espace disque critique! Liberez de l'espace".to_string()
+    } else if free_space < 50.0 {
+        format!("{:.0} GB libres - Pensez a faire du menage", free_space)
+    } else {
+        format!("{:.0} GB libres - Espace suffisant", free_space)
+    };
+
+    let temp_files_mb = temp_dirs().iter().map(|d| dir_size_bytes(d)).sum::<u64>() as f64 / 1_048_576.0;
+    let recycle_bin_mb = recycle_bin_dirs(&disks).iter().map(|d| dir_size_bytes(d)).sum::<u64>() as f64 / 1_048_576.0;
+
+    StorageAnalysis {
+        drives,
+        total_space_gb: total_space,
+        used_space_gb: used_space,
+        free_space_gb: free_space,
+        largest_files: Vec::new(), // Full scan is on demand, see scan_largest_files
+        temp_files_mb,
+        recycle_bin_mb,
+        summary,
+    }
+}
+
+/// Minimum reclaimable space before suggesting a cleanup
+const CLEANUP_MIN_MB: f64 = 100.0;
+
+/// Total size of the files under `path`; unreadable entries are ignored
+fn dir_size_bytes(path: &std::path::Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !is_reparse_point(e))
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+fn temp_dirs() -> Vec<std::path::PathBuf> {
+    let mut dirs = vec![std::env::temp_dir()];
+    #[cfg(windows)]
+    {
+        let root = std::env::var("SystemRoot").unwrap_or_else(|_| r"C:\Windows".to_string());
+        let windows_temp = std::path::PathBuf::from(root).join("Temp");
+        if !dirs.contains(&windows_temp) {
+            dirs.push(windows_temp);
+        }
+    }
+    dirs
+}
+
+/// `$Recycle.Bin` is hidden+system: read it by path, never through a filtered listing.
+/// Other users' SID folders are denied and simply skipped by the walk.
+#[cfg(windows)]
+fn recycle_bin_dirs(disks: &sysinfo::Disks) -> Vec<std::path::PathBuf> {
+    disks.iter()
+        .map(|d| d.mount_point().join("$Recycle.Bin"))
+        .filter(|p| p.is_dir())
+        .collect()
+}
+
+#[cfg(not(windows))]
+fn recycle_bin_dirs(_disks: &sysinfo::Disks) -> Vec<std::path::PathBuf> {
+    dirs::data_dir()
+        .map(|d| d.join("Trash").join("files"))
+        .filter(|p| p.is_dir())
+        .into_iter()
+        .collect()
+}
+
+// ============================================
+// DRIVE SPEED PROBE
+// ============================================
+
+/// Small enough to probe every drive in a couple of seconds
+const SPEED_PROBE_BYTES: usize = 5 * 1024 * 1024;
+/// Multiple of the sector size, as unbuffered reads require
+const SPEED_PROBE_CHUNK: usize = 64 * 1024;
+/// Drives with less free space are not written to
+const SPEED_PROBE_MIN_FREE_BYTES: u64 = 1_073_741_824;
+
+fn is_network_filesystem(file_system: &str) -> bool {
+    let fs = file_system.to_lowercase();
+    ["nfs", "cifs", "smb", "sshfs", "afpfs", "webdav", "fuse.sshfs"].iter().any(|n| fs.starts_with(n))
+}
+
+/// Local, fixed and with room for the test file
+fn speed_probe_allowed(disk: &sysinfo::Disk) -> bool {
+    !disk.is_removable()
+        && !is_network_filesystem(&disk.file_system().to_string_lossy())
+        && disk.available_space() >= SPEED_PROBE_MIN_FREE_BYTES
+}
+
+/// The temp folder when it lives on that drive (drive roots usually need admin rights)
+fn speed_probe_dir(disks: &sysinfo::Disks, mount: &std::path::Path) -> std::path::PathBuf {
+    let temp = std::env::temp_dir();
+    let temp_mount = disks.iter()
+        .map(|d| d.mount_point())
+        .filter(|m| temp.starts_with(m))
+        .max_by_key(|m| m.as_os_str().len());
+    if temp_mount == Some(mount) { temp } else { mount.to_path_buf() }
+}
+
+fn mb_per_sec(bytes: usize, elapsed: std::time::Duration) -> Option<f64> {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 { Some((bytes as f64 / 1_000_000.0) / secs) } else { None }
+}
+
+#[repr(C, align(4096))]
+struct ProbeChunk([u8; SPEED_PROBE_CHUNK]);
+
+/// Reads bypass the file cache; buffers, offsets and lengths must then be sector-aligned
+#[cfg(windows)]
+const FILE_FLAG_NO_BUFFERING: u32 = 0x20000000;
+
+/// Reads the file back bypassing the cache, otherwise the figure is RAM speed
+#[cfg(windows)]
+fn probe_read_speed(path: &std::path::Path) -> Option<f64> {
+    use std::io::Read;
+    use std::os::windows::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(FILE_FLAG_NO_BUFFERING)
+        .open(path)
+        .ok()?;
+    let mut chunk = Box::new(ProbeChunk([0u8; SPEED_PROBE_CHUNK]));
+    let start = std::time::Instant::now();
+    let mut total = 0;
+    while total < SPEED_PROBE_BYTES {
+        match file.read(&mut chunk.0) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => total += n,
+        }
+    }
+    if total < SPEED_PROBE_BYTES {
+        return None;
+    }
+    mb_per_sec(total, start.elapsed())
+}
+
+/// No portable way to skip the page cache without libc, write speed only
+#[cfg(not(windows))]
+fn probe_read_speed(_path: &std::path::Path) -> Option<f64> {
+    None
+}
+
+/// Random data flushed to disk, so the figure is not just the write cache
+fn probe_write_speed(path: &std::path::Path) -> Option<f64> {
+    use std::io::Write;
+    use rand::Rng;
+
+    let mut chunk = Box::new(ProbeChunk([0u8; SPEED_PROBE_CHUNK]));
+    rand::thread_rng().fill(&mut chunk.0[..]);
+
+    let mut file = std::fs::File::create(path).ok()?;
+    let start = std::time::Instant::now();
+    for _ in 0..SPEED_PROBE_BYTES / SPEED_PROBE_CHUNK {
+        file.write_all(&chunk.0).ok()?;
+    }
+    file.sync_all().ok()?;
+    mb_per_sec(SPEED_PROBE_BYTES, start.elapsed())
+}
+
+/// Sequential (read, write) MB/s over a 5 MB file, far quicker than run_disk_benchmark
+fn probe_drive_speed(dir: &std::path::Path) -> (Option<f64>, Option<f64>) {
+    let path = dir.join("microdiag_speed_probe.tmp");
+    let write_speed = probe_write_speed(&path);
+    let read_speed = write_speed.and_then(|_| probe_read_speed(&path));
+    let _ = std::fs::remove_file(&path);
+    (read_speed, write_speed)
+}
+
+// ============================================
+// LARGEST FILES SCAN
+// ============================================
+
+/// Progress callback is called every this many files
+const LARGE_FILE_PROGRESS_EVERY: u64 = 1000;
+
+/// Junctions and symlinks would make the walk loop or count files twice
+fn is_reparse_point(entry: &walkdir::DirEntry) -> bool {
+    if entry.path_is_symlink() {
+        return true;
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+        if let Ok(meta) = entry.metadata() {
+            return meta.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0;
+        }
+    }
+    false
+}
+
+/// Walks `root` and keeps the `top_n` biggest files in a bounded min-heap.
+/// Unreadable folders are skipped; when `cancel` is set the files found so far are returned.
+pub fn scan_largest_files(
+    root: &str,
+    top_n: usize,
+    cancel: &std::sync::atomic::AtomicBool,
+    mut on_progress: impl FnMut(u64, &str),
+) -> Vec<LargeFile> {
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+    use std::sync::atomic::Ordering;
+
+    let mut heap: BinaryHeap<Reverse<(u64, String)>> = BinaryHeap::new();
+    let mut scanned: u64 = 0;
+
+    let walker = walkdir::WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !is_reparse_point(e));
+
+    // Permission denied and vanished files come back as Err, just skip them
+    for entry in walker.filter_map(Result::ok) {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        scanned += 1;
+        if scanned % LARGE_FILE_PROGRESS_EVERY == 0 {
+            on_progress(scanned, &entry.path().to_string_lossy());
+        }
+
+        let size = match entry.metadata() {
+            Ok(meta) => meta.len(),
+            Err(_) => continue,
+        };
+        if heap.len() < top_n {
+            heap.push(Reverse((size, entry.path().to_string_lossy().to_string())));
+        } else if heap.peek().map(|Reverse((smallest, _))| size > *smallest).unwrap_or(false) {
+            heap.pop();
+            heap.push(Reverse((size, entry.path().to_string_lossy().to_string())));
+        }
+    }
+    on_progress(scanned, "");
+
+    // into_sorted_vec on Reverse gives biggest first
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((size, path))| {
+            let file_type = std::path::Path::new(&path)
+                .extension()
+                .map(|e| e.to_string_lossy().to_uppercase())
+                .unwrap_or_else(|| "Fichier".to_string());
+            LargeFile {
+                path,
+                size_mb: size as f64 / 1_048_576.0,
+                file_type,
+            }
+        })
+        .collect()
+}
+
+// ============================================
+// EXTENDED SYSTEM INFO
+// ============================================
+
+pub fn get_extended_system_info(sys: &System) -> ExtendedSystemInfo {
+    let cpus = sys.cpus();
+    let cpu_name = cpus.first().map(|c| c.brand().to_string()).unwrap_or_default();
+    let cpu_cores = sys.physical_core_count().unwrap_or(0);
+    let cpu_threads = cpus.len();
+    let cpu_freq = cpus.first().map(|c| c.frequency()).unwrap_or(0);
+
     let ram_total = sys.total_memory() as f64 / 1_073_741_824.0;
     let uptime = System::uptime() / 3600; // Convert to hours
+    let gpu = crate::godmode::get_gpu_info();
+    let board = crate::godmode::get_board_info();
 
     ExtendedSystemInfo {
         cpu_name,
@@ -685,11 +1945,11 @@ pub fn get_extended_system_info(sys: &System) -> ExtendedSystemInfo {
         cpu_threads,
         cpu_frequency_mhz: cpu_freq,
         ram_total_gb: ram_total,
-        ram_slots_used: String::new(),
-        gpu_name: String::new(), // Would need WMI on Windows
-        gpu_memory_mb: 0,
-        motherboard: String::new(),
-        bios_version: String::new(),
+        ram_slots_used: board.as_ref().map(|b| b.ram_slots_used.clone()).unwrap_or_default(),
+        gpu_name: gpu.as_ref().map(|g| g.name.clone()).unwrap_or_default(),
+        gpu_memory_mb: gpu.as_ref().map(|g| g.memory_mb).unwrap_or(0),
+        motherboard: board.as_ref().map(|b| b.motherboard.clone()).unwrap_or_default(),
+        bios_version: board.as_ref().map(|b| b.bios_version.clone()).unwrap_or_default(),
         windows_version: System::long_os_version().unwrap_or_default(),
         windows_build: String::new(),
         install_date: String::new(),
@@ -707,8 +1967,12 @@ pub fn generate_recommendations(
     processes: &ProcessAnalysis,
     network: &NetworkAnalysis,
     storage: &StorageAnalysis,
+    drivers: &[DriverInfo],
 ) -> Vec<Recommendation> {
     let mut recommendations: Vec<Recommendation> = Vec::new();
+    let lang = i18n::language();
+    let t = |key: &str| i18n::t(key, lang);
+    let tf = |key: &str, args: &[(&str, String)]| i18n::tf(key, lang, args);
 
     // Temperature recommendations
     if let Some(cpu_temp) = temps.cpu_temp {
@@ -716,22 +1980,19 @@ pub fn generate_recommendations(
             recommendations.push(Recommendation {
                 priority: "critical".to_string(),
                 category: "performance".to_string(),
-                title: "Surchauffe CPU detectee".to_string(),
-                description: format!(
-                    "Votre processeur atteint {}°C. Nettoyez les ventilateurs et verifiez la pate thermique.",
-                    cpu_temp as u8
-                ),
+                title: t("rec.cpu_overheat.title"),
+                description: tf("rec.cpu_overheat.description", &[("temp", (cpu_temp as u8).to_string())]),
                 action: None,
-                impact: "Peut causer des ralentissements et reduire la duree de vie du PC".to_string(),
+                impact: t("rec.cpu_overheat.impact"),
             });
         } else if cpu_temp > 75.0 {
             recommendations.push(Recommendation {
                 priority: "warning".to_string(),
                 category: "performance".to_string(),
-                title: "Temperature CPU elevee".to_string(),
-                description: "Verifiez que les ventilateurs fonctionnent correctement.".to_string(),
+                title: t("rec.cpu_hot.title"),
+                description: t("rec.cpu_hot.description"),
                 action: None,
-                impact: "Performances potentiellement reduites".to_string(),
+                impact: t("rec.cpu_hot.impact"),
             });
         }
     }
@@ -741,13 +2002,10 @@ pub fn generate_recommendations(
         recommendations.push(Recommendation {
             priority: "critical".to_string(),
             category: "security".to_string(),
-            title: "Processus suspects detectes".to_string(),
-            description: format!(
-                "{} processus inhabituel(s) detecte(s). Lancez un scan antivirus complet.",
-                processes.suspicious.len()
-            ),
+            title: t("rec.suspicious_processes.title"),
+            description: tf("rec.suspicious_processes.description", &[("count", processes.suspicious.len().to_string())]),
             action: Some("run_antivirus_scan".to_string()),
-            impact: "Risque potentiel pour la securite".to_string(),
+            impact: t("rec.suspicious_processes.impact"),
         });
     }
 
@@ -756,13 +2014,13 @@ pub fn generate_recommendations(
             recommendations.push(Recommendation {
                 priority: "warning".to_string(),
                 category: "performance".to_string(),
-                title: format!("{} utilise beaucoup de CPU", top.name),
-                description: format!(
-                    "Cette application utilise {}% du processeur. {}",
-                    top.cpu_percent as u8, top.description
-                ),
+                title: tf("rec.high_cpu_process.title", &[("name", top.name.clone())]),
+                description: tf("rec.high_cpu_process.description", &[
+                    ("percent", (top.cpu_percent as u8).to_string()),
+                    ("details", top.description.clone()),
+                ]),
                 action: None,
-                impact: "Peut ralentir les autres applications".to_string(),
+                impact: t("rec.high_cpu_process.impact"),
             });
         }
     }
@@ -773,50 +2031,89 @@ pub fn generate_recommendations(
             recommendations.push(Recommendation {
                 priority: "critical".to_string(),
                 category: "storage".to_string(),
-                title: format!("Disque {} presque plein", drive.letter),
-                description: format!(
-                    "Seulement {:.1} GB libres. Liberez de l'espace immediatement.",
-                    drive.free_gb
-                ),
+                title: tf("rec.disk_full.title", &[("drive", drive.letter.clone())]),
+                description: tf("rec.disk_full.description", &[("free", format!("{:.1}", drive.free_gb))]),
                 action: Some("cleanup".to_string()),
-                impact: "Windows peut devenir instable".to_string(),
+                impact: t("rec.disk_full.impact"),
             });
         } else if drive.percent > 85.0 {
             recommendations.push(Recommendation {
                 priority: "warning".to_string(),
                 category: "storage".to_string(),
-                title: format!("Disque {} bientot plein", drive.letter),
-                description: format!(
-                    "{:.1} GB libres. Pensez a supprimer les fichiers inutiles.",
-                    drive.free_gb
-                ),
+                title: tf("rec.disk_almost_full.title", &[("drive", drive.letter.clone())]),
+                description: tf("rec.disk_almost_full.description", &[("free", format!("{:.1}", drive.free_gb))]),
                 action: Some("cleanup".to_string()),
-                impact: "Performances reduites possibles".to_string(),
+                impact: t("rec.disk_almost_full.impact"),
             });
         }
     }
 
+    let reclaimable_mb = storage.temp_files_mb + storage.recycle_bin_mb;
+    if reclaimable_mb >= CLEANUP_MIN_MB {
+        recommendations.push(Recommendation {
+            priority: if reclaimable_mb >= 5120.0 { "warning" } else { "info" }.to_string(),
+            category: "storage".to_string(),
+            title: t("rec.cleanup.title"),
+            description: tf("rec.cleanup.description", &[
+                ("total", format!("{:.0}", reclaimable_mb)),
+                ("temp", format!("{:.0}", storage.temp_files_mb)),
+                ("bin", format!("{:.0}", storage.recycle_bin_mb)),
+            ]),
+            action: Some("cleanup".to_string()),
+            impact: t("rec.cleanup.impact"),
+        });
+    }
+
     // Network recommendations
     if !network.is_connected {
         recommendations.push(Recommendation {
             priority: "critical".to_string(),
             category: "network".to_string(),
-            title: "Pas de connexion Internet".to_string(),
-            description: "Verifiez votre cable reseau ou votre WiFi.".to_string(),
+            title: t("rec.offline.title"),
+            description: t("rec.offline.description"),
             action: Some("fix_network".to_string()),
-            impact: "Impossible d'acceder a Internet".to_string(),
+            impact: t("rec.offline.impact"),
+        });
+    } else if network.dns.status == "slow" || network.dns.status == "failing" {
+        // Slow name resolution feels like a slow connection, but the fix is the resolver
+        let resolver = network.dns.resolver_ip.clone().unwrap_or_else(|| "?".to_string());
+        let key = if network.dns.status == "slow" { "rec.slow_dns" } else { "rec.dns_failing" };
+        recommendations.push(Recommendation {
+            priority: "warning".to_string(),
+            category: "network".to_string(),
+            title: t(&format!("{}.title", key)),
+            description: tf(&format!("{}.description", key), &[
+                ("resolver", resolver),
+                ("ms", network.dns.avg_ms.unwrap_or(0).to_string()),
+            ]),
+            action: Some("flush_dns".to_string()),
+            impact: t(&format!("{}.impact", key)),
         });
     } else if network.latency_ms.map(|l| l > 100).unwrap_or(false) {
         recommendations.push(Recommendation {
             priority: "warning".to_string(),
             category: "network".to_string(),
-            title: "Connexion Internet lente".to_string(),
-            description: format!(
-                "Latence de {}ms. Essayez de redemarrer votre box.",
-                network.latency_ms.unwrap_or(0)
-            ),
+            title: t("rec.slow_network.title"),
+            description: tf("rec.slow_network.description", &[("latency", network.latency_ms.unwrap_or(0).to_string())]),
             action: Some("fix_network".to_string()),
-            impact: "Navigation web et jeux ralentis".to_string(),
+            impact: t("rec.slow_network.impact"),
+        });
+    }
+
+    // Driver recommendations
+    for driver in drivers.iter().filter(|d| d.is_outdated) {
+        let years = driver.driver_age_days.unwrap_or(0) / 365;
+        recommendations.push(Recommendation {
+            priority: "warning".to_string(),
+            category: "drivers".to_string(),
+            title: tf("rec.outdated_driver.title", &[("name", driver.name.clone())]),
+            description: tf("rec.outdated_driver.description", &[
+                ("type", driver.driver_type.clone()),
+                ("years", years.to_string()),
+                ("date", driver.driver_date.clone()),
+            ]),
+            action: Some("update_driver".to_string()),
+            impact: t("rec.outdated_driver.impact"),
         });
     }
 
@@ -825,10 +2122,10 @@ pub fn generate_recommendations(
         recommendations.push(Recommendation {
             priority: "info".to_string(),
             category: "general".to_string(),
-            title: "Votre PC est en bonne sante!".to_string(),
-            description: "Aucun probleme detecte. Continuez les bonnes pratiques.".to_string(),
+            title: t("rec.healthy.title"),
+            description: t("rec.healthy.description"),
             action: None,
-            impact: "Tout fonctionne correctement".to_string(),
+            impact: t("rec.healthy.impact"),
         });
     }
 
@@ -836,58 +2133,125 @@ pub fn generate_recommendations(
 }
 
 // ============================================
-// MAIN DIAGNOSTIC FUNCTION
+// SCORING
 // ============================================
 
-pub fn run_premium_diagnostic(sys: &mut System) -> PremiumDiagnostic {
-    // Refresh all system data
-    sys.refresh_all();
-
-    // Collect all diagnostics
-    let temperatures = get_temperatures();
-    let processes = analyze_processes(sys);
-    let network = analyze_network();
-    let storage = analyze_storage();
-    let system_info = get_extended_system_info(sys);
-
-    // Generate recommendations
-    let recommendations = generate_recommendations(&temperatures, &processes, &network, &storage);
+/// Overall health score and its per-category parts, each out of 100
+#[derive(Serialize, Clone, Debug)]
+pub struct DiagnosticScores {
+    pub overall: u8,
+    pub temperature: u8,
+    pub processes: u8,
+    pub storage: u8,
+    pub network: u8,
+}
 
-    // Calculate overall score
-    let mut score: u8 = 100;
+pub fn compute_scores(
+    temperatures: &TemperatureInfo,
+    processes: &ProcessAnalysis,
+    network: &NetworkAnalysis,
+    storage: &StorageAnalysis,
+    scoring: &ScoringConfig,
+) -> DiagnosticScores {
+    score_parts(
+        temperatures.cpu_temp,
+        processes,
+        &storage.drives,
+        network.is_connected,
+        network.latency_ms,
+        scoring,
+    )
+}
 
+/// Shared by the premium and quick paths so both report the same numbers
+fn score_parts(
+    cpu_temp: Option<f32>,
+    processes: &ProcessAnalysis,
+    drives: &[DriveAnalysis],
+    is_connected: bool,
+    latency_ms: Option<u32>,
+    scoring: &ScoringConfig,
+) -> DiagnosticScores {
     // Deduct for temperature issues
-    if temperatures.cpu_temp.map(|t| t > 85.0).unwrap_or(false) {
-        score = score.saturating_sub(20);
-    } else if temperatures.cpu_temp.map(|t| t > 75.0).unwrap_or(false) {
-        score = score.saturating_sub(10);
+    let mut temperature: u8 = 100;
+    if cpu_temp.map(|t| t > scoring.temp_critical_c).unwrap_or(false) {
+        temperature = temperature.saturating_sub(scoring.temp_critical_penalty);
+    } else if cpu_temp.map(|t| t > scoring.temp_warning_c).unwrap_or(false) {
+        temperature = temperature.saturating_sub(scoring.temp_warning_penalty);
     }
 
-    // Deduct for suspicious processes
-    score = score.saturating_sub((processes.suspicious.len() as u8) * 15);
-
-    // Deduct for high CPU usage
-    if processes.top_cpu.first().map(|p| p.cpu_percent > 90.0).unwrap_or(false) {
-        score = score.saturating_sub(10);
+    // Deduct for suspicious processes and high CPU usage
+    let mut process_score: u8 = 100;
+    process_score = process_score.saturating_sub((processes.suspicious.len().min(u8::MAX as usize) as u8).saturating_mul(scoring.suspicious_process_penalty));
+    if processes.top_cpu.first().map(|p| p.cpu_percent > scoring.top_cpu_threshold).unwrap_or(false) {
+        process_score = process_score.saturating_sub(scoring.top_cpu_penalty);
     }
 
     // Deduct for storage issues
-    for drive in &storage.drives {
-        if drive.percent > 95.0 {
-            score = score.saturating_sub(25);
-        } else if drive.percent > 85.0 {
-            score = score.saturating_sub(10);
+    let mut storage_score: u8 = 100;
+    for drive in drives {
+        if drive.percent > scoring.disk_critical_percent {
+            storage_score = storage_score.saturating_sub(scoring.disk_critical_penalty);
+        } else if drive.percent > scoring.disk_warning_percent {
+            storage_score = storage_score.saturating_sub(scoring.disk_warning_penalty);
         }
     }
 
     // Deduct for network issues
-    if !network.is_connected {
-        score = score.saturating_sub(15);
-    } else if network.latency_ms.map(|l| l > 100).unwrap_or(false) {
-        score = score.saturating_sub(5);
+    let mut network_score: u8 = 100;
+    if !is_connected {
+        network_score = network_score.saturating_sub(scoring.offline_penalty);
+    } else if latency_ms.map(|l| l > scoring.latency_threshold_ms).unwrap_or(false) {
+        network_score = network_score.saturating_sub(scoring.latency_penalty);
     }
 
-    let overall_status = if score >= 85 {
+    let deducted: u32 = [temperature, process_score, storage_score, network_score]
+        .iter()
+        .map(|s| 100 - *s as u32)
+        .sum();
+
+    DiagnosticScores {
+        overall: 100u32.saturating_sub(deducted) as u8,
+        temperature,
+        processes: process_score,
+        storage: storage_score,
+        network: network_score,
+    }
+}
+
+// ============================================
+// HEAVY TASK TRACKING
+// ============================================
+
+/// Benchmarks currently running; background jobs wait until it drops back to zero
+static HEAVY_TASKS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts a heavy task for as long as it is alive
+struct HeavyTaskGuard;
+
+impl HeavyTaskGuard {
+    fn start() -> Self {
+        HEAVY_TASKS.fetch_add(1, Ordering::SeqCst);
+        HeavyTaskGuard
+    }
+}
+
+impl Drop for HeavyTaskGuard {
+    fn drop(&mut self) {
+        HEAVY_TASKS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub fn heavy_task_running() -> bool {
+    HEAVY_TASKS.load(Ordering::SeqCst) > 0
+}
+
+// ============================================
+// MAIN DIAGNOSTIC FUNCTION
+// ============================================
+
+fn overall_status(score: u8) -> String {
+    if score >= 85 {
         "excellent"
     } else if score >= 70 {
         "good"
@@ -895,7 +2259,41 @@ pub fn run_premium_diagnostic(sys: &mut System) -> PremiumDiagnostic {
         "warning"
     } else {
         "critical"
-    }.to_string();
+    }.to_string()
+}
+
+/// Scores only, without system info, drivers or recommendations; used by the scheduled run
+pub fn run_light_diagnostic(sys: &mut System, suspicion: &SuspicionConfig, scoring: &ScoringConfig, latency: &LatencyConfig) -> DiagnosticScores {
+    sys.refresh_all();
+
+    let temperatures = get_temperatures();
+    let processes = analyze_processes(sys, suspicion);
+    let network = analyze_network(latency);
+    let storage = analyze_storage(false);
+
+    compute_scores(&temperatures, &processes, &network, &storage, scoring)
+}
+
+pub fn run_premium_diagnostic(sys: &mut System, suspicion: &SuspicionConfig, scoring: &ScoringConfig, latency: &LatencyConfig) -> PremiumDiagnostic {
+    // Refresh all system data
+    sys.refresh_all();
+    // Before the PowerShell probes below, which would show up as CPU load
+    let cpu_cores = crate::metrics::get_cpu_cores(sys);
+
+    // Collect all diagnostics
+    let temperatures = get_temperatures();
+    let processes = analyze_processes(sys, suspicion);
+    let network = analyze_network(latency);
+    let storage = analyze_storage(false);
+    let system_info = get_extended_system_info(sys);
+    let drivers = crate::godmode::get_driver_list();
+
+    // Generate recommendations
+    let recommendations = generate_recommendations(&temperatures, &processes, &network, &storage, &drivers);
+
+    // Calculate overall score
+    let score = compute_scores(&temperatures, &processes, &network, &storage, scoring).overall;
+    let overall_status = overall_status(score);
 
     PremiumDiagnostic {
         temperatures,
@@ -903,12 +2301,39 @@ pub fn run_premium_diagnostic(sys: &mut System) -> PremiumDiagnostic {
         network,
         storage,
         system_info,
+        cpu_cores,
         recommendations,
         overall_score: score,
         overall_status,
     }
 }
 
+/// Dashboard-open variant: no full refresh, no PowerShell temperature probe, no ping
+/// or DNS test and no cleanup sizing. Signature checks are skipped too, so only
+/// path and name heuristics count towards suspicious processes.
+pub fn run_quick_diagnostic(sys: &mut System, suspicion: &SuspicionConfig, scoring: &ScoringConfig) -> QuickDiagnostic {
+    // The cached state starts empty until the first full refresh
+    if sys.processes().is_empty() {
+        sys.refresh_processes();
+        sys.refresh_memory();
+    }
+
+    let temperatures = read_temperatures(false);
+    let suspicion = SuspicionConfig { check_signatures: false, ..suspicion.clone() };
+    let processes = analyze_processes(sys, &suspicion);
+    let (_, is_connected) = scan_interfaces(&get_interface_addresses());
+    let drives = analyze_drives(&sysinfo::Disks::new_with_refreshed_list(), false);
+
+    let scores = score_parts(temperatures.cpu_temp, &processes, &drives, is_connected, None, scoring);
+
+    QuickDiagnostic {
+        overall_status: overall_status(scores.overall),
+        scores,
+        top_processes: processes.top_cpu.into_iter().take(3).collect(),
+        drives,
+    }
+}
+
 // ============================================
 // DISK BENCHMARK (CrystalDiskMark Style)
 // ============================================
@@ -922,6 +2347,12 @@ pub struct DiskBenchmark {
     pub rand_write_iops: u64,
     pub rand_read_mbps: f64,
     pub rand_write_mbps: f64,
+    /// Same 4K random tests with `queue_depth` workers issuing I/O in parallel
+    pub queue_depth: u32,
+    pub rand_read_iops_qd: u64,
+    pub rand_write_iops_qd: u64,
+    pub rand_read_mbps_qd: f64,
+    pub rand_write_mbps_qd: f64,
     pub latency_us: u64,
     pub score: u32,
     pub grade: String,
@@ -931,32 +2362,63 @@ const BENCHMARK_FILE_SIZE: usize = 64 * 1024 * 1024;  // 64 MB for faster test
 const BLOCK_SIZE_SEQ: usize = 1024 * 1024;  // 1 MB blocks
 const BLOCK_SIZE_RAND: usize = 4096;  // 4 KB blocks
 const RAND_ITERATIONS: usize = 500;
+/// Worker threads for the parallel random test (comparable to CrystalDiskMark Q8T8 order of magnitude)
+const RAND_QUEUE_DEPTH: usize = 8;
+
+/// 4K block aligned for FILE_FLAG_NO_BUFFERING
+#[cfg(windows)]
+#[repr(C, align(4096))]
+struct RandBlock([u8; BLOCK_SIZE_RAND]);
+
+/// The benchmark file was just written and sits in the file cache, random reads
+/// must skip it or they measure RAM. Writes stay buffered and are flushed instead.
+#[cfg(windows)]
+fn open_benchmark_file(path: &str, write: bool) -> std::io::Result<std::fs::File> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.read(true).write(write);
+    if !write {
+        options.custom_flags(FILE_FLAG_NO_BUFFERING);
+    }
+    options.open(path)
+}
+
+pub fn run_disk_benchmark(drive: &str) -> DiskBenchmark {
+    run_disk_benchmark_sized(drive, BENCHMARK_FILE_SIZE)
+}
 
 #[cfg(windows)]
-pub fn run_disk_benchmark(drive: &str) -> DiskBenchmark {
+pub fn run_disk_benchmark_sized(drive: &str, file_size_bytes: usize) -> DiskBenchmark {
     use std::fs::{File, OpenOptions, remove_file};
     use std::io::{Read, Write, Seek, SeekFrom};
     use std::time::Instant;
     use rand::Rng;
 
+    let _busy = HeavyTaskGuard::start();
+
+    // Keep the file large enough for the 4K random tests
+    let file_size_bytes = file_size_bytes.max(BLOCK_SIZE_SEQ * 4);
     let test_path = format!("{}\\microdiag_benchmark_test.tmp", drive);
     let mut rng = rand::thread_rng();
 
     // Generate random data
-    let mut data = vec![0u8; BENCHMARK_FILE_SIZE];
+    let mut data = vec![0u8; file_size_bytes];
     rng.fill(&mut data[..]);
 
     // === Sequential Write Test ===
     let seq_write_mbps = {
         let start = Instant::now();
         if let Ok(mut file) = File::create(&test_path) {
+            // Pre-allocate so later random writes never extend the file
+            let _ = file.set_len(file_size_bytes as u64);
             for chunk in data.chunks(BLOCK_SIZE_SEQ) {
                 let _ = file.write_all(chunk);
             }
             let _ = file.sync_all();
             let elapsed = start.elapsed().as_secs_f64();
             if elapsed > 0.0 {
-                (BENCHMARK_FILE_SIZE as f64 / 1_000_000.0) / elapsed
+                (file_size_bytes as f64 / 1_000_000.0) / elapsed
             } else {
                 0.0
             }
@@ -973,7 +2435,7 @@ pub fn run_disk_benchmark(drive: &str) -> DiskBenchmark {
             while file.read(&mut buffer).unwrap_or(0) > 0 {}
             let elapsed = start.elapsed().as_secs_f64();
             if elapsed > 0.0 {
-                (BENCHMARK_FILE_SIZE as f64 / 1_000_000.0) / elapsed
+                (file_size_bytes as f64 / 1_000_000.0) / elapsed
             } else {
                 0.0
             }
@@ -982,16 +2444,18 @@ pub fn run_disk_benchmark(drive: &str) -> DiskBenchmark {
         }
     };
 
-    // === Random Read Test (4K) ===
-    let file_size = BENCHMARK_FILE_SIZE as u64;
+    // === Random Read Test (4K, uncached) ===
+    let file_size = file_size_bytes as u64;
     let (rand_read_iops, rand_read_mbps) = {
+        use std::os::windows::fs::FileExt;
+
         let start = Instant::now();
-        if let Ok(mut file) = File::open(&test_path) {
-            let mut buffer = vec![0u8; BLOCK_SIZE_RAND];
+        if let Ok(file) = open_benchmark_file(&test_path, false) {
+            let mut block = Box::new(RandBlock([0u8; BLOCK_SIZE_RAND]));
+            let blocks = file_size / BLOCK_SIZE_RAND as u64;
             for _ in 0..RAND_ITERATIONS {
-                let pos = rng.gen_range(0..(file_size - BLOCK_SIZE_RAND as u64));
-                let _ = file.seek(SeekFrom::Start(pos));
-                let _ = file.read_exact(&mut buffer);
+                let offset = rng.gen_range(0..blocks) * BLOCK_SIZE_RAND as u64;
+                let _ = file.seek_read(&mut block.0, offset);
             }
             let elapsed = start.elapsed().as_secs_f64();
             if elapsed > 0.0 {
@@ -1030,6 +2494,10 @@ pub fn run_disk_benchmark(drive: &str) -> DiskBenchmark {
         }
     };
 
+    // === Parallel Random Tests (4K, QD8) ===
+    let (rand_read_iops_qd, rand_read_mbps_qd) = parallel_random_io(&test_path, file_size, RAND_QUEUE_DEPTH, false);
+    let (rand_write_iops_qd, rand_write_mbps_qd) = parallel_random_io(&test_path, file_size, RAND_QUEUE_DEPTH, true);
+
     // === Latency Test ===
     let latency_us = {
         let start = Instant::now();
@@ -1069,14 +2537,87 @@ pub fn run_disk_benchmark(drive: &str) -> DiskBenchmark {
         rand_write_iops,
         rand_read_mbps,
         rand_write_mbps,
+        queue_depth: RAND_QUEUE_DEPTH as u32,
+        rand_read_iops_qd,
+        rand_write_iops_qd,
+        rand_read_mbps_qd,
+        rand_write_mbps_qd,
         latency_us,
         score,
         grade,
     }
 }
 
+/// Random 4K I/O from `threads` workers at once, each with its own handle.
+/// Returns aggregated (IOPS, MB/s) measured on wall-clock time.
+#[cfg(windows)]
+fn parallel_random_io(path: &str, file_size: u64, threads: usize, write: bool) -> (u64, f64) {
+    use std::os::windows::fs::FileExt;
+    use std::sync::{Arc, Barrier};
+    use std::time::Instant;
+    use rand::Rng;
+
+    let blocks = file_size / BLOCK_SIZE_RAND as u64;
+    if blocks == 0 || threads == 0 {
+        return (0, 0.0);
+    }
+
+    // All workers start together once their handles are open
+    let barrier = Arc::new(Barrier::new(threads + 1));
+    let workers: Vec<_> = (0..threads)
+        .map(|_| {
+            let barrier = barrier.clone();
+            let path = path.to_string();
+            std::thread::spawn(move || {
+                let file = open_benchmark_file(&path, write);
+                barrier.wait();
+                let file = match file {
+                    Ok(f) => f,
+                    Err(_) => return 0usize,
+                };
+
+                let mut rng = rand::thread_rng();
+                let mut block = Box::new(RandBlock([0u8; BLOCK_SIZE_RAND]));
+                if write {
+                    rng.fill(&mut block.0[..]);
+                }
+                let mut done = 0;
+                for _ in 0..RAND_ITERATIONS {
+                    let offset = rng.gen_range(0..blocks) * BLOCK_SIZE_RAND as u64;
+                    let result = if write {
+                        file.seek_write(&block.0, offset)
+                    } else {
+                        file.seek_read(&mut block.0, offset)
+                    };
+                    if result.is_ok() {
+                        done += 1;
+                    }
+                }
+                // Flush inside the timed section so writes aren't just cache
+                if write {
+                    let _ = file.sync_all();
+                }
+                done
+            })
+        })
+        .collect();
+
+    barrier.wait();
+    let start = Instant::now();
+    let ops: usize = workers.into_iter().map(|w| w.join().unwrap_or(0)).sum();
+    let elapsed = start.elapsed().as_secs_f64();
+
+    if elapsed > 0.0 && ops > 0 {
+        let iops = (ops as f64 / elapsed) as u64;
+        let mbps = (ops as f64 * BLOCK_SIZE_RAND as f64 / 1_000_000.0) / elapsed;
+        (iops, mbps)
+    } else {
+        (0, 0.0)
+    }
+}
+
 #[cfg(not(windows))]
-pub fn run_disk_benchmark(drive: &str) -> DiskBenchmark {
+pub fn run_disk_benchmark_sized(drive: &str, _file_size_bytes: usize) -> DiskBenchmark {
     DiskBenchmark {
         drive: drive.to_string(),
         seq_read_mbps: 0.0,
@@ -1085,12 +2626,102 @@ pub fn run_disk_benchmark(drive: &str) -> DiskBenchmark {
         rand_write_iops: 0,
         rand_read_mbps: 0.0,
         rand_write_mbps: 0.0,
+        queue_depth: 0,
+        rand_read_iops_qd: 0,
+        rand_write_iops_qd: 0,
+        rand_read_mbps_qd: 0.0,
+        rand_write_mbps_qd: 0.0,
         latency_us: 0,
         score: 0,
         grade: "N/A".into(),
     }
 }
 
+// ============================================
+// DISK BENCHMARK COMPARISON
+// ============================================
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BenchmarkDelta {
+    pub metric: String,
+    pub label: String,
+    pub value_a: f64,
+    pub value_b: f64,
+    pub ratio: f64,        // how many times better the winner is
+    pub winner: String,    // drive letter, or "egalite"
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BenchmarkComparison {
+    pub drive_a: DiskBenchmark,
+    pub drive_b: DiskBenchmark,
+    pub deltas: Vec<BenchmarkDelta>,
+    pub verdict: String,
+}
+
+fn normalize_drive(drive: &str) -> String {
+    drive.trim().trim_end_matches(&['\\', '/'][..]).trim_end_matches(':').to_uppercase()
+}
+
+fn benchmark_delta(metric: &str, label: &str, a: f64, b: f64, lower_is_better: bool, drive_a: &str, drive_b: &str) -> BenchmarkDelta {
+    let (best, worst, winner) = if (a > b) != lower_is_better {
+        (a, b, drive_a)
+    } else {
+        (b, a, drive_b)
+    };
+    let ratio = match (lower_is_better, best, worst) {
+        (_, best, worst) if best == worst => 1.0,
+        (false, best, worst) if worst > 0.0 => best / worst,
+        (true, best, worst) if best > 0.0 => worst / best,
+        _ => 0.0,
+    };
+
+    BenchmarkDelta {
+        metric: metric.to_string(),
+        label: label.to_string(),
+        value_a: a,
+        value_b: b,
+        ratio,
+        winner: if ratio == 1.0 { "egalite".to_string() } else { winner.to_string() },
+    }
+}
+
+pub fn compare_benchmarks(a: DiskBenchmark, b: DiskBenchmark) -> BenchmarkComparison {
+    let deltas = vec![
+        benchmark_delta("seq_read_mbps", "lecture sequentielle", a.seq_read_mbps, b.seq_read_mbps, false, &a.drive, &b.drive),
+        benchmark_delta("seq_write_mbps", "ecriture sequentielle", a.seq_write_mbps, b.seq_write_mbps, false, &a.drive, &b.drive),
+        benchmark_delta("rand_read_iops", "lecture aleatoire 4K", a.rand_read_iops as f64, b.rand_read_iops as f64, false, &a.drive, &b.drive),
+        benchmark_delta("rand_write_iops", "ecriture aleatoire 4K", a.rand_write_iops as f64, b.rand_write_iops as f64, false, &a.drive, &b.drive),
+        benchmark_delta("rand_read_iops_qd", "lecture aleatoire 4K parallele", a.rand_read_iops_qd as f64, b.rand_read_iops_qd as f64, false, &a.drive, &b.drive),
+        benchmark_delta("rand_write_iops_qd", "ecriture aleatoire 4K parallele", a.rand_write_iops_qd as f64, b.rand_write_iops_qd as f64, false, &a.drive, &b.drive),
+        benchmark_delta("latency_us", "latence", a.latency_us as f64, b.latency_us as f64, true, &a.drive, &b.drive),
+    ];
+
+    // Headline on the metric with the biggest gap
+    let verdict = deltas.iter()
+        .filter(|d| d.ratio > 1.0)
+        .max_by(|x, y| x.ratio.partial_cmp(&y.ratio).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|d| format!("Le disque {} est {:.1}x plus rapide en {}", d.winner, d.ratio, d.label))
+        .unwrap_or_else(|| "Performances equivalentes".to_string());
+
+    BenchmarkComparison { drive_a: a, drive_b: b, deltas, verdict }
+}
+
+/// Benchmarks both drives one after the other so they don't compete for I/O
+pub fn run_disk_benchmark_compare(drive_a: &str, drive_b: &str, size_mb: Option<u32>) -> Result<BenchmarkComparison, String> {
+    if normalize_drive(drive_a) == normalize_drive(drive_b) {
+        return Err("Choisissez deux disques differents".to_string());
+    }
+
+    let file_size = size_mb
+        .map(|mb| (mb.clamp(16, 1024) as usize) * 1024 * 1024)
+        .unwrap_or(BENCHMARK_FILE_SIZE);
+
+    let a = run_disk_benchmark_sized(drive_a, file_size);
+    let b = run_disk_benchmark_sized(drive_b, file_size);
+    Ok(compare_benchmarks(a, b))
+}
+
 // ============================================
 // BSOD ANALYSIS
 // ============================================
@@ -1112,11 +2743,16 @@ pub struct BsodCrash {
     pub description: String,
     pub probable_cause: String,
     pub driver: Option<String>,
+    pub parameters: Vec<String>,
     pub solution: String,
 }
 
 fn get_bsod_info(code: u32) -> (&'static str, &'static str, &'static str, &'static str) {
     match code {
+        0x0000000A => ("IRQL_NOT_LESS_OR_EQUAL",
+                       "Acces memoire a un niveau IRQL trop eleve",
+                       "Driver defectueux",
+                       "Identifier le driver fautif et le mettre a jour"),
         0x0000001E => ("KMODE_EXCEPTION_NOT_HANDLED",
                        "Le noyau a rencontre une exception non geree",
                        "Driver defaillant ou incompatible",
@@ -1184,6 +2820,247 @@ fn get_bsod_info(code: u32) -> (&'static str, &'static str, &'static str, &'stat
     }
 }
 
+/// Bugchecks raised on behalf of a driver (IRQL, DPC watchdog, power state...)
+#[cfg(windows)]
+const DRIVER_BUGCHECKS: &[u32] = &[0x0A, 0x1E, 0x3B, 0x7E, 0x9F, 0xBE, 0xC2, 0xD1, 0x133];
+
+/// Device installation log, one section per driver install
+#[cfg(windows)]
+const SETUPAPI_LOG: &str = r"C:\Windows\INF\setupapi.dev.log";
+/// A driver installed longer than this before the crash is not blamed for it
+#[cfg(windows)]
+const DRIVER_SUSPECT_WINDOW_DAYS: i64 = 30;
+
+/// Latest successful install per device description (lowercase). DriverDate is the vendor's
+/// build date, only setupapi knows when the driver landed on this machine:
+/// ">>>  Section start 2024/05/01 10:11:12.345", "dvi: Description: ...", "<<<  [Exit status: SUCCESS]"
+#[cfg(windows)]
+fn parse_driver_installs(log: &str) -> std::collections::HashMap<String, chrono::NaiveDateTime> {
+    let mut installs = std::collections::HashMap::new();
+    let mut started: Option<chrono::NaiveDateTime> = None;
+    let mut devices: Vec<String> = Vec::new();
+
+    for line in log.lines() {
+        let line = line.trim();
+        if let Some(ts) = line.strip_prefix(">>>  Section start ") {
+            started = chrono::NaiveDateTime::parse_from_str(ts.trim(), "%Y/%m/%d %H:%M:%S%.f").ok();
+            devices.clear();
+        } else if let Some((_, description)) = line.split_once("Description:") {
+            let description = description.trim().to_lowercase();
+            if !description.is_empty() && !devices.contains(&description) {
+                devices.push(description);
+            }
+        } else if let Some(status) = line.strip_prefix("<<<  [Exit status: ") {
+            match started {
+                Some(at) if status.starts_with("SUCCESS") => {
+                    for device in devices.drain(..) {
+                        let latest = installs.entry(device).or_insert(at);
+                        if at > *latest {
+                            *latest = at;
+                        }
+                    }
+                }
+                _ => {}
+            }
+            started = None;
+            devices.clear();
+        }
+    }
+
+    installs
+}
+
+/// Driver installed most recently before the crash, the usual suspect when a crash started after an update
+#[cfg(windows)]
+fn driver_installed_before<'a>(
+    drivers: &'a [DriverInfo],
+    installs: &std::collections::HashMap<String, chrono::NaiveDateTime>,
+    crash: chrono::NaiveDateTime,
+) -> Option<(&'a DriverInfo, chrono::NaiveDateTime)> {
+    let window_start = crash - chrono::Duration::days(DRIVER_SUSPECT_WINDOW_DAYS);
+    drivers.iter()
+        .filter_map(|d| installs.get(&d.name.to_lowercase()).map(|at| (d, *at)))
+        .filter(|(_, at)| *at <= crash && *at >= window_start)
+        .max_by_key(|(_, at)| *at)
+}
+
+/// Names the culprit in `driver` and `solution`: the module found in the dump when there is one,
+/// otherwise the critical driver installed last before the crash (drivers and log read once, only if needed)
+#[cfg(windows)]
+fn blame_drivers(crashes: &mut [BsodCrash]) {
+    let mut drivers: Option<(Vec<DriverInfo>, std::collections::HashMap<String, chrono::NaiveDateTime>)> = None;
+
+    for crash in crashes.iter_mut() {
+        let code = u32::from_str_radix(crash.bug_check_code.trim_start_matches("0x"), 16).unwrap_or(0);
+        let from_dump = crash.driver.as_deref().filter(|name| !is_core_kernel_module(name));
+
+        if let Some(module) = from_dump {
+            crash.solution = format!(
+                "Pilote en cause: {} (identifie dans le dump). Le mettre a jour ou le desinstaller, puis revenir a la version precedente si le crash persiste",
+                module
+            );
+            continue;
+        }
+        if !DRIVER_BUGCHECKS.contains(&code) {
+            continue;
+        }
+
+        let crashed_at = match chrono::NaiveDateTime::parse_from_str(&format!("{} {}", crash.date, crash.time), "%d/%m/%Y %H:%M") {
+            Ok(at) => at,
+            Err(_) => continue,
+        };
+        let (list, installs) = drivers.get_or_insert_with(|| {
+            let log = std::fs::read(SETUPAPI_LOG).map(|b| String::from_utf8_lossy(&b).into_owned()).unwrap_or_default();
+            (crate::godmode::get_driver_list(), parse_driver_installs(&log))
+        });
+        if let Some((suspect, installed_at)) = driver_installed_before(list, installs, crashed_at) {
+            crash.driver = Some(suspect.name.clone());
+            crash.solution = format!(
+                "Pilote suspect: {} ({}, installe le {}). Revenir a la version precedente ou installer la derniere version du fabricant",
+                suspect.name, suspect.version, installed_at.format("%d/%m/%Y")
+            );
+        }
+    }
+}
+
+// ============================================
+// KERNEL MINIDUMP PARSING
+// ============================================
+
+/// Bugcheck data read from a C:\Windows\Minidump file ("PAGEDU64" / "PAGEDUMP" header)
+#[cfg(windows)]
+struct MinidumpInfo {
+    bug_check_code: u32,
+    parameters: [u64; 4],
+    driver: Option<String>,
+}
+
+#[cfg(windows)]
+const DUMP_HEADER64_SIZE: usize = 0x2000;
+#[cfg(windows)]
+const KERNEL_ADDRESS_MIN: u64 = 0xFFFF_8000_0000_0000;
+/// DUMP_DRIVER_ENTRY64 grew across Windows builds, candidates are validated against the data
+#[cfg(windows)]
+const DRIVER_ENTRY64_STRIDES: &[usize] = &[0xA8, 0xA0, 0xB0, 0xB8, 0xC0, 0x98];
+
+#[cfg(windows)]
+fn read_u32_le(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(windows)]
+fn read_u64_le(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8).map(|b| {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(b);
+        u64::from_le_bytes(bytes)
+    })
+}
+
+#[cfg(windows)]
+fn parse_kernel_minidump(data: &[u8]) -> Option<MinidumpInfo> {
+    match data.get(0..8)? {
+        b"PAGEDU64" => {
+            let mut parameters = [0u64; 4];
+            for (i, param) in parameters.iter_mut().enumerate() {
+                *param = read_u64_le(data, 0x40 + i * 8)?;
+            }
+            Some(MinidumpInfo {
+                bug_check_code: read_u32_le(data, 0x38)?,
+                driver: faulting_driver64(data, &parameters),
+                parameters,
+            })
+        }
+        b"PAGEDUMP" => {
+            let mut parameters = [0u64; 4];
+            for (i, param) in parameters.iter_mut().enumerate() {
+                *param = read_u32_le(data, 0x2C + i * 4)? as u64;
+            }
+            Some(MinidumpInfo {
+                bug_check_code: read_u32_le(data, 0x28)?,
+                parameters,
+                driver: None,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// DUMP_STRING: u32 length in UTF-16 units followed by the characters
+#[cfg(windows)]
+fn read_dump_string(data: &[u8], offset: usize) -> Option<String> {
+    let len = read_u32_le(data, offset)? as usize;
+    if len == 0 || len > 260 {
+        return None;
+    }
+    let units: Vec<u16> = data
+        .get(offset + 4..offset + 4 + len * 2)?
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16(&units).ok()
+}
+
+/// (name, base, size) of one loaded driver, None if the entry doesn't look valid
+#[cfg(windows)]
+fn driver_entry64(data: &[u8], offset: usize, pool: std::ops::Range<usize>) -> Option<(String, u64, u64)> {
+    let name_offset = read_u32_le(data, offset)? as usize;
+    if !pool.contains(&name_offset) {
+        return None;
+    }
+    // KLDR_DATA_TABLE_ENTRY64 follows the 8-byte name offset: DllBase at +0x30, SizeOfImage at +0x40
+    let base = read_u64_le(data, offset + 8 + 0x30)?;
+    let size = read_u32_le(data, offset + 8 + 0x40)? as u64;
+    if base < KERNEL_ADDRESS_MIN || size == 0 {
+        return None;
+    }
+    Some((read_dump_string(data, name_offset)?, base, size))
+}
+
+#[cfg(windows)]
+fn is_core_kernel_module(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.starts_with("ntoskrnl") || name.starts_with("ntkrnl") || name == "hal.dll"
+}
+
+/// Driver whose image contains one of the bugcheck addresses (triage dump driver list)
+#[cfg(windows)]
+fn faulting_driver64(data: &[u8], parameters: &[u64; 4]) -> Option<String> {
+    let triage = DUMP_HEADER64_SIZE;
+    let list_offset = read_u32_le(data, triage + 0x30)? as usize;
+    let count = read_u32_le(data, triage + 0x34)? as usize;
+    let pool_offset = read_u32_le(data, triage + 0x38)? as usize;
+    let pool_size = read_u32_le(data, triage + 0x3C)? as usize;
+    if count == 0 || count > 2048 || list_offset >= data.len() || pool_offset >= data.len() {
+        return None;
+    }
+    let pool = pool_offset..pool_offset.saturating_add(pool_size).min(data.len());
+
+    let stride = DRIVER_ENTRY64_STRIDES.iter().copied().find(|&stride| {
+        (0..count.min(8)).all(|i| driver_entry64(data, list_offset + i * stride, pool.clone()).is_some())
+    })?;
+    let drivers: Vec<(String, u64, u64)> = (0..count)
+        .filter_map(|i| driver_entry64(data, list_offset + i * stride, pool.clone()))
+        .collect();
+
+    let hits: Vec<&str> = parameters
+        .iter()
+        .filter(|addr| **addr >= KERNEL_ADDRESS_MIN)
+        .filter_map(|addr| {
+            drivers
+                .iter()
+                .find(|(_, base, size)| *addr >= *base && *addr < base.saturating_add(*size))
+                .map(|(name, _, _)| name.as_str())
+        })
+        .collect();
+
+    // A third-party driver is a better culprit than the kernel that raised the bugcheck
+    hits.iter()
+        .find(|name| !is_core_kernel_module(name))
+        .or_else(|| hits.first())
+        .map(|name| name.to_string())
+}
+
 #[cfg(windows)]
 pub fn analyze_bsod_history() -> BsodAnalysis {
     use std::fs;
@@ -1204,8 +3081,9 @@ pub fn analyze_bsod_history() -> BsodAnalysis {
                         if let Ok(modified) = metadata.modified() {
                             let datetime: chrono::DateTime<chrono::Local> = modified.into();
 
-                            // Default code (we can't easily parse minidump without external lib)
-                            let bug_code = 0x0000009F;
+                            // Unreadable or unknown dump formats still count as a crash
+                            let info = fs::read(&path).ok().and_then(|data| parse_kernel_minidump(&data));
+                            let bug_code = info.as_ref().map(|i| i.bug_check_code).unwrap_or(0);
                             let (name, desc, cause, solution) = get_bsod_info(bug_code);
 
                             crashes.push(BsodCrash {
@@ -1215,7 +3093,10 @@ pub fn analyze_bsod_history() -> BsodAnalysis {
                                 bug_check_name: name.to_string(),
                                 description: desc.to_string(),
                                 probable_cause: cause.to_string(),
-                                driver: None,
+                                driver: info.as_ref().and_then(|i| i.driver.clone()),
+                                parameters: info
+                                    .map(|i| i.parameters.iter().map(|p| format!("0x{:016X}", p)).collect())
+                                    .unwrap_or_default(),
                                 solution: solution.to_string(),
                             });
                         }
@@ -1225,15 +3106,17 @@ pub fn analyze_bsod_history() -> BsodAnalysis {
         }
     }
 
-    // Also check Windows Event Log for BugCheck events
-    let event_crashes = get_bsod_from_event_log();
-    crashes.extend(event_crashes);
+    // Fall back to the Event Log when dumps were cleared (Disk Cleanup, CCleaner...)
+    if crashes.is_empty() {
+        crashes.extend(get_bsod_from_event_log());
+    }
 
     // Sort by date (newest first)
     crashes.sort_by(|a, b| b.date.cmp(&a.date));
 
     // Keep only last 10
     crashes.truncate(10);
+    blame_drivers(&mut crashes);
 
     let total = crashes.len() as u32;
 
@@ -1268,9 +3151,13 @@ fn get_bsod_from_event_log() -> Vec<BsodCrash> {
     use std::process::Command;
 
     let mut crashes = Vec::new();
+    // Minidump scan still runs without PowerShell
+    if !powershell::is_available() {
+        return crashes;
+    }
 
     // Query Windows Event Log for BugCheck events
-    let output = Command::new("powershell")
+    let output = Command::new(powershell::program())
         .args([
             "-NoProfile", "-Command",
             r#"
@@ -1323,6 +3210,7 @@ fn get_bsod_from_event_log() -> Vec<BsodCrash> {
                                 description: desc.to_string(),
                                 probable_cause: cause.to_string(),
                                 driver: None,
+                                parameters: Vec::new(),
                                 solution: solution.to_string(),
                             });
                         }
@@ -1345,6 +3233,235 @@ pub fn analyze_bsod_history() -> BsodAnalysis {
     }
 }
 
+// ============================================
+// RELIABILITY MONITOR (Stability Index)
+// ============================================
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ReliabilityPoint {
+    pub date: String,
+    pub index: f32,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ReliabilityIndex {
+    pub current: Option<f32>,
+    pub average: Option<f32>,
+    pub lowest: Option<ReliabilityPoint>,
+    pub trend: String,   // improving, stable, degrading
+    pub status: String,
+    pub message: String,
+    pub history: Vec<ReliabilityPoint>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ReliabilityEvent {
+    pub date: String,
+    pub source: String,
+    pub product: String,
+    pub event_type: String, // os_failure, app_crash, app_hang, other
+    pub message: String,
+    pub impact: u8,
+}
+
+/// PowerShell returns a bare object when there's a single result
+fn json_as_array(value: serde_json::Value) -> Vec<serde_json::Value> {
+    match value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Null => Vec::new(),
+        other => vec![other],
+    }
+}
+
+/// Samples are hourly: one point per day, keeping the day's lowest index so a crash isn't averaged away
+fn parse_reliability_metrics(json: &str) -> Vec<ReliabilityPoint> {
+    let value = serde_json::from_str::<serde_json::Value>(json.trim()).unwrap_or(serde_json::Value::Null);
+    let mut daily_min: std::collections::BTreeMap<String, f32> = std::collections::BTreeMap::new();
+    for item in json_as_array(value) {
+        let sample = item.get("Date").and_then(|d| d.as_str())
+            .zip(item.get("Index").and_then(|i| i.as_f64()));
+        if let Some((date, index)) = sample {
+            let day = date.get(..10).unwrap_or(date).to_string();
+            let entry = daily_min.entry(day).or_insert(index as f32);
+            *entry = entry.min(index as f32);
+        }
+    }
+    daily_min.into_iter().map(|(date, index)| ReliabilityPoint { date, index }).collect()
+}
+
+fn classify_reliability(index: f32) -> (&'static str, &'static str) {
+    if index >= 8.0 {
+        ("stable", "Systeme tres stable")
+    } else if index >= 5.0 {
+        ("moyen", "Quelques incidents recents, a surveiller")
+    } else if index >= 3.0 {
+        ("instable", "Systeme instable: crashs ou erreurs frequents")
+    } else {
+        ("critique", "Stabilite critique: intervention recommandee")
+    }
+}
+
+fn build_reliability_index(history: Vec<ReliabilityPoint>) -> ReliabilityIndex {
+    if history.is_empty() {
+        return ReliabilityIndex {
+            current: None,
+            average: None,
+            lowest: None,
+            trend: "unknown".into(),
+            status: "inconnu".into(),
+            message: "Aucune donnee de fiabilite (service RAC desactive ?)".into(),
+            history,
+        };
+    }
+
+    let current = history.last().map(|p| p.index);
+    let average = history.iter().map(|p| p.index).sum::<f32>() / history.len() as f32;
+    let lowest = history.iter()
+        .min_by(|a, b| a.index.partial_cmp(&b.index).unwrap_or(std::cmp::Ordering::Equal))
+        .cloned();
+
+    // Compare the last week against the one before it
+    let recent: Vec<f32> = history.iter().rev().take(7).map(|p| p.index).collect();
+    let previous: Vec<f32> = history.iter().rev().skip(7).take(7).map(|p| p.index).collect();
+    let trend = if previous.is_empty() {
+        "stable"
+    } else {
+        let recent_avg = recent.iter().sum::<f32>() / recent.len() as f32;
+        let previous_avg = previous.iter().sum::<f32>() / previous.len() as f32;
+        if recent_avg - previous_avg > 0.5 {
+            "improving"
+        } else if previous_avg - recent_avg > 0.5 {
+            "degrading"
+        } else {
+            "stable"
+        }
+    };
+
+    let (status, message) = classify_reliability(current.unwrap_or(average));
+
+    ReliabilityIndex {
+        current,
+        average: Some(average),
+        lowest,
+        trend: trend.into(),
+        status: status.into(),
+        message: message.into(),
+        history,
+    }
+}
+
+#[cfg(windows)]
+fn classify_reliability_event(source: &str) -> (&'static str, u8) {
+    let source = source.to_lowercase();
+    if source.contains("wer-systemerrorreporting") || source.contains("eventlog") || source.contains("kernel-power") {
+        ("os_failure", 3)
+    } else if source.contains("application error") || source.contains("windows error reporting") {
+        ("app_crash", 2)
+    } else if source.contains("application hang") {
+        ("app_hang", 1)
+    } else {
+        ("other", 0)
+    }
+}
+
+#[cfg(windows)]
+pub fn get_reliability_index() -> ReliabilityIndex {
+    use std::process::Command;
+
+    if !powershell::is_available() {
+        let mut index = build_reliability_index(Vec::new());
+        index.message = powershell::UNAVAILABLE_MESSAGE.to_string();
+        return index;
+    }
+
+    let ps_script = r#"
+try {
+    $since = (Get-Date).Date.AddDays(-29)
+    Get-CimInstance Win32_ReliabilityStabilityMetrics -ErrorAction Stop |
+        Where-Object { $_.TimeGenerated -ge $since } |
+        ForEach-Object { @{ Date = $_.TimeGenerated.ToString('yyyy-MM-dd HH:mm'); Index = [double]$_.SystemStabilityIndex } } |
+        ConvertTo-Json -Compress
+} catch { '[]' }
+"#;
+
+    let json = Command::new(powershell::program())
+        .args(["-NoProfile", "-Command", ps_script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .unwrap_or_default();
+
+    build_reliability_index(parse_reliability_metrics(&json))
+}
+
+#[cfg(windows)]
+pub fn get_reliability_events(days: u32) -> Vec<ReliabilityEvent> {
+    use std::process::Command;
+
+    if !powershell::is_available() {
+        return Vec::new();
+    }
+
+    let ps_script = format!(r#"
+try {{
+    $since = (Get-Date).AddDays(-{})
+    Get-CimInstance Win32_ReliabilityRecords -ErrorAction Stop |
+        Where-Object {{ $_.TimeGenerated -ge $since }} |
+        Sort-Object TimeGenerated -Descending | Select-Object -First 200 |
+        ForEach-Object {{ @{{
+            Date = $_.TimeGenerated.ToString('dd/MM/yyyy HH:mm')
+            Source = $_.SourceName
+            Product = $_.ProductName
+            Message = $_.Message
+        }} }} | ConvertTo-Json -Compress
+}} catch {{ '[]' }}
+"#, days.max(1));
+
+    let json = Command::new(powershell::program())
+        .args(["-NoProfile", "-Command", &ps_script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .unwrap_or_default();
+
+    let value = serde_json::from_str::<serde_json::Value>(json.trim()).unwrap_or(serde_json::Value::Null);
+    let mut events: Vec<ReliabilityEvent> = json_as_array(value)
+        .iter()
+        .filter_map(|item| {
+            let source = item.get("Source")?.as_str()?.to_string();
+            let (event_type, impact) = classify_reliability_event(&source);
+            Some(ReliabilityEvent {
+                date: item.get("Date").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                product: item.get("Product").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                message: item.get("Message").and_then(|v| v.as_str()).unwrap_or_default()
+                    .chars().take(300).collect(),
+                source,
+                event_type: event_type.to_string(),
+                impact,
+            })
+        })
+        .filter(|e| e.impact > 0)
+        .collect();
+
+    // Most impactful first, newest first within the same impact (input already newest first)
+    events.sort_by(|a, b| b.impact.cmp(&a.impact));
+    events.truncate(25);
+    events
+}
+
+#[cfg(not(windows))]
+pub fn get_reliability_index() -> ReliabilityIndex {
+    // No PowerShell output to read here
+    build_reliability_index(parse_reliability_metrics(""))
+}
+
+#[cfg(not(windows))]
+pub fn get_reliability_events(_days: u32) -> Vec<ReliabilityEvent> {
+    Vec::new()
+}
+
 // ============================================
 // INTERNET SPEEDTEST (v3.3.0)
 // ============================================
@@ -1355,87 +3472,221 @@ pub struct SpeedtestResult {
     pub upload_mbps: f64,
     pub ping_ms: u32,
     pub jitter_ms: u32,
+    pub packet_loss_percent: f32,
     pub server: String,
+    pub upload_server: String,  // Differs from `server` when the closest one has no upload endpoint
     pub isp: String,
     pub grade: String,
     pub status: String,
+    pub streams: u32,
 }
 
-pub async fn run_speedtest() -> SpeedtestResult {
-    use std::time::Instant;
+pub const SPEEDTEST_DEFAULT_STREAMS: u32 = 4;
+const SPEEDTEST_MAX_STREAMS: u32 = 16;
+/// A transfer shorter than this is dominated by TCP slow start, retry with a bigger payload
+const SPEEDTEST_MIN_SECS: f64 = 5.0;
+const SPEEDTEST_TARGET_SECS: f64 = 6.0;
+const SPEEDTEST_MAX_ROUNDS: u32 = 4;
+const SPEEDTEST_DOWNLOAD_START_BYTES: u64 = 10_000_000;
+const SPEEDTEST_DOWNLOAD_MAX_BYTES: u64 = 100_000_000;
+const SPEEDTEST_UPLOAD_START_BYTES: u64 = 2_000_000;
+const SPEEDTEST_UPLOAD_MAX_BYTES: u64 = 25_000_000;
+
+const SPEEDTEST_PING_SAMPLES: u32 = 5;
+const CLOUDFLARE_UPLOAD_URL: &str = "https://speed.cloudflare.com/__up";
+
+/// Candidate test server. Servers without an upload endpoint fall back to Cloudflare for upload.
+struct SpeedtestServer {
+    name: &'static str,
+    location: &'static str,
+    ping_host: &'static str,
+    download_url: fn(u64) -> String,
+    upload_url: Option<&'static str>,
+}
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .unwrap_or_default();
+fn cloudflare_download_url(bytes: u64) -> String {
+    format!("https://speed.cloudflare.com/__down?bytes={}", bytes)
+}
 
-    // Test ping (latency)
-    let ping_ms = {
-        let start = Instant::now();
-        match client.get("https://www.google.com").send().await {
-            Ok(_) => start.elapsed().as_millis() as u32,
-            Err(_) => 0,
-        }
+/// OVH only serves fixed-size files, take the smallest one covering the request
+fn ovh_download_url(bytes: u64) -> String {
+    let file = if bytes <= 10_000_000 { "10Mb.dat" } else { "100Mb.dat" };
+    format!("https://proof.ovh.net/files/{}", file)
+}
+
+const SPEEDTEST_SERVERS: &[SpeedtestServer] = &[
+    SpeedtestServer {
+        name: "Cloudflare",
+        location: "Anycast",
+        ping_host: "speed.cloudflare.com",
+        download_url: cloudflare_download_url,
+        upload_url: Some(CLOUDFLARE_UPLOAD_URL),
+    },
+    SpeedtestServer {
+        name: "OVHcloud",
+        location: "Roubaix, France",
+        ping_host: "proof.ovh.net",
+        download_url: ovh_download_url,
+        upload_url: None,
+    },
+];
+
+/// Mean absolute difference between consecutive samples (RFC 3550 style)
+fn jitter_from_samples(samples: &[u32]) -> u32 {
+    if samples.len() < 2 {
+        return 0;
+    }
+    let total: u32 = samples.windows(2).map(|w| w[0].abs_diff(w[1])).sum();
+    total / (samples.len() as u32 - 1)
+}
+
+/// Cloudflare answers from the closest datacenter, its IATA code tells which one
+async fn cloudflare_colo(client: &reqwest::Client) -> Option<String> {
+    let trace = client.get("https://speed.cloudflare.com/cdn-cgi/trace").send().await.ok()?.text().await.ok()?;
+    trace.lines().find_map(|l| l.strip_prefix("colo=")).map(|c| c.trim().to_string())
+}
+
+/// "Cloudflare (Anycast CDG)", "OVHcloud (Roubaix, France)"
+async fn speedtest_server_label(client: &reqwest::Client, server: &SpeedtestServer) -> String {
+    let location = if server.name == "Cloudflare" {
+        cloudflare_colo(client).await.map(|colo| format!("{} {}", server.location, colo))
+    } else {
+        None
     };
+    format!("{} ({})", server.name, location.as_deref().unwrap_or(server.location))
+}
 
-    // Download test (10MB file from Cloudflare)
-    let download_mbps = {
-        let download_url = "https://speed.cloudflare.com/__down?bytes=10000000";
-        let start = Instant::now();
-        match client.get(download_url).send().await {
-            Ok(response) => {
-                match response.bytes().await {
-                    Ok(bytes) => {
-                        let elapsed = start.elapsed().as_secs_f64();
-                        if elapsed > 0.0 {
-                            (bytes.len() as f64 * 8.0 / 1_000_000.0) / elapsed
-                        } else {
-                            0.0
-                        }
-                    }
-                    Err(_) => 0.0,
+/// Runs `streams` concurrent transfers of `bytes_per_stream` each, returns (total bytes, wall seconds)
+async fn parallel_transfer(client: &reqwest::Client, server: &SpeedtestServer, streams: u32, bytes_per_stream: u64, upload: bool) -> Result<(u64, f64), String> {
+    let start = std::time::Instant::now();
+    let upload_url = server.upload_url.unwrap_or(CLOUDFLARE_UPLOAD_URL);
+    let download_url = (server.download_url)(bytes_per_stream);
+    let handles: Vec<_> = (0..streams)
+        .map(|_| {
+            let client = client.clone();
+            let download_url = download_url.clone();
+            tokio::spawn(async move {
+                if upload {
+                    let body = vec![0u8; bytes_per_stream as usize];
+                    let response = client.post(upload_url).body(body).send().await
+                        .map_err(|e| e.to_string())?;
+                    response.error_for_status().map_err(|e| e.to_string())?;
+                    Ok::<u64, String>(bytes_per_stream)
+                } else {
+                    let response = client.get(download_url).send().await.map_err(|e| e.to_string())?;
+                    let bytes = response.error_for_status().map_err(|e| e.to_string())?
+                        .bytes().await.map_err(|e| e.to_string())?;
+                    Ok(bytes.len() as u64)
                 }
+            })
+        })
+        .collect();
+
+    let mut total = 0u64;
+    for handle in handles {
+        total += handle.await.map_err(|e| e.to_string())??;
+    }
+    Ok((total, start.elapsed().as_secs_f64()))
+}
+
+/// Aggregate Mbps, growing the payload until a round lasts at least SPEEDTEST_MIN_SECS
+async fn measure_throughput(client: &reqwest::Client, server: &SpeedtestServer, streams: u32, upload: bool) -> Result<f64, String> {
+    let (mut bytes, max_bytes) = if upload {
+        (SPEEDTEST_UPLOAD_START_BYTES, SPEEDTEST_UPLOAD_MAX_BYTES)
+    } else {
+        (SPEEDTEST_DOWNLOAD_START_BYTES, SPEEDTEST_DOWNLOAD_MAX_BYTES)
+    };
+
+    let mut mbps = 0.0;
+    for _ in 0..SPEEDTEST_MAX_ROUNDS {
+        let (total, secs) = parallel_transfer(client, server, streams, bytes, upload).await?;
+        if secs <= 0.0 {
+            return Err("Mesure invalide".into());
+        }
+        mbps = total as f64 * 8.0 / 1_000_000.0 / secs;
+        if secs >= SPEEDTEST_MIN_SECS || bytes >= max_bytes {
+            break;
+        }
+        bytes = ((bytes as f64 * SPEEDTEST_TARGET_SECS / secs) as u64).min(max_bytes);
+    }
+    Ok(mbps)
+}
+
+/// Fallback: one 10MB download
+async fn single_stream_download(client: &reqwest::Client) -> f64 {
+    let start = std::time::Instant::now();
+    match client.get("https://speed.cloudflare.com/__down?bytes=10000000").send().await {
+        Ok(response) => match response.bytes().await {
+            Ok(bytes) => {
+                let elapsed = start.elapsed().as_secs_f64();
+                if elapsed > 0.0 { (bytes.len() as f64 * 8.0 / 1_000_000.0) / elapsed } else { 0.0 }
             }
             Err(_) => 0.0,
+        },
+        Err(_) => 0.0,
+    }
+}
+
+/// Fallback: one 1MB upload
+async fn single_stream_upload(client: &reqwest::Client) -> f64 {
+    let upload_data = vec![0u8; 1_000_000];
+    let size = upload_data.len();
+    let start = std::time::Instant::now();
+    match client.post("https://speed.cloudflare.com/__up").body(upload_data).send().await {
+        Ok(_) => {
+            let elapsed = start.elapsed().as_secs_f64();
+            if elapsed > 0.0 { (size as f64 * 8.0 / 1_000_000.0) / elapsed } else { 0.0 }
         }
-    };
+        Err(_) => 0.0,
+    }
+}
+
+pub async fn run_speedtest(streams: u32) -> SpeedtestResult {
+    let _busy = HeavyTaskGuard::start();
+    let public_info = fetch_public_ip_info().await;
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .unwrap_or_default();
 
-    // Upload test (1MB to Cloudflare)
-    let upload_mbps = {
-        let upload_data = vec![0u8; 1_000_000];
-        let start = Instant::now();
-        match client.post("https://speed.cloudflare.com/__up")
-            .body(upload_data.clone())
-            .send()
+    // Latency: measure every candidate, keep the closest one for throughput
+    let mut best: Option<(&SpeedtestServer, LatencyResult)> = None;
+    for server in SPEEDTEST_SERVERS {
+        let host = server.ping_host;
+        let latency = tokio::task::spawn_blocking(move || measure_latency(host, SPEEDTEST_PING_SAMPLES))
             .await
-        {
-            Ok(_) => {
-                let elapsed = start.elapsed().as_secs_f64();
-                if elapsed > 0.0 {
-                    (upload_data.len() as f64 * 8.0 / 1_000_000.0) / elapsed
-                } else {
-                    0.0
-                }
-            }
-            Err(_) => 0.0,
+            .unwrap_or_default();
+        let avg_ms = match latency.avg_ms {
+            Some(ms) => ms,
+            None => continue,
+        };
+        let closer = best.as_ref().and_then(|(_, b)| b.avg_ms).map(|b| avg_ms < b).unwrap_or(true);
+        if closer {
+            best = Some((server, latency));
         }
+    }
+    let (server, latency) = best.unwrap_or_else(|| (&SPEEDTEST_SERVERS[0], LatencyResult::default()));
+    let ping_ms = latency.avg_ms.unwrap_or(0);
+    let jitter_ms = latency.jitter_ms.unwrap_or(0);
+    let server_label = speedtest_server_label(&client, server).await;
+    // Servers without an upload endpoint send the upload to Cloudflare, report it as such
+    let upload_server = if server.upload_url.is_some() { server } else { &SPEEDTEST_SERVERS[0] };
+    let upload_server_label = if std::ptr::eq(upload_server, server) {
+        server_label.clone()
+    } else {
+        speedtest_server_label(&client, upload_server).await
     };
 
-    // Calculate jitter (simplified - difference between pings)
-    let jitter_ms = {
-        let mut pings = Vec::new();
-        for _ in 0..3 {
-            let start = Instant::now();
-            if client.get("https://www.google.com").send().await.is_ok() {
-                pings.push(start.elapsed().as_millis() as i32);
-            }
-        }
-        if pings.len() >= 2 {
-            let diffs: Vec<i32> = pings.windows(2).map(|w| (w[1] - w[0]).abs()).collect();
-            (diffs.iter().sum::<i32>() / diffs.len() as i32) as u32
-        } else {
-            0
-        }
+    // Throughput: parallel streams, single stream if any of them fails
+    let streams = streams.clamp(1, SPEEDTEST_MAX_STREAMS);
+    let (download_mbps, download_streams) = match measure_throughput(&client, server, streams, false).await {
+        Ok(mbps) => (mbps, streams),
+        Err(_) => (single_stream_download(&client).await, 1),
+    };
+    let (upload_mbps, upload_streams) = match measure_throughput(&client, upload_server, streams, true).await {
+        Ok(mbps) => (mbps, streams),
+        Err(_) => (single_stream_upload(&client).await, 1),
     };
 
     // Grade based on download speed
@@ -1465,10 +3716,13 @@ pub async fn run_speedtest() -> SpeedtestResult {
         upload_mbps,
         ping_ms,
         jitter_ms,
-        server: "Cloudflare".to_string(),
-        isp: "Auto-detecte".to_string(),
+        packet_loss_percent: latency.loss_percent,
+        server: server_label,
+        upload_server: upload_server_label,
+        isp: public_info.and_then(|i| i.isp).unwrap_or_else(|| "Inconnu".to_string()),
         grade,
         status,
+        streams: download_streams.min(upload_streams),
     }
 }
 
@@ -1496,12 +3750,27 @@ pub struct AppBootImpact {
     pub impact_level: String,
     pub can_disable: bool,
     pub recommendation: String,
+    pub scheduled_task_path: Option<String>,
 }
 
 #[cfg(windows)]
 pub fn analyze_boot_time() -> BootAnalysis {
     use std::process::Command;
 
+    if !powershell::is_available() {
+        return BootAnalysis {
+            total_boot_time_seconds: 0,
+            bios_time_seconds: 0,
+            windows_boot_seconds: 0,
+            desktop_ready_seconds: 0,
+            apps_impact: Vec::new(),
+            grade: "N/A".to_string(),
+            optimization_potential_seconds: 0,
+            recommendations: vec![powershell::UNAVAILABLE_MESSAGE.to_string()],
+            last_boot_time: String::new(),
+        };
+    }
+
     let mut total_boot = 60u32;
     let mut bios_time = 5u32;
     let mut windows_boot = 30u32;
@@ -1510,7 +3779,7 @@ pub fn analyze_boot_time() -> BootAnalysis {
     let mut apps_impact: Vec<AppBootImpact> = Vec::new();
 
     // Get boot time from Windows Event Log
-    let output = Command::new("powershell")
+    let output = Command::new(powershell::program())
         .args([
             "-NoProfile", "-Command",
             r#"
@@ -1553,7 +3822,7 @@ pub fn analyze_boot_time() -> BootAnalysis {
     }
 
     // Get startup apps impact
-    let startup_output = Command::new("powershell")
+    let startup_output = Command::new(powershell::program())
         .args([
             "-NoProfile", "-Command",
             r#"
@@ -1599,6 +3868,7 @@ pub fn analyze_boot_time() -> BootAnalysis {
                                 impact_level,
                                 can_disable,
                                 recommendation,
+                                scheduled_task_path: None,
                             });
                         }
                     }
@@ -1618,10 +3888,27 @@ pub fn analyze_boot_time() -> BootAnalysis {
                 impact_level: if impact > 5.0 { "high" } else if impact > 2.0 { "medium" } else { "low" }.to_string(),
                 can_disable: !is_essential_startup(&item.name),
                 recommendation: get_startup_recommendation(&item.name),
+                scheduled_task_path: None,
             });
         }
     }
 
+    // Task Scheduler entries with logon/boot triggers never show up in Run keys
+    for task in crate::godmode::get_scheduled_startup_tasks() {
+        if task.state == "disabled" || apps_impact.iter().any(|a| a.name.eq_ignore_ascii_case(&task.name)) {
+            continue;
+        }
+        let impact = estimate_app_impact(&task.name);
+        apps_impact.push(AppBootImpact {
+            name: task.name.clone(),
+            impact_seconds: impact,
+            impact_level: if impact > 5.0 { "high" } else if impact > 2.0 { "medium" } else { "low" }.to_string(),
+            can_disable: !is_essential_startup(&task.name),
+            recommendation: format!("Tache planifiee ({}) - {}", task.trigger, get_startup_recommendation(&task.name)),
+            scheduled_task_path: Some(task.path),
+        });
+    }
+
     // Sort by impact (highest first)
     apps_impact.sort_by(|a, b| b.impact_seconds.partial_cmp(&a.impact_seconds).unwrap_or(std::cmp::Ordering::Equal));
     apps_impact.truncate(10);
@@ -1781,36 +4068,34 @@ fn parse_version(version: &str) -> Vec<u32> {
         .collect()
 }
 
-fn version_below(installed: &str, vulnerable_below: &str) -> bool {
-    let inst = parse_version(installed);
-    let vuln = parse_version(vulnerable_below);
-    for i in 0..vuln.len().max(inst.len()) {
-        let a = inst.get(i).copied().unwrap_or(0);
-        let b = vuln.get(i).copied().unwrap_or(0);
-        if a < b { return true; }
-        if a > b { return false; }
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let a = parse_version(a);
+    let b = parse_version(b);
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        if x != y {
+            return x.cmp(&y);
+        }
     }
-    false
+    std::cmp::Ordering::Equal
+}
+
+fn version_below(installed: &str, vulnerable_below: &str) -> bool {
+    compare_versions(installed, vulnerable_below) == std::cmp::Ordering::Less
 }
 
+/// Matches installed apps against the built-in list (works offline)
 #[cfg(windows)]
-pub fn scan_cve_vulnerabilities() -> CveReport {
-    let apps = crate::godmode::get_installed_apps_native();
+fn offline_cve_matches(apps: &[crate::godmode::InstalledApp]) -> Vec<VulnerableApp> {
     let vulns = get_known_vulnerabilities();
     let mut vulnerable_apps = Vec::new();
-    let (mut critical, mut high, mut medium, mut low) = (0u32, 0u32, 0u32, 0u32);
 
-    for app in &apps {
+    for app in apps {
         for (pattern, vuln_ver, cve, severity, cvss, desc) in &vulns {
             if app.name.to_lowercase().contains(&pattern.to_lowercase())
                && !app.version.is_empty()
                && version_below(&app.version, vuln_ver) {
-                match *severity {
-                    "CRITICAL" => critical += 1,
-                    "HIGH" => high += 1,
-                    "MEDIUM" => medium += 1,
-                    _ => low += 1,
-                }
                 vulnerable_apps.push(VulnerableApp {
                     name: app.name.clone(),
                     version: app.version.clone(),
@@ -1824,6 +4109,21 @@ pub fn scan_cve_vulnerabilities() -> CveReport {
         }
     }
 
+    vulnerable_apps
+}
+
+#[cfg(windows)]
+fn build_cve_report(mut vulnerable_apps: Vec<VulnerableApp>) -> CveReport {
+    let (mut critical, mut high, mut medium, mut low) = (0u32, 0u32, 0u32, 0u32);
+    for vuln in &vulnerable_apps {
+        match vuln.severity.as_str() {
+            "CRITICAL" => critical += 1,
+            "HIGH" => high += 1,
+            "MEDIUM" => medium += 1,
+            _ => low += 1,
+        }
+    }
+
     vulnerable_apps.sort_by(|a, b| b.cvss_score.partial_cmp(&a.cvss_score).unwrap_or(std::cmp::Ordering::Equal));
     let total = critical + high + medium + low;
 
@@ -1845,6 +4145,12 @@ pub fn scan_cve_vulnerabilities() -> CveReport {
     }
 }
 
+#[cfg(windows)]
+pub fn scan_cve_vulnerabilities() -> CveReport {
+    let apps = crate::godmode::get_installed_apps_native();
+    build_cve_report(offline_cve_matches(&apps))
+}
+
 #[cfg(not(windows))]
 pub fn scan_cve_vulnerabilities() -> CveReport {
     CveReport {
@@ -1855,6 +4161,274 @@ pub fn scan_cve_vulnerabilities() -> CveReport {
     }
 }
 
+// ============================================
+// CVE ONLINE LOOKUP (NVD)
+// ============================================
+
+#[cfg(windows)]
+const NVD_API_URL: &str = "https://services.nvd.nist.gov/rest/json/cves/2.0";
+/// NVD responses are cached per product for a day
+#[cfg(windows)]
+const NVD_CACHE_TTL_MINUTES: i32 = 24 * 60;
+/// Public NVD quota is 5 requests per 30s without an API key
+#[cfg(windows)]
+const NVD_REQUEST_DELAY_MS: u64 = 6500;
+/// Uncached products beyond this are looked up on the next scans
+#[cfg(windows)]
+const NVD_MAX_LOOKUPS_PER_SCAN: usize = 40;
+
+/// Affected range from an NVD cpeMatch entry
+#[cfg(windows)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct NvdVersionRange {
+    #[serde(default)]
+    vendor: String,
+    product: String,
+    version: String,
+    start_including: Option<String>,
+    start_excluding: Option<String>,
+    end_including: Option<String>,
+    end_excluding: Option<String>,
+}
+
+#[cfg(windows)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct NvdCve {
+    id: String,
+    severity: String,
+    cvss_score: f32,
+    description: String,
+    ranges: Vec<NvdVersionRange>,
+}
+
+/// "7-Zip 23.01 (x64)" -> "7-Zip": drops the version and architecture suffixes
+#[cfg(windows)]
+fn cve_product_name(app_name: &str) -> String {
+    app_name
+        .split_whitespace()
+        .take_while(|word| {
+            !word.starts_with('(')
+                && !word.starts_with(|c: char| c.is_ascii_digit())
+                && !(word.starts_with('v') && word[1..].starts_with(|c: char| c.is_ascii_digit()))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Lowercase, CPE escapes dropped, separators ("_", "-", spaces) collapsed to one space
+#[cfg(windows)]
+fn normalize_cpe_name(name: &str) -> String {
+    name.replace('\\', "")
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '+' && c != '.')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(windows)]
+impl NvdVersionRange {
+    fn contains(&self, installed: &str) -> bool {
+        use std::cmp::Ordering::*;
+
+        // Exact version in the CPE itself
+        if self.version != "*" && self.version != "-" {
+            return compare_versions(installed, &self.version) == Equal;
+        }
+        // A wildcard CPE without bounds would flag every version, ignore it
+        if self.start_including.is_none() && self.start_excluding.is_none()
+            && self.end_including.is_none() && self.end_excluding.is_none() {
+            return false;
+        }
+
+        let check = |bound: &Option<String>, ok: &[std::cmp::Ordering]| {
+            bound.as_ref().map(|b| ok.contains(&compare_versions(installed, b))).unwrap_or(true)
+        };
+        check(&self.start_including, &[Greater, Equal])
+            && check(&self.start_excluding, &[Greater])
+            && check(&self.end_including, &[Less, Equal])
+            && check(&self.end_excluding, &[Less])
+    }
+
+    /// CPE product ("notepad\+\+", "7-zip"), optionally prefixed by its vendor
+    /// ("google" + "chrome"), must equal the app name once both are normalized
+    fn matches_product(&self, product: &str) -> bool {
+        let cpe = normalize_cpe_name(&self.product);
+        let product = normalize_cpe_name(product);
+        if cpe.is_empty() || product.is_empty() {
+            return false;
+        }
+        product == cpe || product == format!("{} {}", normalize_cpe_name(&self.vendor), cpe)
+    }
+}
+
+#[cfg(windows)]
+fn parse_nvd_response(body: &serde_json::Value) -> Vec<NvdCve> {
+    let mut cves = Vec::new();
+
+    for item in body["vulnerabilities"].as_array().into_iter().flatten() {
+        let cve = &item["cve"];
+        let id = cve["id"].as_str().unwrap_or_default().to_string();
+        if id.is_empty() {
+            continue;
+        }
+
+        // Prefer CVSS v3.1, then v3.0, then v2
+        let metric = ["cvssMetricV31", "cvssMetricV30", "cvssMetricV2"]
+            .iter()
+            .find_map(|k| cve["metrics"][k].as_array().and_then(|a| a.first()));
+        let (cvss_score, severity) = match metric {
+            Some(m) => (
+                m["cvssData"]["baseScore"].as_f64().unwrap_or(0.0) as f32,
+                m["cvssData"]["baseSeverity"].as_str()
+                    .or_else(|| m["baseSeverity"].as_str())
+                    .unwrap_or("LOW")
+                    .to_uppercase(),
+            ),
+            None => (0.0, "LOW".to_string()),
+        };
+
+        let description = cve["descriptions"].as_array().into_iter().flatten()
+            .find(|d| d["lang"] == "en")
+            .and_then(|d| d["value"].as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let mut ranges = Vec::new();
+        for config in cve["configurations"].as_array().into_iter().flatten() {
+            for node in config["nodes"].as_array().into_iter().flatten() {
+                for m in node["cpeMatch"].as_array().into_iter().flatten() {
+                    if !m["vulnerable"].as_bool().unwrap_or(false) {
+                        continue;
+                    }
+                    // cpe:2.3:a:vendor:product:version:...
+                    let parts: Vec<&str> = m["criteria"].as_str().unwrap_or_default().split(':').collect();
+                    if parts.len() < 6 || parts[2] != "a" {
+                        continue;
+                    }
+                    let bound = |k: &str| m[k].as_str().map(|s| s.to_string());
+                    ranges.push(NvdVersionRange {
+                        vendor: parts[3].to_string(),
+                        product: parts[4].to_string(),
+                        version: parts[5].to_string(),
+                        start_including: bound("versionStartIncluding"),
+                        start_excluding: bound("versionStartExcluding"),
+                        end_including: bound("versionEndIncluding"),
+                        end_excluding: bound("versionEndExcluding"),
+                    });
+                }
+            }
+        }
+
+        if !ranges.is_empty() {
+            cves.push(NvdCve { id, severity, cvss_score, description, ranges });
+        }
+    }
+
+    cves
+}
+
+#[cfg(windows)]
+async fn fetch_nvd_cves(client: &reqwest::Client, product: &str) -> Result<Vec<NvdCve>, String> {
+    let response = client
+        .get(NVD_API_URL)
+        .query(&[("keywordSearch", product), ("resultsPerPage", "2000")])
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("API error: {}", response.status()));
+    }
+
+    let body: serde_json::Value = response.json().await.map_err(|e| format!("JSON error: {}", e))?;
+    Ok(parse_nvd_response(&body))
+}
+
+/// Offline list + live NVD lookup for every installed app, merged into one report
+#[cfg(windows)]
+pub async fn scan_cve_online(db: &crate::database::Database) -> CveReport {
+    // Registry walk is blocking, keep it off the async runtime
+    let apps = tokio::task::spawn_blocking(crate::godmode::get_installed_apps_native)
+        .await
+        .unwrap_or_default();
+    let mut vulnerable_apps = offline_cve_matches(&apps);
+
+    let client = match reqwest::Client::builder().timeout(std::time::Duration::from_secs(15)).build() {
+        Ok(c) => c,
+        Err(_) => return build_cve_report(vulnerable_apps),
+    };
+
+    let mut cves_by_product: std::collections::HashMap<String, Vec<NvdCve>> = std::collections::HashMap::new();
+    let mut lookups = 0;
+
+    for app in apps.iter().filter(|a| !a.version.is_empty()) {
+        let product = cve_product_name(&app.name);
+        if product.len() < 3 {
+            continue;
+        }
+        let key = product.to_lowercase();
+
+        if !cves_by_product.contains_key(&key) {
+            let cache_key = format!("cve_nvd:{}", key);
+            let cached = db.get_cache(&cache_key).ok().flatten()
+                .and_then(|json| serde_json::from_str::<Vec<NvdCve>>(&json).ok());
+
+            let cves = match cached {
+                Some(cves) => cves,
+                None if lookups < NVD_MAX_LOOKUPS_PER_SCAN => {
+                    if lookups > 0 {
+                        tokio::time::sleep(std::time::Duration::from_millis(NVD_REQUEST_DELAY_MS)).await;
+                    }
+                    lookups += 1;
+                    match fetch_nvd_cves(&client, &product).await {
+                        Ok(cves) => {
+                            if let Ok(json) = serde_json::to_string(&cves) {
+                                let _ = db.set_cache(&cache_key, &json, Some(NVD_CACHE_TTL_MINUTES));
+                            }
+                            cves
+                        }
+                        Err(e) => {
+                            println!("[CVE] NVD lookup failed for {}: {}", product, e);
+                            Vec::new()
+                        }
+                    }
+                }
+                None => Vec::new(),
+            };
+            cves_by_product.insert(key.clone(), cves);
+        }
+
+        for cve in &cves_by_product[&key] {
+            let affected: Vec<&NvdVersionRange> = cve.ranges.iter()
+                .filter(|r| r.matches_product(&product) && r.contains(&app.version))
+                .collect();
+            if affected.is_empty() {
+                continue;
+            }
+            if vulnerable_apps.iter().any(|v| v.name == app.name && v.cve_id == cve.id) {
+                continue;
+            }
+            vulnerable_apps.push(VulnerableApp {
+                name: app.name.clone(),
+                version: app.version.clone(),
+                cve_id: cve.id.clone(),
+                severity: cve.severity.clone(),
+                description: cve.description.clone(),
+                fix_version: affected.iter().find_map(|r| r.end_excluding.clone()),
+                cvss_score: cve.cvss_score,
+            });
+        }
+    }
+
+    build_cve_report(vulnerable_apps)
+}
+
+#[cfg(not(windows))]
+pub async fn scan_cve_online(_db: &crate::database::Database) -> CveReport {
+    scan_cve_vulnerabilities()
+}
+
 // ============================================
 // FAILURE PREDICTION (v3.4.0)
 // ============================================
@@ -1896,8 +4470,137 @@ pub struct PredictedIssue {
     pub prevention: String,
 }
 
+// ============================================
+// SMART TREND (history-based degradation)
+// ============================================
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SmartTrend {
+    pub serial: String,
+    pub model: String,
+    pub points: Vec<SmartHistoryEntry>,
+    pub span_days: u32,
+    pub reallocated_delta: i64,
+    pub pending_delta: i64,
+    pub uncorrectable_delta: i64,
+    pub wear_delta: i64,
+    pub degrading: bool,
+    pub verdict: String,
+}
+
+fn history_span_days(points: &[SmartHistoryEntry]) -> u32 {
+    let parse = |ts: &str| chrono::NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S").ok();
+    match (points.first().and_then(|p| parse(&p.timestamp)), points.last().and_then(|p| parse(&p.timestamp))) {
+        (Some(first), Some(last)) => (last - first).num_days().max(0) as u32,
+        _ => 0,
+    }
+}
+
+fn attribute_delta(points: &[SmartHistoryEntry], get: fn(&SmartHistoryEntry) -> Option<i64>) -> i64 {
+    let first = points.iter().find_map(get);
+    let last = points.iter().rev().find_map(get);
+    match (first, last) {
+        (Some(a), Some(b)) => b - a,
+        _ => 0,
+    }
+}
+
+/// Compare the oldest and newest snapshots of a drive and produce a verdict
+pub fn analyze_smart_trend(serial: &str, points: Vec<SmartHistoryEntry>) -> SmartTrend {
+    let model = points.iter().rev().find_map(|p| p.model.clone()).unwrap_or_default();
+    let span_days = history_span_days(&points);
+    let reallocated_delta = attribute_delta(&points, |p| p.reallocated_sectors);
+    let pending_delta = attribute_delta(&points, |p| p.pending_sectors);
+    let uncorrectable_delta = attribute_delta(&points, |p| p.uncorrectable_sectors);
+    let wear_delta = attribute_delta(&points, |p| p.percentage_used);
+    let span_label = span_days.max(1);
+
+    let (degrading, verdict) = if points.len() < 2 {
+        (false, "Historique insuffisant pour une tendance".to_string())
+    } else if reallocated_delta > 0 {
+        (true, format!(
+            "Secteurs realloues +{} en {} jours - remplacement conseille",
+            reallocated_delta, span_label
+        ))
+    } else if pending_delta > 0 {
+        (true, format!(
+            "Secteurs en attente +{} en {} jours - sauvegardez vos donnees",
+            pending_delta, span_label
+        ))
+    } else if uncorrectable_delta > 0 {
+        (true, format!(
+            "Secteurs irrecuperables +{} en {} jours - sauvegardez vos donnees",
+            uncorrectable_delta, span_label
+        ))
+    } else if wear_delta >= 5 {
+        (true, format!(
+            "Usure NVMe +{}% en {} jours - surveillez l'usure",
+            wear_delta, span_label
+        ))
+    } else {
+        (false, format!("Stable sur {} jours", span_label))
+    };
+
+    SmartTrend {
+        serial: serial.to_string(),
+        model,
+        points,
+        span_days,
+        reallocated_delta,
+        pending_delta,
+        uncorrectable_delta,
+        wear_delta,
+        degrading,
+        verdict,
+    }
+}
+
+#[cfg(windows)]
+fn apply_smart_trends(trends: &[SmartTrend], disk_risk: &mut DiskRisk, predicted_issues: &mut Vec<PredictedIssue>) {
+    for trend in trends.iter().filter(|t| t.degrading) {
+        disk_risk.warning_signs.push(format!("{}: {}", trend.model, trend.verdict));
+
+        if trend.reallocated_delta > 0 || trend.pending_delta > 0 {
+            disk_risk.health_percent = disk_risk.health_percent.min(40);
+            disk_risk.risk_level = "Eleve".into();
+            // Rough extrapolation: each new bad sector per day shortens the horizon
+            let per_day = (trend.reallocated_delta + trend.pending_delta) as f32 / trend.span_days.max(1) as f32;
+            disk_risk.estimated_lifespan_days = Some((90.0 / per_day.max(1.0)) as u32);
+            predicted_issues.push(PredictedIssue {
+                component: "Disque".into(),
+                issue: format!("Degradation progressive ({})", trend.model),
+                probability_percent: 60,
+                timeframe: "1-3 mois".into(),
+                impact: "Perte de donnees".into(),
+                prevention: "Sauvegardez et planifiez le remplacement".into(),
+            });
+        } else {
+            disk_risk.health_percent = disk_risk.health_percent.min(70);
+            if disk_risk.risk_level == "Faible" {
+                disk_risk.risk_level = "Moyen".into();
+            }
+        }
+    }
+}
+
+/// Model and status of the first disk straight from WMI (no PowerShell)
+#[cfg(windows)]
+fn wmi_first_disk_status() -> Option<(String, String)> {
+    use wmi::{COMLibrary, WMIConnection, Variant};
+
+    let wmi_con = WMIConnection::new(COMLibrary::new().ok()?).ok()?;
+    let disks: Vec<std::collections::HashMap<String, Variant>> =
+        crate::wmi_cache::cached_query(&wmi_con, "SELECT Model, Status FROM Win32_DiskDrive").ok()?;
+    let disk = disks.first()?;
+    let text = |key: &str| match disk.get(key) {
+        Some(Variant::String(s)) => s.clone(),
+        _ => String::new(),
+    };
+    Some((text("Model"), text("Status")))
+}
+
 #[cfg(windows)]
-pub fn predict_failures() -> FailurePrediction {
+pub fn predict_failures(smart_trends: &[SmartTrend]) -> FailurePrediction {
     use std::process::Command;
 
     let mut disk_risk = DiskRisk {
@@ -1910,29 +4613,36 @@ pub fn predict_failures() -> FailurePrediction {
     };
     let mut predicted_issues = Vec::new();
     let mut recommendations = Vec::new();
+    let ps_available = powershell::is_available();
 
     // Disk SMART check
     let ps_disk = r#"
 $d = Get-CimInstance Win32_DiskDrive | Select-Object -First 1
 @{ Model=$d.Model; Status=$d.Status } | ConvertTo-Json -Compress
 "#;
-    if let Ok(out) = Command::new("powershell").args(["-NoProfile", "-Command", ps_disk])
-        .creation_flags(CREATE_NO_WINDOW).output() {
-        if let Ok(json) = String::from_utf8(out.stdout) {
-            if let Ok(data) = serde_json::from_str::<serde_json::Value>(json.trim()) {
-                disk_risk.model = data.get("Model").and_then(|v| v.as_str()).unwrap_or("Unknown").into();
-                let status = data.get("Status").and_then(|v| v.as_str()).unwrap_or("OK");
-                if status == "Pred Fail" {
-                    disk_risk.health_percent = 25;
-                    disk_risk.risk_level = "Critique".into();
-                    disk_risk.warning_signs.push("SMART predit defaillance".into());
-                    predicted_issues.push(PredictedIssue {
-                        component: "Disque".into(), issue: "Defaillance imminente".into(),
-                        probability_percent: 85, timeframe: "1-4 semaines".into(),
-                        impact: "Perte de donnees".into(), prevention: "Sauvegardez et remplacez".into(),
-                    });
-                }
-            }
+    let disk_status = if ps_available {
+        Command::new(powershell::program()).args(["-NoProfile", "-Command", ps_disk])
+            .creation_flags(CREATE_NO_WINDOW).output().ok()
+            .and_then(|out| String::from_utf8(out.stdout).ok())
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(json.trim()).ok())
+            .map(|data| (
+                data.get("Model").and_then(|v| v.as_str()).unwrap_or("Unknown").to_string(),
+                data.get("Status").and_then(|v| v.as_str()).unwrap_or("OK").to_string(),
+            ))
+    } else {
+        wmi_first_disk_status()
+    };
+    if let Some((model, status)) = disk_status {
+        disk_risk.model = model;
+        if status == "Pred Fail" {
+            disk_risk.health_percent = 25;
+            disk_risk.risk_level = "Critique".into();
+            disk_risk.warning_signs.push("SMART predit defaillance".into());
+            predicted_issues.push(PredictedIssue {
+                component: "Disque".into(), issue: "Defaillance imminente".into(),
+                probability_percent: 85, timeframe: "1-4 semaines".into(),
+                impact: "Perte de donnees".into(), prevention: "Sauvegardez et remplacez".into(),
+            });
         }
     }
 
@@ -1941,8 +4651,8 @@ $d = Get-CimInstance Win32_DiskDrive | Select-Object -First 1
 try { $s = Get-CimInstance -Namespace root\wmi -ClassName MSStorageDriver_FailurePredictStatus -EA Stop
 @{Predict=$s.PredictFailure} | ConvertTo-Json -Compress } catch { '{}' }
 "#;
-    if let Ok(out) = Command::new("powershell").args(["-NoProfile", "-Command", ps_smart])
-        .creation_flags(CREATE_NO_WINDOW).output() {
+    if let Ok(out) = powershell::require().and_then(|ps| Command::new(ps).args(["-NoProfile", "-Command", ps_smart])
+        .creation_flags(CREATE_NO_WINDOW).output().map_err(|e| e.to_string())) {
         if let Ok(json) = String::from_utf8(out.stdout) {
             if let Ok(data) = serde_json::from_str::<serde_json::Value>(json.trim()) {
                 if data.get("Predict").and_then(|v| v.as_bool()).unwrap_or(false) {
@@ -1953,33 +4663,41 @@ try { $s = Get-CimInstance -Namespace root\wmi -ClassName MSStorageDriver_Failur
         }
     }
 
+    // SMART history trends (slow degradation)
+    apply_smart_trends(smart_trends, &mut disk_risk, &mut predicted_issues);
+
     // RAM info
     let sys = sysinfo::System::new_all();
     ram_risk.total_gb = sys.total_memory() as f32 / 1_073_741_824.0;
 
     // Memory Diagnostic results
-    let ps_ram = r#"
-try { $e = Get-WinEvent -FilterHashtable @{LogName='System';ProviderName='Microsoft-Windows-MemoryDiagnostics-Results'} -MaxEvents 1 -EA Stop
-@{Date=$e.TimeCreated.ToString('dd/MM/yyyy');Msg=$e.Message} | ConvertTo-Json -Compress } catch { '{}' }
-"#;
-    if let Ok(out) = Command::new("powershell").args(["-NoProfile", "-Command", ps_ram])
-        .creation_flags(CREATE_NO_WINDOW).output() {
-        if let Ok(json) = String::from_utf8(out.stdout) {
-            if let Ok(data) = serde_json::from_str::<serde_json::Value>(json.trim()) {
-                ram_risk.last_test_date = data.get("Date").and_then(|v| v.as_str()).map(|s| s.into());
-                if let Some(msg) = data.get("Msg").and_then(|v| v.as_str()) {
-                    if msg.to_lowercase().contains("error") {
-                        ram_risk.risk_level = "Eleve".into();
-                        ram_risk.error_count = 1;
-                        ram_risk.warning_signs.push("Erreurs RAM detectees".into());
-                    }
-                }
-            }
-        }
+    let memory_test = get_memory_test_result();
+    ram_risk.last_test_date = memory_test.date.clone();
+    if memory_test.status == "fail" {
+        ram_risk.risk_level = "Eleve".into();
+        ram_risk.error_count = memory_test.error_count.max(1);
+        ram_risk.warning_signs.push("Erreurs RAM detectees par le diagnostic memoire".into());
+        predicted_issues.push(PredictedIssue {
+            component: "RAM".into(), issue: "Barrette memoire defectueuse".into(),
+            probability_percent: 80, timeframe: "Immediat".into(),
+            impact: "Ecrans bleus et corruption de fichiers".into(),
+            prevention: "Testez les barrettes une par une et remplacez la fautive".into(),
+        });
+    } else if memory_test.error_count > 0 {
+        // Latest run passed but an older one failed: intermittent fault
+        ram_risk.risk_level = "Moyen".into();
+        ram_risk.error_count = memory_test.error_count;
+        ram_risk.warning_signs.push("Erreurs RAM lors d'un test precedent".into());
     }
 
-    let overall_risk = ((100 - disk_risk.health_percent) as f32 * 0.7
-                       + if ram_risk.error_count > 0 { 50.0 } else { 0.0 } * 0.3) as u8;
+    let ram_score = match ram_risk.risk_level.as_str() {
+        "Eleve" => 100.0,
+        "Moyen" => 50.0,
+        _ => 0.0,
+    };
+    let disk_part = (100 - disk_risk.health_percent) as f32 * 0.7 + ram_score * 0.3;
+    // A failed memory test alone is enough to flag the machine
+    let overall_risk = if ram_risk.error_count > 0 { disk_part.max(ram_score * 0.6) } else { disk_part }.min(100.0) as u8;
 
     if disk_risk.health_percent < 50 {
         recommendations.push("URGENT: Sauvegardez vos donnees".into());
@@ -1990,12 +4708,15 @@ try { $e = Get-WinEvent -FilterHashtable @{LogName='System';ProviderName='Micros
     if recommendations.is_empty() {
         recommendations.push("Aucun signe de defaillance. Continuez les sauvegardes.".into());
     }
+    if !ps_available {
+        recommendations.push(format!("{}: analyse RAM et SMART detaillee ignoree", powershell::UNAVAILABLE_MESSAGE));
+    }
 
     FailurePrediction { disk_risk, ram_risk, overall_risk_percent: overall_risk, predicted_issues, recommendations }
 }
 
 #[cfg(not(windows))]
-pub fn predict_failures() -> FailurePrediction {
+pub fn predict_failures(_smart_trends: &[SmartTrend]) -> FailurePrediction {
     FailurePrediction {
         disk_risk: DiskRisk { model: "N/A".into(), health_percent: 100, risk_level: "N/A".into(), estimated_lifespan_days: None, warning_signs: Vec::new() },
         ram_risk: RamRisk { total_gb: 0.0, risk_level: "N/A".into(), error_count: 0, last_test_date: None, warning_signs: Vec::new() },
@@ -2004,3 +4725,365 @@ pub fn predict_failures() -> FailurePrediction {
         recommendations: vec!["Disponible uniquement sur Windows".into()],
     }
 }
+
+// ============================================
+// MEMORY TEST (Windows Memory Diagnostic)
+// ============================================
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MemoryTestResult {
+    pub status: String,           // pass, fail, none
+    pub error_count: u32,         // failed runs among the recent results
+    pub date: Option<String>,
+    pub message: String,
+}
+
+/// MemoryDiagnostics-Results: 1201 no errors, 1202 hardware errors detected
+#[cfg(windows)]
+fn memory_test_failed(event_id: u64, message: &str) -> bool {
+    let msg = message.to_lowercase();
+    event_id == 1202
+        || msg.contains("hardware errors")
+        || msg.contains("erreurs materielles")
+        || msg.contains("erreurs matérielles")
+}
+
+#[cfg(windows)]
+pub fn get_memory_test_result() -> MemoryTestResult {
+    use std::process::Command;
+
+    let mut result = MemoryTestResult {
+        status: "none".into(),
+        error_count: 0,
+        date: None,
+        message: "Aucun test memoire trouve".into(),
+    };
+
+    let script = r#"
+[Console]::OutputEncoding = [System.Text.Encoding]::UTF8
+try { $e = Get-WinEvent -FilterHashtable @{LogName='System';ProviderName='Microsoft-Windows-MemoryDiagnostics-Results'} -MaxEvents 10 -EA Stop
+@($e | ForEach-Object { @{Id=$_.Id;Date=$_.TimeCreated.ToString('dd/MM/yyyy HH:mm');Msg=$_.Message} }) | ConvertTo-Json -Compress } catch { '[]' }
+"#;
+    let output = match powershell::require().and_then(|ps| Command::new(ps).args(["-NoProfile", "-Command", script])
+        .creation_flags(CREATE_NO_WINDOW).output().map_err(|e| e.to_string())) {
+        Ok(out) => out,
+        Err(e) => {
+            result.message = e;
+            return result;
+        }
+    };
+
+    let events = match serde_json::from_str::<serde_json::Value>(String::from_utf8_lossy(&output.stdout).trim()) {
+        Ok(serde_json::Value::Array(items)) => items,
+        Ok(serde_json::Value::Null) | Err(_) => Vec::new(),
+        Ok(single) => vec![single],
+    };
+
+    // Newest first: the latest run gives the status, older failures still count
+    let runs: Vec<(bool, Option<String>, String)> = events.iter()
+        .map(|e| {
+            let msg = e["Msg"].as_str().unwrap_or_default().to_string();
+            let failed = memory_test_failed(e["Id"].as_u64().unwrap_or(0), &msg);
+            (failed, e["Date"].as_str().map(|s| s.to_string()), msg)
+        })
+        .collect();
+
+    if let Some((failed, date, msg)) = runs.first() {
+        result.status = if *failed { "fail" } else { "pass" }.into();
+        result.error_count = runs.iter().filter(|(f, _, _)| *f).count() as u32;
+        result.date = date.clone();
+        result.message = if msg.is_empty() { "Resultat sans message".into() } else { msg.clone() };
+    }
+    result
+}
+
+#[cfg(not(windows))]
+pub fn get_memory_test_result() -> MemoryTestResult {
+    MemoryTestResult {
+        status: "none".into(),
+        error_count: 0,
+        date: None,
+        message: "Test memoire disponible uniquement sur Windows".into(),
+    }
+}
+
+/// Schedules the Windows Memory Diagnostic for the next boot.
+/// mdsched.exe only offers an interactive dialog, so the boot entry is queued
+/// with bcdedit (what mdsched does under the hood) and mdsched is the fallback.
+#[cfg(windows)]
+pub fn schedule_memory_test(reboot_now: bool) -> Result<String, String> {
+    use std::process::Command;
+
+    let queued = Command::new("bcdedit")
+        .args(["/bootsequence", "{memdiag}", "/addfirst"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+
+    if !queued {
+        Command::new("mdsched.exe")
+            .spawn()
+            .map_err(|e| format!("Impossible de lancer mdsched: {}", e))?;
+        return Ok("Outil de diagnostic memoire ouvert: choisissez le redemarrage".into());
+    }
+
+    if reboot_now {
+        Command::new("shutdown")
+            .args(["/r", "/t", "5", "/c", "Microdiag: test de la memoire au redemarrage"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .spawn()
+            .map_err(|e| format!("Redemarrage impossible: {}", e))?;
+        return Ok("Test memoire programme, redemarrage dans 5 secondes".into());
+    }
+    Ok("Test memoire programme au prochain demarrage".into())
+}
+
+#[cfg(not(windows))]
+pub fn schedule_memory_test(_reboot_now: bool) -> Result<String, String> {
+    Err("Test memoire disponible uniquement sur Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn smart_point(timestamp: &str, reallocated: i64, pending: i64) -> SmartHistoryEntry {
+        SmartHistoryEntry {
+            id: None,
+            serial: "WD-123".to_string(),
+            model: Some("WDC WD10EZEX".to_string()),
+            timestamp: timestamp.to_string(),
+            reallocated_sectors: Some(reallocated),
+            pending_sectors: Some(pending),
+            uncorrectable_sectors: Some(0),
+            temperature_c: Some(38),
+            power_on_hours: Some(12000),
+            percentage_used: None,
+        }
+    }
+
+    fn process(name: &str, exe_path: &str, cpu_percent: f32) -> ProcessInfo {
+        ProcessInfo {
+            name: name.to_string(),
+            pid: 4242,
+            cpu_percent,
+            memory_mb: 150.0,
+            memory_percent: 1.0,
+            status: "Run".to_string(),
+            description: String::new(),
+            category: "other".to_string(),
+            exe_path: exe_path.to_string(),
+            suspicion_reasons: Vec::new(),
+        }
+    }
+
+    fn signed(is_signed: bool) -> SignatureInfo {
+        SignatureInfo { is_signed, publisher: if is_signed { "CN=Discord Inc.".into() } else { String::new() } }
+    }
+
+    #[test]
+    fn roaming_alone_is_not_suspicious() {
+        let config = SuspicionConfig::default();
+        let discord = process("Discord.exe", r"C:\Users\marie\AppData\Roaming\Discord\Discord.exe", 5.0);
+
+        assert!(suspicion_reasons(&discord, &config, None).is_empty());
+        assert!(suspicion_reasons(&discord, &config, Some(&signed(true))).is_empty());
+        let busy = process("Discord.exe", &discord.exe_path, 80.0);
+        assert!(suspicion_reasons(&busy, &config, None).is_empty());
+    }
+
+    #[test]
+    fn roaming_with_second_signal_is_suspicious() {
+        let config = SuspicionConfig::default();
+        let roaming = process("updater.exe", r"C:\Users\marie\AppData\Roaming\upd\updater.exe", 1.0);
+        let reasons = suspicion_reasons(&roaming, &config, Some(&signed(false)));
+        assert!(reasons.contains(&"Execute depuis AppData/Telechargements".to_string()));
+        assert!(reasons.contains(&"Executable non signe".to_string()));
+
+        let temp = process("setup.exe", r"C:\Users\marie\AppData\Local\Temp\x\setup.exe", 1.0);
+        assert_eq!(suspicion_reasons(&temp, &config, None), vec!["Execute depuis un dossier temporaire".to_string()]);
+    }
+
+    fn benchmark(drive: &str, seq_read_mbps: f64, rand_read_iops: u64, latency_us: u64) -> DiskBenchmark {
+        DiskBenchmark {
+            drive: drive.to_string(),
+            seq_read_mbps,
+            seq_write_mbps: 400.0,
+            rand_read_iops,
+            rand_write_iops: 5000,
+            rand_read_mbps: 0.0,
+            rand_write_mbps: 0.0,
+            queue_depth: 8,
+            rand_read_iops_qd: 0,
+            rand_write_iops_qd: 0,
+            rand_read_mbps_qd: 0.0,
+            rand_write_mbps_qd: 0.0,
+            latency_us,
+            score: 0,
+            grade: "N/A".into(),
+        }
+    }
+
+    #[test]
+    fn benchmark_comparison_picks_winners_and_headline() {
+        let comparison = compare_benchmarks(
+            benchmark("C", 3000.0, 40000, 80),
+            benchmark("D", 150.0, 400, 4000),
+        );
+
+        let delta = |metric: &str| comparison.deltas.iter().find(|d| d.metric == metric).unwrap().clone();
+        assert_eq!(delta("seq_read_mbps").winner, "C");
+        assert_eq!(delta("seq_read_mbps").ratio, 20.0);
+        assert_eq!(delta("seq_write_mbps").winner, "egalite");
+        assert_eq!(delta("latency_us").winner, "C");
+        assert_eq!(delta("latency_us").ratio, 50.0);
+        assert_eq!(delta("rand_read_iops").ratio, 100.0);
+        assert_eq!(comparison.verdict, "Le disque C est 100.0x plus rapide en lecture aleatoire 4K");
+    }
+
+    #[test]
+    fn identical_benchmarks_are_equivalent() {
+        let comparison = compare_benchmarks(benchmark("C", 500.0, 9000, 100), benchmark("D", 500.0, 9000, 100));
+        assert!(comparison.deltas.iter().all(|d| d.winner == "egalite"));
+        assert_eq!(comparison.verdict, "Performances equivalentes");
+    }
+
+    #[test]
+    fn smart_trend_flags_increasing_reallocated_sectors() {
+        let trend = analyze_smart_trend("WD-123", vec![
+            smart_point("2024-05-01 10:00:00", 2, 0),
+            smart_point("2024-05-05 10:00:00", 4, 0),
+            smart_point("2024-05-11 10:00:00", 10, 0),
+        ]);
+
+        assert!(trend.degrading);
+        assert_eq!(trend.reallocated_delta, 8);
+        assert_eq!(trend.span_days, 10);
+        assert_eq!(trend.model, "WDC WD10EZEX");
+        assert_eq!(trend.verdict, "Secteurs realloues +8 en 10 jours - remplacement conseille");
+    }
+
+    #[test]
+    fn reliability_keeps_each_days_lowest_sample() {
+        let json = r#"[
+            {"Date":"2024-05-02 09:00","Index":7.1},
+            {"Date":"2024-05-01 08:00","Index":8.4},
+            {"Date":"2024-05-01 14:00","Index":3.2},
+            {"Date":"2024-05-01 20:00","Index":3.9},
+            {"Date":"2024-05-02 18:00","Index":7.4}
+        ]"#;
+
+        let points = parse_reliability_metrics(json);
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].date, "2024-05-01");
+        assert_eq!(points[0].index, 3.2);
+        assert_eq!(points[1].date, "2024-05-02");
+        assert_eq!(points[1].index, 7.1);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn uncached_random_reads_use_aligned_blocks() {
+        use std::os::windows::fs::FileExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bench.tmp");
+        std::fs::write(&path, vec![7u8; BLOCK_SIZE_RAND * 16]).unwrap();
+
+        let file = open_benchmark_file(path.to_str().unwrap(), false).unwrap();
+        let mut block = Box::new(RandBlock([0u8; BLOCK_SIZE_RAND]));
+        assert_eq!(file.seek_read(&mut block.0, 5 * BLOCK_SIZE_RAND as u64).unwrap(), BLOCK_SIZE_RAND);
+        assert!(block.0.iter().all(|b| *b == 7));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn crash_blames_the_last_driver_installed_before_it() {
+        let log = "\
+>>>  [Device Install (Hardware initiated) - PCI\\VEN_10DE&DEV_2504]
+>>>  Section start 2024/05/01 10:11:12.345
+     dvi:           Description: NVIDIA GeForce RTX 3060
+<<<  Section end 2024/05/01 10:12:00.000
+<<<  [Exit status: SUCCESS]
+>>>  [Device Install (Hardware initiated) - PCI\\VEN_8086&DEV_15F3]
+>>>  Section start 2024/05/10 09:00:00.000
+     dvi:           Description: Intel(R) Ethernet Controller I225-V
+<<<  [Exit status: SUCCESS]
+>>>  [Device Install (Hardware initiated) - USB\\VID_046D]
+>>>  Section start 2024/05/12 09:00:00.000
+     dvi:           Description: Logitech Broken Device
+<<<  [Exit status: FAILURE(0xe0000203)]
+>>>  [Device Install (Hardware initiated) - PCI\\VEN_10EC&DEV_8168]
+>>>  Section start 2024/06/20 08:00:00.000
+     dvi:           Description: Realtek PCIe GbE Family Controller
+<<<  [Exit status: SUCCESS]
+";
+        let installs = parse_driver_installs(log);
+        assert_eq!(installs.len(), 3);
+        assert!(!installs.contains_key("logitech broken device"));
+
+        let driver = |name: &str| DriverInfo {
+            name: name.to_string(),
+            version: "1.0".to_string(),
+            driver_type: "Network".to_string(),
+            manufacturer: String::new(),
+            // Vendor build date, must not be mistaken for the install date
+            driver_date: "01/01/2019".to_string(),
+            status: "OK".to_string(),
+            driver_age_days: Some(2000),
+            is_outdated: true,
+            gpu_vendor: None,
+            download_url: None,
+        };
+        let drivers = vec![
+            driver("NVIDIA GeForce RTX 3060"),
+            driver("Intel(R) Ethernet Controller I225-V"),
+            driver("Realtek PCIe GbE Family Controller"),
+        ];
+        let at = |s: &str| chrono::NaiveDateTime::parse_from_str(s, "%d/%m/%Y %H:%M").unwrap();
+
+        // The Realtek driver came after the crash, it can't be the cause
+        let (suspect, installed) = driver_installed_before(&drivers, &installs, at("15/05/2024 22:00")).unwrap();
+        assert_eq!(suspect.name, "Intel(R) Ethernet Controller I225-V");
+        assert_eq!(installed, at("10/05/2024 09:00"));
+
+        assert!(driver_installed_before(&drivers, &installs, at("01/04/2024 12:00")).is_none());
+        assert!(driver_installed_before(&drivers, &installs, at("01/12/2024 12:00")).is_none());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn cpe_product_must_match_the_app_name_exactly() {
+        let range = |vendor: &str, product: &str| NvdVersionRange {
+            vendor: vendor.to_string(),
+            product: product.to_string(),
+            version: "*".to_string(),
+            start_including: None,
+            start_excluding: None,
+            end_including: None,
+            end_excluding: Some("2.0".to_string()),
+        };
+
+        assert!(range("notepad-plus-plus", "notepad\\+\\+").matches_product("Notepad++"));
+        assert!(range("7-zip", "7-zip").matches_product("7-Zip"));
+        assert!(range("videolan", "vlc_media_player").matches_product("VLC media player"));
+        assert!(range("google", "chrome").matches_product("Google Chrome"));
+
+        assert!(!range("google", "chrome").matches_product("Chrome Remote Desktop Host"));
+        assert!(!range("zip", "zip").matches_product("7-Zip"));
+        assert!(!range("microsoft", "office").matches_product("Microsoft Office Click-to-Run"));
+    }
+
+    #[test]
+    fn smart_trend_stable_when_counters_do_not_move() {
+        let trend = analyze_smart_trend("WD-123", vec![
+            smart_point("2024-05-01 10:00:00", 2, 1),
+            smart_point("2024-05-11 10:00:00", 2, 1),
+        ]);
+
+        assert!(!trend.degrading);
+        assert_eq!(trend.verdict, "Stable sur 10 jours");
+    }
+}
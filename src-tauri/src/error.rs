@@ -0,0 +1,144 @@
+// ============================================
+// MICRODIAG AGENT - Errors
+// Typed command errors, serialized as { code, message } for the UI
+// ============================================
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub enum MicrodiagError {
+    /// Request never got an answer (offline, DNS, timeout on the wire)
+    NetworkError(String),
+    PermissionDenied(String),
+    NotFound(String),
+    WmiError(String),
+    Io(String),
+    Parse(String),
+    NotSupportedOnPlatform(String),
+    Database(String),
+    InvalidInput(String),
+    /// Script exited with a non-zero code, message is its stderr
+    ScriptFailed(String),
+    Cancelled(String),
+    Timeout(String),
+    /// Worker thread panicked or was aborted
+    Internal(String),
+}
+
+impl MicrodiagError {
+    /// Stable identifier the UI branches on; never rename, only add
+    pub fn code(&self) -> &'static str {
+        match self {
+            MicrodiagError::NetworkError(_) => "network_error",
+            MicrodiagError::PermissionDenied(_) => "permission_denied",
+            MicrodiagError::NotFound(_) => "not_found",
+            MicrodiagError::WmiError(_) => "wmi_error",
+            MicrodiagError::Io(_) => "io",
+            MicrodiagError::Parse(_) => "parse",
+            MicrodiagError::NotSupportedOnPlatform(_) => "not_supported_on_platform",
+            MicrodiagError::Database(_) => "database",
+            MicrodiagError::InvalidInput(_) => "invalid_input",
+            MicrodiagError::ScriptFailed(_) => "script_failed",
+            MicrodiagError::Cancelled(_) => "cancelled",
+            MicrodiagError::Timeout(_) => "timeout",
+            MicrodiagError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            MicrodiagError::NetworkError(m)
+            | MicrodiagError::PermissionDenied(m)
+            | MicrodiagError::NotFound(m)
+            | MicrodiagError::WmiError(m)
+            | MicrodiagError::Io(m)
+            | MicrodiagError::Parse(m)
+            | MicrodiagError::NotSupportedOnPlatform(m)
+            | MicrodiagError::Database(m)
+            | MicrodiagError::InvalidInput(m)
+            | MicrodiagError::ScriptFailed(m)
+            | MicrodiagError::Cancelled(m)
+            | MicrodiagError::Timeout(m)
+            | MicrodiagError::Internal(m) => m,
+        }
+    }
+
+    /// WMI query failure on Windows; elsewhere there is no WMI at all
+    pub fn wmi(message: impl Into<String>) -> Self {
+        if cfg!(windows) {
+            MicrodiagError::WmiError(message.into())
+        } else {
+            MicrodiagError::NotSupportedOnPlatform(message.into())
+        }
+    }
+
+    /// Supabase answered with an error status
+    pub fn from_status(status: reqwest::StatusCode) -> Self {
+        let message = format!("API error: {}", status);
+        match status.as_u16() {
+            401 | 403 => MicrodiagError::PermissionDenied(message),
+            404 => MicrodiagError::NotFound(message),
+            _ => MicrodiagError::NetworkError(message),
+        }
+    }
+}
+
+impl fmt::Display for MicrodiagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for MicrodiagError {}
+
+impl Serialize for MicrodiagError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("MicrodiagError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.end()
+    }
+}
+
+impl From<rusqlite::Error> for MicrodiagError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e {
+            rusqlite::Error::QueryReturnedNoRows => MicrodiagError::NotFound(e.to_string()),
+            _ => MicrodiagError::Database(e.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for MicrodiagError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::PermissionDenied => MicrodiagError::PermissionDenied(e.to_string()),
+            std::io::ErrorKind::NotFound => MicrodiagError::NotFound(e.to_string()),
+            std::io::ErrorKind::TimedOut => MicrodiagError::Timeout(e.to_string()),
+            _ => MicrodiagError::Io(e.to_string()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for MicrodiagError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            MicrodiagError::Parse(format!("JSON error: {}", e))
+        } else {
+            MicrodiagError::NetworkError(format!("Network error: {}", e))
+        }
+    }
+}
+
+impl From<serde_json::Error> for MicrodiagError {
+    fn from(e: serde_json::Error) -> Self {
+        MicrodiagError::Parse(format!("JSON error: {}", e))
+    }
+}
+
+impl From<tokio::task::JoinError> for MicrodiagError {
+    fn from(e: tokio::task::JoinError) -> Self {
+        MicrodiagError::Internal(e.to_string())
+    }
+}
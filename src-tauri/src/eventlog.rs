@@ -0,0 +1,135 @@
+// ============================================
+// MICRODIAG AGENT - Event Log Viewer
+// Last Critical/Error/Warning events without opening Event Viewer
+// ============================================
+
+use serde::Serialize;
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+#[cfg(windows)]
+use crate::powershell;
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EventEntry {
+    pub time: String,
+    pub source: String,
+    pub event_id: u32,
+    pub level: String,
+    pub message: String,
+}
+
+const SUPPORTED_LOGS: &[&str] = &["System", "Application", "Security"];
+#[cfg(windows)]
+const MAX_EVENTS_LIMIT: i32 = 500;
+#[cfg(windows)]
+const MESSAGE_MAX_CHARS: usize = 2000;
+
+/// Canonical log name (case-insensitive match against the supported ones)
+fn resolve_log(log_name: &str) -> Result<&'static str, String> {
+    SUPPORTED_LOGS.iter()
+        .find(|l| l.eq_ignore_ascii_case(log_name.trim()))
+        .copied()
+        .ok_or_else(|| format!("Journal non supporte: {} (System, Application ou Security)", log_name))
+}
+
+/// Event levels for Get-WinEvent: 1 Critical, 2 Error, 3 Warning
+fn resolve_levels(level: &str) -> Result<&'static str, String> {
+    match level.trim().to_lowercase().as_str() {
+        "critical" => Ok("1"),
+        "error" => Ok("2"),
+        "warning" => Ok("3"),
+        "" | "all" => Ok("1,2,3"),
+        other => Err(format!("Niveau non supporte: {} (Critical, Error ou Warning)", other)),
+    }
+}
+
+/// ConvertTo-Json emits a bare object when there is a single event
+#[cfg(windows)]
+fn json_items(value: serde_json::Value) -> Vec<serde_json::Value> {
+    match value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Null => Vec::new(),
+        single => vec![single],
+    }
+}
+
+#[cfg(windows)]
+fn parse_events(json: &str) -> Vec<EventEntry> {
+    let value: serde_json::Value = match serde_json::from_str(json.trim()) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+
+    json_items(value)
+        .iter()
+        .map(|e| EventEntry {
+            time: e["Time"].as_str().unwrap_or_default().to_string(),
+            source: e["Source"].as_str().unwrap_or_default().to_string(),
+            event_id: e["Id"].as_u64().unwrap_or(0) as u32,
+            level: e["Level"].as_str().unwrap_or_default().to_string(),
+            message: e["Message"].as_str()
+                .unwrap_or("(message indisponible)")
+                .chars()
+                .take(MESSAGE_MAX_CHARS)
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+pub fn get_event_log(log_name: &str, level: &str, max_events: i32) -> Result<Vec<EventEntry>, String> {
+    use std::process::Command;
+
+    let log = resolve_log(log_name)?;
+    let levels = resolve_levels(level)?;
+    let max_events = max_events.clamp(1, MAX_EVENTS_LIMIT);
+    let program = powershell::require()?;
+
+    // Inputs are whitelisted above, safe to inline
+    let script = format!(
+        r#"
+[Console]::OutputEncoding = [System.Text.Encoding]::UTF8
+try {{
+    $events = Get-WinEvent -FilterHashtable @{{LogName='{log}'; Level={levels}}} -MaxEvents {max} -ErrorAction Stop
+    @($events | ForEach-Object {{
+        [PSCustomObject]@{{
+            Time = $_.TimeCreated.ToString('dd/MM/yyyy HH:mm:ss')
+            Source = $_.ProviderName
+            Id = $_.Id
+            Level = $_.LevelDisplayName
+            Message = $_.Message
+        }}
+    }}) | ConvertTo-Json -Compress
+}} catch [System.Exception] {{
+    if ($_.FullyQualifiedErrorId -like 'NoMatchingEventsFound*') {{ '[]' }}
+    else {{ Write-Error $_.Exception.Message; exit 1 }}
+}}
+"#,
+        log = log,
+        levels = levels,
+        max = max_events
+    );
+
+    let output = Command::new(program)
+        .args(["-NoProfile", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Erreur lancement: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Lecture du journal {} impossible: {}", log, stderr.trim()));
+    }
+
+    Ok(parse_events(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(windows))]
+pub fn get_event_log(log_name: &str, level: &str, _max_events: i32) -> Result<Vec<EventEntry>, String> {
+    resolve_log(log_name)?;
+    resolve_levels(level)?;
+    Err("Journal d'evenements disponible uniquement sur Windows".to_string())
+}
@@ -7,10 +7,13 @@
 use serde::{Deserialize, Serialize};
 use std::process::{Command, Stdio};
 use std::io::{BufRead, BufReader};
+use crate::i18n;
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 #[cfg(windows)]
+use crate::powershell;
+#[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 // ============================================
@@ -23,6 +26,33 @@ pub struct FixResult {
     pub message: String,
     pub output: Vec<String>,
     pub requires_reboot: bool,
+    /// What SFC/DISM reported, only set by the system file repairs
+    #[serde(default)]
+    pub repair_outcome: Option<RepairOutcome>,
+}
+
+/// Final verdict printed by sfc /scannow
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SfcOutcome {
+    NoViolations,
+    Repaired,
+    Unrepairable,   // Corrupt files found, some could not be fixed
+    CouldNotRun,    // Pending repair or servicing stack issue
+}
+
+/// Final status printed by DISM
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DismOutcome {
+    Success,
+    Failed { error_code: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepairOutcome {
+    pub sfc: Option<SfcOutcome>,
+    pub dism: Option<DismOutcome>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,22 +82,37 @@ pub struct StreamOutput {
     pub progress: Option<u8>,
 }
 
+/// Combined progress of the auto maintenance sequence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceProgress {
+    pub fix_id: String,
+    pub step_name: String,
+    pub step_index: usize,  // 1-based
+    pub step_count: usize,
+    pub line: String,
+    pub line_type: String,
+    pub percent: u8,        // Whole sequence
+}
+
 // ============================================
 // FIX CATEGORIES DEFINITION
 // ============================================
 
 pub fn get_fix_categories() -> Vec<FixCategory> {
+    let lang = i18n::language();
+    let t = |key: &str| i18n::t(key, lang);
+
     vec![
         FixCategory {
             id: "network".into(),
-            name: "Reseau".into(),
-            description: "Reparation des problemes de connexion".into(),
+            name: t("fixcat.network.name"),
+            description: t("fixcat.network.description"),
             icon: "wifi".into(),
             fixes: vec![
                 FixItem {
                     id: "flush_dns".into(),
-                    name: "Vider le cache DNS".into(),
-                    description: "Efface le cache DNS pour resoudre les problemes de resolution de noms".into(),
+                    name: t("fix.flush_dns.name"),
+                    description: t("fix.flush_dns.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: true,
@@ -75,8 +120,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "reset_winsock".into(),
-                    name: "Reinitialiser Winsock".into(),
-                    description: "Repare la pile reseau Windows (sockets)".into(),
+                    name: t("fix.reset_winsock.name"),
+                    description: t("fix.reset_winsock.description"),
                     risk_level: "medium".into(),
                     requires_reboot: true,
                     requires_admin: true,
@@ -84,8 +129,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "reset_tcpip".into(),
-                    name: "Reinitialiser TCP/IP".into(),
-                    description: "Remet a zero la configuration TCP/IP".into(),
+                    name: t("fix.reset_tcpip.name"),
+                    description: t("fix.reset_tcpip.description"),
                     risk_level: "medium".into(),
                     requires_reboot: true,
                     requires_admin: true,
@@ -93,8 +138,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "reset_network_complete".into(),
-                    name: "Reinitialisation reseau complete".into(),
-                    description: "DNS + Winsock + TCP/IP + Release/Renew IP".into(),
+                    name: t("fix.reset_network_complete.name"),
+                    description: t("fix.reset_network_complete.description"),
                     risk_level: "medium".into(),
                     requires_reboot: true,
                     requires_admin: true,
@@ -102,25 +147,34 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "reset_firewall".into(),
-                    name: "Reinitialiser le pare-feu".into(),
-                    description: "Remet le pare-feu Windows aux parametres par defaut".into(),
+                    name: t("fix.reset_firewall.name"),
+                    description: t("fix.reset_firewall.description"),
                     risk_level: "medium".into(),
                     requires_reboot: false,
                     requires_admin: true,
                     estimated_time: "~10 sec".into(),
                 },
+                FixItem {
+                    id: "reset_network_adapter".into(),
+                    name: t("fix.reset_network_adapter.name"),
+                    description: t("fix.reset_network_adapter.description"),
+                    risk_level: "medium".into(),
+                    requires_reboot: false,
+                    requires_admin: true,
+                    estimated_time: "~15 sec".into(),
+                },
             ],
         },
         FixCategory {
             id: "system".into(),
-            name: "Systeme".into(),
-            description: "Reparation des fichiers systeme Windows".into(),
+            name: t("fixcat.system.name"),
+            description: t("fixcat.system.description"),
             icon: "settings".into(),
             fixes: vec![
                 FixItem {
                     id: "sfc_scannow".into(),
-                    name: "SFC /scannow".into(),
-                    description: "Analyse et repare les fichiers systeme corrompus".into(),
+                    name: t("fix.sfc_scannow.name"),
+                    description: t("fix.sfc_scannow.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: true,
@@ -128,8 +182,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "dism_health".into(),
-                    name: "DISM Repair".into(),
-                    description: "Repare l'image systeme Windows (Component Store)".into(),
+                    name: t("fix.dism_health.name"),
+                    description: t("fix.dism_health.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: true,
@@ -137,8 +191,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "sfc_dism_full".into(),
-                    name: "Reparation complete (DISM + SFC)".into(),
-                    description: "DISM puis SFC pour une reparation approfondie".into(),
+                    name: t("fix.sfc_dism_full.name"),
+                    description: t("fix.sfc_dism_full.description"),
                     risk_level: "low".into(),
                     requires_reboot: true,
                     requires_admin: true,
@@ -146,34 +200,70 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "chkdsk_scan".into(),
-                    name: "Verifier le disque (CHKDSK)".into(),
-                    description: "Analyse le disque pour erreurs (lecture seule)".into(),
+                    name: t("fix.chkdsk_scan.name"),
+                    description: t("fix.chkdsk_scan.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: true,
                     estimated_time: "~5-10 min".into(),
                 },
+                FixItem {
+                    id: "optimize_drive".into(),
+                    name: t("fix.optimize_drive.name"),
+                    description: t("fix.optimize_drive.description"),
+                    risk_level: "low".into(),
+                    requires_reboot: false,
+                    requires_admin: true,
+                    estimated_time: "~1 min (SSD), ~30 min+ (HDD)".into(),
+                },
                 FixItem {
                     id: "restore_point".into(),
-                    name: "Creer un point de restauration".into(),
-                    description: "Sauvegarde l'etat actuel du systeme".into(),
+                    name: t("fix.restore_point.name"),
+                    description: t("fix.restore_point.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: true,
                     estimated_time: "~1-2 min".into(),
                 },
+                FixItem {
+                    id: "boot_safe_minimal".into(),
+                    name: t("fix.boot_safe_minimal.name"),
+                    description: t("fix.boot_safe_minimal.description"),
+                    risk_level: "high".into(),
+                    requires_reboot: true,
+                    requires_admin: true,
+                    estimated_time: "~5 sec".into(),
+                },
+                FixItem {
+                    id: "boot_safe_network".into(),
+                    name: t("fix.boot_safe_network.name"),
+                    description: t("fix.boot_safe_network.description"),
+                    risk_level: "high".into(),
+                    requires_reboot: true,
+                    requires_admin: true,
+                    estimated_time: "~5 sec".into(),
+                },
+                FixItem {
+                    id: "boot_normal".into(),
+                    name: t("fix.boot_normal.name"),
+                    description: t("fix.boot_normal.description"),
+                    risk_level: "high".into(),
+                    requires_reboot: true,
+                    requires_admin: true,
+                    estimated_time: "~5 sec".into(),
+                },
             ],
         },
         FixCategory {
             id: "explorer".into(),
-            name: "Explorateur".into(),
-            description: "Reparation de l'explorateur Windows".into(),
+            name: t("fixcat.explorer.name"),
+            description: t("fixcat.explorer.description"),
             icon: "folder".into(),
             fixes: vec![
                 FixItem {
                     id: "restart_explorer".into(),
-                    name: "Redemarrer l'Explorateur".into(),
-                    description: "Tue et relance explorer.exe".into(),
+                    name: t("fix.restart_explorer.name"),
+                    description: t("fix.restart_explorer.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: false,
@@ -181,8 +271,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "reset_icon_cache".into(),
-                    name: "Vider le cache des icones".into(),
-                    description: "Supprime iconcache.db et redemarrer l'explorateur".into(),
+                    name: t("fix.reset_icon_cache.name"),
+                    description: t("fix.reset_icon_cache.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: false,
@@ -190,8 +280,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "reset_thumbnail_cache".into(),
-                    name: "Vider le cache des miniatures".into(),
-                    description: "Supprime les fichiers thumbcache".into(),
+                    name: t("fix.reset_thumbnail_cache.name"),
+                    description: t("fix.reset_thumbnail_cache.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: false,
@@ -199,8 +289,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "reset_folder_options".into(),
-                    name: "Reinitialiser les options de dossier".into(),
-                    description: "Remet les options d'affichage par defaut".into(),
+                    name: t("fix.reset_folder_options.name"),
+                    description: t("fix.reset_folder_options.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: false,
@@ -210,14 +300,14 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
         },
         FixCategory {
             id: "windows_update".into(),
-            name: "Windows Update".into(),
-            description: "Reparation des mises a jour Windows".into(),
+            name: t("fixcat.windows_update.name"),
+            description: t("fixcat.windows_update.description"),
             icon: "download".into(),
             fixes: vec![
                 FixItem {
                     id: "clear_update_cache".into(),
-                    name: "Vider le cache des mises a jour".into(),
-                    description: "Supprime les fichiers telecharges de Windows Update".into(),
+                    name: t("fix.clear_update_cache.name"),
+                    description: t("fix.clear_update_cache.description"),
                     risk_level: "medium".into(),
                     requires_reboot: false,
                     requires_admin: true,
@@ -225,8 +315,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "reset_windows_update".into(),
-                    name: "Reinitialiser Windows Update".into(),
-                    description: "Arrete les services, vide le cache, et redemarre".into(),
+                    name: t("fix.reset_windows_update.name"),
+                    description: t("fix.reset_windows_update.description"),
                     risk_level: "medium".into(),
                     requires_reboot: true,
                     requires_admin: true,
@@ -234,8 +324,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "reregister_dlls".into(),
-                    name: "Reenregistrer les DLLs Windows Update".into(),
-                    description: "Execute regsvr32 sur les DLLs necessaires".into(),
+                    name: t("fix.reregister_dlls.name"),
+                    description: t("fix.reregister_dlls.description"),
                     risk_level: "medium".into(),
                     requires_reboot: false,
                     requires_admin: true,
@@ -245,14 +335,14 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
         },
         FixCategory {
             id: "cleanup".into(),
-            name: "Nettoyage".into(),
-            description: "Nettoyage des fichiers temporaires".into(),
+            name: t("fixcat.cleanup.name"),
+            description: t("fixcat.cleanup.description"),
             icon: "trash".into(),
             fixes: vec![
                 FixItem {
                     id: "clean_temp".into(),
-                    name: "Nettoyer les fichiers temporaires".into(),
-                    description: "Supprime les fichiers temp de l'utilisateur".into(),
+                    name: t("fix.clean_temp.name"),
+                    description: t("fix.clean_temp.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: false,
@@ -260,8 +350,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "clean_system_temp".into(),
-                    name: "Nettoyer les fichiers systeme temp".into(),
-                    description: "Supprime les fichiers temp systeme (Windows\\Temp)".into(),
+                    name: t("fix.clean_system_temp.name"),
+                    description: t("fix.clean_system_temp.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: true,
@@ -269,8 +359,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "clean_prefetch".into(),
-                    name: "Vider le cache Prefetch".into(),
-                    description: "Supprime les fichiers de prefetch Windows".into(),
+                    name: t("fix.clean_prefetch.name"),
+                    description: t("fix.clean_prefetch.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: true,
@@ -278,8 +368,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "disk_cleanup".into(),
-                    name: "Nettoyage de disque avance".into(),
-                    description: "Lance cleanmgr avec options systeme".into(),
+                    name: t("fix.disk_cleanup.name"),
+                    description: t("fix.disk_cleanup.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: true,
@@ -289,14 +379,14 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
         },
         FixCategory {
             id: "services".into(),
-            name: "Services".into(),
-            description: "Gestion des services Windows".into(),
+            name: t("fixcat.services.name"),
+            description: t("fixcat.services.description"),
             icon: "zap".into(),
             fixes: vec![
                 FixItem {
                     id: "restart_audio".into(),
-                    name: "Redemarrer le service audio".into(),
-                    description: "Redemarre Windows Audio et AudioEndpointBuilder".into(),
+                    name: t("fix.restart_audio.name"),
+                    description: t("fix.restart_audio.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: true,
@@ -304,8 +394,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "restart_print_spooler".into(),
-                    name: "Redemarrer le spooler d'impression".into(),
-                    description: "Redemarre le service Print Spooler".into(),
+                    name: t("fix.restart_print_spooler.name"),
+                    description: t("fix.restart_print_spooler.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: true,
@@ -313,8 +403,8 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
                 },
                 FixItem {
                     id: "restart_search".into(),
-                    name: "Redemarrer Windows Search".into(),
-                    description: "Redemarre le service d'indexation".into(),
+                    name: t("fix.restart_search.name"),
+                    description: t("fix.restart_search.description"),
                     risk_level: "low".into(),
                     requires_reboot: false,
                     requires_admin: true,
@@ -333,7 +423,20 @@ pub fn get_fix_categories() -> Vec<FixCategory> {
 pub fn run_powershell_streaming<F>(command: &str, mut on_output: F) -> FixResult
 where F: FnMut(StreamOutput)
 {
-    let mut cmd = Command::new("powershell");
+    let ps = match powershell::require() {
+        Ok(ps) => ps,
+        Err(e) => {
+            return FixResult {
+                success: false,
+                message: e,
+                output: vec![],
+                requires_reboot: false,
+                repair_outcome: None,
+            };
+        }
+    };
+
+    let mut cmd = Command::new(ps);
     cmd.args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-Command", command])
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -347,6 +450,7 @@ where F: FnMut(StreamOutput)
                 message: format!("Erreur lancement PowerShell: {}", e),
                 output: vec![],
                 requires_reboot: false,
+                repair_outcome: None,
             };
         }
     };
@@ -355,19 +459,17 @@ where F: FnMut(StreamOutput)
     let reader = BufReader::new(stdout);
     let mut output_lines = Vec::new();
 
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            let line_type = detect_line_type(&line);
-            let progress = extract_progress(&line);
+    read_console_lines(reader, |line| {
+        let line_type = detect_line_type(&line);
+        let progress = extract_progress(&line);
 
-            output_lines.push(line.clone());
-            on_output(StreamOutput {
-                line,
-                line_type,
-                progress,
-            });
-        }
-    }
+        output_lines.push(line.clone());
+        on_output(StreamOutput {
+            line,
+            line_type,
+            progress,
+        });
+    });
 
     let status = child.wait().unwrap_or_else(|_| std::process::ExitStatus::default());
 
@@ -376,6 +478,7 @@ where F: FnMut(StreamOutput)
         message: if status.success() { "Operation terminee avec succes".into() } else { "Operation terminee avec erreurs".into() },
         output: output_lines,
         requires_reboot: false,
+        repair_outcome: None,
     }
 }
 
@@ -393,10 +496,178 @@ where F: FnMut(StreamOutput)
         message: "Disponible uniquement sur Windows".into(),
         output: vec![],
         requires_reboot: false,
+        repair_outcome: None,
+    }
+}
+
+/// Pending console line: UTF-8/OEM bytes and UTF-16 units are kept apart until decoded,
+/// a line can start in one encoding and end in the other
+#[cfg(windows)]
+#[derive(Default)]
+struct ConsoleLine {
+    text: String,
+    bytes: Vec<u8>,
+    units: Vec<u16>,
+    after_cr: bool,
+}
+
+#[cfg(windows)]
+impl ConsoleLine {
+    fn settle(&mut self) {
+        if !self.bytes.is_empty() {
+            self.text.push_str(&String::from_utf8_lossy(&self.bytes));
+            self.bytes.clear();
+        }
+        if !self.units.is_empty() {
+            self.text.push_str(&String::from_utf16_lossy(&self.units));
+            self.units.clear();
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.bytes.is_empty() && self.units.is_empty()
+    }
+
+    /// \r ends the line right away (progress redraw), \n only if \r did not already
+    fn line_break(&mut self, cr: bool, on_line: &mut impl FnMut(String)) {
+        if cr {
+            if !self.is_empty() {
+                self.flush(on_line);
+            }
+        } else if !(self.after_cr && self.is_empty()) {
+            self.flush(on_line);
+        }
+        self.after_cr = cr;
+    }
+
+    fn push_byte(&mut self, byte: u8, on_line: &mut impl FnMut(String)) {
+        match byte {
+            b'\r' | b'\n' => self.line_break(byte == b'\r', on_line),
+            _ => {
+                if !self.units.is_empty() {
+                    self.settle();
+                }
+                self.bytes.push(byte);
+                self.after_cr = false;
+            }
+        }
+    }
+
+    fn push_unit(&mut self, unit: u16, on_line: &mut impl FnMut(String)) {
+        match unit {
+            0x0D | 0x0A => self.line_break(unit == 0x0D, on_line),
+            0xFEFF => {}
+            _ => {
+                if !self.bytes.is_empty() {
+                    self.settle();
+                }
+                self.units.push(unit);
+                self.after_cr = false;
+            }
+        }
     }
+
+    fn flush(&mut self, on_line: &mut impl FnMut(String)) {
+        self.settle();
+        on_line(std::mem::take(&mut self.text));
+    }
+}
+
+#[cfg(windows)]
+#[derive(Clone, Copy, PartialEq)]
+enum ConsoleEncoding {
+    /// Start of a line, decided from its first two bytes
+    Undecided,
+    Bytes,
+    /// Low byte of the current unit, once read
+    Utf16(Option<u8>),
+}
+
+/// Byte-level decoder: PowerShell writes UTF-8/OEM lines and sfc, run from it, writes
+/// UTF-16LE. The encoding is picked again for every line: "V\0" is UTF-16, "[I" is not.
+#[cfg(windows)]
+struct ConsoleDecoder {
+    encoding: ConsoleEncoding,
+    lookahead: Vec<u8>,
+    line: ConsoleLine,
 }
 
-fn detect_line_type(line: &str) -> String {
+#[cfg(windows)]
+impl ConsoleDecoder {
+    fn new() -> Self {
+        ConsoleDecoder { encoding: ConsoleEncoding::Undecided, lookahead: Vec::new(), line: ConsoleLine::default() }
+    }
+
+    fn feed(&mut self, byte: u8, on_line: &mut impl FnMut(String)) {
+        match self.encoding {
+            ConsoleEncoding::Undecided => {
+                // High byte of the UTF-16 "\n" that ended the previous line
+                if self.lookahead.is_empty() && byte == 0 {
+                    return;
+                }
+                self.lookahead.push(byte);
+                if self.lookahead.len() < 2 {
+                    return;
+                }
+                let utf16 = self.lookahead[1] == 0 || self.lookahead[..] == [0xFF, 0xFE];
+                self.encoding = if utf16 { ConsoleEncoding::Utf16(None) } else { ConsoleEncoding::Bytes };
+                for byte in std::mem::take(&mut self.lookahead) {
+                    self.feed(byte, on_line);
+                }
+            }
+            // A NUL mid-line: UTF-16 output started right after the previous (ASCII) byte
+            ConsoleEncoding::Bytes if byte == 0 => self.encoding = ConsoleEncoding::Utf16(None),
+            ConsoleEncoding::Bytes => {
+                self.line.push_byte(byte, on_line);
+                if byte == b'\n' {
+                    self.encoding = ConsoleEncoding::Undecided;
+                }
+            }
+            ConsoleEncoding::Utf16(None) => self.encoding = ConsoleEncoding::Utf16(Some(byte)),
+            ConsoleEncoding::Utf16(Some(low)) => {
+                let unit = u16::from_le_bytes([low, byte]);
+                self.line.push_unit(unit, on_line);
+                self.encoding = if unit == 0x0A { ConsoleEncoding::Undecided } else { ConsoleEncoding::Utf16(None) };
+            }
+        }
+    }
+
+    fn finish(mut self, on_line: &mut impl FnMut(String)) {
+        for byte in std::mem::take(&mut self.lookahead) {
+            self.line.push_byte(byte, on_line);
+        }
+        if !self.line.is_empty() {
+            self.line.flush(on_line);
+        }
+    }
+}
+
+/// Split on \n and on bare \r: DISM redraws its progress bar in place with \r,
+/// so a line-based reader would only see it once the operation is over.
+/// Lines may be UTF-8/OEM or UTF-16LE (sfc), see `ConsoleDecoder`.
+#[cfg(windows)]
+pub(crate) fn read_console_lines<R: BufRead>(mut reader: R, mut on_line: impl FnMut(String)) {
+    let mut decoder = ConsoleDecoder::new();
+
+    loop {
+        let consumed = match reader.fill_buf() {
+            Ok([]) => break,
+            Ok(buf) => {
+                for &byte in buf {
+                    decoder.feed(byte, &mut on_line);
+                }
+                buf.len()
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        };
+        reader.consume(consumed);
+    }
+
+    decoder.finish(&mut on_line);
+}
+
+pub(crate) fn detect_line_type(line: &str) -> String {
     let lower = line.to_lowercase();
     if lower.contains("erreur") || lower.contains("error") || lower.contains("failed") || lower.contains("echec") {
         "error".into()
@@ -411,7 +682,20 @@ fn detect_line_type(line: &str) -> String {
     }
 }
 
-fn extract_progress(line: &str) -> Option<u8> {
+/// DISM bar: "[=====                      10.0%                          ]"
+fn extract_dism_progress(line: &str) -> Option<u8> {
+    let re_bar = regex::Regex::new(r"\[[=\s]*(\d{1,3}(?:[.,]\d+)?)\s*%[=\s]*\]").ok()?;
+    let caps = re_bar.captures(line)?;
+    let percent: f32 = caps[1].replace(',', ".").parse().ok()?;
+    Some(percent.clamp(0.0, 100.0) as u8)
+}
+
+pub(crate) fn extract_progress(line: &str) -> Option<u8> {
+    // The generic pattern would read "50.0%" as 0
+    if let Some(percent) = extract_dism_progress(line) {
+        return Some(percent);
+    }
+
     // Try to extract percentage from line like "45%" or "Progress: 45"
     let re_percent = regex::Regex::new(r"(\d+)\s*%").ok()?;
     if let Some(caps) = re_percent.captures(line) {
@@ -505,13 +789,103 @@ pub fn fix_reset_firewall<F>(on_output: F) -> FixResult where F: FnMut(StreamOut
     )
 }
 
+/// Lists the adapters so one can be picked for cycle_network_adapter
+#[cfg(windows)]
+pub fn fix_reset_network_adapter<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput) {
+    run_powershell_streaming(
+        r#"
+        Write-Output "[INFO] Cartes reseau detectees:"
+        Get-NetAdapter -ErrorAction SilentlyContinue | Sort-Object Name | ForEach-Object {
+            Write-Output "  - $($_.Name) [$($_.InterfaceDescription)] : $($_.Status) $($_.LinkSpeed)"
+        }
+        Write-Output "[INFO] Choisissez une carte a desactiver puis reactiver"
+        "#,
+        on_output
+    )
+}
+
+/// Disable then enable the named adapter, streaming its link status before and after
+#[cfg(windows)]
+pub fn cycle_network_adapter<F>(name: &str, on_output: F) -> FixResult where F: FnMut(StreamOutput) {
+    let name = name.trim();
+    if name.is_empty() {
+        return FixResult {
+            success: false,
+            message: "Nom de carte reseau manquant".into(),
+            output: vec![],
+            requires_reboot: false,
+            repair_outcome: None,
+        };
+    }
+    // Get-NetAdapter -Name treats these as wildcards and would cycle several adapters
+    if name.contains(['*', '?', '[', ']']) {
+        return FixResult {
+            success: false,
+            message: format!("Nom de carte reseau invalide: {}", name),
+            output: vec![],
+            requires_reboot: false,
+            repair_outcome: None,
+        };
+    }
+
+    let command = format!(
+        r#"
+        $name = '{name}'
+        $found = @(Get-NetAdapter -ErrorAction SilentlyContinue | Where-Object {{ $_.Name -eq $name }})
+        if ($found.Count -eq 0) {{
+            Write-Output "[ERREUR] Carte reseau introuvable: $name"
+            exit 1
+        }}
+        if ($found.Count -gt 1) {{
+            Write-Output "[ERREUR] Plusieurs cartes reseau portent ce nom: $name"
+            exit 1
+        }}
+        $adapter = $found[0]
+        Write-Output "[INFO] Avant: $($adapter.Name) - $($adapter.Status) $($adapter.LinkSpeed)"
+        $failed = $false
+        try {{
+            Write-Output "[INFO] Desactivation de la carte..."
+            $adapter | Disable-NetAdapter -Confirm:$false -ErrorAction Stop
+            Start-Sleep -Seconds 3
+        }} catch {{
+            Write-Output "[ERREUR] $($_.Exception.Message)"
+            $failed = $true
+        }} finally {{
+            # Always bring the adapter back, even if disabling failed halfway
+            Write-Output "[INFO] Reactivation de la carte..."
+            try {{
+                $adapter | Enable-NetAdapter -Confirm:$false -ErrorAction Stop
+            }} catch {{
+                Write-Output "[ERREUR] Reactivation impossible: $($_.Exception.Message)"
+                $failed = $true
+            }}
+        }}
+        if ($failed) {{ exit 1 }}
+        for ($i = 0; $i -lt 20; $i++) {{
+            Start-Sleep -Seconds 1
+            $adapter = Get-NetAdapter -Name $name -ErrorAction SilentlyContinue
+            if ($adapter.Status -eq 'Up') {{ break }}
+            Write-Output "[INFO] Etat: $($adapter.Status)"
+        }}
+        if ($adapter.Status -eq 'Up') {{
+            Write-Output "[OK] Apres: $($adapter.Name) - Up $($adapter.LinkSpeed)"
+        }} else {{
+            Write-Output "[ATTENTION] Apres: $($adapter.Name) - $($adapter.Status) (cable ou Wi-Fi a verifier)"
+        }}
+        "#,
+        name = name.replace('\'', "''")
+    );
+
+    run_powershell_streaming(&command, on_output)
+}
+
 // ============================================
 // SYSTEM FIXES
 // ============================================
 
 #[cfg(windows)]
 pub fn fix_sfc_scannow<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput) {
-    run_powershell_streaming(
+    let mut result = run_powershell_streaming(
         r#"
         Write-Output "[INFO] Lancement de SFC /scannow..."
         Write-Output "[INFO] Cette operation peut prendre 10-15 minutes"
@@ -521,12 +895,14 @@ pub fn fix_sfc_scannow<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput
         Write-Output "[OK] Analyse SFC terminee"
         "#,
         on_output
-    )
+    );
+    attach_repair_summary(&mut result);
+    result
 }
 
 #[cfg(windows)]
 pub fn fix_dism_health<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput) {
-    run_powershell_streaming(
+    let mut result = run_powershell_streaming(
         r#"
         Write-Output "[INFO] Lancement de DISM pour reparer l'image systeme..."
         Write-Output "[INFO] Cette operation peut prendre 15-20 minutes"
@@ -541,7 +917,9 @@ pub fn fix_dism_health<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput
         Write-Output "[OK] Reparation DISM terminee"
         "#,
         on_output
-    )
+    );
+    attach_repair_summary(&mut result);
+    result
 }
 
 #[cfg(windows)]
@@ -563,9 +941,270 @@ pub fn fix_sfc_dism_full<F>(on_output: F) -> FixResult where F: FnMut(StreamOutp
         on_output
     );
     result.requires_reboot = true;
+    attach_repair_summary(&mut result);
     result
 }
 
+// ============================================
+// REPAIR LOG SUMMARY (CBS.log / dism.log)
+// ============================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RepairLogSummary {
+    pub cbs_available: bool,
+    pub dism_available: bool,
+    pub corrupt_found: u32,
+    pub repaired: u32,
+    pub unrepaired_files: Vec<String>,
+    pub dism_status: String,   // "repaired", "source_missing", "healthy", "unknown"
+    pub summary: String,
+}
+
+/// Logs can reach hundreds of MB: only read the last few MB
+const REPAIR_LOG_TAIL_BYTES: u64 = 4 * 1024 * 1024;
+
+fn read_log_tail(path: &std::path::Path, max_bytes: u64) -> Option<String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    if len > max_bytes {
+        file.seek(SeekFrom::Start(len - max_bytes)).ok()?;
+    }
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).ok()?;
+    Some(String::from_utf8_lossy(&buffer).to_string())
+}
+
+/// Keep only what was logged since the last session marker
+fn since_last_marker<'a>(content: &'a str, markers: &[&str]) -> &'a str {
+    markers.iter()
+        .filter_map(|m| content.rfind(m))
+        .max()
+        .map(|pos| &content[pos..])
+        .unwrap_or(content)
+}
+
+fn extract_quoted_file(line: &str) -> Option<String> {
+    let start = line.find('"')? + 1;
+    let end = start + line[start..].find('"')?;
+    let name = line[start..end].trim_start_matches("\\??\\");
+    if name.is_empty() { None } else { Some(name.to_string()) }
+}
+
+/// Parse the tail of CBS.log: (corrupt files found, files that could not be repaired)
+fn parse_cbs_log(content: &str) -> (std::collections::BTreeSet<String>, std::collections::BTreeSet<String>) {
+    let session = since_last_marker(content, &[
+        "TI: --- Initializing Trusted Installer ---",
+        "Starting TrustedInstaller initialization",
+    ]);
+
+    let mut corrupt = std::collections::BTreeSet::new();
+    let mut unrepaired = std::collections::BTreeSet::new();
+
+    for line in session.lines() {
+        let is_corrupt = line.contains("[SR] Repairing corrupted file")
+            || line.contains("[SR] Cannot repair member file")
+            || (line.contains("Hashes for file member") && line.contains("do not match"))
+            || line.contains("CSI Payload Corrupt");
+        if !is_corrupt {
+            continue;
+        }
+        let name = extract_quoted_file(line).unwrap_or_else(|| "fichier inconnu".to_string());
+        if line.contains("[SR] Cannot repair member file") {
+            unrepaired.insert(name.clone());
+        }
+        corrupt.insert(name);
+    }
+
+    (corrupt, unrepaired)
+}
+
+/// Parse the tail of dism.log into a coarse status
+fn parse_dism_log(content: &str) -> &'static str {
+    let session = since_last_marker(content, &["<----- Starting Dism.exe session ----->"]);
+    let lower = session.to_lowercase();
+
+    if lower.contains("0x800f081f") || lower.contains("source files could not be found") {
+        "source_missing"
+    } else if lower.contains("the restore operation completed successfully")
+        || lower.contains("component store corruption was repaired") {
+        "repaired"
+    } else if lower.contains("no component store corruption detected") {
+        "healthy"
+    } else {
+        "unknown"
+    }
+}
+
+pub fn get_repair_log_summary() -> RepairLogSummary {
+    let windir = std::env::var("SystemRoot").unwrap_or_else(|_| r"C:\Windows".to_string());
+    let logs = std::path::Path::new(&windir).join("Logs");
+
+    let mut summary = RepairLogSummary {
+        dism_status: "unknown".into(),
+        ..Default::default()
+    };
+
+    if let Some(cbs) = read_log_tail(&logs.join("CBS").join("CBS.log"), REPAIR_LOG_TAIL_BYTES) {
+        let (corrupt, unrepaired) = parse_cbs_log(&cbs);
+        summary.cbs_available = true;
+        summary.corrupt_found = corrupt.len() as u32;
+        summary.repaired = corrupt.len().saturating_sub(unrepaired.len()) as u32;
+        summary.unrepaired_files = unrepaired.into_iter().collect();
+    }
+
+    if let Some(dism) = read_log_tail(&logs.join("DISM").join("dism.log"), REPAIR_LOG_TAIL_BYTES) {
+        summary.dism_available = true;
+        summary.dism_status = parse_dism_log(&dism).to_string();
+    }
+
+    summary.summary = describe_repair_summary(&summary);
+    summary
+}
+
+fn describe_repair_summary(s: &RepairLogSummary) -> String {
+    if !s.cbs_available && !s.dism_available {
+        return "Journaux de reparation introuvables".to_string();
+    }
+
+    let mut parts = Vec::new();
+    if s.corrupt_found == 0 {
+        parts.push("Aucun fichier corrompu detecte".to_string());
+    } else if s.unrepaired_files.is_empty() {
+        parts.push(format!("{} fichier(s) corrompu(s) trouve(s), tous repares", s.corrupt_found));
+    } else {
+        parts.push(format!(
+            "{} fichier(s) corrompu(s), {} non reparable(s): {}",
+            s.corrupt_found,
+            s.unrepaired_files.len(),
+            s.unrepaired_files.iter().take(3).cloned().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    match s.dism_status.as_str() {
+        "repaired" => parts.push("Image systeme reparee par DISM".to_string()),
+        "source_missing" => parts.push("DISM: fichiers source introuvables (0x800f081f)".to_string()),
+        "healthy" => parts.push("Image systeme saine".to_string()),
+        _ => {}
+    }
+
+    parts.join(" - ")
+}
+
+/// sfc writes UTF-16 when redirected and both tools follow the Windows language:
+/// drop the NULs and fold accents before matching
+#[cfg(windows)]
+fn console_text(line: &str) -> String {
+    line.replace('\0', "")
+        .to_lowercase()
+        .replace(['é', 'è', 'ê'], "e")
+        .replace('’', "'")
+}
+
+/// Last verdict line of sfc /scannow (English or French Windows)
+#[cfg(windows)]
+fn parse_sfc_outcome(lines: &[String]) -> Option<SfcOutcome> {
+    lines.iter().rev().map(|l| console_text(l)).find_map(|line| {
+        if line.contains("did not find any integrity violations") || line.contains("aucune violation d'integrite") {
+            Some(SfcOutcome::NoViolations)
+        } else if line.contains("unable to fix") || line.contains("n'a pas pu en reparer") {
+            Some(SfcOutcome::Unrepairable)
+        } else if line.contains("successfully repaired") || line.contains("les a correctement repares") {
+            Some(SfcOutcome::Repaired)
+        } else if line.contains("could not perform the requested operation") || line.contains("n'a pas pu effectuer l'operation") {
+            Some(SfcOutcome::CouldNotRun)
+        } else {
+            None
+        }
+    })
+}
+
+/// Status of the last DISM run: "Error: 0x800f081f" or "The operation completed successfully"
+#[cfg(windows)]
+fn parse_dism_outcome(lines: &[String]) -> Option<DismOutcome> {
+    let error = regex::Regex::new(r"(?:error|erreur)\s*:\s*(0x[0-9a-f]+|\d+)").ok()?;
+    lines.iter().rev().map(|l| console_text(l)).find_map(|line| {
+        if let Some(caps) = error.captures(&line) {
+            Some(DismOutcome::Failed { error_code: caps[1].to_string() })
+        } else if line.contains("the operation completed successfully") || line.contains("l'operation a reussi") {
+            Some(DismOutcome::Success)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(windows)]
+fn describe_repair_outcome(outcome: &RepairOutcome, logs: &RepairLogSummary) -> String {
+    let mut parts = Vec::new();
+
+    match outcome.sfc {
+        Some(SfcOutcome::NoViolations) => parts.push("Aucune violation d'integrite detectee".to_string()),
+        Some(SfcOutcome::Repaired) if logs.repaired > 0 => {
+            parts.push(format!("{} fichier(s) corrompu(s) repare(s)", logs.repaired));
+        }
+        Some(SfcOutcome::Repaired) => parts.push("Fichiers corrompus trouves et repares".to_string()),
+        Some(SfcOutcome::Unrepairable) if !logs.unrepaired_files.is_empty() => parts.push(format!(
+            "{} fichier(s) corrompu(s) non reparable(s): {}",
+            logs.unrepaired_files.len(),
+            logs.unrepaired_files.iter().take(3).cloned().collect::<Vec<_>>().join(", ")
+        )),
+        Some(SfcOutcome::Unrepairable) => parts.push("Fichiers corrompus trouves, certains non reparables".to_string()),
+        Some(SfcOutcome::CouldNotRun) => {
+            parts.push("SFC n'a pas pu s'executer (reparation en attente, redemarrez puis relancez)".to_string());
+        }
+        None => {}
+    }
+
+    match &outcome.dism {
+        Some(DismOutcome::Success) => parts.push("DISM: operation reussie".to_string()),
+        Some(DismOutcome::Failed { error_code }) if error_code == "0x800f081f" => {
+            parts.push("DISM: fichiers source introuvables (0x800f081f)".to_string());
+        }
+        Some(DismOutcome::Failed { error_code }) => parts.push(format!("DISM: echec ({})", error_code)),
+        None => {}
+    }
+
+    parts.join(" - ")
+}
+
+/// Replace the generic "termine" message with what the tools and logs say was repaired
+#[cfg(windows)]
+fn attach_repair_summary(result: &mut FixResult) {
+    let outcome = RepairOutcome {
+        sfc: parse_sfc_outcome(&result.output),
+        dism: parse_dism_outcome(&result.output),
+    };
+
+    let summary = get_repair_log_summary();
+    let logs_available = summary.cbs_available || summary.dism_available;
+    if logs_available {
+        result.output.push(format!("[INFO] {}", summary.summary));
+    }
+
+    let detail = if outcome.sfc.is_some() || outcome.dism.is_some() {
+        Some(describe_repair_outcome(&outcome, &summary))
+    } else if logs_available {
+        Some(summary.summary.clone())
+    } else {
+        None
+    };
+    if let Some(detail) = detail.filter(|d| !d.is_empty()) {
+        // A failed run keeps its error, the log summary only adds context
+        result.message = if result.success { detail } else { format!("{} - {}", result.message, detail) };
+    }
+
+    if outcome.sfc.is_some() || outcome.dism.is_some() {
+        let failed = matches!(outcome.sfc, Some(SfcOutcome::Unrepairable | SfcOutcome::CouldNotRun))
+            || matches!(outcome.dism, Some(DismOutcome::Failed { .. }));
+        if failed {
+            result.success = false;
+        }
+        result.repair_outcome = Some(outcome);
+    }
+}
+
 #[cfg(windows)]
 pub fn fix_chkdsk_scan<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput) {
     run_powershell_streaming(
@@ -578,6 +1217,70 @@ pub fn fix_chkdsk_scan<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput
     )
 }
 
+/// "c", "C:", "C:\\" -> 'C'
+#[cfg(windows)]
+fn normalize_drive_letter(letter: &str) -> Option<char> {
+    let trimmed = letter.trim().trim_end_matches(['\\', '/']).trim_end_matches(':');
+    let mut chars = trimmed.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Some(c.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+/// TRIM for SSDs, defragmentation for HDDs; removable and network drives are refused
+#[cfg(windows)]
+pub fn optimize_drive<F>(letter: &str, on_output: F) -> FixResult where F: FnMut(StreamOutput) {
+    let fail = |message: String| FixResult {
+        success: false,
+        message,
+        output: vec![],
+        requires_reboot: false,
+        repair_outcome: None,
+    };
+
+    let letter = match normalize_drive_letter(letter) {
+        Some(letter) => letter,
+        None => return fail(format!("Lettre de lecteur invalide: {}", letter)),
+    };
+
+    // Network shares are not listed by sysinfo, so "not found" covers them too
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    let prefix = format!("{}:", letter);
+    let disk = match disks.iter().find(|d| d.mount_point().to_string_lossy().to_uppercase().starts_with(&prefix)) {
+        Some(disk) => disk,
+        None => return fail(format!("Lecteur {}: introuvable ou lecteur reseau", letter)),
+    };
+    if disk.is_removable() {
+        return fail(format!("Lecteur {}: amovible, optimisation ignoree", letter));
+    }
+
+    // Unknown media: let Optimize-Volume pick the right operation
+    let (mode, label) = match disk.kind() {
+        sysinfo::DiskKind::SSD => ("-ReTrim", "TRIM (SSD)"),
+        sysinfo::DiskKind::HDD => ("-Defrag", "Defragmentation (HDD)"),
+        _ => ("", "Optimisation"),
+    };
+
+    let command = format!(
+        r#"
+        Write-Output "[INFO] {label} du lecteur {letter}:..."
+        try {{
+            Optimize-Volume -DriveLetter {letter} {mode} -Verbose -ErrorAction Stop 4>&1 | ForEach-Object {{ Write-Output "$_" }}
+            Write-Output "[OK] Lecteur {letter}: optimise"
+        }} catch {{
+            Write-Output "[ERREUR] $($_.Exception.Message)"
+            exit 1
+        }}
+        "#,
+        label = label,
+        letter = letter,
+        mode = mode
+    );
+
+    run_powershell_streaming(&command, on_output)
+}
+
 #[cfg(windows)]
 pub fn fix_create_restore_point<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput) {
     run_powershell_streaming(
@@ -598,6 +1301,154 @@ pub fn fix_create_restore_point<F>(on_output: F) -> FixResult where F: FnMut(Str
     )
 }
 
+/// Boot mode change through bcdedit (BCD backed up first), effective at the next restart
+pub fn fix_boot_mode<F>(mode: &str, mut on_output: F) -> FixResult where F: FnMut(StreamOutput) {
+    let result = crate::godmode::set_boot_mode(mode);
+    if let Some(path) = &result.backup_path {
+        on_output(StreamOutput {
+            line: format!("[INFO] Sauvegarde BCD: {}", path),
+            line_type: "info".into(),
+            progress: None,
+        });
+    }
+    on_output(StreamOutput {
+        line: result.message.clone(),
+        line_type: if result.success { "warning" } else { "error" }.into(),
+        progress: Some(100),
+    });
+
+    FixResult {
+        success: result.success,
+        message: result.message.clone(),
+        output: vec![result.message],
+        requires_reboot: result.success,
+        repair_outcome: None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestorePoint {
+    pub sequence: u32,
+    pub description: String,
+    pub created: String,
+    #[serde(rename = "type")]
+    pub restore_type: String,
+}
+
+/// RestorePointType values from the SystemRestore WMI class
+#[cfg(windows)]
+fn restore_point_type_name(code: u64) -> &'static str {
+    match code {
+        0 => "Installation d'application",
+        1 => "Desinstallation d'application",
+        10 => "Installation de pilote",
+        12 => "Modification des parametres",
+        13 => "Operation annulee",
+        _ => "Autre",
+    }
+}
+
+/// WMI datetime "20240131143000.000000-000" -> "31/01/2024 14:30"
+#[cfg(windows)]
+fn format_wmi_datetime(value: &str) -> String {
+    value.get(..14)
+        .and_then(|s| chrono::NaiveDateTime::parse_from_str(s, "%Y%m%d%H%M%S").ok())
+        .map(|dt| dt.format("%d/%m/%Y %H:%M").to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
+#[cfg(windows)]
+pub fn list_restore_points() -> Vec<RestorePoint> {
+    let script = r#"
+try {
+    @(Get-ComputerRestorePoint -ErrorAction Stop | ForEach-Object {
+        @{ Seq = $_.SequenceNumber; Desc = $_.Description; Created = $_.CreationTime; Type = $_.RestorePointType }
+    }) | ConvertTo-Json -Compress
+} catch { '[]' }
+"#;
+    let output = match powershell::require().and_then(|ps| Command::new(ps)
+        .args(["-NoProfile", "-Command", script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| e.to_string()))
+    {
+        Ok(out) => out,
+        Err(_) => return Vec::new(),
+    };
+
+    let items = match serde_json::from_str::<serde_json::Value>(String::from_utf8_lossy(&output.stdout).trim()) {
+        Ok(serde_json::Value::Array(items)) => items,
+        Ok(serde_json::Value::Null) | Err(_) => Vec::new(),
+        Ok(single) => vec![single],
+    };
+
+    let mut points: Vec<RestorePoint> = items.iter()
+        .filter_map(|p| Some(RestorePoint {
+            sequence: p["Seq"].as_u64()? as u32,
+            description: p["Desc"].as_str().unwrap_or_default().to_string(),
+            created: format_wmi_datetime(p["Created"].as_str().unwrap_or_default()),
+            restore_type: restore_point_type_name(p["Type"].as_u64().unwrap_or(u64::MAX)).to_string(),
+        }))
+        .collect();
+    // Newest first
+    points.sort_by(|a, b| b.sequence.cmp(&a.sequence));
+    points
+}
+
+#[cfg(not(windows))]
+pub fn list_restore_points() -> Vec<RestorePoint> {
+    Vec::new()
+}
+
+/// Schedules a System Restore rollback for the next restart.
+/// Restore-Computer reboots on the spot, so the SystemRestore.Restore CIM method it
+/// wraps is called directly and the reboot is left to the technician.
+#[cfg(windows)]
+pub fn restore_to_point(sequence: u32) -> FixResult {
+    let point = match list_restore_points().into_iter().find(|p| p.sequence == sequence) {
+        Some(point) => point,
+        None => {
+            return FixResult {
+                success: false,
+                message: format!("Point de restauration {} introuvable", sequence),
+                output: vec![],
+                requires_reboot: false,
+                repair_outcome: None,
+            };
+        }
+    };
+
+    let command = format!(
+        r#"
+        Write-Output "[INFO] Restauration vers le point {seq}..."
+        try {{
+            $r = Invoke-CimMethod -Namespace root\default -ClassName SystemRestore -MethodName Restore -Arguments @{{ SequenceNumber = [uint32]{seq} }} -ErrorAction Stop
+            if ($r.ReturnValue -ne 0) {{ throw "code $($r.ReturnValue)" }}
+            Write-Output "[OK] Restauration programmee au prochain redemarrage"
+        }} catch {{
+            Write-Output "[ERREUR] Restauration impossible: $_"
+            exit 1
+        }}
+        "#,
+        seq = sequence
+    );
+
+    let mut result = run_powershell_streaming(&command, |_| {});
+    if result.success {
+        result.requires_reboot = true;
+        result.message = format!(
+            "Retour au {} ({}) programme. Attention: le redemarrage annule les programmes et pilotes installes depuis, fermez vos documents avant.",
+            point.created, point.description
+        );
+    }
+    result
+}
+
+#[cfg(not(windows))]
+pub fn restore_to_point(_sequence: u32) -> FixResult {
+    run_powershell_streaming("", |_| {})
+}
+
 // ============================================
 // EXPLORER FIXES
 // ============================================
@@ -775,38 +1626,97 @@ pub fn fix_reset_windows_update<F>(on_output: F) -> FixResult where F: FnMut(Str
     result
 }
 
+/// DLLs re-registered for the Windows Update repair
 #[cfg(windows)]
-pub fn fix_reregister_dlls<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput) {
-    run_powershell_streaming(
+const REREGISTER_DLLS: &[&str] = &[
+    "atl.dll", "urlmon.dll", "mshtml.dll", "shdocvw.dll",
+    "browseui.dll", "jscript.dll", "vbscript.dll", "scrrun.dll",
+    "msxml.dll", "msxml3.dll", "msxml6.dll", "actxprxy.dll",
+    "softpub.dll", "wintrust.dll", "dssenh.dll", "rsaenh.dll",
+    "gpkcsp.dll", "sccbase.dll", "slbcsp.dll", "cryptdlg.dll",
+    "oleaut32.dll", "ole32.dll", "shell32.dll", "initpki.dll",
+    "wuapi.dll", "wuaueng.dll", "wuaueng1.dll", "wucltui.dll",
+    "wups.dll", "wups2.dll", "wuweb.dll", "qmgr.dll", "qmgrprxy.dll",
+    "wucltux.dll", "muweb.dll", "wuwebv.dll",
+];
+
+/// Windows Update can't work if one of these fails to register
+#[cfg(windows)]
+const CRITICAL_DLLS: &[&str] = &["wuaueng.dll", "qmgr.dll", "wups.dll"];
+
+/// regsvr32 exit code when the DLL file doesn't exist on this Windows version
+#[cfg(windows)]
+const REGSVR32_MODULE_NOT_FOUND: i32 = 3;
+
+#[cfg(windows)]
+pub fn fix_reregister_dlls<F>(mut on_output: F) -> FixResult where F: FnMut(StreamOutput) {
+    let dll_list = REREGISTER_DLLS.iter()
+        .map(|d| format!("\"{}\"", d))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let command = format!(
         r#"
         Write-Output "[INFO] Reenregistrement des DLLs Windows Update..."
 
-        $dlls = @(
-            "atl.dll", "urlmon.dll", "mshtml.dll", "shdocvw.dll",
-            "browseui.dll", "jscript.dll", "vbscript.dll", "scrrun.dll",
-            "msxml.dll", "msxml3.dll", "msxml6.dll", "actxprxy.dll",
-            "softpub.dll", "wintrust.dll", "dssenh.dll", "rsaenh.dll",
-            "gpkcsp.dll", "sccbase.dll", "slbcsp.dll", "cryptdlg.dll",
-            "oleaut32.dll", "ole32.dll", "shell32.dll", "initpki.dll",
-            "wuapi.dll", "wuaueng.dll", "wuaueng1.dll", "wucltui.dll",
-            "wups.dll", "wups2.dll", "wuweb.dll", "qmgr.dll", "qmgrprxy.dll",
-            "wucltux.dll", "muweb.dll", "wuwebv.dll"
-        )
+        $dlls = @({dlls})
 
         $total = $dlls.Count
         $current = 0
 
-        foreach ($dll in $dlls) {
+        foreach ($dll in $dlls) {{
             $current++
             $percent = [math]::Round(($current / $total) * 100)
             Write-Output "[$percent%] Enregistrement de $dll..."
-            regsvr32 /s $dll 2>$null
-        }
-
-        Write-Output "[OK] DLLs reenregistrees"
+            $p = Start-Process -FilePath regsvr32.exe -ArgumentList "/s", $dll -Wait -PassThru -WindowStyle Hidden
+            if ($p.ExitCode -ne 0) {{ Write-Output "[DLL_FAIL] $dll $($p.ExitCode)" }}
+        }}
         "#,
-        on_output
-    )
+        dlls = dll_list
+    );
+
+    // regsvr32 /s reports failures only through its exit code
+    let mut failures: Vec<(String, i32)> = Vec::new();
+    let mut result = run_powershell_streaming(&command, |out| {
+        let rest = match out.line.trim().strip_prefix("[DLL_FAIL] ") {
+            Some(rest) => rest.to_string(),
+            None => {
+                on_output(out);
+                return;
+            }
+        };
+        let mut parts = rest.split_whitespace();
+        let dll = parts.next().unwrap_or_default().to_string();
+        let code = parts.next().and_then(|c| c.parse().ok()).unwrap_or(-1);
+        let line = if code == REGSVR32_MODULE_NOT_FOUND {
+            format!("{} absente sur cette version de Windows (code {})", dll, code)
+        } else {
+            format!("Echec de l'enregistrement de {} (code {})", dll, code)
+        };
+        on_output(StreamOutput { line, line_type: "warning".into(), progress: None });
+        failures.push((dll, code));
+    });
+
+    let total = REREGISTER_DLLS.len();
+    let summary = format!("{}/{} OK, {} echecs", total - failures.len(), total, failures.len());
+    let critical: Vec<&str> = failures.iter()
+        .map(|(dll, _)| dll.as_str())
+        .filter(|dll| CRITICAL_DLLS.iter().any(|c| c.eq_ignore_ascii_case(dll)))
+        .collect();
+
+    on_output(StreamOutput {
+        line: summary.clone(),
+        line_type: if failures.is_empty() { "success" } else { "warning" }.into(),
+        progress: Some(100),
+    });
+    result.output.push(summary.clone());
+
+    if !critical.is_empty() {
+        result.success = false;
+        result.message = format!("{} - DLLs critiques en echec: {}. Reparation Windows Update incomplete", summary, critical.join(", "));
+    } else if result.success {
+        result.message = format!("DLLs reenregistrees: {}", summary);
+    }
+    result
 }
 
 // ============================================
@@ -1013,6 +1923,12 @@ pub fn fix_reset_network_complete<F>(on_output: F) -> FixResult where F: FnMut(S
 #[cfg(not(windows))]
 pub fn fix_reset_firewall<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput) { run_powershell_streaming("", on_output) }
 #[cfg(not(windows))]
+pub fn fix_reset_network_adapter<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput) { run_powershell_streaming("", on_output) }
+#[cfg(not(windows))]
+pub fn cycle_network_adapter<F>(_name: &str, on_output: F) -> FixResult where F: FnMut(StreamOutput) { run_powershell_streaming("", on_output) }
+#[cfg(not(windows))]
+pub fn optimize_drive<F>(_letter: &str, on_output: F) -> FixResult where F: FnMut(StreamOutput) { run_powershell_streaming("", on_output) }
+#[cfg(not(windows))]
 pub fn fix_sfc_scannow<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput) { run_powershell_streaming("", on_output) }
 #[cfg(not(windows))]
 pub fn fix_dism_health<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput) { run_powershell_streaming("", on_output) }
@@ -1051,6 +1967,161 @@ pub fn fix_restart_print_spooler<F>(on_output: F) -> FixResult where F: FnMut(St
 #[cfg(not(windows))]
 pub fn fix_restart_search<F>(on_output: F) -> FixResult where F: FnMut(StreamOutput) { run_powershell_streaming("", on_output) }
 
+// ============================================
+// AUTO MAINTENANCE - Safe fixes in one click
+// ============================================
+
+/// Candidates, in order; each must still be low risk and reboot-free in the catalog to run
+const AUTO_MAINTENANCE_FIXES: &[&str] = &["flush_dns", "clean_temp", "reset_icon_cache", "restart_explorer"];
+
+/// "(12.5 MB liberes)" as printed by the cleanup scripts
+fn freed_mb(lines: &[String]) -> f64 {
+    let re = match regex::Regex::new(r"(\d+(?:[.,]\d+)?)\s*MB liberes") {
+        Ok(re) => re,
+        Err(_) => return 0.0,
+    };
+    lines.iter()
+        .filter_map(|line| re.captures(line))
+        .filter_map(|caps| caps[1].replace(',', ".").parse::<f64>().ok())
+        .sum()
+}
+
+/// Every fix of every category
+fn fix_catalog() -> Vec<FixItem> {
+    get_fix_categories().into_iter().flat_map(|c| c.fixes).collect()
+}
+
+fn auto_maintenance_steps() -> Vec<FixItem> {
+    let catalog = fix_catalog();
+    AUTO_MAINTENANCE_FIXES.iter()
+        .filter_map(|id| catalog.iter().find(|f| f.id == *id))
+        .filter(|f| f.risk_level == "low" && !f.requires_reboot)
+        .cloned()
+        .collect()
+}
+
+/// Runs the safe fixes in sequence and stops at the first failing step
+pub fn run_auto_maintenance<F>(mut on_progress: F) -> FixResult
+where F: FnMut(MaintenanceProgress)
+{
+    let steps = auto_maintenance_steps();
+    let step_count = steps.len();
+    let mut summary = Vec::new();
+    let mut total_freed_mb = 0.0;
+
+    for (index, step) in steps.iter().enumerate() {
+        let base = index * 100;
+        let mut emit = |line: String, line_type: String, step_percent: u8| {
+            on_progress(MaintenanceProgress {
+                fix_id: step.id.clone(),
+                step_name: step.name.clone(),
+                step_index: index + 1,
+                step_count,
+                line,
+                line_type,
+                percent: ((base + step_percent.min(100) as usize) / step_count.max(1)) as u8,
+            });
+        };
+
+        emit(format!("[INFO] Etape {}/{}: {}", index + 1, step_count, step.name), "info".into(), 0);
+        let result = execute_fix(&step.id, |output| {
+            emit(output.line, output.line_type, output.progress.unwrap_or(0));
+        });
+        let freed = freed_mb(&result.output);
+        total_freed_mb += freed;
+
+        if !result.success {
+            summary.push(format!("[ERREUR] {}: {}", step.name, result.message));
+            emit(format!("[ERREUR] {}: {}", step.name, result.message), "error".into(), 100);
+            return FixResult {
+                success: false,
+                message: format!(
+                    "Maintenance interrompue a l'etape {}/{} ({}): {}",
+                    index + 1, step_count, step.name, result.message
+                ),
+                output: summary,
+                requires_reboot: false,
+                repair_outcome: None,
+            };
+        }
+
+        summary.push(if freed > 0.0 {
+            format!("[OK] {} ({:.1} MB liberes)", step.name, freed)
+        } else {
+            format!("[OK] {}", step.name)
+        });
+        emit(format!("[OK] {}", step.name), "success".into(), 100);
+    }
+
+    FixResult {
+        success: true,
+        message: format!("Maintenance terminee: {} etapes, {:.1} MB liberes", step_count, total_freed_mb),
+        output: summary,
+        requires_reboot: false,
+        repair_outcome: None,
+    }
+}
+
+// ============================================
+// BATCH - Several fixes, one report
+// ============================================
+
+pub enum BatchEvent<'a> {
+    StepStart { fix_id: &'a str, index: usize, total: usize },
+    Output { fix_id: &'a str, output: StreamOutput },
+    StepDone { fix_id: &'a str, index: usize, total: usize, result: &'a FixResult },
+}
+
+/// Runs the fixes in order. A failing high-risk fix aborts the rest, other failures don't.
+pub fn execute_fixes<F>(fix_ids: &[String], mut on_event: F) -> FixResult
+where F: FnMut(BatchEvent)
+{
+    let catalog = fix_catalog();
+    let total = fix_ids.len();
+    let mut breakdown = Vec::new();
+    let mut succeeded = 0;
+    let mut requires_reboot = false;
+    let mut aborted_by: Option<String> = None;
+
+    for (index, fix_id) in fix_ids.iter().enumerate() {
+        let item = catalog.iter().find(|f| &f.id == fix_id);
+        let name = item.map(|f| f.name.clone()).unwrap_or_else(|| fix_id.clone());
+
+        on_event(BatchEvent::StepStart { fix_id, index, total });
+        let result = execute_fix(fix_id, |output| on_event(BatchEvent::Output { fix_id, output }));
+        on_event(BatchEvent::StepDone { fix_id, index, total, result: &result });
+
+        requires_reboot |= result.requires_reboot;
+        if result.success {
+            succeeded += 1;
+            breakdown.push(format!("[OK] {}: {}", name, result.message));
+            continue;
+        }
+
+        breakdown.push(format!("[ERREUR] {}: {}", name, result.message));
+        if item.map(|f| f.risk_level == "high").unwrap_or(false) {
+            for skipped in &fix_ids[index + 1..] {
+                breakdown.push(format!("[ANNULE] {}", skipped));
+            }
+            aborted_by = Some(name);
+            break;
+        }
+    }
+
+    let mut message = format!("{}/{} correctifs reussis", succeeded, total);
+    if let Some(name) = &aborted_by {
+        message.push_str(&format!(" - arret apres l'echec de {} (risque eleve)", name));
+    }
+
+    FixResult {
+        success: succeeded == total,
+        message,
+        output: breakdown,
+        requires_reboot,
+        repair_outcome: None,
+    }
+}
+
 // ============================================
 // DISPATCHER - Execute fix by ID
 // ============================================
@@ -1065,12 +2136,20 @@ where F: FnMut(StreamOutput)
         "reset_tcpip" => fix_reset_tcpip(on_output),
         "reset_network_complete" => fix_reset_network_complete(on_output),
         "reset_firewall" => fix_reset_firewall(on_output),
+        "reset_network_adapter" => fix_reset_network_adapter(on_output),
         // System
         "sfc_scannow" => fix_sfc_scannow(on_output),
         "dism_health" => fix_dism_health(on_output),
         "sfc_dism_full" => fix_sfc_dism_full(on_output),
         "chkdsk_scan" => fix_chkdsk_scan(on_output),
+        "optimize_drive" => {
+            let system_drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+            optimize_drive(&system_drive, on_output)
+        }
         "restore_point" => fix_create_restore_point(on_output),
+        "boot_safe_minimal" => fix_boot_mode("safe_minimal", on_output),
+        "boot_safe_network" => fix_boot_mode("safe_network", on_output),
+        "boot_normal" => fix_boot_mode("normal", on_output),
         // Explorer
         "restart_explorer" => fix_restart_explorer(on_output),
         "reset_icon_cache" => fix_reset_icon_cache(on_output),
@@ -1095,6 +2174,97 @@ where F: FnMut(StreamOutput)
             message: format!("Fix inconnu: {}", fix_id),
             output: vec![],
             requires_reboot: false,
+            repair_outcome: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CBS_FRAGMENT: &str = r#"2024-05-01 09:00:00, Info                  CBS    TI: --- Initializing Trusted Installer ---
+2024-05-01 09:01:12, Info                  CSI    00000008 [SR] Repairing corrupted file \??\C:\Windows\System32\old.dll from store
+2024-05-02 10:00:00, Info                  CBS    TI: --- Initializing Trusted Installer ---
+2024-05-02 10:03:41, Info                  CSI    00000012 [SR] Repairing corrupted file "\??\C:\Windows\System32\drivers\netio.sys" from store
+2024-05-02 10:03:42, Info                  CSI    00000013 [SR] Cannot repair member file [l:10]"msvcp.dll" of Microsoft-Windows-CRT, Version = 10.0.19041.1
+2024-05-02 10:03:43, Info                  CSI    00000014 Hashes for file member [l:10]"msvcp.dll" do not match
+2024-05-02 10:05:00, Info                  CSI    00000020 [SR] Verify complete"#;
+
+    #[test]
+    fn cbs_log_counts_only_the_last_session() {
+        let (corrupt, unrepaired) = parse_cbs_log(CBS_FRAGMENT);
+
+        assert_eq!(corrupt.len(), 2);
+        assert!(corrupt.contains(r"C:\Windows\System32\drivers\netio.sys"));
+        assert!(corrupt.contains("msvcp.dll"));
+        assert_eq!(unrepaired.into_iter().collect::<Vec<_>>(), vec!["msvcp.dll".to_string()]);
+    }
+
+    #[test]
+    fn repair_summary_lists_unrepaired_files() {
+        let summary = RepairLogSummary {
+            cbs_available: true,
+            dism_available: true,
+            corrupt_found: 2,
+            repaired: 1,
+            unrepaired_files: vec!["msvcp.dll".to_string()],
+            dism_status: "source_missing".to_string(),
+            summary: String::new(),
+        };
+
+        assert_eq!(
+            describe_repair_summary(&summary),
+            "2 fichier(s) corrompu(s), 1 non reparable(s): msvcp.dll - DISM: fichiers source introuvables (0x800f081f)"
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn console_lines_split_progress_redraws() {
+        let mut lines = Vec::new();
+        read_console_lines(&b"[==  10.0%  ]\r[====  20.0%  ]\r\nThe operation completed successfully.\r\n"[..], |l| lines.push(l));
+
+        assert_eq!(lines, vec!["[==  10.0%  ]", "[====  20.0%  ]", "The operation completed successfully."]);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn console_lines_decode_utf16_output() {
+        let text = "V\u{e9}rification 100% termin\u{e9}e.\r\nLa protection des ressources Windows n\u{2019}a trouv\u{e9} aucune violation d\u{2019}int\u{e9}grit\u{e9}.\r\n";
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(|u| u.to_le_bytes()));
+
+        // Tiny buffer so UTF-16 units get split across reads
+        let mut lines = Vec::new();
+        read_console_lines(std::io::BufReader::with_capacity(3, &bytes[..]), |l| lines.push(l));
+
+        assert_eq!(lines, vec![
+            "V\u{e9}rification 100% termin\u{e9}e.",
+            "La protection des ressources Windows n\u{2019}a trouv\u{e9} aucune violation d\u{2019}int\u{e9}grit\u{e9}.",
+        ]);
+        assert_eq!(parse_sfc_outcome(&lines), Some(SfcOutcome::NoViolations));
+    }
+    #[cfg(windows)]
+    #[test]
+    fn console_lines_switch_from_powershell_text_to_sfc_utf16() {
+        let utf16 = |text: &str| text.encode_utf16().flat_map(|u| u.to_le_bytes()).collect::<Vec<u8>>();
+        let mut bytes = b"[INFO] Lancement de SFC /scannow...\r\n\r\n".to_vec();
+        bytes.extend(utf16("V\u{e9}rification 45% termin\u{e9}e.\rV\u{e9}rification 100% termin\u{e9}e.\r\n"));
+        bytes.extend(utf16("La protection des ressources Windows n\u{2019}a trouv\u{e9} aucune violation d\u{2019}int\u{e9}grit\u{e9}.\r\n"));
+        bytes.extend_from_slice(b"[OK] Analyse SFC terminee\r\n");
+
+        let mut lines = Vec::new();
+        read_console_lines(std::io::BufReader::with_capacity(5, &bytes[..]), |l| lines.push(l));
+
+        assert_eq!(lines, vec![
+            "[INFO] Lancement de SFC /scannow...",
+            "V\u{e9}rification 45% termin\u{e9}e.",
+            "V\u{e9}rification 100% termin\u{e9}e.",
+            "La protection des ressources Windows n\u{2019}a trouv\u{e9} aucune violation d\u{2019}int\u{e9}grit\u{e9}.",
+            "[OK] Analyse SFC terminee",
+        ]);
+        assert_eq!(extract_progress(&lines[1]), Some(45));
+        assert_eq!(parse_sfc_outcome(&lines), Some(SfcOutcome::NoViolations));
+    }
+}
@@ -3,8 +3,10 @@
 // Native Windows Performance (No PowerShell)
 // ============================================
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use crate::i18n;
+use crate::tools;
 
 #[cfg(windows)]
 use winreg::enums::*;
@@ -14,13 +16,17 @@ use winreg::RegKey;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 #[cfg(windows)]
+use crate::powershell;
+#[cfg(windows)]
+use crate::wmi_cache::cached_query;
+#[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 // ============================================
 // TYPES
 // ============================================
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct InstalledApp {
     pub name: String,
     pub version: String,
@@ -30,7 +36,7 @@ pub struct InstalledApp {
     pub uninstall_string: String,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct StartupItem {
     pub name: String,
     pub command: String,
@@ -38,6 +44,16 @@ pub struct StartupItem {
     pub enabled: bool,
 }
 
+#[derive(Serialize, Clone, Debug)]
+pub struct ScheduledTask {
+    pub name: String,
+    pub path: String,       // full task path, e.g. \Vendor\Updater
+    pub trigger: String,    // logon or boot
+    pub state: String,      // ready, running, disabled...
+    pub last_run: Option<String>,
+    pub action: String,
+}
+
 #[derive(Serialize, Clone)]
 pub struct DeepHealth {
     pub bios_serial: String,
@@ -61,6 +77,10 @@ pub struct DriverInfo {
     pub manufacturer: String,
     pub driver_date: String,
     pub status: String,
+    pub driver_age_days: Option<i64>,
+    pub is_outdated: bool,
+    pub gpu_vendor: Option<String>,    // nvidia, amd, intel (GPU only)
+    pub download_url: Option<String>,  // Vendor driver page for the UI deep link
 }
 
 // ============================================
@@ -87,6 +107,10 @@ pub struct SmartDiskInfo {
     pub read_error_rate: Option<u32>,
     pub seek_error_rate: Option<u32>,
     pub spin_retry_count: Option<u32>,
+    pub percentage_used: Option<u8>,  // NVMe wear indicator
+    pub available_spare_percent: Option<u8>,  // NVMe spare blocks left
+    pub data_units_written: Option<u64>,  // NVMe units of 512 000 bytes
+    pub self_test: Option<SmartSelfTestStatus>,  // Last polled self-test, see run_smart_self_test
 }
 
 #[derive(Serialize, Clone)]
@@ -97,6 +121,7 @@ pub struct BatteryHealth {
     pub status: String,
     pub design_capacity: u32,
     pub full_charge_capacity: u32,
+    pub cycle_count: Option<u32>,
 }
 
 #[derive(Serialize, Clone)]
@@ -120,6 +145,13 @@ pub struct RegBackup {
     pub path: String,
     pub created_at: String,
     pub size_bytes: u64,
+    pub kind: String,   // "key" (.reg export) or "value" (single value, .json)
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct BackupCleanup {
+    pub removed_files: u32,
+    pub removed_bytes: u64,
 }
 
 // ============================================
@@ -167,30 +199,190 @@ pub fn get_installed_apps_native() -> Vec<InstalledApp> {
     Vec::new()
 }
 
+/// Program and raw argument string of an UninstallString, silent switch appended when known
+#[cfg(windows)]
+fn parse_uninstall_string(uninstall_string: &str, silent: bool) -> Option<(String, String)> {
+    let s = uninstall_string.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    // MSI: MsiExec.exe /I{GUID} or /X{GUID} -> always uninstall (/X) by product code
+    if s.to_lowercase().starts_with("msiexec") {
+        let start = s.find('{')?;
+        let end = start + s[start..].find('}')?;
+        let product_code = &s[start..=end];
+        let mut args = format!("/X{}", product_code);
+        if silent {
+            args.push_str(" /quiet /norestart");
+        }
+        return Some(("msiexec.exe".into(), args));
+    }
+
+    // EXE: quoted path, or unquoted path that may contain spaces ("C:\Program Files\App\unins000.exe /x")
+    let (program, rest) = if let Some(stripped) = s.strip_prefix('"') {
+        let end = stripped.find('"')?;
+        (stripped[..end].to_string(), stripped[end + 1..].trim().to_string())
+    } else {
+        match s.to_lowercase().find(".exe") {
+            Some(pos) => (s[..pos + 4].to_string(), s[pos + 4..].trim().to_string()),
+            None => (s.to_string(), String::new()),
+        }
+    };
+
+    let mut args = rest;
+    if silent {
+        let file = std::path::Path::new(&program)
+            .file_name()
+            .map(|f| f.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let switch = if file.starts_with("unins0") {
+            Some("/VERYSILENT /SUPPRESSMSGBOXES /NORESTART") // Inno Setup
+        } else if file == "uninstall.exe" || file == "uninst.exe" || file.starts_with("au_") {
+            Some("/S") // NSIS
+        } else {
+            None
+        };
+        if let Some(switch) = switch {
+            let already = args.to_lowercase().split_whitespace()
+                .any(|a| switch.to_lowercase().split_whitespace().any(|w| w == a));
+            if !already {
+                args = format!("{} {}", args, switch).trim().to_string();
+            }
+        }
+    }
+    Some((program, args))
+}
+
+#[cfg(windows)]
+pub fn uninstall_app(uninstall_string: &str, silent: bool) -> TweakResult {
+    use std::process::Command;
+
+    let (program, args) = match parse_uninstall_string(uninstall_string, silent) {
+        Some(parsed) => parsed,
+        None => {
+            return TweakResult {
+                success: false,
+                message: i18n::tr("uninstall.invalid_command"),
+                backup_path: None,
+            };
+        }
+    };
+
+    // Arguments are passed verbatim: vendors rely on their own quoting
+    let mut cmd = Command::new(&program);
+    if !args.is_empty() {
+        cmd.raw_arg(&args);
+    }
+
+    match cmd.status() {
+        Ok(status) => match status.code() {
+            Some(0) => TweakResult {
+                success: true,
+                message: i18n::tr("uninstall.done"),
+                backup_path: None,
+            },
+            Some(3010) | Some(1641) => TweakResult {
+                success: true,
+                message: i18n::tr("uninstall.done_reboot"),
+                backup_path: None,
+            },
+            Some(1602) => TweakResult {
+                success: false,
+                message: i18n::tr("uninstall.cancelled"),
+                backup_path: None,
+            },
+            Some(1605) => TweakResult {
+                success: false,
+                message: i18n::tr("uninstall.already_removed"),
+                backup_path: None,
+            },
+            code => TweakResult {
+                success: false,
+                message: i18n::trf("uninstall.failed", &[("code", code.unwrap_or(-1).to_string())]),
+                backup_path: None,
+            },
+        },
+        Err(e) => TweakResult {
+            success: false,
+            message: i18n::trf("uninstall.launch_failed", &[("program", program.clone()), ("error", e.to_string())]),
+            backup_path: None,
+        },
+    }
+}
+
+#[cfg(not(windows))]
+pub fn uninstall_app(_uninstall_string: &str, _silent: bool) -> TweakResult {
+    TweakResult {
+        success: false,
+        message: i18n::tr("common.unavailable_platform"),
+        backup_path: None,
+    }
+}
+
 // ============================================
 // STARTUP MANAGER (Registry)
 // ============================================
 
+/// (root, Run key, StartupApproved key, label) for each registry startup location
+#[cfg(windows)]
+const STARTUP_LOCATIONS: &[(winreg::HKEY, &str, &str, &str)] = &[
+    (
+        HKEY_LOCAL_MACHINE,
+        r"SOFTWARE\Microsoft\Windows\CurrentVersion\Run",
+        r"SOFTWARE\Microsoft\Windows\CurrentVersion\Explorer\StartupApproved\Run",
+        "HKLM (Tous les utilisateurs)",
+    ),
+    (
+        HKEY_CURRENT_USER,
+        r"SOFTWARE\Microsoft\Windows\CurrentVersion\Run",
+        r"SOFTWARE\Microsoft\Windows\CurrentVersion\Explorer\StartupApproved\Run",
+        "HKCU (Utilisateur actuel)",
+    ),
+    (
+        HKEY_LOCAL_MACHINE,
+        r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Run",
+        r"SOFTWARE\Microsoft\Windows\CurrentVersion\Explorer\StartupApproved\Run32",
+        "HKLM (32-bit)",
+    ),
+];
+
+#[cfg(windows)]
+fn startup_location(location: &str) -> (winreg::HKEY, &'static str, &'static str) {
+    let index = if location.starts_with("HKLM") {
+        if location.contains("32-bit") { 2 } else { 0 }
+    } else {
+        1
+    };
+    let (root, run, approved, _) = STARTUP_LOCATIONS[index];
+    (root, run, approved)
+}
+
+/// StartupApproved entries (Task Manager toggle): low bit of the first byte set = disabled
+#[cfg(windows)]
+fn is_startup_approved(root: winreg::HKEY, approved_path: &str, name: &str) -> bool {
+    RegKey::predef(root)
+        .open_subkey(approved_path)
+        .and_then(|key| key.get_raw_value(name))
+        .map(|value| value.bytes.first().map(|b| b & 1 == 0).unwrap_or(true))
+        .unwrap_or(true)
+}
+
 #[cfg(windows)]
 pub fn get_startup_items() -> Vec<StartupItem> {
     let mut items = Vec::new();
 
-    let paths = vec![
-        (HKEY_LOCAL_MACHINE, r"SOFTWARE\Microsoft\Windows\CurrentVersion\Run", "HKLM (Tous les utilisateurs)"),
-        (HKEY_CURRENT_USER, r"SOFTWARE\Microsoft\Windows\CurrentVersion\Run", "HKCU (Utilisateur actuel)"),
-        (HKEY_LOCAL_MACHINE, r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Run", "HKLM (32-bit)"),
-    ];
-
-    for (root, path, location) in paths {
+    for &(root, path, approved_path, location) in STARTUP_LOCATIONS {
         let root_key = RegKey::predef(root);
         if let Ok(key) = root_key.open_subkey(path) {
             for value_result in key.enum_values() {
                 if let Ok((name, value)) = value_result {
+                    let enabled = is_startup_approved(root, approved_path, &name);
                     items.push(StartupItem {
                         name,
                         command: value.to_string(),
                         location: location.to_string(),
-                        enabled: true,
+                        enabled,
                     });
                 }
             }
@@ -207,36 +399,28 @@ pub fn get_startup_items() -> Vec<StartupItem> {
 
 #[cfg(windows)]
 pub fn disable_startup_item(name: &str, location: &str) -> TweakResult {
-    let (root, path) = if location.starts_with("HKLM") {
-        if location.contains("32-bit") {
-            (HKEY_LOCAL_MACHINE, r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Run")
-        } else {
-            (HKEY_LOCAL_MACHINE, r"SOFTWARE\Microsoft\Windows\CurrentVersion\Run")
-        }
-    } else {
-        (HKEY_CURRENT_USER, r"SOFTWARE\Microsoft\Windows\CurrentVersion\Run")
-    };
+    let (root, path, _) = startup_location(location);
 
     // Create backup first
-    let backup_result = create_reg_backup(&format!("startup_{}", name), root, path);
+    let backup_result = backup_before_write(&format!("startup_{}", name), root, path, name);
 
     let root_key = RegKey::predef(root);
     match root_key.open_subkey_with_flags(path, KEY_WRITE) {
         Ok(key) => match key.delete_value(name) {
             Ok(_) => TweakResult {
                 success: true,
-                message: format!("{} retiré du démarrage", name),
+                message: i18n::trf("startup.removed", &[("name", name.to_string())]),
                 backup_path: backup_result.ok(),
             },
             Err(e) => TweakResult {
                 success: false,
-                message: format!("Erreur: {}", e),
+                message: i18n::trf("common.error", &[("error", e.to_string())]),
                 backup_path: None,
             },
         },
         Err(e) => TweakResult {
             success: false,
-            message: format!("Accès refusé: {}", e),
+            message: i18n::trf("common.access_denied", &[("error", e.to_string())]),
             backup_path: None,
         },
     }
@@ -246,11 +430,314 @@ pub fn disable_startup_item(name: &str, location: &str) -> TweakResult {
 pub fn disable_startup_item(_name: &str, _location: &str) -> TweakResult {
     TweakResult {
         success: false,
-        message: "Non disponible sur cette plateforme".into(),
+        message: i18n::tr("common.unavailable_platform"),
+        backup_path: None,
+    }
+}
+
+/// Puts a Run value back and clears any Task Manager "disabled" flag for it
+#[cfg(windows)]
+pub fn enable_startup_item(name: &str, command: &str, location: &str) -> TweakResult {
+    use winreg::enums::RegType::REG_BINARY;
+    use winreg::RegValue;
+
+    if name.trim().is_empty() || command.trim().is_empty() {
+        return TweakResult {
+            success: false,
+            message: i18n::tr("startup.name_command_required"),
+            backup_path: None,
+        };
+    }
+
+    let (root, path, approved_path) = startup_location(location);
+    let backup_result = backup_before_write(&format!("startup_{}", name), root, path, name);
+
+    let root_key = RegKey::predef(root);
+    let key = match root_key.create_subkey(path) {
+        Ok((key, _)) => key,
+        Err(e) => {
+            return TweakResult {
+                success: false,
+                message: i18n::trf("common.access_denied", &[("error", e.to_string())]),
+                backup_path: None,
+            };
+        }
+    };
+
+    if let Err(e) = key.set_value(name, &command.to_string()) {
+        return TweakResult {
+            success: false,
+            message: i18n::trf("common.error", &[("error", e.to_string())]),
+            backup_path: backup_result.ok(),
+        };
+    }
+
+    // 0x02 + zeroed timestamp is what Task Manager writes for "enabled"
+    if let Ok(approved) = root_key.open_subkey_with_flags(approved_path, KEY_READ | KEY_WRITE) {
+        if approved.get_raw_value(name).is_ok() {
+            let mut bytes = vec![0u8; 12];
+            bytes[0] = 0x02;
+            let _ = approved.set_raw_value(name, &RegValue { bytes, vtype: REG_BINARY });
+        }
+    }
+
+    TweakResult {
+        success: true,
+        message: i18n::trf("startup.restored", &[("name", name.to_string())]),
+        backup_path: backup_result.ok(),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn enable_startup_item(_name: &str, _command: &str, _location: &str) -> TweakResult {
+    TweakResult {
+        success: false,
+        message: i18n::tr("common.unavailable_platform"),
+        backup_path: None,
+    }
+}
+
+// ============================================
+// SCHEDULED TASKS (logon / boot triggers)
+// ============================================
+
+/// Third-party tasks only: the \Microsoft\ tree is Windows maintenance
+#[cfg(windows)]
+pub fn get_scheduled_startup_tasks() -> Vec<ScheduledTask> {
+    use std::process::Command;
+
+    let script = r#"
+[Console]::OutputEncoding = [System.Text.Encoding]::UTF8
+try {
+    $tasks = Get-ScheduledTask -ErrorAction Stop | Where-Object {
+        $_.TaskPath -notlike '\Microsoft\*' -and
+        ($_.Triggers | Where-Object { $_.CimClass.CimClassName -in 'MSFT_TaskLogonTrigger','MSFT_TaskBootTrigger' })
+    }
+    @($tasks | ForEach-Object {
+        $info = $_ | Get-ScheduledTaskInfo -ErrorAction SilentlyContinue
+        $trigger = if ($_.Triggers | Where-Object { $_.CimClass.CimClassName -eq 'MSFT_TaskBootTrigger' }) { 'boot' } else { 'logon' }
+        $exec = $_.Actions | Where-Object { $_.Execute } | Select-Object -First 1
+        [PSCustomObject]@{
+            Name = $_.TaskName
+            Path = $_.TaskPath + $_.TaskName
+            Trigger = $trigger
+            State = "$($_.State)".ToLower()
+            LastRun = if ($info -and $info.LastRunTime -and $info.LastRunTime.Year -gt 2000) { $info.LastRunTime.ToString('dd/MM/yyyy HH:mm') } else { $null }
+            Action = if ($exec) { ("$($exec.Execute) $($exec.Arguments)").Trim() } else { '' }
+        }
+    }) | ConvertTo-Json -Compress
+} catch { '[]' }
+"#;
+
+    let output = match powershell::require().and_then(|ps| Command::new(ps)
+        .args(["-NoProfile", "-Command", script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| e.to_string()))
+    {
+        Ok(out) => out,
+        Err(_) => return Vec::new(),
+    };
+
+    let items = match serde_json::from_str::<serde_json::Value>(String::from_utf8_lossy(&output.stdout).trim()) {
+        Ok(serde_json::Value::Array(items)) => items,
+        Ok(serde_json::Value::Null) | Err(_) => Vec::new(),
+        Ok(single) => vec![single],
+    };
+
+    items.iter()
+        .map(|t| ScheduledTask {
+            name: t["Name"].as_str().unwrap_or_default().to_string(),
+            path: t["Path"].as_str().unwrap_or_default().to_string(),
+            trigger: t["Trigger"].as_str().unwrap_or("logon").to_string(),
+            state: t["State"].as_str().unwrap_or("unknown").to_string(),
+            last_run: t["LastRun"].as_str().map(|s| s.to_string()),
+            action: t["Action"].as_str().unwrap_or_default().to_string(),
+        })
+        .filter(|t| !t.path.is_empty())
+        .collect()
+}
+
+#[cfg(not(windows))]
+pub fn get_scheduled_startup_tasks() -> Vec<ScheduledTask> {
+    Vec::new()
+}
+
+/// Exports the task definition (schtasks /create /xml can re-import it) before disabling
+#[cfg(windows)]
+fn create_task_backup(path: &str) -> Result<String, String> {
+    use std::process::Command;
+
+    let output = Command::new("schtasks")
+        .args(["/query", "/tn", path, "/xml"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let name: String = path.trim_matches('\\')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let backup_path = get_backup_dir().join(format!("task_{}_{}.xml", name, timestamp));
+    std::fs::write(&backup_path, &output.stdout).map_err(|e| e.to_string())?;
+    Ok(backup_path.to_string_lossy().to_string())
+}
+
+#[cfg(windows)]
+pub fn disable_scheduled_task(path: &str) -> TweakResult {
+    use std::process::Command;
+
+    let path = path.trim();
+    if path.is_empty() || path.contains('"') {
+        return TweakResult {
+            success: false,
+            message: i18n::trf("task.invalid", &[("path", path.to_string())]),
+            backup_path: None,
+        };
+    }
+
+    // Create backup first
+    let backup_result = create_task_backup(path);
+
+    let result = Command::new("schtasks")
+        .args(["/change", "/tn", path, "/disable"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+
+    match result {
+        Ok(output) if output.status.success() => TweakResult {
+            success: true,
+            message: i18n::trf("startup.removed", &[("name", path.to_string())]),
+            backup_path: backup_result.ok(),
+        },
+        Ok(output) => TweakResult {
+            success: false,
+            message: i18n::trf("common.error", &[("error", String::from_utf8_lossy(&output.stderr).trim().to_string())]),
+            backup_path: None,
+        },
+        Err(e) => TweakResult {
+            success: false,
+            message: i18n::trf("common.error", &[("error", e.to_string())]),
+            backup_path: None,
+        },
+    }
+}
+
+#[cfg(not(windows))]
+pub fn disable_scheduled_task(_path: &str) -> TweakResult {
+    TweakResult {
+        success: false,
+        message: i18n::tr("common.unavailable_platform"),
         backup_path: None,
     }
 }
 
+// ============================================
+// CHANGE AUDIT (installed apps + startup snapshot)
+// ============================================
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AppsSnapshot {
+    pub taken_at: String,
+    pub apps: Vec<InstalledApp>,
+    pub startup_items: Vec<StartupItem>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AppVersionChange {
+    pub name: String,
+    pub publisher: String,
+    pub old_version: String,
+    pub new_version: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AppsDiff {
+    pub snapshot_taken_at: String,
+    pub added: Vec<InstalledApp>,   // newest install date first
+    pub removed: Vec<InstalledApp>,
+    pub version_changed: Vec<AppVersionChange>,
+    pub startup_added: Vec<StartupItem>,
+    pub startup_removed: Vec<StartupItem>,
+}
+
+pub fn take_apps_snapshot() -> AppsSnapshot {
+    AppsSnapshot {
+        taken_at: chrono::Local::now().format("%d/%m/%Y %H:%M").to_string(),
+        apps: get_installed_apps_native(),
+        startup_items: get_startup_items(),
+    }
+}
+
+/// Same app registered under both 32 and 64-bit keys collapses to one entry
+fn apps_by_name(apps: &[InstalledApp]) -> HashMap<String, &InstalledApp> {
+    let mut map = HashMap::new();
+    for app in apps {
+        map.entry(app.name.trim().to_lowercase()).or_insert(app);
+    }
+    map
+}
+
+fn startup_key(item: &StartupItem) -> String {
+    format!("{}|{}", item.location, item.name.to_lowercase())
+}
+
+pub fn diff_apps_snapshots(previous: &AppsSnapshot, current: &AppsSnapshot) -> AppsDiff {
+    let before = apps_by_name(&previous.apps);
+    let after = apps_by_name(&current.apps);
+
+    let mut added: Vec<InstalledApp> = after.iter()
+        .filter(|(key, _)| !before.contains_key(*key))
+        .map(|(_, app)| (*app).clone())
+        .collect();
+    // install_date is YYYYMMDD, so string order is date order
+    added.sort_by(|a, b| b.install_date.cmp(&a.install_date).then_with(|| a.name.cmp(&b.name)));
+
+    let mut removed: Vec<InstalledApp> = before.iter()
+        .filter(|(key, _)| !after.contains_key(*key))
+        .map(|(_, app)| (*app).clone())
+        .collect();
+    removed.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut version_changed: Vec<AppVersionChange> = after.iter()
+        .filter_map(|(key, app)| {
+            let old = before.get(key)?;
+            (old.version != app.version).then(|| AppVersionChange {
+                name: app.name.clone(),
+                publisher: app.publisher.clone(),
+                old_version: old.version.clone(),
+                new_version: app.version.clone(),
+            })
+        })
+        .collect();
+    version_changed.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let startup_before: HashMap<String, &StartupItem> = previous.startup_items.iter().map(|i| (startup_key(i), i)).collect();
+    let startup_after: HashMap<String, &StartupItem> = current.startup_items.iter().map(|i| (startup_key(i), i)).collect();
+
+    let startup_added = current.startup_items.iter()
+        .filter(|i| !startup_before.contains_key(&startup_key(i)))
+        .cloned()
+        .collect();
+    let startup_removed = previous.startup_items.iter()
+        .filter(|i| !startup_after.contains_key(&startup_key(i)))
+        .cloned()
+        .collect();
+
+    AppsDiff {
+        snapshot_taken_at: previous.taken_at.clone(),
+        added,
+        removed,
+        version_changed,
+        startup_added,
+        startup_removed,
+    }
+}
+
 // ============================================
 // SMART DISK INFO (WMI Queries)
 // ============================================
@@ -260,8 +747,7 @@ fn get_smart_disk_info(wmi_con: &wmi::WMIConnection) -> Vec<SmartDiskInfo> {
     let mut disks = Vec::new();
 
     // Query Win32_DiskDrive for basic disk info
-    let disk_results: Vec<HashMap<String, wmi::Variant>> = wmi_con
-        .raw_query("SELECT DeviceID, Model, SerialNumber, FirmwareRevision, InterfaceType, MediaType, Size, Status FROM Win32_DiskDrive")
+    let disk_results: Vec<HashMap<String, wmi::Variant>> = cached_query(wmi_con, "SELECT DeviceID, Model, SerialNumber, FirmwareRevision, InterfaceType, MediaType, Size, Status FROM Win32_DiskDrive")
         .unwrap_or_default();
 
     for disk in disk_results {
@@ -313,6 +799,10 @@ fn get_smart_disk_info(wmi_con: &wmi::WMIConnection) -> Vec<SmartDiskInfo> {
             read_error_rate: None,
             seek_error_rate: None,
             spin_retry_count: None,
+            percentage_used: None,
+            available_spare_percent: None,
+            data_units_written: None,
+            self_test: None,
         });
     }
 
@@ -340,6 +830,9 @@ fn get_smart_disk_info(wmi_con: &wmi::WMIConnection) -> Vec<SmartDiskInfo> {
                 disk.reallocated_sectors = attrs.reallocated_sectors;
                 disk.pending_sectors = attrs.pending_sectors;
                 disk.uncorrectable_errors = attrs.uncorrectable_errors;
+                disk.percentage_used = attrs.wear_percent;
+                disk.available_spare_percent = attrs.available_spare_percent;
+                disk.data_units_written = attrs.data_units_written;
 
                 // Recalculate health based on SMART attributes
                 let mut health = 100u8;
@@ -358,6 +851,15 @@ fn get_smart_disk_info(wmi_con: &wmi::WMIConnection) -> Vec<SmartDiskInfo> {
                     if temp > 60 { health = health.saturating_sub(10); }
                     if temp > 70 { health = health.saturating_sub(20); }
                 }
+                // SSDs wear out instead of growing bad sectors: remaining life caps health
+                if disk.media_type != "HDD" {
+                    if let Some(wear) = attrs.wear_percent {
+                        health = health.min(100u8.saturating_sub(wear.min(100)));
+                    }
+                    if let Some(spare) = attrs.available_spare_percent {
+                        if spare < 10 { health = health.saturating_sub(30); }
+                    }
+                }
                 disk.health_percent = health;
                 disk.health_status = if health >= 80 { "Bon" } else if health >= 50 { "Attention" } else { "Critique" }.to_string();
             }
@@ -392,87 +894,231 @@ fn get_smart_disk_info(wmi_con: &wmi::WMIConnection) -> Vec<SmartDiskInfo> {
         }
     }
 
+    // A failed self-test overrides whatever the attributes suggest
+    for disk in disks.iter_mut() {
+        disk.self_test = cached_self_test(&disk.device_id);
+        if disk.self_test.as_ref().and_then(|t| t.passed) == Some(false) {
+            disk.health_percent = disk.health_percent.min(20);
+            disk.health_status = "Critique".to_string();
+        }
+    }
+
     disks
 }
 
 // ============================================
-// CRYSTALDISKINFO INTEGRATION
+// SMART SELF-TEST (smartctl)
 // ============================================
 
+#[derive(Serialize, Clone, Debug)]
+pub struct SmartSelfTestStatus {
+    pub device_id: String,
+    pub in_progress: bool,
+    pub remaining_percent: Option<u8>,
+    pub last_test_type: Option<String>,  // e.g. "Short offline", "Extended"
+    pub last_result: Option<String>,     // smartctl wording, e.g. "Completed without error"
+    pub passed: Option<bool>,
+    pub message: String,
+}
+
 #[cfg(windows)]
-fn find_crystaldiskinfo_exe() -> Option<std::path::PathBuf> {
-    use std::path::PathBuf;
+const SMARTCTL_ADMIN_NOTE: &str = "Les auto-tests SMART necessitent les droits administrateur";
 
-    // PRIORITY 1: Bundled portable version (zero friction)
-    if let Ok(exe_path) = std::env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            // Check in tools subfolder next to executable
-            let bundled = exe_dir.join("tools").join("CrystalDiskInfo").join("DiskInfo64.exe");
-            if bundled.exists() {
-                return Some(bundled);
-            }
-            // Also check directly in tools folder (flat structure)
-            let bundled_flat = exe_dir.join("tools").join("DiskInfo64.exe");
-            if bundled_flat.exists() {
-                return Some(bundled_flat);
-            }
-        }
-    }
+/// Last status polled per device, folded into SmartDiskInfo without running smartctl again
+#[cfg(windows)]
+fn self_test_cache() -> &'static std::sync::Mutex<HashMap<String, SmartSelfTestStatus>> {
+    static CACHE: std::sync::OnceLock<std::sync::Mutex<HashMap<String, SmartSelfTestStatus>>> = std::sync::OnceLock::new();
+    CACHE.get_or_init(|| std::sync::Mutex::new(HashMap::new()))
+}
 
-    // PRIORITY 2: System installed versions (fallback)
-    let localappdata = std::env::var("LOCALAPPDATA").unwrap_or_default();
-    let programfiles = std::env::var("ProgramFiles").unwrap_or_else(|_| r"C:\Program Files".to_string());
-    let programfiles86 = std::env::var("ProgramFiles(x86)").unwrap_or_else(|_| r"C:\Program Files (x86)".to_string());
+#[cfg(windows)]
+fn cached_self_test(device_id: &str) -> Option<SmartSelfTestStatus> {
+    self_test_cache().lock().ok()?.get(device_id).cloned()
+}
 
-    let possible_paths = vec![
-        PathBuf::from(format!(r"{}\CrystalDiskInfo\DiskInfo64.exe", programfiles)),
-        PathBuf::from(format!(r"{}\CrystalDiskInfo\DiskInfo32.exe", programfiles86)),
-        PathBuf::from(format!(r"{}\Microsoft\WinGet\Packages\CrystalDewWorld.CrystalDiskInfo_Microsoft.Winget.Source_8wekyb3d8bbwe\DiskInfo64.exe", localappdata)),
-        PathBuf::from(format!(r"{}\Programs\CrystalDiskInfo\DiskInfo64.exe", localappdata)),
-        PathBuf::from(format!(r"{}\CrystalDiskInfo\DiskInfo64.exe", localappdata)),
-    ];
 
-    for path in possible_paths {
-        if path.exists() {
-            return Some(path);
-        }
+/// \\.\PHYSICALDRIVE1 -> /dev/pd1 (smartctl's Windows device naming)
+#[cfg(windows)]
+fn smartctl_device(device_id: &str) -> Result<String, String> {
+    let upper = device_id.trim().to_uppercase();
+    let index = upper.rsplit("PHYSICALDRIVE").next().filter(|_| upper.contains("PHYSICALDRIVE"));
+    match index.and_then(|i| i.parse::<u32>().ok()) {
+        Some(i) => Ok(format!("/dev/pd{}", i)),
+        None => Err(format!("Disque invalide: {}", device_id)),
     }
+}
 
-    // PRIORITY 3: Try where command
+#[cfg(windows)]
+fn run_smartctl(args: &[&str]) -> Result<(String, i32), String> {
     use std::process::Command;
-    if let Ok(output) = Command::new("where")
-        .arg("DiskInfo64.exe")
+
+    let exe = tools::find_tool(tools::SMARTCTL)
+        .ok_or("smartctl introuvable: installez smartmontools (winget install smartmontools.smartmontools)")?;
+    let output = Command::new(exe)
+        .args(args)
         .creation_flags(CREATE_NO_WINDOW)
         .output()
-    {
-        if output.status.success() {
-            let path_str = String::from_utf8_lossy(&output.stdout);
-            if let Some(first_line) = path_str.lines().next() {
-                let path = PathBuf::from(first_line.trim());
-                if path.exists() {
-                    return Some(path);
-                }
-            }
-        }
+        .map_err(|e| format!("Impossible de lancer smartctl: {}", e))?;
+    let code = output.status.code().unwrap_or(-1);
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    // Bits 0-1 of the exit status are command line / open failures, the rest are disk health flags
+    if code & 0b11 != 0 {
+        let detail = stdout.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with("smartctl ") && !l.starts_with("Copyright"))
+            .last()
+            .unwrap_or("erreur inconnue")
+            .to_string();
+        return Err(format!("smartctl a echoue ({}). {}", detail, SMARTCTL_ADMIN_NOTE));
     }
-
-    None
+    Ok((stdout, code))
 }
 
+/// kind: short or extended. Returns immediately, the drive runs the test in the background.
 #[cfg(windows)]
-fn get_smart_from_crystaldiskinfo() -> Option<Vec<SmartDiskInfo>> {
+pub fn run_smart_self_test(device_id: &str, kind: &str) -> Result<String, String> {
+    let test = match kind.trim().to_lowercase().as_str() {
+        "short" => "short",
+        "extended" | "long" => "long",
+        other => return Err(format!("Type de test inconnu: {} (short ou extended)", other)),
+    };
+    let device = smartctl_device(device_id)?;
+    let (stdout, _) = run_smartctl(&["-t", test, &device])?;
+
+    // "Please wait 2 minutes for test to complete."
+    let minutes = stdout.lines()
+        .find(|l| l.contains("Please wait"))
+        .and_then(|l| l.split_whitespace().find_map(|w| w.parse::<u32>().ok()));
+
+    let mut message = format!("Auto-test {} lance sur {}", if test == "short" { "court" } else { "etendu" }, device_id);
+    if let Some(minutes) = minutes {
+        message.push_str(&format!(", duree estimee {} min", minutes));
+    }
+    message.push_str(&format!(". {}.", SMARTCTL_ADMIN_NOTE));
+    Ok(message)
+}
+
+#[cfg(not(windows))]
+pub fn run_smart_self_test(_device_id: &str, _kind: &str) -> Result<String, String> {
+    Err("Auto-test SMART disponible uniquement sur Windows".to_string())
+}
+
+/// ATA and NVMe report progress and the self-test log under different keys
+#[cfg(windows)]
+fn parse_self_test_status(device_id: &str, json: &serde_json::Value) -> SmartSelfTestStatus {
+    let mut status = SmartSelfTestStatus {
+        device_id: device_id.to_string(),
+        in_progress: false,
+        remaining_percent: None,
+        last_test_type: None,
+        last_result: None,
+        passed: None,
+        message: String::new(),
+    };
+
+    if let Some(ata) = json["ata_smart_data"]["self_test"]["status"].as_object() {
+        // Value 0xF? = test in progress, low nibble = tenths remaining
+        let value = ata.get("value").and_then(|v| v.as_u64()).unwrap_or(0);
+        status.in_progress = value >> 4 == 0xF;
+        status.remaining_percent = ata.get("remaining_percent").and_then(|v| v.as_u64()).map(|v| v as u8);
+        if let Some(last) = json["ata_smart_self_test_log"]["standard"]["table"].get(0) {
+            status.last_test_type = last["type"]["string"].as_str().map(str::to_string);
+            status.last_result = last["status"]["string"].as_str().map(str::to_string);
+            status.passed = last["status"]["passed"].as_bool();
+        }
+    } else if json["nvme_self_test_log"].is_object() {
+        let log = &json["nvme_self_test_log"];
+        status.in_progress = log["current_self_test_operation"]["value"].as_u64().unwrap_or(0) != 0;
+        status.remaining_percent = log["current_self_test_completion_percent"].as_u64()
+            .map(|done| 100u64.saturating_sub(done) as u8);
+        if let Some(last) = log["table"].get(0) {
+            status.last_test_type = last["self_test_code"]["string"].as_str().map(str::to_string);
+            status.last_result = last["self_test_result"]["string"].as_str().map(str::to_string);
+            status.passed = last["self_test_result"]["value"].as_u64().map(|v| v == 0);
+        }
+    }
+
+    status.message = if status.in_progress {
+        match status.remaining_percent {
+            Some(left) => format!("Auto-test en cours, {}% restant", left),
+            None => "Auto-test en cours".to_string(),
+        }
+    } else {
+        match (status.passed, &status.last_result) {
+            (Some(true), _) => "Dernier auto-test reussi".to_string(),
+            (Some(false), Some(result)) => format!("Echec du dernier auto-test: {}. Sauvegardez vos donnees.", result),
+            (Some(false), None) => "Echec du dernier auto-test. Sauvegardez vos donnees.".to_string(),
+            (None, _) => "Aucun auto-test enregistre".to_string(),
+        }
+    };
+    status
+}
+
+#[cfg(windows)]
+pub fn get_smart_self_test_status(device_id: &str) -> Result<SmartSelfTestStatus, String> {
+    let device = smartctl_device(device_id)?;
+    let (stdout, _) = run_smartctl(&["-j", "-c", "-l", "selftest", &device])?;
+    let json: serde_json::Value = serde_json::from_str(&stdout)
+        .map_err(|e| format!("Sortie smartctl illisible: {}", e))?;
+
+    let status = parse_self_test_status(device_id, &json);
+    if let Ok(mut cache) = self_test_cache().lock() {
+        cache.insert(device_id.to_string(), status.clone());
+    }
+    Ok(status)
+}
+
+#[cfg(not(windows))]
+pub fn get_smart_self_test_status(_device_id: &str) -> Result<SmartSelfTestStatus, String> {
+    Err("Auto-test SMART disponible uniquement sur Windows".to_string())
+}
+
+// ============================================
+// CRYSTALDISKINFO INTEGRATION
+// ============================================
+
+
+/// Poll until `path` exists with an mtime at or after `since`, up to `timeout`.
+/// Guards against reading a stale report from a previous run.
+#[cfg(windows)]
+fn wait_for_file_newer_than(path: &std::path::Path, since: std::time::SystemTime, timeout: std::time::Duration) -> bool {
+    use std::time::Instant;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let is_fresh = std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map(|modified| modified >= since)
+            .unwrap_or(false);
+        if is_fresh {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+}
+
+#[cfg(windows)]
+fn get_smart_from_crystaldiskinfo() -> Option<Vec<SmartDiskInfo>> {
     use std::process::Command;
     use std::fs;
     use std::thread;
-    use std::time::Duration;
+    use std::time::{Duration, SystemTime};
 
-    let exe_path = find_crystaldiskinfo_exe()?;
+    let exe_path = tools::find_tool(tools::CRYSTALDISKINFO)?;
     let exe_dir = exe_path.parent()?;
     let output_file = exe_dir.join("DiskInfo.txt");
 
     // Delete old output file
     let _ = fs::remove_file(&output_file);
 
+    // Some filesystems only keep 1-2s mtime resolution: back off a little
+    let launched_at = SystemTime::now()
+        .checked_sub(Duration::from_secs(2))
+        .unwrap_or_else(SystemTime::now);
+
     // Run CrystalDiskInfo with /CopyExit to generate report
     let result = Command::new(&exe_path)
         .arg("/CopyExit")
@@ -484,12 +1130,26 @@ fn get_smart_from_crystaldiskinfo() -> Option<Vec<SmartDiskInfo>> {
         return None;
     }
 
-    // Wait for file to be written
-    thread::sleep(Duration::from_millis(500));
+    // Wait for a fresh report instead of trusting a fixed delay
+    if !wait_for_file_newer_than(&output_file, launched_at, Duration::from_secs(5)) {
+        if output_file.exists() {
+            println!("[SMART] CrystalDiskInfo timeout: {:?} is stale, ignoring it", output_file);
+        } else {
+            println!("[SMART] CrystalDiskInfo timeout: no report written after 5s");
+        }
+        return None;
+    }
+    // Give the writer a moment to flush before reading
+    thread::sleep(Duration::from_millis(50));
 
-    // Read and parse the output file
+    // Read and parse the output file, only accept it if it describes a real disk
     let content = fs::read_to_string(&output_file).ok()?;
-    parse_crystaldiskinfo_output(&content)
+    let disks = parse_crystaldiskinfo_output(&content)?;
+    if disks.iter().any(|d| !d.model.is_empty()) {
+        Some(disks)
+    } else {
+        None
+    }
 }
 
 #[cfg(windows)]
@@ -529,6 +1189,10 @@ fn parse_crystaldiskinfo_output(content: &str) -> Option<Vec<SmartDiskInfo>> {
                 read_error_rate: None,
                 seek_error_rate: None,
                 spin_retry_count: None,
+                percentage_used: None,
+                available_spare_percent: None,
+                data_units_written: None,
+                self_test: None,
             });
         }
 
@@ -616,68 +1280,38 @@ pub struct CrystalDiskInfoResult {
 
 #[cfg(windows)]
 pub async fn check_crystaldiskinfo() -> CrystalDiskInfoResult {
-    if find_crystaldiskinfo_exe().is_some() {
+    if tools::find_tool(tools::CRYSTALDISKINFO).is_some() {
         CrystalDiskInfoResult {
             installed: true,
-            message: "CrystalDiskInfo est installe".to_string(),
+            message: i18n::trf("tool.installed", &[("tool", "CrystalDiskInfo".to_string())]),
         }
     } else {
         CrystalDiskInfoResult {
             installed: false,
-            message: "CrystalDiskInfo n'est pas installe".to_string(),
+            message: i18n::trf("tool.not_installed", &[("tool", "CrystalDiskInfo".to_string())]),
         }
     }
 }
 
 #[cfg(windows)]
 pub async fn install_crystaldiskinfo() -> TweakResult {
-    use std::process::Command;
-    use std::thread;
-    use std::time::Duration;
-
-    // Check if already installed
-    if find_crystaldiskinfo_exe().is_some() {
+    if tools::find_tool(tools::CRYSTALDISKINFO).is_some() {
         return TweakResult {
             success: true,
-            message: "CrystalDiskInfo est deja installe".to_string(),
+            message: i18n::trf("tool.already_installed", &[("tool", "CrystalDiskInfo".to_string())]),
             backup_path: None,
         };
     }
 
-    // Install via winget
-    let result = Command::new("winget")
-        .args([
-            "install",
-            "--id", "CrystalDewWorld.CrystalDiskInfo",
-            "-e",
-            "--silent",
-            "--accept-package-agreements",
-            "--accept-source-agreements",
-        ])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output();
-
-    match result {
-        Ok(output) if output.status.success() => {
-            thread::sleep(Duration::from_secs(2));
-            TweakResult {
-                success: true,
-                message: "CrystalDiskInfo installe avec succes. Redemarrez l'app pour voir les donnees SMART.".to_string(),
-                backup_path: None,
-            }
-        }
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            TweakResult {
-                success: false,
-                message: format!("Erreur: {} {}", stdout, stderr),
-                backup_path: None,
-            }
-        }
+    match tools::ensure_tool(tools::CRYSTALDISKINFO) {
+        Ok(_) => TweakResult {
+            success: true,
+            message: i18n::tr("tool.crystaldiskinfo_installed"),
+            backup_path: None,
+        },
         Err(e) => TweakResult {
             success: false,
-            message: format!("Winget non disponible: {}", e),
+            message: e,
             backup_path: None,
         },
     }
@@ -687,7 +1321,7 @@ pub async fn install_crystaldiskinfo() -> TweakResult {
 pub async fn check_crystaldiskinfo() -> CrystalDiskInfoResult {
     CrystalDiskInfoResult {
         installed: false,
-        message: "CrystalDiskInfo uniquement disponible sur Windows".to_string(),
+        message: i18n::trf("tool.windows_only", &[("tool", "CrystalDiskInfo".to_string())]),
     }
 }
 
@@ -695,7 +1329,7 @@ pub async fn check_crystaldiskinfo() -> CrystalDiskInfoResult {
 pub async fn install_crystaldiskinfo() -> TweakResult {
     TweakResult {
         success: false,
-        message: "CrystalDiskInfo uniquement disponible sur Windows".to_string(),
+        message: i18n::trf("tool.windows_only", &[("tool", "CrystalDiskInfo".to_string())]),
         backup_path: None,
     }
 }
@@ -704,6 +1338,40 @@ pub async fn install_crystaldiskinfo() -> TweakResult {
 // LIBREHARDWAREMONITOR INTEGRATION
 // ============================================
 
+/// Any LibreHardwareMonitor sensor: temperature, fan, load, clock, voltage, power...
+#[derive(Serialize, Clone, Debug)]
+pub struct HardwareSensor {
+    pub name: String,
+    pub sensor_type: String,    // LHM SensorType: Temperature, Fan, Load, Clock, Voltage, Power...
+    pub value: f32,
+    pub max: Option<f32>,
+    pub unit: String,
+    pub parent: String,         // Hardware identifier, e.g. /amdcpu/0 or /gpu-nvidia/0
+    pub hardware_name: String,  // Display name of the parent component
+    pub hardware_type: String,  // LHM HardwareType: Cpu, GpuNvidia, Storage, Motherboard...
+}
+
+#[cfg(windows)]
+fn sensor_unit(sensor_type: &str) -> &'static str {
+    match sensor_type {
+        "Temperature" => "°C",
+        "Fan" => "RPM",
+        "Load" | "Control" | "Level" | "Humidity" => "%",
+        "Clock" => "MHz",
+        "Frequency" => "Hz",
+        "Voltage" => "V",
+        "Current" => "A",
+        "Power" => "W",
+        "Energy" => "mWh",
+        "Data" => "GB",
+        "SmallData" => "MB",
+        "Throughput" => "B/s",
+        "Noise" => "dBA",
+        "TimeSpan" => "s",
+        _ => "",
+    }
+}
+
 #[derive(Serialize, Clone, Debug)]
 pub struct TemperatureSensor {
     pub name: String,
@@ -722,67 +1390,10 @@ pub struct HardwareTemperatures {
     pub disk_temps: Vec<(String, f32)>,
 }
 
-#[cfg(windows)]
-fn find_librehardwaremonitor_exe() -> Option<std::path::PathBuf> {
-    use std::path::PathBuf;
-
-    // PRIORITY 1: Bundled portable version (zero friction)
-    if let Ok(exe_path) = std::env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            let bundled = exe_dir.join("tools").join("LibreHardwareMonitor").join("LibreHardwareMonitor.exe");
-            if bundled.exists() {
-                return Some(bundled);
-            }
-            let bundled_flat = exe_dir.join("tools").join("LibreHardwareMonitor.exe");
-            if bundled_flat.exists() {
-                return Some(bundled_flat);
-            }
-        }
-    }
-
-    // PRIORITY 2: System installed versions (fallback)
-    let localappdata = std::env::var("LOCALAPPDATA").unwrap_or_default();
-    let programfiles = std::env::var("ProgramFiles").unwrap_or_else(|_| r"C:\Program Files".to_string());
-    let programfiles86 = std::env::var("ProgramFiles(x86)").unwrap_or_else(|_| r"C:\Program Files (x86)".to_string());
-
-    let possible_paths = vec![
-        PathBuf::from(format!(r"{}\LibreHardwareMonitor\LibreHardwareMonitor.exe", programfiles)),
-        PathBuf::from(format!(r"{}\LibreHardwareMonitor\LibreHardwareMonitor.exe", programfiles86)),
-        PathBuf::from(format!(r"{}\Microsoft\WinGet\Packages\LibreHardwareMonitor.LibreHardwareMonitor_Microsoft.Winget.Source_8wekyb3d8bbwe\LibreHardwareMonitor.exe", localappdata)),
-        PathBuf::from(format!(r"{}\Programs\LibreHardwareMonitor\LibreHardwareMonitor.exe", localappdata)),
-        PathBuf::from(format!(r"{}\LibreHardwareMonitor\LibreHardwareMonitor.exe", localappdata)),
-    ];
-
-    for path in possible_paths {
-        if path.exists() {
-            return Some(path);
-        }
-    }
-
-    // PRIORITY 3: Try where command
-    use std::process::Command;
-    if let Ok(output) = Command::new("where")
-        .arg("LibreHardwareMonitor.exe")
-        .creation_flags(CREATE_NO_WINDOW)
-        .output()
-    {
-        if output.status.success() {
-            let path_str = String::from_utf8_lossy(&output.stdout);
-            if let Some(first_line) = path_str.lines().next() {
-                let path = PathBuf::from(first_line.trim());
-                if path.exists() {
-                    return Some(path);
-                }
-            }
-        }
-    }
-
-    None
-}
 
 #[cfg(windows)]
 pub fn get_all_temperatures() -> HardwareTemperatures {
-    let lhm_installed = find_librehardwaremonitor_exe().is_some();
+    let lhm_installed = tools::find_tool(tools::LIBREHARDWAREMONITOR).is_some();
 
     // Try to read from LibreHardwareMonitor WMI namespace
     if let Some(temps) = get_temperatures_from_lhm_wmi() {
@@ -811,17 +1422,84 @@ pub fn get_all_temperatures() -> HardwareTemperatures {
     }
 }
 
+/// Every sensor LibreHardwareMonitor publishes over WMI, sorted by component then type.
+/// None when LHM isn't running (namespace missing) so callers can fall back.
 #[cfg(windows)]
-fn get_temperatures_from_lhm_wmi() -> Option<HardwareTemperatures> {
-    use wmi::{COMLibrary, WMIConnection};
+fn lhm_sensors() -> Option<Vec<HardwareSensor>> {
+    use wmi::{COMLibrary, Variant, WMIConnection};
 
     let com_con = COMLibrary::new().ok()?;
     let wmi_con = WMIConnection::with_namespace_path("root\\LibreHardwareMonitor", com_con).ok()?;
 
-    let results: Vec<std::collections::HashMap<String, wmi::Variant>> = wmi_con
-        .raw_query("SELECT Name, SensorType, Value, Max, Parent FROM Sensor WHERE SensorType='Temperature'")
+    let text = |row: &HashMap<String, Variant>, key: &str| match row.get(key) {
+        Some(Variant::String(s)) => s.clone(),
+        _ => String::new(),
+    };
+    let number = |row: &HashMap<String, Variant>, key: &str| match row.get(key) {
+        Some(Variant::R4(v)) => Some(*v),
+        Some(Variant::R8(v)) => Some(*v as f32),
+        _ => None,
+    };
+
+    // Identifier -> (name, type) to label each sensor with its component
+    let hardware: HashMap<String, (String, String)> = wmi_con
+        .raw_query::<HashMap<String, Variant>>("SELECT Identifier, Name, HardwareType FROM Hardware")
+        .unwrap_or_default()
+        .iter()
+        .map(|row| (text(row, "Identifier"), (text(row, "Name"), text(row, "HardwareType"))))
+        .collect();
+
+    let results: Vec<HashMap<String, Variant>> = wmi_con
+        .raw_query("SELECT Name, SensorType, Value, Max, Parent FROM Sensor")
         .ok()?;
 
+    let mut sensors: Vec<HardwareSensor> = results.iter()
+        .filter_map(|row| {
+            let name = text(row, "Name");
+            let value = number(row, "Value")?;
+            if name.is_empty() {
+                return None;
+            }
+            let sensor_type = text(row, "SensorType");
+            let parent = text(row, "Parent");
+            let (hardware_name, hardware_type) = hardware.get(&parent).cloned().unwrap_or_else(|| (parent.clone(), String::new()));
+            Some(HardwareSensor {
+                unit: sensor_unit(&sensor_type).to_string(),
+                name,
+                sensor_type,
+                value,
+                max: number(row, "Max"),
+                parent,
+                hardware_name,
+                hardware_type,
+            })
+        })
+        .collect();
+
+    if sensors.is_empty() {
+        return None;
+    }
+    sensors.sort_by(|a, b| a.parent.cmp(&b.parent).then_with(|| a.sensor_type.cmp(&b.sensor_type)));
+    Some(sensors)
+}
+
+#[cfg(windows)]
+pub fn get_all_sensors() -> Vec<HardwareSensor> {
+    lhm_sensors().unwrap_or_default()
+}
+
+#[cfg(not(windows))]
+pub fn get_all_sensors() -> Vec<HardwareSensor> {
+    Vec::new()
+}
+
+#[cfg(windows)]
+fn get_temperatures_from_lhm_wmi() -> Option<HardwareTemperatures> {
+    let results: Vec<HardwareSensor> = lhm_sensors()?
+        .into_iter()
+        .filter(|s| s.sensor_type == "Temperature")
+        .collect();
+
     if results.is_empty() {
         return None;
     }
@@ -832,24 +1510,10 @@ fn get_temperatures_from_lhm_wmi() -> Option<HardwareTemperatures> {
     let mut disk_temps: Vec<(String, f32)> = Vec::new();
 
     for sensor in results {
-        let name = match sensor.get("Name") {
-            Some(wmi::Variant::String(s)) => s.clone(),
-            _ => continue,
-        };
-        let value = match sensor.get("Value") {
-            Some(wmi::Variant::R4(v)) => *v,
-            Some(wmi::Variant::R8(v)) => *v as f32,
-            _ => continue,
-        };
-        let max = match sensor.get("Max") {
-            Some(wmi::Variant::R4(v)) => Some(*v),
-            Some(wmi::Variant::R8(v)) => Some(*v as f32),
-            _ => None,
-        };
-        let parent = match sensor.get("Parent") {
-            Some(wmi::Variant::String(s)) => s.to_lowercase(),
-            _ => String::new(),
-        };
+        let name = sensor.name;
+        let value = sensor.value;
+        let max = sensor.max;
+        let parent = sensor.parent.to_lowercase();
 
         // Determine sensor type
         let sensor_type = if parent.contains("cpu") || name.to_lowercase().contains("cpu") || name.contains("Core") {
@@ -913,7 +1577,9 @@ try {
 $result | ConvertTo-Json -Compress -Depth 3
 "#;
 
-    let output = Command::new("powershell")
+    powershell::require().ok()?;
+
+    let output = Command::new(powershell::program())
         .args(["-NoProfile", "-Command", ps_script])
         .creation_flags(CREATE_NO_WINDOW)
         .output()
@@ -944,7 +1610,7 @@ $result | ConvertTo-Json -Compress -Depth 3
 
     Some(HardwareTemperatures {
         available: true,
-        lhm_installed: find_librehardwaremonitor_exe().is_some(),
+        lhm_installed: tools::find_tool(tools::LIBREHARDWAREMONITOR).is_some(),
         sensors,
         cpu_temp,
         gpu_temp: None,
@@ -954,80 +1620,142 @@ $result | ConvertTo-Json -Compress -Depth 3
 
 #[cfg(windows)]
 pub async fn check_librehardwaremonitor() -> CrystalDiskInfoResult {
-    if find_librehardwaremonitor_exe().is_some() {
+    if tools::find_tool(tools::LIBREHARDWAREMONITOR).is_some() {
         CrystalDiskInfoResult {
             installed: true,
-            message: "LibreHardwareMonitor est installe".to_string(),
+            message: i18n::trf("tool.installed", &[("tool", "LibreHardwareMonitor".to_string())]),
         }
     } else {
         CrystalDiskInfoResult {
             installed: false,
-            message: "LibreHardwareMonitor n'est pas installe".to_string(),
+            message: i18n::trf("tool.not_installed", &[("tool", "LibreHardwareMonitor".to_string())]),
         }
     }
 }
 
 #[cfg(windows)]
 pub async fn install_librehardwaremonitor() -> TweakResult {
-    use std::process::Command;
-    use std::thread;
-    use std::time::Duration;
-
-    if find_librehardwaremonitor_exe().is_some() {
+    if tools::find_tool(tools::LIBREHARDWAREMONITOR).is_some() {
         return TweakResult {
             success: true,
-            message: "LibreHardwareMonitor est deja installe".to_string(),
+            message: i18n::trf("tool.already_installed", &[("tool", "LibreHardwareMonitor".to_string())]),
             backup_path: None,
         };
     }
 
-    let result = Command::new("winget")
-        .args([
-            "install",
-            "--id", "LibreHardwareMonitor.LibreHardwareMonitor",
-            "-e",
-            "--silent",
-            "--accept-package-agreements",
-            "--accept-source-agreements",
-        ])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output();
-
-    match result {
-        Ok(output) if output.status.success() => {
-            thread::sleep(Duration::from_secs(2));
-            TweakResult {
-                success: true,
-                message: "LibreHardwareMonitor installe. Lancez-le une fois pour activer les capteurs.".to_string(),
-                backup_path: None,
-            }
-        }
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            TweakResult {
-                success: false,
-                message: format!("Erreur: {} {}", stdout, stderr),
-                backup_path: None,
-            }
-        }
+    match tools::ensure_tool(tools::LIBREHARDWAREMONITOR) {
+        Ok(_) => TweakResult {
+            success: true,
+            message: i18n::tr("tool.librehardwaremonitor_installed"),
+            backup_path: None,
+        },
         Err(e) => TweakResult {
             success: false,
-            message: format!("Winget non disponible: {}", e),
+            message: e,
             backup_path: None,
         },
     }
 }
 
+/// hwmon chip name -> sensor_type (coretemp/k10temp for the CPU, amdgpu for the GPU...)
+#[cfg(target_os = "linux")]
+fn hwmon_sensor_type(chip: &str) -> &'static str {
+    match chip {
+        "coretemp" | "k10temp" | "zenpower" | "cpu_thermal" | "soc_thermal" | "x86_pkg_temp" => "CPU",
+        "amdgpu" | "radeon" | "nouveau" | "i915" | "xe" => "GPU",
+        "nvme" | "drivetemp" => "Disk",
+        _ => "Motherboard",
+    }
+}
+
+/// Every temp*_input under /sys/class/hwmon, in millidegrees
+#[cfg(target_os = "linux")]
+fn platform_temperature_sensors() -> Vec<TemperatureSensor> {
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok().map(|v| v.trim().to_string());
+    let millis = |path: std::path::PathBuf| read(path).and_then(|v| v.parse::<f32>().ok()).map(|v| v / 1000.0);
+
+    let mut sensors = Vec::new();
+    let chips = match std::fs::read_dir("/sys/class/hwmon") {
+        Ok(entries) => entries,
+        Err(_) => return sensors,
+    };
+
+    for chip_dir in chips.flatten().map(|e| e.path()) {
+        let chip = read(chip_dir.join("name")).unwrap_or_else(|| "hwmon".to_string());
+        let mut inputs: Vec<String> = match std::fs::read_dir(&chip_dir) {
+            Ok(files) => files.flatten()
+                .filter_map(|f| f.file_name().into_string().ok())
+                .filter(|name| name.starts_with("temp") && name.ends_with("_input"))
+                .collect(),
+            Err(_) => continue,
+        };
+        inputs.sort();
+
+        for input in inputs {
+            let prefix = input.trim_end_matches("_input");
+            let value = match millis(chip_dir.join(&input)) {
+                Some(v) if v > 0.0 && v < 150.0 => v,
+                _ => continue,
+            };
+            let label = read(chip_dir.join(format!("{}_label", prefix))).unwrap_or_else(|| prefix.to_string());
+            sensors.push(TemperatureSensor {
+                name: format!("{} {}", chip, label),
+                sensor_type: hwmon_sensor_type(&chip).to_string(),
+                value,
+                max: millis(chip_dir.join(format!("{}_crit", prefix)))
+                    .or_else(|| millis(chip_dir.join(format!("{}_max", prefix)))),
+            });
+        }
+    }
+
+    sensors
+}
+
+/// sysinfo reads the SMC on macOS; labels look like "CPU Proximity", "GPU Proximity", "PMU tdie1"
+#[cfg(not(any(windows, target_os = "linux")))]
+fn platform_temperature_sensors() -> Vec<TemperatureSensor> {
+    sysinfo::Components::new_with_refreshed_list()
+        .iter()
+        .filter(|c| c.temperature() > 0.0 && c.temperature() < 150.0)
+        .map(|c| {
+            let label = c.label().to_lowercase();
+            let sensor_type = if label.contains("cpu") || label.contains("tdie") || label.contains("core") {
+                "CPU"
+            } else if label.contains("gpu") {
+                "GPU"
+            } else if label.contains("ssd") || label.contains("nand") || label.contains("disk") {
+                "Disk"
+            } else {
+                "Motherboard"
+            };
+            TemperatureSensor {
+                name: c.label().to_string(),
+                sensor_type: sensor_type.to_string(),
+                value: c.temperature(),
+                max: c.critical(),
+            }
+        })
+        .collect()
+}
+
 #[cfg(not(windows))]
 pub fn get_all_temperatures() -> HardwareTemperatures {
+    let sensors = platform_temperature_sensors();
+    let hottest = |kind: &str| sensors.iter()
+        .filter(|s| s.sensor_type == kind)
+        .map(|s| s.value)
+        .fold(None, |max: Option<f32>, v| Some(max.map_or(v, |m| m.max(v))));
+
     HardwareTemperatures {
-        available: false,
+        available: !sensors.is_empty(),
         lhm_installed: false,
-        sensors: Vec::new(),
-        cpu_temp: None,
-        gpu_temp: None,
-        disk_temps: Vec::new(),
+        cpu_temp: hottest("CPU"),
+        gpu_temp: hottest("GPU"),
+        disk_temps: sensors.iter()
+            .filter(|s| s.sensor_type == "Disk")
+            .map(|s| (s.name.clone(), s.value))
+            .collect(),
+        sensors,
     }
 }
 
@@ -1035,7 +1763,7 @@ pub fn get_all_temperatures() -> HardwareTemperatures {
 pub async fn check_librehardwaremonitor() -> CrystalDiskInfoResult {
     CrystalDiskInfoResult {
         installed: false,
-        message: "LibreHardwareMonitor uniquement disponible sur Windows".to_string(),
+        message: i18n::trf("tool.windows_only", &[("tool", "LibreHardwareMonitor".to_string())]),
     }
 }
 
@@ -1043,7 +1771,7 @@ pub async fn check_librehardwaremonitor() -> CrystalDiskInfoResult {
 pub async fn install_librehardwaremonitor() -> TweakResult {
     TweakResult {
         success: false,
-        message: "LibreHardwareMonitor uniquement disponible sur Windows".to_string(),
+        message: i18n::trf("tool.windows_only", &[("tool", "LibreHardwareMonitor".to_string())]),
         backup_path: None,
     }
 }
@@ -1064,6 +1792,7 @@ pub struct DiagnosticToolsStatus {
     pub needs_admin: bool,
     pub message: String,
     pub errors: Vec<String>,
+    pub tools: Vec<tools::ToolStatus>,  // Every registered tool with its source and version
 }
 
 #[cfg(windows)]
@@ -1072,8 +1801,22 @@ pub async fn auto_setup_diagnostic_tools() -> DiagnosticToolsStatus {
     use std::thread;
     use std::time::Duration;
 
-    let cdi_path = find_crystaldiskinfo_exe();
-    let lhm_path = find_librehardwaremonitor_exe();
+    let mut messages = Vec::new();
+    let mut errors = Vec::new();
+
+    // Auto-install every registered tool that is missing
+    for tool in tools::TOOL_REGISTRY {
+        if tools::find_tool(tool.id).is_some() {
+            continue;
+        }
+        match tools::ensure_tool(tool.id) {
+            Ok(_) => messages.push(format!("{} installe", tool.name)),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    let cdi_path = tools::find_tool(tools::CRYSTALDISKINFO);
+    let lhm_path = tools::find_tool(tools::LIBREHARDWAREMONITOR);
 
     let mut status = DiagnosticToolsStatus {
         crystaldiskinfo_installed: cdi_path.is_some(),
@@ -1085,114 +1828,33 @@ pub async fn auto_setup_diagnostic_tools() -> DiagnosticToolsStatus {
         librehardwaremonitor_running: false,
         needs_admin: false,
         message: String::new(),
-        errors: Vec::new(),
+        errors,
+        tools: Vec::new(),
     };
 
-    let mut messages = Vec::new();
-
-    // Auto-install CrystalDiskInfo if not present
-    if !status.crystaldiskinfo_installed {
-        status.crystaldiskinfo_installing = true;
-        let result = Command::new("winget")
-            .args([
-                "install",
-                "--id", "CrystalDewWorld.CrystalDiskInfo",
-                "-e",
-                "--silent",
-                "--accept-package-agreements",
-                "--accept-source-agreements",
-            ])
-            .creation_flags(CREATE_NO_WINDOW)
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    thread::sleep(Duration::from_secs(3));
-                    let new_path = find_crystaldiskinfo_exe();
-                    status.crystaldiskinfo_installed = new_path.is_some();
-                    status.crystaldiskinfo_path = new_path.map(|p| p.to_string_lossy().to_string());
-                    if status.crystaldiskinfo_installed {
-                        messages.push("CrystalDiskInfo installe".to_string());
-                    } else {
-                        status.errors.push("CrystalDiskInfo: winget OK mais exe non trouve".to_string());
-                    }
-                } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    status.errors.push(format!("CrystalDiskInfo install: {}{}", stdout, stderr));
-                }
-            }
-            Err(e) => {
-                status.errors.push(format!("winget non disponible: {}", e));
-            }
-        }
-        status.crystaldiskinfo_installing = false;
-    }
-
-    // Auto-install LibreHardwareMonitor if not present
-    if !status.librehardwaremonitor_installed {
-        status.librehardwaremonitor_installing = true;
-        let result = Command::new("winget")
-            .args([
-                "install",
-                "--id", "LibreHardwareMonitor.LibreHardwareMonitor",
-                "-e",
-                "--silent",
-                "--accept-package-agreements",
-                "--accept-source-agreements",
-            ])
-            .creation_flags(CREATE_NO_WINDOW)
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    thread::sleep(Duration::from_secs(3));
-                    let new_path = find_librehardwaremonitor_exe();
-                    status.librehardwaremonitor_installed = new_path.is_some();
-                    status.librehardwaremonitor_path = new_path.map(|p| p.to_string_lossy().to_string());
-                    if status.librehardwaremonitor_installed {
-                        messages.push("LibreHardwareMonitor installe".to_string());
-                    } else {
-                        status.errors.push("LHM: winget OK mais exe non trouve".to_string());
-                    }
-                } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    status.errors.push(format!("LHM install: {}{}", stdout, stderr));
-                }
-            }
-            Err(e) => {
-                status.errors.push(format!("winget: {}", e));
-            }
-        }
-        status.librehardwaremonitor_installing = false;
-    }
-
-    // Run CrystalDiskInfo to generate report
-    if status.crystaldiskinfo_installed {
-        if let Some(ref path_str) = status.crystaldiskinfo_path {
-            let exe_path = std::path::PathBuf::from(path_str);
-            if let Some(exe_dir) = exe_path.parent() {
-                match Command::new(&exe_path)
-                    .arg("/CopyExit")
-                    .current_dir(exe_dir)
-                    .creation_flags(CREATE_NO_WINDOW)
-                    .output()
-                {
-                    Ok(_) => {
-                        thread::sleep(Duration::from_millis(1000));
-                        // Check if DiskInfo.txt was created
-                        let txt_path = exe_dir.join("DiskInfo.txt");
-                        if txt_path.exists() {
-                            messages.push("SMART OK".to_string());
-                        } else {
-                            status.errors.push("DiskInfo.txt non genere".to_string());
-                        }
-                    }
-                    Err(e) => {
-                        status.errors.push(format!("CrystalDiskInfo run: {}", e));
+    // Run CrystalDiskInfo to generate report
+    if status.crystaldiskinfo_installed {
+        if let Some(ref path_str) = status.crystaldiskinfo_path {
+            let exe_path = std::path::PathBuf::from(path_str);
+            if let Some(exe_dir) = exe_path.parent() {
+                match Command::new(&exe_path)
+                    .arg("/CopyExit")
+                    .current_dir(exe_dir)
+                    .creation_flags(CREATE_NO_WINDOW)
+                    .output()
+                {
+                    Ok(_) => {
+                        thread::sleep(Duration::from_millis(1000));
+                        // Check if DiskInfo.txt was created
+                        let txt_path = exe_dir.join("DiskInfo.txt");
+                        if txt_path.exists() {
+                            messages.push("SMART OK".to_string());
+                        } else {
+                            status.errors.push("DiskInfo.txt non genere".to_string());
+                        }
+                    }
+                    Err(e) => {
+                        status.errors.push(format!("CrystalDiskInfo run: {}", e));
                     }
                 }
             }
@@ -1261,6 +1923,7 @@ pub async fn auto_setup_diagnostic_tools() -> DiagnosticToolsStatus {
     } else {
         messages.join(" | ")
     };
+    status.tools = tools::list_tools(false);
 
     status
 }
@@ -1276,8 +1939,9 @@ pub async fn auto_setup_diagnostic_tools() -> DiagnosticToolsStatus {
         librehardwaremonitor_path: None,
         librehardwaremonitor_running: false,
         needs_admin: false,
-        message: "Disponible uniquement sur Windows".to_string(),
+        message: i18n::tr("common.unavailable_platform"),
         errors: vec![],
+        tools: tools::list_tools(false),
     }
 }
 
@@ -1289,6 +1953,9 @@ struct SmartAttributes {
     reallocated_sectors: Option<u32>,
     pending_sectors: Option<u32>,
     uncorrectable_errors: Option<u32>,
+    wear_percent: Option<u8>,
+    available_spare_percent: Option<u8>,
+    data_units_written: Option<u64>,
 }
 
 #[cfg(windows)]
@@ -1299,6 +1966,37 @@ fn get_smart_attributes_powershell() -> Option<HashMap<String, SmartAttributes>>
     let ps_script = r#"
 $result = @{}
 try {
+    # NVMe SMART / Health log page (02h) through IOCTL_STORAGE_QUERY_PROPERTY
+    Add-Type -TypeDefinition @'
+using System;
+using System.Runtime.InteropServices;
+using Microsoft.Win32.SafeHandles;
+public static class MicrodiagNvme {
+    [DllImport("kernel32.dll", SetLastError = true, CharSet = CharSet.Unicode)]
+    static extern SafeFileHandle CreateFile(string name, uint access, uint share, IntPtr sa, uint disposition, uint flags, IntPtr template);
+    [DllImport("kernel32.dll", SetLastError = true)]
+    static extern bool DeviceIoControl(SafeFileHandle h, uint code, byte[] inBuf, int inSize, byte[] outBuf, int outSize, out int returned, IntPtr overlapped);
+    public static byte[] SmartLog(string diskNumber) {
+        using (var h = CreateFile(@"\\.\PhysicalDrive" + diskNumber, 0, 3, IntPtr.Zero, 3, 0, IntPtr.Zero)) {
+            if (h.IsInvalid) return null;
+            // STORAGE_PROPERTY_QUERY header (8) + STORAGE_PROTOCOL_SPECIFIC_DATA (40) + log page (512)
+            var buf = new byte[8 + 40 + 512];
+            BitConverter.GetBytes(50).CopyTo(buf, 0);   // StorageDeviceProtocolSpecificProperty
+            BitConverter.GetBytes(3).CopyTo(buf, 8);    // ProtocolTypeNvme
+            BitConverter.GetBytes(2).CopyTo(buf, 12);   // NVMeDataTypeLogPage
+            BitConverter.GetBytes(2).CopyTo(buf, 16);   // SMART / Health Information
+            BitConverter.GetBytes(40).CopyTo(buf, 24);  // ProtocolDataOffset
+            BitConverter.GetBytes(512).CopyTo(buf, 28); // ProtocolDataLength
+            int returned;
+            if (!DeviceIoControl(h, 0x2D1400, buf, buf.Length, buf, buf.Length, out returned, IntPtr.Zero)) return null;
+            var log = new byte[512];
+            Array.Copy(buf, 48, log, 0, 512);
+            return log;
+        }
+    }
+}
+'@ -ErrorAction SilentlyContinue
+
     # Method 1: Native Windows 10/11 Storage cmdlets (best, no admin)
     $disks = Get-PhysicalDisk -ErrorAction SilentlyContinue
     foreach ($disk in $disks) {
@@ -1313,6 +2011,17 @@ try {
                 write_errors = $reliability.WriteErrorsTotal
                 wear = $reliability.Wear
             }
+            if ("$($disk.BusType)" -eq "NVMe") {
+                try {
+                    $log = [MicrodiagNvme]::SmartLog($disk.DeviceId)
+                    if ($log) {
+                        $result[$key]['available_spare'] = [int]$log[3]
+                        $result[$key]['percentage_used'] = [int]$log[5]
+                        # Data Units Written is 128-bit, the low 64 bits are plenty
+                        $result[$key]['data_units_written'] = [BitConverter]::ToUInt64($log, 48)
+                    }
+                } catch {}
+            }
         }
     }
 
@@ -1351,7 +2060,9 @@ try {
 }
 "#;
 
-    let output = Command::new("powershell")
+    powershell::require().ok()?;
+
+    let output = Command::new(powershell::program())
         .args(["-NoProfile", "-Command", ps_script])
         .creation_flags(CREATE_NO_WINDOW)
         .output()
@@ -1380,6 +2091,11 @@ try {
             reallocated_sectors: attrs.get("reallocated_sectors").and_then(|v| v.as_u64()).map(|v| v as u32),
             pending_sectors: attrs.get("pending_sectors").and_then(|v| v.as_u64()).map(|v| v as u32),
             uncorrectable_errors: attrs.get("uncorrectable").and_then(|v| v.as_u64()).map(|v| v as u32),
+            // NVMe log page value first, storage counter "Wear" otherwise
+            wear_percent: attrs.get("percentage_used").or_else(|| attrs.get("wear"))
+                .and_then(|v| v.as_u64()).map(|v| v.min(255) as u8),
+            available_spare_percent: attrs.get("available_spare").and_then(|v| v.as_u64()).map(|v| v.min(100) as u8),
+            data_units_written: attrs.get("data_units_written").and_then(|v| v.as_u64()),
         };
 
         result.insert(instance_name.clone(), smart_attrs);
@@ -1403,13 +2119,58 @@ fn extract_u64(variant: Option<&wmi::Variant>) -> u64 {
 // CRITICAL DRIVERS (GPU, Network, Chipset)
 // ============================================
 
+/// Drivers older than this are flagged for update (~2 years)
+#[cfg(windows)]
+pub const DRIVER_OUTDATED_DAYS: i64 = 730;
+
+/// WMI CIM_DATETIME "yyyymmddHHMMSS.mmmmmm+UUU", also seen truncated or with a "+0000" offset.
+/// Only the leading date digits matter, the offset is ignored.
+#[cfg(windows)]
+fn parse_wmi_driver_date(raw: &str) -> Option<chrono::NaiveDate> {
+    let digits: String = raw.trim().chars().take_while(|c| c.is_ascii_digit()).collect();
+    let date = digits.get(..8)?;
+    let year: i32 = date[0..4].parse().ok()?;
+    let month: u32 = date[4..6].parse().ok()?;
+    let day: u32 = date[6..8].parse().ok()?;
+    chrono::NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// Vendor id and driver download page for a GPU, from its name or manufacturer
+#[cfg(windows)]
+fn gpu_driver_vendor(name: &str, manufacturer: &str) -> Option<(&'static str, &'static str)> {
+    let text = format!("{} {}", name, manufacturer).to_lowercase();
+    if text.contains("nvidia") || text.contains("geforce") || text.contains("quadro") {
+        Some(("nvidia", "https://www.nvidia.com/Download/index.aspx"))
+    } else if text.contains("amd") || text.contains("radeon") || text.contains("advanced micro devices") {
+        Some(("amd", "https://www.amd.com/en/support/download/drivers.html"))
+    } else if text.contains("intel") {
+        Some(("intel", "https://www.intel.com/content/www/us/en/support/detect.html"))
+    } else {
+        None
+    }
+}
+
+/// Age, outdated flag and GPU vendor link; Microsoft inbox drivers are updated by Windows Update
+#[cfg(windows)]
+fn annotate_driver_age(driver: &mut DriverInfo, date: Option<chrono::NaiveDate>, today: chrono::NaiveDate) {
+    driver.driver_age_days = date.map(|d| (today - d).num_days().max(0));
+    driver.is_outdated = !driver.manufacturer.to_lowercase().contains("microsoft")
+        && driver.driver_age_days.map(|days| days > DRIVER_OUTDATED_DAYS).unwrap_or(false);
+
+    if driver.driver_type == "GPU" {
+        if let Some((vendor, url)) = gpu_driver_vendor(&driver.name, &driver.manufacturer) {
+            driver.gpu_vendor = Some(vendor.to_string());
+            driver.download_url = Some(url.to_string());
+        }
+    }
+}
+
 #[cfg(windows)]
 fn get_critical_drivers(wmi_con: &wmi::WMIConnection) -> Vec<DriverInfo> {
     let mut drivers = Vec::new();
 
     // Query ALL PnP Signed Drivers and filter in code (more reliable)
-    let driver_results: Vec<HashMap<String, wmi::Variant>> = wmi_con
-        .raw_query("SELECT DeviceName, DriverVersion, Manufacturer, DriverDate, DeviceClass, Status FROM Win32_PnPSignedDriver WHERE DriverVersion IS NOT NULL")
+    let driver_results: Vec<HashMap<String, wmi::Variant>> = cached_query(wmi_con, "SELECT DeviceName, DriverVersion, Manufacturer, DriverDate, DeviceClass, Status FROM Win32_PnPSignedDriver WHERE DriverVersion IS NOT NULL")
         .unwrap_or_default();
 
     for drv in driver_results {
@@ -1471,29 +2232,26 @@ fn get_critical_drivers(wmi_con: &wmi::WMIConnection) -> Vec<DriverInfo> {
 
         // Only add important drivers (GPU, Network, Chipset, Audio)
         if driver_type != "Other" {
-            // Format driver date (remove time part if exists)
-            let formatted_date = if let Some(date_part) = driver_date.split('.').next() {
-                // Convert WMI date format (YYYYMMDD) to DD/MM/YYYY
-                if date_part.len() >= 8 {
-                    let year = &date_part[0..4];
-                    let month = &date_part[4..6];
-                    let day = &date_part[6..8];
-                    format!("{}/{}/{}", day, month, year)
-                } else {
-                    driver_date.clone()
-                }
-            } else {
-                driver_date.clone()
-            };
+            // Convert WMI date format (YYYYMMDD...) to DD/MM/YYYY
+            let parsed_date = parse_wmi_driver_date(&driver_date);
+            let formatted_date = parsed_date
+                .map(|d| d.format("%d/%m/%Y").to_string())
+                .unwrap_or_else(|| driver_date.clone());
 
-            drivers.push(DriverInfo {
+            let mut driver = DriverInfo {
                 name: device_name,
                 version,
                 driver_type: driver_type.to_string(),
                 manufacturer,
                 driver_date: formatted_date,
                 status: if status == "OK" { "OK".to_string() } else { status },
-            });
+                driver_age_days: None,
+                is_outdated: false,
+                gpu_vendor: None,
+                download_url: None,
+            };
+            annotate_driver_age(&mut driver, parsed_date, chrono::Local::now().date_naive());
+            drivers.push(driver);
         }
     }
 
@@ -1523,6 +2281,244 @@ fn get_critical_drivers(wmi_con: &wmi::WMIConnection) -> Vec<DriverInfo> {
     drivers
 }
 
+/// Critical drivers with their own WMI connection, for callers outside the deep health scan
+#[cfg(windows)]
+pub fn get_driver_list() -> Vec<DriverInfo> {
+    use wmi::{COMLibrary, WMIConnection};
+
+    match COMLibrary::new().ok().and_then(|com| WMIConnection::new(com).ok()) {
+        Some(con) => get_critical_drivers(&con),
+        None => Vec::new(),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn get_driver_list() -> Vec<DriverInfo> {
+    Vec::new()
+}
+
+// ============================================
+// GPU INFO (WMI + Registry)
+// ============================================
+
+#[derive(Serialize, Clone, Debug)]
+pub struct GpuInfo {
+    pub name: String,
+    pub memory_mb: u64,
+    pub driver_version: String,
+    pub refresh_rate_hz: Option<u32>,
+    pub is_discrete: bool,
+}
+
+/// Integrated chips (Intel UHD/Iris, AMD APU "Radeon Graphics") vs dedicated cards
+#[cfg(windows)]
+fn is_discrete_gpu(name: &str) -> bool {
+    let n = name.to_lowercase();
+    n.contains("nvidia") || n.contains("geforce") || n.contains("quadro")
+        || n.contains("intel(r) arc") || n.contains("intel arc")
+        || n.contains("radeon rx") || n.contains("radeon pro") || n.contains("firepro")
+}
+
+/// Real VRAM from the display class key: AdapterRAM is a 32-bit field and caps at 4 GB
+#[cfg(windows)]
+fn gpu_memory_from_registry(name: &str) -> Option<u64> {
+    const DISPLAY_CLASS: &str = r"SYSTEM\CurrentControlSet\Control\Class\{4d36e968-e325-11ce-bfc1-08002be10318}";
+
+    let class = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(DISPLAY_CLASS).ok()?;
+    for sub in class.enum_keys().filter_map(Result::ok) {
+        let key = match class.open_subkey(&sub) {
+            Ok(k) => k,
+            Err(_) => continue,
+        };
+        let desc: String = key.get_value("DriverDesc").unwrap_or_default();
+        if !desc.eq_ignore_ascii_case(name) {
+            continue;
+        }
+        if let Ok(raw) = key.get_raw_value("HardwareInformation.qwMemorySize") {
+            if raw.bytes.len() >= 8 {
+                let bytes: [u8; 8] = raw.bytes[..8].try_into().ok()?;
+                return Some(u64::from_le_bytes(bytes) / 1_048_576);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(windows)]
+pub fn get_gpu_adapters() -> Vec<GpuInfo> {
+    use wmi::{COMLibrary, Variant, WMIConnection};
+
+    let wmi_con = match COMLibrary::new().ok().and_then(|com| WMIConnection::new(com).ok()) {
+        Some(con) => con,
+        None => return Vec::new(),
+    };
+
+    let rows: Vec<HashMap<String, Variant>> = cached_query(&wmi_con, "SELECT Name, AdapterRAM, DriverVersion, CurrentRefreshRate FROM Win32_VideoController")
+        .unwrap_or_default();
+
+    rows.iter()
+        .filter_map(|row| {
+            let name = match row.get("Name") {
+                Some(Variant::String(s)) => s.trim().to_string(),
+                _ => return None,
+            };
+            // Virtual adapters (RDP, basic display) say nothing about the hardware
+            let lower = name.to_lowercase();
+            if lower.contains("basic display") || lower.contains("remote") || lower.contains("virtual") {
+                return None;
+            }
+
+            // Signed 32-bit in WMI: reinterpret as unsigned, then prefer the registry value
+            let adapter_ram = match row.get("AdapterRAM") {
+                Some(Variant::UI4(n)) => *n as u64,
+                Some(Variant::I4(n)) => *n as u32 as u64,
+                _ => 0,
+            };
+            let memory_mb = gpu_memory_from_registry(&name).unwrap_or(adapter_ram / 1_048_576);
+
+            let refresh_rate_hz = match row.get("CurrentRefreshRate") {
+                Some(Variant::UI4(n)) if *n > 1 => Some(*n),
+                _ => None,
+            };
+
+            Some(GpuInfo {
+                is_discrete: is_discrete_gpu(&name),
+                driver_version: match row.get("DriverVersion") {
+                    Some(Variant::String(s)) => s.clone(),
+                    _ => String::new(),
+                },
+                name,
+                memory_mb,
+                refresh_rate_hz,
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(windows))]
+pub fn get_gpu_adapters() -> Vec<GpuInfo> {
+    Vec::new()
+}
+
+/// Main GPU: on hybrid laptops the discrete card rather than the integrated one
+pub fn get_gpu_info() -> Option<GpuInfo> {
+    let adapters = get_gpu_adapters();
+    adapters.iter()
+        .filter(|g| g.is_discrete)
+        .max_by_key(|g| g.memory_mb)
+        .or_else(|| adapters.iter().max_by_key(|g| g.memory_mb))
+        .cloned()
+}
+
+// ============================================
+// MOTHERBOARD & MEMORY (WMI)
+// ============================================
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BoardInfo {
+    pub motherboard: String,
+    pub bios_version: String,
+    pub ram_slots_used: String,  // "2/4 (16 GB DDR4-3200)"
+}
+
+/// (serial, manufacturer, version), shared by deep health and the board info
+#[cfg(windows)]
+fn query_bios(wmi_con: &wmi::WMIConnection) -> (String, String, String) {
+    let bios_results: Vec<HashMap<String, wmi::Variant>> = cached_query(wmi_con, "SELECT SerialNumber, Manufacturer, SMBIOSBIOSVersion FROM Win32_BIOS")
+        .unwrap_or_default();
+
+    bios_results.first()
+        .map(|bios| (
+            extract_string(bios.get("SerialNumber")),
+            extract_string(bios.get("Manufacturer")),
+            extract_string(bios.get("SMBIOSBIOSVersion")),
+        ))
+        .unwrap_or(("Unknown".into(), "Unknown".into(), "Unknown".into()))
+}
+
+/// SMBIOS memory type codes (Win32_PhysicalMemory.SMBIOSMemoryType)
+#[cfg(windows)]
+fn memory_type_name(code: u32) -> Option<&'static str> {
+    match code {
+        20 => Some("DDR"),
+        21 => Some("DDR2"),
+        24 => Some("DDR3"),
+        26 => Some("DDR4"),
+        27 => Some("LPDDR"),
+        28 => Some("LPDDR2"),
+        29 => Some("LPDDR3"),
+        30 => Some("LPDDR4"),
+        34 => Some("DDR5"),
+        35 => Some("LPDDR5"),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+fn query_ram_slots(wmi_con: &wmi::WMIConnection) -> String {
+    let modules: Vec<HashMap<String, wmi::Variant>> = cached_query(wmi_con, "SELECT Capacity, SMBIOSMemoryType, ConfiguredClockSpeed, Speed FROM Win32_PhysicalMemory")
+        .unwrap_or_default();
+    // Use = 3: system memory (excludes video/flash arrays)
+    let arrays: Vec<HashMap<String, wmi::Variant>> = cached_query(wmi_con, "SELECT MemoryDevices FROM Win32_PhysicalMemoryArray WHERE Use = 3")
+        .unwrap_or_default();
+
+    if modules.is_empty() {
+        return String::new();
+    }
+
+    let used = modules.len() as u32;
+    let total_slots: u32 = arrays.iter().map(|a| extract_u32(a.get("MemoryDevices"))).sum();
+    let total_gb = modules.iter().map(|m| extract_u64(m.get("Capacity"))).sum::<u64>() as f64 / 1_073_741_824.0;
+
+    let first = &modules[0];
+    let kind = memory_type_name(extract_u32(first.get("SMBIOSMemoryType")));
+    let speed = match extract_u32(first.get("ConfiguredClockSpeed")) {
+        0 => extract_u32(first.get("Speed")),
+        configured => configured,
+    };
+
+    let mut spec = format!("{:.0} GB", total_gb);
+    match (kind, speed) {
+        (Some(kind), s) if s > 0 => spec.push_str(&format!(" {}-{}", kind, s)),
+        (Some(kind), _) => spec.push_str(&format!(" {}", kind)),
+        (None, s) if s > 0 => spec.push_str(&format!(" {} MHz", s)),
+        _ => {}
+    }
+
+    // Some firmwares report 0 slots: show the populated count only
+    if total_slots >= used {
+        format!("{}/{} ({})", used, total_slots, spec)
+    } else {
+        format!("{} ({})", used, spec)
+    }
+}
+
+#[cfg(windows)]
+pub fn get_board_info() -> Option<BoardInfo> {
+    use wmi::{COMLibrary, WMIConnection};
+
+    let wmi_con = WMIConnection::new(COMLibrary::new().ok()?).ok()?;
+
+    let boards: Vec<HashMap<String, wmi::Variant>> = cached_query(&wmi_con, "SELECT Manufacturer, Product FROM Win32_BaseBoard")
+        .unwrap_or_default();
+    let motherboard = boards.first()
+        .map(|b| format!("{} {}", extract_string(b.get("Manufacturer")), extract_string(b.get("Product"))).trim().to_string())
+        .unwrap_or_default();
+
+    let (_, _, bios_version) = query_bios(&wmi_con);
+
+    Some(BoardInfo {
+        motherboard,
+        bios_version,
+        ram_slots_used: query_ram_slots(&wmi_con),
+    })
+}
+
+#[cfg(not(windows))]
+pub fn get_board_info() -> Option<BoardInfo> {
+    None
+}
+
 // ============================================
 // DEEP HEALTH (WMI)
 // ============================================
@@ -1538,21 +2534,10 @@ pub fn get_deep_health() -> DeepHealth {
         let wmi_con = WMIConnection::new(com_con).ok()?;
 
         // BIOS Info
-        let bios_results: Vec<HashMap<String, wmi::Variant>> = wmi_con
-            .raw_query("SELECT SerialNumber, Manufacturer, SMBIOSBIOSVersion FROM Win32_BIOS")
-            .unwrap_or_default();
-
-        let (bios_serial, bios_manufacturer, bios_version) = bios_results.first()
-            .map(|bios| (
-                extract_string(bios.get("SerialNumber")),
-                extract_string(bios.get("Manufacturer")),
-                extract_string(bios.get("SMBIOSBIOSVersion")),
-            ))
-            .unwrap_or(("Unknown".into(), "Unknown".into(), "Unknown".into()));
+        let (bios_serial, bios_manufacturer, bios_version) = query_bios(&wmi_con);
 
         // Disk Health
-        let disk_results: Vec<HashMap<String, wmi::Variant>> = wmi_con
-            .raw_query("SELECT Model, Status FROM Win32_DiskDrive")
+        let disk_results: Vec<HashMap<String, wmi::Variant>> = cached_query(&wmi_con, "SELECT Model, Status FROM Win32_DiskDrive")
             .unwrap_or_default();
 
         let (disk_model, disk_smart_status) = disk_results.first()
@@ -1563,8 +2548,7 @@ pub fn get_deep_health() -> DeepHealth {
             .unwrap_or(("Unknown".into(), "Unknown".into()));
 
         // OS Info
-        let os_results: Vec<HashMap<String, wmi::Variant>> = wmi_con
-            .raw_query("SELECT Caption, LastBootUpTime, CSName FROM Win32_OperatingSystem")
+        let os_results: Vec<HashMap<String, wmi::Variant>> = cached_query(&wmi_con, "SELECT Caption, LastBootUpTime, CSName FROM Win32_OperatingSystem")
             .unwrap_or_default();
 
         let (windows_version, last_boot_time, computer_name) = os_results.first()
@@ -1635,10 +2619,13 @@ try {
 $result | ConvertTo-Json -Compress
 "#;
 
-    let output = Command::new("powershell")
-        .args(["-NoProfile", "-Command", ps_script])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output();
+    let output = powershell::require().and_then(|ps| {
+        Command::new(ps)
+            .args(["-NoProfile", "-Command", ps_script])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| e.to_string())
+    });
 
     let mut health = DeepHealth {
         bios_serial: "N/A".into(),
@@ -1653,6 +2640,7 @@ $result | ConvertTo-Json -Compress
             status: "No Battery".into(),
             design_capacity: 0,
             full_charge_capacity: 0,
+            cycle_count: None,
         },
         last_boot_time: "N/A".into(),
         windows_version: "Windows".into(),
@@ -1706,6 +2694,7 @@ $result | ConvertTo-Json -Compress
                         status: status.into(),
                         design_capacity: 0,
                         full_charge_capacity: 0,
+                        cycle_count: None,
                     };
                 }
             }
@@ -1718,8 +2707,7 @@ $result | ConvertTo-Json -Compress
 #[cfg(windows)]
 fn get_battery_health(wmi_con: &wmi::WMIConnection) -> BatteryHealth {
     // First check if this is a laptop/portable device
-    let chassis_results: Vec<HashMap<String, wmi::Variant>> = wmi_con
-        .raw_query("SELECT ChassisTypes FROM Win32_SystemEnclosure")
+    let chassis_results: Vec<HashMap<String, wmi::Variant>> = cached_query(wmi_con, "SELECT ChassisTypes FROM Win32_SystemEnclosure")
         .unwrap_or_default();
 
     let is_portable = chassis_results.first()
@@ -1737,8 +2725,7 @@ fn get_battery_health(wmi_con: &wmi::WMIConnection) -> BatteryHealth {
         .unwrap_or(false);
 
     // Battery status
-    let battery_results: Vec<HashMap<String, wmi::Variant>> = wmi_con
-        .raw_query("SELECT EstimatedChargeRemaining, BatteryStatus FROM Win32_Battery")
+    let battery_results: Vec<HashMap<String, wmi::Variant>> = cached_query(wmi_con, "SELECT EstimatedChargeRemaining, BatteryStatus FROM Win32_Battery")
         .unwrap_or_default();
 
     if let Some(bat) = battery_results.first() {
@@ -1755,7 +2742,7 @@ fn get_battery_health(wmi_con: &wmi::WMIConnection) -> BatteryHealth {
         };
 
         // Try to get battery wear level via PowerShell
-        let (health_percent, design_cap, full_cap) = get_battery_wear_powershell();
+        let (health_percent, design_cap, full_cap, cycle_count) = get_battery_wear_powershell();
 
         BatteryHealth {
             is_present: true,
@@ -1764,6 +2751,7 @@ fn get_battery_health(wmi_con: &wmi::WMIConnection) -> BatteryHealth {
             status: status.into(),
             design_capacity: design_cap,
             full_charge_capacity: full_cap,
+            cycle_count,
         }
     } else {
         // No battery detected
@@ -1780,24 +2768,27 @@ fn get_battery_health(wmi_con: &wmi::WMIConnection) -> BatteryHealth {
             status: status.into(),
             design_capacity: 0,
             full_charge_capacity: 0,
+            cycle_count: None,
         }
     }
 }
 
 #[cfg(windows)]
-fn get_battery_wear_powershell() -> (u8, u32, u32) {
+fn get_battery_wear_powershell() -> (u8, u32, u32, Option<u32>) {
     use std::process::Command;
 
     let ps_script = r#"
 try {
     $battery = Get-CimInstance -Namespace root\wmi -ClassName BatteryFullChargedCapacity -ErrorAction SilentlyContinue | Select-Object -First 1
     $design = Get-CimInstance -Namespace root\wmi -ClassName BatteryStaticData -ErrorAction SilentlyContinue | Select-Object -First 1
+    $cycles = Get-CimInstance -Namespace root\wmi -ClassName BatteryCycleCount -ErrorAction SilentlyContinue | Select-Object -First 1
     if ($battery -and $design -and $design.DesignedCapacity -gt 0) {
         $health = [math]::Round(($battery.FullChargedCapacity / $design.DesignedCapacity) * 100)
         @{
             health = [math]::Min($health, 100)
             design = $design.DesignedCapacity
             full = $battery.FullChargedCapacity
+            cycles = if ($cycles) { $cycles.CycleCount } else { 0 }
         } | ConvertTo-Json -Compress
     } else {
         @{ health = 100; design = 0; full = 0 } | ConvertTo-Json -Compress
@@ -1807,10 +2798,13 @@ try {
 }
 "#;
 
-    let output = Command::new("powershell")
-        .args(["-NoProfile", "-Command", ps_script])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output();
+    let output = powershell::require().and_then(|ps| {
+        Command::new(ps)
+            .args(["-NoProfile", "-Command", ps_script])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| e.to_string())
+    });
 
     if let Ok(out) = output {
         if let Ok(json_str) = String::from_utf8(out.stdout) {
@@ -1818,11 +2812,12 @@ try {
                 let health = data.get("health").and_then(|v| v.as_u64()).unwrap_or(100) as u8;
                 let design = data.get("design").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
                 let full = data.get("full").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
-                return (health, design, full);
+                let cycles = data.get("cycles").and_then(|v| v.as_u64()).filter(|&c| c > 0).map(|c| c as u32);
+                return (health, design, full, cycles);
             }
         }
     }
-    (100, 0, 0)
+    (100, 0, 0, None)
 }
 
 #[cfg(windows)]
@@ -1859,6 +2854,7 @@ pub fn get_deep_health() -> DeepHealth {
             status: "N/A".into(),
             design_capacity: 0,
             full_charge_capacity: 0,
+            cycle_count: None,
         },
         last_boot_time: "N/A".into(),
         windows_version: "Linux".into(),
@@ -1868,160 +2864,1015 @@ pub fn get_deep_health() -> DeepHealth {
 }
 
 // ============================================
-// WINGET INTEGRATION
+// POWER STATUS (GetSystemPowerStatus)
 // ============================================
 
 #[cfg(windows)]
-pub async fn check_winget_updates() -> Vec<OutdatedApp> {
-    use std::process::Command;
+#[repr(C)]
+#[derive(Default)]
+struct SystemPowerStatus {
+    ac_line_status: u8,
+    battery_flag: u8,
+    battery_life_percent: u8,
+    system_status_flag: u8,
+    battery_life_time: u32,
+    battery_full_life_time: u32,
+}
 
-    let output = Command::new("winget")
-        .args(["upgrade", "--include-unknown"])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output();
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+}
 
-    let mut updates = Vec::new();
+/// (on_battery, charge_percent), cheap enough for background loops.
+/// None on desktops without battery or when Windows reports an unknown charge.
+#[cfg(windows)]
+pub fn get_power_status() -> Option<(bool, u8)> {
+    const NO_SYSTEM_BATTERY: u8 = 128;
+    const UNKNOWN: u8 = 255;
 
-    if let Ok(o) = output {
-        let stdout = String::from_utf8_lossy(&o.stdout);
-        let lines: Vec<&str> = stdout.lines().collect();
+    let mut status = SystemPowerStatus::default();
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return None;
+    }
+    if status.battery_flag & NO_SYSTEM_BATTERY != 0 || status.battery_life_percent == UNKNOWN {
+        return None;
+    }
+    Some((status.ac_line_status == 0, status.battery_life_percent.min(100)))
+}
 
-        // Skip header lines and parse
-        let mut parsing = false;
-        for line in lines {
-            if line.contains("---") {
-                parsing = true;
-                continue;
-            }
-            if !parsing || line.trim().is_empty() {
-                continue;
-            }
+#[cfg(not(windows))]
+pub fn get_power_status() -> Option<(bool, u8)> {
+    None
+}
 
-            // Parse winget output (format varies by locale)
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 4 {
-                // This is simplified - real parsing needs column-based extraction
-                let name = parts[0..parts.len()-3].join(" ");
-                let id = parts[parts.len()-3].to_string();
-                let current = parts[parts.len()-2].to_string();
-                let available = parts[parts.len()-1].to_string();
-
-                if !available.is_empty() && available != current {
-                    updates.push(OutdatedApp {
-                        name,
-                        id,
-                        current_version: current,
-                        available_version: available,
-                    });
-                }
-            }
-        }
-    }
+// ============================================
+// BATTERY REPORT (powercfg /batteryreport)
+// ============================================
 
-    updates
+#[derive(Serialize, Clone, Debug)]
+pub struct BatteryReport {
+    pub manufacturer: String,
+    pub chemistry: String,
+    pub design_capacity_mwh: u32,
+    pub full_charge_capacity_mwh: u32,
+    pub wear_percent: u8,
+    pub cycle_count: Option<u32>,
+    pub capacity_history: Vec<BatteryCapacityPoint>,
+    pub recent_drains: Vec<BatteryDrain>,
 }
 
-#[cfg(not(windows))]
-pub async fn check_winget_updates() -> Vec<OutdatedApp> {
-    Vec::new()
+#[derive(Serialize, Clone, Debug)]
+pub struct BatteryCapacityPoint {
+    pub date: String,
+    pub full_charge_capacity_mwh: u32,
+    pub design_capacity_mwh: u32,
+}
+
+/// One on-battery active session taken from RecentUsage
+#[derive(Serialize, Clone, Debug)]
+pub struct BatteryDrain {
+    pub start: String,
+    pub duration_minutes: u32,
+    pub drain_mw: u32,
+    pub percent_per_hour: f32,
 }
 
+/// Drains shown to the user (most recent first)
 #[cfg(windows)]
-pub async fn install_winget_apps(app_ids: Vec<String>) -> TweakResult {
-    use std::process::Command;
+const MAX_BATTERY_DRAINS: usize = 10;
+#[cfg(windows)]
+const NO_BATTERY_MESSAGE: &str = "Aucune batterie detectee sur ce PC";
 
-    let mut success_count = 0;
-    let mut errors = Vec::new();
+/// Text of the first <tag>...</tag> in the block
+#[cfg(windows)]
+fn xml_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&close)?;
+    Some(xml[start..end].trim())
+}
 
-    for id in &app_ids {
-        let result = Command::new("winget")
-            .args([
-                "install",
-                "--id", id,
-                "-e",
-                "--silent",
-                "--accept-package-agreements",
-                "--accept-source-agreements",
-            ])
-            .creation_flags(CREATE_NO_WINDOW)
-            .output();
+/// Opening tags of every <tag .../> element (attributes only, the report has no nested data there)
+#[cfg(windows)]
+fn xml_elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{} ", tag);
+    let mut elements = Vec::new();
+    let mut rest = xml;
+    while let Some(pos) = rest.find(&open) {
+        let after = &rest[pos..];
+        let end = after.find('>').unwrap_or(after.len());
+        elements.push(&after[..end]);
+        rest = &after[end..];
+    }
+    elements
+}
 
-        match result {
-            Ok(output) if output.status.success() => success_count += 1,
-            Ok(output) => {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                errors.push(format!("{}: {}", id, stderr.lines().next().unwrap_or("Unknown error")));
+#[cfg(windows)]
+fn xml_attr<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!(" {}=\"", name);
+    let start = element.find(&key)? + key.len();
+    let end = start + element[start..].find('"')?;
+    Some(&element[start..end])
+}
+
+#[cfg(windows)]
+fn parse_report_time(value: &str) -> Option<chrono::NaiveDateTime> {
+    chrono::NaiveDateTime::parse_from_str(value.get(..19)?, "%Y-%m-%dT%H:%M:%S").ok()
+}
+
+/// Consecutive active on-battery entries give the capacity lost over the elapsed time
+#[cfg(windows)]
+fn parse_recent_drains(xml: &str) -> Vec<BatteryDrain> {
+    let entries: Vec<(chrono::NaiveDateTime, bool, u32, u32)> = xml_elements(xml, "UsageEntry")
+        .into_iter()
+        .filter_map(|e| {
+            let time = parse_report_time(xml_attr(e, "LocalTimestamp")?)?;
+            let on_battery = xml_attr(e, "Ac") == Some("0") && xml_attr(e, "EntryType") == Some("Active");
+            let charge = xml_attr(e, "ChargeCapacity")?.parse().ok()?;
+            let full = xml_attr(e, "FullChargeCapacity")?.parse().ok()?;
+            Some((time, on_battery, charge, full))
+        })
+        .collect();
+
+    let mut drains: Vec<BatteryDrain> = entries.windows(2)
+        .filter_map(|pair| {
+            let (start, on_battery, charge_start, full) = pair[0];
+            let (end, _, charge_end, _) = pair[1];
+            let minutes = (end - start).num_minutes();
+            if !on_battery || minutes < 5 || charge_end >= charge_start || full == 0 {
+                return None;
+            }
+            let hours = minutes as f32 / 60.0;
+            let lost = (charge_start - charge_end) as f32;
+            Some(BatteryDrain {
+                start: start.format("%d/%m/%Y %H:%M").to_string(),
+                duration_minutes: minutes as u32,
+                drain_mw: (lost / hours) as u32,
+                percent_per_hour: (lost / full as f32 * 100.0 / hours * 10.0).round() / 10.0,
+            })
+        })
+        .collect();
+
+    drains.reverse();
+    drains.truncate(MAX_BATTERY_DRAINS);
+    drains
+}
+
+#[cfg(windows)]
+fn parse_battery_report(xml: &str) -> Result<BatteryReport, String> {
+    let battery = xml_text(xml, "Battery").ok_or(NO_BATTERY_MESSAGE)?;
+    let number = |tag: &str| xml_text(battery, tag).and_then(|v| v.parse::<u32>().ok());
+
+    let design = number("DesignCapacity").unwrap_or(0);
+    let full = number("FullChargeCapacity").unwrap_or(0);
+    if design == 0 && full == 0 {
+        return Err(NO_BATTERY_MESSAGE.to_string());
+    }
+    let wear_percent = if design > 0 && full < design {
+        (100 - full as u64 * 100 / design as u64) as u8
+    } else {
+        0
+    };
+
+    let capacity_history = xml_elements(xml, "HistoryEntry")
+        .into_iter()
+        .filter_map(|e| {
+            let date = parse_report_time(xml_attr(e, "LocalStartDate")?)?;
+            Some(BatteryCapacityPoint {
+                date: date.format("%d/%m/%Y").to_string(),
+                full_charge_capacity_mwh: xml_attr(e, "FullChargeCapacity")?.parse().ok()?,
+                design_capacity_mwh: xml_attr(e, "DesignCapacity")?.parse().ok()?,
+            })
+        })
+        .collect();
+
+    Ok(BatteryReport {
+        manufacturer: xml_text(battery, "Manufacturer").unwrap_or("N/A").to_string(),
+        chemistry: xml_text(battery, "Chemistry").unwrap_or("N/A").to_string(),
+        design_capacity_mwh: design,
+        full_charge_capacity_mwh: full,
+        wear_percent,
+        // Many firmwares report 0 when they don't track cycles
+        cycle_count: number("CycleCount").filter(|&c| c > 0),
+        capacity_history,
+        recent_drains: parse_recent_drains(xml),
+    })
+}
+
+#[cfg(windows)]
+pub fn generate_battery_report() -> Result<BatteryReport, String> {
+    use std::process::Command;
+
+    let path = std::env::temp_dir().join(format!("microdiag_battery_{}.xml", std::process::id()));
+    let output = Command::new("powercfg")
+        .arg("/batteryreport")
+        .arg("/output")
+        .arg(&path)
+        .arg("/xml")
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Impossible de lancer powercfg: {}", e))?;
+
+    let xml = std::fs::read(&path).ok().map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+    let _ = std::fs::remove_file(&path);
+
+    match xml {
+        Some(xml) => parse_battery_report(&xml),
+        // powercfg fails without writing the file when there is no battery
+        None if !output.status.success() => Err(NO_BATTERY_MESSAGE.to_string()),
+        None => Err("Rapport batterie introuvable".to_string()),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn generate_battery_report() -> Result<BatteryReport, String> {
+    Err("Rapport batterie disponible uniquement sur Windows".to_string())
+}
+
+// ============================================
+// POWER PLANS (powercfg)
+// ============================================
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PowerPlan {
+    pub guid: String,
+    pub name: String,
+    pub active: bool,
+}
+
+/// Hidden "Ultimate Performance" template shipped with Windows 10 1803+
+#[cfg(windows)]
+const ULTIMATE_PERFORMANCE_GUID: &str = "e9a42b02-d5df-448d-aa00-03f14749eb61";
+
+/// "Power Scheme GUID: <guid>  (<name>) *" - the label is localized, the layout is not
+#[cfg(windows)]
+fn parse_power_plan_line(line: &str) -> Option<PowerPlan> {
+    let re = regex::Regex::new(r"([0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12})\s*\((.*)\)").ok()?;
+    let caps = re.captures(line)?;
+    Some(PowerPlan {
+        guid: caps[1].to_lowercase(),
+        name: caps[2].trim().to_string(),
+        active: line.trim_end().ends_with('*'),
+    })
+}
+
+#[cfg(windows)]
+fn powercfg(args: &[&str]) -> Result<String, String> {
+    use std::process::Command;
+
+    let output = Command::new("powercfg")
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Impossible de lancer powercfg: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        Err(format!("powercfg a echoue: {}", stdout.trim()))
+    }
+}
+
+#[cfg(windows)]
+pub fn list_power_plans() -> Vec<PowerPlan> {
+    powercfg(&["/list"])
+        .map(|out| out.lines().filter_map(parse_power_plan_line).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(not(windows))]
+pub fn list_power_plans() -> Vec<PowerPlan> {
+    Vec::new()
+}
+
+#[cfg(windows)]
+pub fn set_active_power_plan(guid: &str) -> TweakResult {
+    let guid = guid.trim().to_lowercase();
+    if !list_power_plans().iter().any(|p| p.guid == guid) {
+        return TweakResult {
+            success: false,
+            message: i18n::trf("power.unknown_plan", &[("guid", guid.clone())]),
+            backup_path: None,
+        };
+    }
+
+    if let Err(e) = powercfg(&["/setactive", &guid]) {
+        return TweakResult { success: false, message: e, backup_path: None };
+    }
+
+    // Read back so the UI shows what Windows actually applied
+    match list_power_plans().into_iter().find(|p| p.active) {
+        Some(active) if active.guid == guid => TweakResult {
+            success: true,
+            message: i18n::trf("power.active", &[("name", active.name.clone())]),
+            backup_path: None,
+        },
+        Some(active) => TweakResult {
+            success: false,
+            message: i18n::trf("power.not_applied", &[("name", active.name.clone())]),
+            backup_path: None,
+        },
+        None => TweakResult {
+            success: false,
+            message: i18n::tr("power.active_not_found"),
+            backup_path: None,
+        },
+    }
+}
+
+#[cfg(not(windows))]
+pub fn set_active_power_plan(_guid: &str) -> TweakResult {
+    TweakResult {
+        success: false,
+        message: i18n::tr("common.unavailable_platform"),
+        backup_path: None,
+    }
+}
+
+/// Unlocks the Ultimate Performance plan (once) and activates it
+#[cfg(windows)]
+pub fn enable_ultimate_performance() -> Result<PowerPlan, String> {
+    let is_ultimate = |p: &PowerPlan| {
+        let name = p.name.to_lowercase();
+        name.contains("ultimate") || name.contains("optimales")
+    };
+
+    let guid = match list_power_plans().into_iter().find(is_ultimate) {
+        Some(plan) => plan.guid,
+        None => {
+            let out = powercfg(&["-duplicatescheme", ULTIMATE_PERFORMANCE_GUID])?;
+            out.lines()
+                .find_map(parse_power_plan_line)
+                .map(|p| p.guid)
+                .ok_or("Plan Performances optimales non disponible sur cette edition de Windows")?
+        }
+    };
+
+    let result = set_active_power_plan(&guid);
+    if !result.success {
+        return Err(result.message);
+    }
+    list_power_plans()
+        .into_iter()
+        .find(|p| p.active)
+        .ok_or_else(|| "Plan actif introuvable".to_string())
+}
+
+#[cfg(not(windows))]
+pub fn enable_ultimate_performance() -> Result<PowerPlan, String> {
+    Err("Non disponible sur cette plateforme".to_string())
+}
+
+// ============================================
+// BOOT MODE (bcdedit)
+// ============================================
+
+/// Boot modes accepted by `set_boot_mode`
+#[cfg(windows)]
+const BOOT_MODES: &[&str] = &["safe_minimal", "safe_network", "normal"];
+
+#[cfg(windows)]
+fn bcdedit(args: &[&str]) -> Result<String, String> {
+    use std::process::Command;
+
+    let output = Command::new("bcdedit")
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Impossible de lancer bcdedit: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        Err(format!("bcdedit a echoue: {}", stdout.trim()))
+    }
+}
+
+/// Full BCD store export, re-importable with `bcdedit /import <path>`
+#[cfg(windows)]
+fn create_bcd_backup() -> Result<String, String> {
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let backup_path = get_backup_dir().join(format!("bcd_{}.bcd", timestamp));
+    let backup = backup_path.to_string_lossy().to_string();
+    bcdedit(&["/export", &backup])?;
+    Ok(backup)
+}
+
+/// Exports the BCD, then runs the change; nothing is modified when the backup fails
+#[cfg(windows)]
+fn apply_bcd_change(args: &[&str], done_key: &str) -> TweakResult {
+    let backup_path = match create_bcd_backup() {
+        Ok(path) => path,
+        Err(e) => {
+            return TweakResult {
+                success: false,
+                message: i18n::trf("bootmode.backup_failed", &[("error", e)]),
+                backup_path: None,
+            };
+        }
+    };
+
+    match bcdedit(args) {
+        Ok(_) => TweakResult {
+            success: true,
+            message: i18n::trf(done_key, &[("backup", backup_path.clone())]),
+            backup_path: Some(backup_path),
+        },
+        Err(e) => TweakResult { success: false, message: e, backup_path: Some(backup_path) },
+    }
+}
+
+/// Safe Mode (minimal or with networking) or back to a normal boot, applied at next restart
+#[cfg(windows)]
+pub fn set_boot_mode(mode: &str) -> TweakResult {
+    match mode {
+        "safe_minimal" => apply_bcd_change(&["/set", "{current}", "safeboot", "minimal"], "bootmode.safe_minimal"),
+        "safe_network" => apply_bcd_change(&["/set", "{current}", "safeboot", "network"], "bootmode.safe_network"),
+        "normal" => {
+            // deletevalue fails when safeboot isn't set, which already is a normal boot
+            let safeboot_set = bcdedit(&["/enum", "{current}"])
+                .map(|out| out.to_lowercase().contains("safeboot"))
+                .unwrap_or(true);
+            if !safeboot_set {
+                return TweakResult {
+                    success: true,
+                    message: i18n::tr("bootmode.already_normal"),
+                    backup_path: None,
+                };
+            }
+            apply_bcd_change(&["/deletevalue", "{current}", "safeboot"], "bootmode.normal")
+        }
+        _ => TweakResult {
+            success: false,
+            message: i18n::trf("bootmode.invalid", &[("mode", mode.to_string()), ("modes", BOOT_MODES.join(", "))]),
+            backup_path: None,
+        },
+    }
+}
+
+#[cfg(not(windows))]
+pub fn set_boot_mode(_mode: &str) -> TweakResult {
+    TweakResult {
+        success: false,
+        message: i18n::tr("common.unavailable_platform"),
+        backup_path: None,
+    }
+}
+
+/// Boot logging to %SystemRoot%\ntbtlog.txt, stays on for every boot until disabled
+#[cfg(windows)]
+pub fn set_boot_logging(enabled: bool) -> TweakResult {
+    if enabled {
+        apply_bcd_change(&["/set", "{current}", "bootlog", "yes"], "bootmode.bootlog_on")
+    } else {
+        apply_bcd_change(&["/set", "{current}", "bootlog", "no"], "bootmode.bootlog_off")
+    }
+}
+
+#[cfg(not(windows))]
+pub fn set_boot_logging(_enabled: bool) -> TweakResult {
+    TweakResult {
+        success: false,
+        message: i18n::tr("common.unavailable_platform"),
+        backup_path: None,
+    }
+}
+
+// ============================================
+// WINGET INTEGRATION
+// ============================================
+
+#[cfg(windows)]
+#[derive(Serialize, Clone, Debug)]
+pub struct WingetError {
+    pub code: String,     // no_package, source_agreement, install_in_progress, network, needs_admin, already_installed, winget_missing, unknown
+    pub message: String,
+}
+
+#[cfg(windows)]
+impl WingetError {
+    /// Short message with the machine-readable code, for TweakResult::message
+    pub fn describe(&self) -> String {
+        format!("{} (winget: {})", self.message, self.code)
+    }
+
+    pub(crate) fn missing(e: &std::io::Error) -> Self {
+        WingetError {
+            code: "winget_missing".into(),
+            message: i18n::trf("winget.missing", &[("error", e.to_string())]),
+        }
+    }
+}
+
+// HRESULTs returned by winget (exit code is the HRESULT as i32)
+#[cfg(windows)]
+const WINGET_NO_APPLICATIONS_FOUND: u32 = 0x8A150014;
+#[cfg(windows)]
+const WINGET_UPDATE_NOT_APPLICABLE: u32 = 0x8A15002B;
+#[cfg(windows)]
+const WINGET_PACKAGE_ALREADY_INSTALLED: u32 = 0x8A150061;
+#[cfg(windows)]
+const WINGET_DOWNLOAD_FAILED: u32 = 0x8A150008;
+#[cfg(windows)]
+const WINGET_INSTALL_IN_PROGRESS: u32 = 0x8A150102;
+#[cfg(windows)]
+const WINGET_INSTALL_NO_NETWORK: u32 = 0x8A150107;
+#[cfg(windows)]
+const WINGET_INSTALL_ALREADY_INSTALLED: u32 = 0x8A15010D;
+#[cfg(windows)]
+const MSI_ANOTHER_INSTALL_IN_PROGRESS: u32 = 1618;
+#[cfg(windows)]
+const E_ACCESSDENIED: u32 = 0x80070005;
+/// WinINet failures: name not resolved, timeout, cannot connect, TLS failure
+#[cfg(windows)]
+const WININET_ERRORS: &[u32] = &[0x80072EE7, 0x80072EE2, 0x80072EFD, 0x80072F8F];
+
+// Winget messages (en-US, distinctive part of the fr-FR ones), compared lowercased
+#[cfg(windows)]
+const WINGET_NO_PACKAGE_MESSAGES: &[&str] = &[
+    "no package found matching input criteria",
+    "no installed package found matching input criteria",
+    "correspondant aux critères d'entrée",
+];
+#[cfg(windows)]
+const WINGET_SOURCE_AGREEMENT_MESSAGES: &[&str] = &[
+    "do you agree to all the source agreements terms",
+    "conditions de la source",
+];
+#[cfg(windows)]
+const WINGET_IN_PROGRESS_MESSAGES: &[&str] = &[
+    "another installation is already in progress",
+    "installation est déjà en cours",
+];
+#[cfg(windows)]
+const WINGET_ACCESS_DENIED_MESSAGES: &[&str] = &["access is denied", "accès refusé"];
+#[cfg(windows)]
+const WINGET_UP_TO_DATE_MESSAGES: &[&str] = &[
+    "no available upgrade found",
+    "no newer package versions are available from the configured sources",
+    "found an existing package already installed",
+    "aucune mise à niveau disponible",
+    "package existant déjà installé",
+];
+
+/// Spinner frame ("-", "\\", "|", "/") rather than actual output
+#[cfg(windows)]
+fn is_winget_spinner(line: &str) -> bool {
+    line.chars().all(|c| matches!(c, '-' | '\\' | '|' | '/' | ' '))
+}
+
+/// Last meaningful line of winget output, without the progress spinner/bar noise
+#[cfg(windows)]
+fn winget_summary_line(stdout: &str, stderr: &str) -> Option<String> {
+    stdout.lines()
+        .chain(stderr.lines())
+        // Spinner frames are separated by carriage returns
+        .flat_map(|line| line.split('\r'))
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .filter(|line| !is_winget_spinner(line))
+        .filter(|line| !line.contains('█') && !line.contains('▒'))
+        .last()
+        .map(|line| line.chars().take(200).collect())
+}
+
+/// Map raw winget output to an actionable category and message
+#[cfg(windows)]
+pub fn parse_winget_error(stdout: &str, stderr: &str, exit_code: Option<i32>) -> WingetError {
+    // Typographic apostrophes appear in localized builds
+    let text = format!("{}\n{}", stdout, stderr).to_lowercase().replace('’', "'");
+    let code = exit_code.map(|c| c as u32);
+    let has = |needles: &[&str]| needles.iter().any(|n| text.contains(n));
+    let has_code = |codes: &[u32]| codes.iter().any(|c| code == Some(*c) || text.contains(&format!("0x{:08x}", c)));
+
+    let (code, message) = if has_code(&[WINGET_NO_APPLICATIONS_FOUND]) || has(WINGET_NO_PACKAGE_MESSAGES) {
+        ("no_package", i18n::tr("winget.error.no_package"))
+    } else if has(WINGET_SOURCE_AGREEMENT_MESSAGES) {
+        ("source_agreement", i18n::tr("winget.error.source_agreement"))
+    } else if has_code(&[WINGET_INSTALL_IN_PROGRESS, MSI_ANOTHER_INSTALL_IN_PROGRESS]) || has(WINGET_IN_PROGRESS_MESSAGES) {
+        ("install_in_progress", i18n::tr("winget.error.install_in_progress"))
+    } else if has_code(&[WINGET_DOWNLOAD_FAILED, WINGET_INSTALL_NO_NETWORK]) || has_code(WININET_ERRORS) {
+        ("network", i18n::tr("winget.error.network"))
+    } else if has_code(&[E_ACCESSDENIED]) || has(WINGET_ACCESS_DENIED_MESSAGES) {
+        ("needs_admin", i18n::tr("winget.error.needs_admin"))
+    } else if has_code(&[WINGET_UPDATE_NOT_APPLICABLE, WINGET_PACKAGE_ALREADY_INSTALLED, WINGET_INSTALL_ALREADY_INSTALLED])
+        || has(WINGET_UP_TO_DATE_MESSAGES)
+    {
+        ("already_installed", i18n::tr("winget.error.already_installed"))
+    } else {
+        let detail = winget_summary_line(stdout, stderr)
+            .or_else(|| exit_code.map(|c| format!("code 0x{:08X}", c as u32)))
+            .unwrap_or_else(|| i18n::tr("winget.error.unknown_detail"));
+        ("unknown", i18n::trf("winget.error.unknown", &[("detail", detail)]))
+    };
+
+    WingetError { code: code.to_string(), message }
+}
+
+#[cfg(windows)]
+pub(crate) fn winget_failure(output: &std::process::Output) -> WingetError {
+    parse_winget_error(
+        &String::from_utf8_lossy(&output.stdout),
+        &String::from_utf8_lossy(&output.stderr),
+        output.status.code(),
+    )
+}
+
+#[cfg(windows)]
+pub async fn check_winget_updates() -> Vec<OutdatedApp> {
+    use std::process::Command;
+
+    let output = Command::new("winget")
+        .args(["upgrade", "--include-unknown"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+
+    match output {
+        Ok(o) => parse_winget_upgrade_table(&String::from_utf8_lossy(&o.stdout)),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Char offsets where each column title starts (winget pads columns to fixed widths)
+#[cfg(windows)]
+fn winget_column_starts(header: &str) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut previous_is_space = true;
+    for (i, c) in header.chars().enumerate() {
+        if !c.is_whitespace() && previous_is_space {
+            starts.push(i);
+        }
+        previous_is_space = c.is_whitespace();
+    }
+    starts
+}
+
+#[cfg(windows)]
+fn slice_winget_columns(line: &str, starts: &[usize]) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    starts.iter().enumerate().map(|(i, &start)| {
+        let end = starts.get(i + 1).copied().unwrap_or(chars.len()).min(chars.len());
+        if start >= end {
+            String::new()
+        } else {
+            chars[start..end].iter().collect::<String>().trim().to_string()
+        }
+    }).collect()
+}
+
+/// Parses `winget upgrade` output by column position, whatever the locale of the headers.
+/// Columns are always Name / Id / Version / Available / Source.
+#[cfg(windows)]
+pub fn parse_winget_upgrade_table(stdout: &str) -> Vec<OutdatedApp> {
+    // Progress spinner frames are separated by carriage returns, keep the final text
+    let lines: Vec<&str> = stdout
+        .lines()
+        .map(|l| l.rsplit('\r').next().unwrap_or(l))
+        .collect();
+
+    let mut updates = Vec::new();
+    let mut columns: Option<Vec<usize>> = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        // Separator under the header row; a second table (explicit targeting) restarts here
+        if !trimmed.is_empty() && trimmed.chars().all(|c| c == '-') {
+            columns = i.checked_sub(1)
+                .map(|h| winget_column_starts(lines[h]))
+                .filter(|starts| starts.len() >= 4);
+            continue;
+        }
+        let starts = match &columns {
+            Some(starts) if !trimmed.is_empty() => starts,
+            _ => continue,
+        };
+
+        let fields = slice_winget_columns(line, starts);
+        let (name, id, current, available) = (&fields[0], &fields[1], &fields[2], &fields[3]);
+        // Footer lines ("3 upgrades available.") don't fill the id/available columns
+        if name.is_empty() || id.is_empty() || id.contains(' ') || available.is_empty() {
+            continue;
+        }
+        if available != current {
+            updates.push(OutdatedApp {
+                name: name.clone(),
+                id: id.clone(),
+                current_version: current.clone(),
+                available_version: available.clone(),
+            });
+        }
+    }
+
+    updates
+}
+
+#[cfg(not(windows))]
+pub async fn check_winget_updates() -> Vec<OutdatedApp> {
+    Vec::new()
+}
+
+#[cfg(windows)]
+pub async fn install_winget_apps(app_ids: Vec<String>) -> TweakResult {
+    use std::process::Command;
+
+    let mut success_count = 0;
+    let mut errors = Vec::new();
+
+    for id in &app_ids {
+        let result = Command::new("winget")
+            .args([
+                "install",
+                "--id", id,
+                "-e",
+                "--silent",
+                "--accept-package-agreements",
+                "--accept-source-agreements",
+            ])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output();
+
+        match result {
+            Ok(output) if output.status.success() => success_count += 1,
+            Ok(output) => errors.push(format!("{}: {}", id, winget_failure(&output).describe())),
+            Err(e) => errors.push(format!("{}: {}", id, WingetError::missing(&e).describe())),
+        }
+    }
+
+    if errors.is_empty() {
+        TweakResult {
+            success: true,
+            message: i18n::trf("winget.installed_all", &[("count", success_count.to_string())]),
+            backup_path: None,
+        }
+    } else {
+        TweakResult {
+            success: success_count > 0,
+            message: i18n::trf("winget.installed_partial", &[
+                ("ok", success_count.to_string()),
+                ("failed", errors.len().to_string()),
+                ("errors", errors.join("; ")),
+            ]),
+            backup_path: None,
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub async fn install_winget_apps(_app_ids: Vec<String>) -> TweakResult {
+    TweakResult {
+        success: false,
+        message: i18n::tr("winget.unavailable"),
+        backup_path: None,
+    }
+}
+
+#[cfg(windows)]
+pub async fn update_all_winget() -> TweakResult {
+    use std::process::Command;
+
+    let result = Command::new("winget")
+        .args([
+            "upgrade",
+            "--all",
+            "--silent",
+            "--accept-source-agreements",
+            "--accept-package-agreements",
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+
+    match result {
+        Ok(output) if output.status.success() => TweakResult {
+            success: true,
+            message: i18n::tr("winget.upgrades_started"),
+            backup_path: None,
+        },
+        Ok(output) => TweakResult {
+            success: false,
+            message: winget_failure(&output).describe(),
+            backup_path: None,
+        },
+        Err(e) => TweakResult {
+            success: false,
+            message: WingetError::missing(&e).describe(),
+            backup_path: None,
+        },
+    }
+}
+
+#[cfg(not(windows))]
+pub async fn update_all_winget() -> TweakResult {
+    TweakResult {
+        success: false,
+        message: i18n::tr("winget.unavailable"),
+        backup_path: None,
+    }
+}
+
+/// One line of winget output, with the app it belongs to in a batch
+#[derive(Serialize, Clone, Debug)]
+pub struct WingetProgress {
+    pub app_id: Option<String>,     // None for upgrade --all
+    pub index: usize,               // 1-based
+    pub total: usize,
+    pub line: String,
+    pub line_type: String,          // Same categories as the fixwin output
+    pub percent: Option<u8>,
+}
+
+/// Download bar "██████▒▒▒▒  1.50 MB / 10.0 MB" as a percentage
+#[cfg(windows)]
+fn winget_download_percent(line: &str) -> Option<u8> {
+    let re = regex::Regex::new(r"([\d.]+)\s*([KMG]B)\s*/\s*([\d.]+)\s*([KMG]B)").ok()?;
+    let caps = re.captures(line)?;
+    let bytes = |value: &str, unit: &str| -> Option<f64> {
+        let scale = match unit {
+            "KB" => 1024.0,
+            "MB" => 1024.0 * 1024.0,
+            _ => 1024.0 * 1024.0 * 1024.0,
+        };
+        value.parse::<f64>().ok().map(|v| v * scale)
+    };
+    let done = bytes(&caps[1], &caps[2])?;
+    let total = bytes(&caps[3], &caps[4])?;
+    if total <= 0.0 {
+        return None;
+    }
+    Some((done / total * 100.0).clamp(0.0, 100.0) as u8)
+}
+
+/// Runs winget with piped output, calling `on_line` for each line (progress bars redraw with \r)
+#[cfg(windows)]
+fn run_winget_streaming<F>(args: &[&str], mut on_line: F) -> Result<(), WingetError>
+where F: FnMut(String, String, Option<u8>)
+{
+    use std::io::{BufReader, Read};
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new("winget")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .creation_flags(CREATE_NO_WINDOW)
+        .spawn()
+        .map_err(|e| WingetError::missing(&e))?;
+
+    // Drained on its own thread so a chatty stderr can't block stdout
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut text = String::new();
+            let _ = stderr.read_to_string(&mut text);
+            text
+        })
+    });
+
+    let mut stdout_text = String::new();
+    if let Some(stdout) = child.stdout.take() {
+        crate::fixwin::read_console_lines(BufReader::new(stdout), |line| {
+            let line = line.replace(['█', '▒'], " ").trim().to_string();
+            if line.is_empty() || is_winget_spinner(&line) {
+                return;
+            }
+            stdout_text.push_str(&line);
+            stdout_text.push('\n');
+            let percent = winget_download_percent(&line).or_else(|| crate::fixwin::extract_progress(&line));
+            let line_type = crate::fixwin::detect_line_type(&line);
+            on_line(line, line_type, percent);
+        });
+    }
+
+    let stderr_text = stderr_reader.and_then(|h| h.join().ok()).unwrap_or_default();
+    let status = child.wait().map_err(|e| WingetError::missing(&e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(parse_winget_error(&stdout_text, &stderr_text, status.code()))
+    }
+}
+
+/// Streaming `install_winget_apps`: "Installation 2/5: VideoLAN.VLC" then winget's own lines
+#[cfg(windows)]
+pub fn install_winget_apps_streaming<F>(app_ids: &[String], mut on_progress: F) -> TweakResult
+where F: FnMut(WingetProgress)
+{
+    let total = app_ids.len();
+    let mut success_count = 0;
+    let mut errors = Vec::new();
+
+    for (index, id) in app_ids.iter().enumerate() {
+        let mut emit = |line: String, line_type: String, percent: Option<u8>| {
+            on_progress(WingetProgress {
+                app_id: Some(id.clone()),
+                index: index + 1,
+                total,
+                line,
+                line_type,
+                percent,
+            });
+        };
+
+        let installing = i18n::trf("winget.progress.installing", &[
+            ("index", (index + 1).to_string()),
+            ("total", total.to_string()),
+            ("id", id.clone()),
+        ]);
+        emit(format!("[INFO] {}", installing), "info".into(), Some(0));
+        let result = run_winget_streaming(
+            &[
+                "install",
+                "--id", id,
+                "-e",
+                "--silent",
+                "--accept-package-agreements",
+                "--accept-source-agreements",
+            ],
+            &mut emit,
+        );
+
+        match result {
+            Ok(()) => {
+                success_count += 1;
+                emit(format!("[OK] {}", i18n::trf("winget.progress.installed", &[("id", id.clone())])), "success".into(), Some(100));
+            }
+            Err(e) => {
+                emit(format!("[ERREUR] {}: {}", id, e.message), "error".into(), None);
+                errors.push(format!("{}: {}", id, e.describe()));
             }
-            Err(e) => errors.push(format!("{}: {}", id, e)),
         }
     }
 
     if errors.is_empty() {
         TweakResult {
             success: true,
-            message: format!("{} applications installées avec succès", success_count),
+            message: i18n::trf("winget.installed_all", &[("count", success_count.to_string())]),
             backup_path: None,
         }
     } else {
         TweakResult {
             success: success_count > 0,
-            message: format!("{} OK, {} erreurs: {}", success_count, errors.len(), errors.join("; ")),
+            message: i18n::trf("winget.installed_partial", &[
+                ("ok", success_count.to_string()),
+                ("failed", errors.len().to_string()),
+                ("errors", errors.join("; ")),
+            ]),
             backup_path: None,
         }
     }
 }
 
 #[cfg(not(windows))]
-pub async fn install_winget_apps(_app_ids: Vec<String>) -> TweakResult {
+pub fn install_winget_apps_streaming<F>(_app_ids: &[String], _on_progress: F) -> TweakResult
+where F: FnMut(WingetProgress)
+{
     TweakResult {
         success: false,
-        message: "Winget non disponible sur cette plateforme".into(),
+        message: i18n::tr("winget.unavailable"),
         backup_path: None,
     }
 }
 
+/// Streaming `update_all_winget`; winget announces each package itself ("(2/5) Found ...")
 #[cfg(windows)]
-pub async fn update_all_winget() -> TweakResult {
-    use std::process::Command;
-
-    let result = Command::new("winget")
-        .args([
+pub fn update_all_winget_streaming<F>(mut on_progress: F) -> TweakResult
+where F: FnMut(WingetProgress)
+{
+    let result = run_winget_streaming(
+        &[
             "upgrade",
             "--all",
             "--silent",
             "--accept-source-agreements",
             "--accept-package-agreements",
-        ])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output();
+        ],
+        |line, line_type, percent| {
+            on_progress(WingetProgress { app_id: None, index: 1, total: 1, line, line_type, percent });
+        },
+    );
 
     match result {
-        Ok(output) if output.status.success() => TweakResult {
+        Ok(()) => TweakResult {
             success: true,
-            message: "Toutes les mises à jour lancées".into(),
+            message: i18n::tr("winget.upgrades_started"),
             backup_path: None,
         },
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            TweakResult {
-                success: false,
-                message: format!("Erreur: {}", stderr.lines().next().unwrap_or("Unknown")),
-                backup_path: None,
-            }
-        }
         Err(e) => TweakResult {
             success: false,
-            message: format!("Erreur: {}", e),
+            message: e.describe(),
             backup_path: None,
         },
     }
 }
 
 #[cfg(not(windows))]
-pub async fn update_all_winget() -> TweakResult {
+pub fn update_all_winget_streaming<F>(_on_progress: F) -> TweakResult
+where F: FnMut(WingetProgress)
+{
     TweakResult {
         success: false,
-        message: "Winget non disponible".into(),
+        message: i18n::tr("winget.unavailable"),
         backup_path: None,
     }
 }
@@ -2030,23 +3881,93 @@ pub async fn update_all_winget() -> TweakResult {
 // PRIVACY TWEAKS
 // ============================================
 
+#[derive(Serialize, Clone, Debug)]
+pub struct TweakPreview {
+    pub tweak_id: String,
+    pub key_path: String,
+    pub value_name: String,
+    pub current_value: Option<u32>,   // None when the value doesn't exist yet
+    pub new_value: u32,
+    pub will_change: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PrivacyTweakState {
+    pub tweak_id: String,
+    pub enabled: bool,
+    pub current_value: Option<u32>,
+}
+
+/// (tweak_id, key_path under HKLM, value_name, enabled_value, disabled_value)
+#[cfg(windows)]
+const PRIVACY_TWEAKS: &[(&str, &str, &str, u32, u32)] = &[
+    ("telemetry", r"SOFTWARE\Policies\Microsoft\Windows\DataCollection", "AllowTelemetry", 3, 0),
+    ("cortana", r"SOFTWARE\Policies\Microsoft\Windows\Windows Search", "AllowCortana", 1, 0),
+    ("advertising_id", r"SOFTWARE\Microsoft\Windows\CurrentVersion\AdvertisingInfo", "Enabled", 1, 0),
+    ("activity_history", r"SOFTWARE\Policies\Microsoft\Windows\System", "EnableActivityFeed", 1, 0),
+    ("location", r"SOFTWARE\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\location", "Value", 1, 0),
+    ("feedback", r"SOFTWARE\Policies\Microsoft\Windows\DataCollection", "DoNotShowFeedbackNotifications", 0, 1),
+];
+
+#[cfg(windows)]
+fn read_tweak_value(path: &str, value_name: &str) -> Option<u32> {
+    RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(path)
+        .and_then(|key| key.get_value::<u32, _>(value_name))
+        .ok()
+}
+
+/// What apply_privacy_tweak would write, without touching the registry
+#[cfg(windows)]
+pub fn preview_privacy_tweak(tweak_id: &str, enable: bool) -> Result<TweakPreview, String> {
+    let &(_, path, value_name, enabled_val, disabled_val) = PRIVACY_TWEAKS.iter()
+        .find(|t| t.0 == tweak_id)
+        .ok_or_else(|| format!("Tweak inconnu: {}", tweak_id))?;
+
+    let current_value = read_tweak_value(path, value_name);
+    let new_value = if enable { enabled_val } else { disabled_val };
+    Ok(TweakPreview {
+        tweak_id: tweak_id.to_string(),
+        key_path: format!("HKLM\\{}", path),
+        value_name: value_name.to_string(),
+        current_value,
+        new_value,
+        will_change: current_value != Some(new_value),
+    })
+}
+
+#[cfg(not(windows))]
+pub fn preview_privacy_tweak(_tweak_id: &str, _enable: bool) -> Result<TweakPreview, String> {
+    Err(i18n::tr("common.unavailable_platform"))
+}
+
+/// Current on/off state of every tweak. A missing value means the Windows default (feature on).
+#[cfg(windows)]
+pub fn get_privacy_tweak_states() -> Vec<PrivacyTweakState> {
+    PRIVACY_TWEAKS.iter()
+        .map(|&(tweak_id, path, value_name, _, disabled_val)| {
+            let current_value = read_tweak_value(path, value_name);
+            PrivacyTweakState {
+                tweak_id: tweak_id.to_string(),
+                enabled: current_value != Some(disabled_val),
+                current_value,
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(windows))]
+pub fn get_privacy_tweak_states() -> Vec<PrivacyTweakState> {
+    Vec::new()
+}
+
 #[cfg(windows)]
 pub fn apply_privacy_tweak(tweak_id: &str, enable: bool) -> TweakResult {
-    let tweaks: HashMap<&str, (&str, &str, u32, u32)> = [
-        // (key_path, value_name, enabled_value, disabled_value)
-        ("telemetry", (r"SOFTWARE\Policies\Microsoft\Windows\DataCollection", "AllowTelemetry", 3, 0)),
-        ("cortana", (r"SOFTWARE\Policies\Microsoft\Windows\Windows Search", "AllowCortana", 1, 0)),
-        ("advertising_id", (r"SOFTWARE\Microsoft\Windows\CurrentVersion\AdvertisingInfo", "Enabled", 1, 0)),
-        ("activity_history", (r"SOFTWARE\Policies\Microsoft\Windows\System", "EnableActivityFeed", 1, 0)),
-        ("location", (r"SOFTWARE\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\location", "Value", 1, 0)),
-        ("feedback", (r"SOFTWARE\Policies\Microsoft\Windows\DataCollection", "DoNotShowFeedbackNotifications", 0, 1)),
-    ].iter().cloned().collect();
-
-    if let Some((path, value_name, enabled_val, disabled_val)) = tweaks.get(tweak_id) {
-        let target_value = if enable { *enabled_val } else { *disabled_val };
+    if let Some(&(_, path, value_name, enabled_val, disabled_val)) = PRIVACY_TWEAKS.iter().find(|t| t.0 == tweak_id) {
+        let target_value = if enable { enabled_val } else { disabled_val };
 
         // Create backup first
-        let backup_result = create_reg_backup(&format!("tweak_{}", tweak_id), HKEY_LOCAL_MACHINE, path);
+        let backup_result = backup_before_write(&format!("tweak_{}", tweak_id), HKEY_LOCAL_MACHINE, path, value_name);
 
         let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
 
@@ -2056,7 +3977,7 @@ pub fn apply_privacy_tweak(tweak_id: &str, enable: bool) -> TweakResult {
             Err(e) => {
                 return TweakResult {
                     success: false,
-                    message: format!("Impossible de créer la clé: {}", e),
+                    message: i18n::trf("tweak.key_create_failed", &[("error", e.to_string())]),
                     backup_path: None,
                 };
             }
@@ -2065,19 +3986,19 @@ pub fn apply_privacy_tweak(tweak_id: &str, enable: bool) -> TweakResult {
         match key.set_value(value_name, &target_value) {
             Ok(_) => TweakResult {
                 success: true,
-                message: format!("{} {} avec succès", tweak_id, if enable { "activé" } else { "désactivé" }),
+                message: i18n::trf(if enable { "tweak.enabled" } else { "tweak.disabled" }, &[("id", tweak_id.to_string())]),
                 backup_path: backup_result.ok(),
             },
             Err(e) => TweakResult {
                 success: false,
-                message: format!("Erreur: {}", e),
+                message: i18n::trf("common.error", &[("error", e.to_string())]),
                 backup_path: backup_result.ok(),
             },
         }
     } else {
         TweakResult {
             success: false,
-            message: format!("Tweak inconnu: {}", tweak_id),
+            message: i18n::trf("tweak.unknown", &[("id", tweak_id.to_string())]),
             backup_path: None,
         }
     }
@@ -2087,7 +4008,7 @@ pub fn apply_privacy_tweak(tweak_id: &str, enable: bool) -> TweakResult {
 pub fn apply_privacy_tweak(_tweak_id: &str, _enable: bool) -> TweakResult {
     TweakResult {
         success: false,
-        message: "Non disponible sur cette plateforme".into(),
+        message: i18n::tr("common.unavailable_platform"),
         backup_path: None,
     }
 }
@@ -2096,71 +4017,627 @@ pub fn apply_privacy_tweak(_tweak_id: &str, _enable: bool) -> TweakResult {
 // GHOST MODE (Clear Traces)
 // ============================================
 
+#[derive(Serialize, Clone, Debug)]
+pub struct GhostModeResult {
+    pub target: String,
+    pub success: bool,
+    pub message: String,
+    pub items_cleared: u32,
+}
+
+#[cfg(windows)]
+fn ghost_clear_target(target: &str) -> GhostModeResult {
+    use std::process::Command;
+
+    let result = |success: bool, message: String, items_cleared: u32| GhostModeResult {
+        target: target.to_string(),
+        success,
+        message,
+        items_cleared,
+    };
+    let local = dirs::data_local_dir().unwrap_or_default();
+    let roaming = dirs::data_dir().unwrap_or_default();
+
+    match target {
+        "clipboard" => {
+            match Command::new("cmd")
+                .args(["/C", "echo off | clip"])
+                .creation_flags(CREATE_NO_WINDOW)
+                .status()
+            {
+                Ok(s) if s.success() => result(true, "Presse-papier vidé".into(), 1),
+                _ => result(false, "Impossible de vider le presse-papier".into(), 0),
+            }
+        }
+        "dns" => {
+            match Command::new("ipconfig")
+                .args(["/flushdns"])
+                .creation_flags(CREATE_NO_WINDOW)
+                .status()
+            {
+                Ok(s) if s.success() => result(true, "Cache DNS vidé".into(), 1),
+                _ => result(false, "Impossible de vider le cache DNS".into(), 0),
+            }
+        }
+        "recent" => {
+            let mut cleaned = PrivacyCleanResult::default();
+            privacy_clean_dir(&roaming.join("Microsoft\\Windows\\Recent"), &mut cleaned);
+            result(true, format!("{} fichiers récents supprimés", cleaned.items_removed), cleaned.items_removed)
+        }
+        "temp" => {
+            let mut cleaned = PrivacyCleanResult::default();
+            privacy_clean_dir(&std::env::temp_dir(), &mut cleaned);
+            let message = if cleaned.skipped.is_empty() {
+                format!("{} fichiers temp supprimés", cleaned.items_removed)
+            } else {
+                format!("{} fichiers temp supprimés ({} en cours d'utilisation)", cleaned.items_removed, cleaned.skipped.len())
+            };
+            result(true, message, cleaned.items_removed)
+        }
+        "prefetch" => {
+            // Requires admin, access denied otherwise
+            let windir = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".into());
+            let entries = match std::fs::read_dir(std::path::Path::new(&windir).join("Prefetch")) {
+                Ok(entries) => entries,
+                Err(e) => return result(false, format!("Prefetch inaccessible: {}", e), 0),
+            };
+            let mut cleared = 0;
+            for entry in entries.flatten() {
+                let is_pf = entry.path().extension().map(|e| e.eq_ignore_ascii_case("pf")).unwrap_or(false);
+                if is_pf && std::fs::remove_file(entry.path()).is_ok() {
+                    cleared += 1;
+                }
+            }
+            result(true, format!("Prefetch nettoyé ({} fichiers)", cleared), cleared)
+        }
+        "browser_cache" => {
+            let cleared: u32 = [PrivacyTarget::ChromeCache, PrivacyTarget::EdgeCache, PrivacyTarget::FirefoxCache]
+                .iter()
+                .map(|t| clean_privacy_target(*t, &local, &roaming).items_removed)
+                .sum();
+            result(true, format!("Cache navigateurs vidé ({} fichiers)", cleared), cleared)
+        }
+        other => result(false, format!("Cible inconnue: {}", other), 0),
+    }
+}
+
+/// Clears only the selected traces and reports each target separately.
+/// targets: clipboard, dns, recent, temp, prefetch, browser_cache
+#[cfg(windows)]
+pub async fn activate_ghost_mode(targets: Vec<String>) -> Vec<GhostModeResult> {
+    let mut seen: Vec<String> = Vec::new();
+    let mut results = Vec::new();
+    for target in targets {
+        let target = target.trim().to_lowercase();
+        if seen.contains(&target) {
+            continue;
+        }
+        results.push(ghost_clear_target(&target));
+        seen.push(target);
+    }
+    results
+}
+
+#[cfg(not(windows))]
+pub async fn activate_ghost_mode(targets: Vec<String>) -> Vec<GhostModeResult> {
+    targets
+        .into_iter()
+        .map(|target| GhostModeResult {
+            target,
+            success: false,
+            message: i18n::tr("common.unavailable_platform"),
+            items_cleared: 0,
+        })
+        .collect()
+}
+
+// ============================================
+// PRIVACY CLEANER (Selective)
+// ============================================
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyTarget {
+    RecentDocs,
+    JumpLists,
+    RunMru,
+    ThumbnailCache,
+    ChromeCache,
+    EdgeCache,
+    FirefoxCache,
+    // Only cleared when explicitly selected, logs the user out of websites
+    BrowserCookies,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct PrivacyCleanResult {
+    pub target: String,
+    pub items_removed: u32,
+    pub bytes_freed: u64,
+    pub skipped: Vec<String>,
+}
+
+#[cfg(windows)]
+const PRIVACY_MAX_SKIPPED_REPORTED: usize = 50;
+
+#[cfg(windows)]
+impl PrivacyCleanResult {
+    fn skip(&mut self, path: &std::path::Path) {
+        if self.skipped.len() < PRIVACY_MAX_SKIPPED_REPORTED {
+            self.skipped.push(path.to_string_lossy().to_string());
+        }
+    }
+}
+
+/// Delete a single file, counting it only if the removal succeeded (locked files are reported)
+#[cfg(windows)]
+fn privacy_remove_file(path: &std::path::Path, result: &mut PrivacyCleanResult) {
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    match std::fs::remove_file(path) {
+        Ok(_) => {
+            result.items_removed += 1;
+            result.bytes_freed += size;
+        }
+        Err(_) => result.skip(path),
+    }
+}
+
+/// Empty a directory recursively, keeping the directory itself
+#[cfg(windows)]
+fn privacy_clean_dir(dir: &std::path::Path, result: &mut PrivacyCleanResult) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(ft) if ft.is_dir() => {
+                privacy_clean_dir(&path, result);
+                let _ = std::fs::remove_dir(&path);
+            }
+            Ok(_) => privacy_remove_file(&path, result),
+            Err(_) => result.skip(&path),
+        }
+    }
+}
+
+/// Profile folders of a Chromium browser ("Default", "Profile 1", ...)
+#[cfg(windows)]
+fn chromium_profiles(user_data: &std::path::Path) -> Vec<std::path::PathBuf> {
+    std::fs::read_dir(user_data)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir() && p.join("Preferences").exists())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(windows)]
+fn firefox_profiles(root: &std::path::Path) -> Vec<std::path::PathBuf> {
+    std::fs::read_dir(root.join("Mozilla\\Firefox\\Profiles"))
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(windows)]
+fn clean_privacy_target(target: PrivacyTarget, local: &std::path::Path, roaming: &std::path::Path) -> PrivacyCleanResult {
+    let mut result = PrivacyCleanResult {
+        target: serde_json::to_value(target)
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let recent = roaming.join("Microsoft\\Windows\\Recent");
+    let chromium_roots = [
+        local.join("Google\\Chrome\\User Data"),
+        local.join("Microsoft\\Edge\\User Data"),
+    ];
+
+    match target {
+        PrivacyTarget::RecentDocs => {
+            // Only the shortcuts at the top level, jump lists live in subfolders
+            if let Ok(entries) = std::fs::read_dir(&recent) {
+                for entry in entries.flatten() {
+                    if entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                        privacy_remove_file(&entry.path(), &mut result);
+                    }
+                }
+            }
+        }
+        PrivacyTarget::JumpLists => {
+            privacy_clean_dir(&recent.join("AutomaticDestinations"), &mut result);
+            privacy_clean_dir(&recent.join("CustomDestinations"), &mut result);
+        }
+        PrivacyTarget::RunMru => {
+            let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+            let path = r"Software\Microsoft\Windows\CurrentVersion\Explorer\RunMRU";
+            if let Ok(key) = hkcu.open_subkey_with_flags(path, KEY_READ | KEY_WRITE) {
+                let names: Vec<String> = key.enum_values().flatten().map(|(name, _)| name).collect();
+                for name in names {
+                    match key.delete_value(&name) {
+                        Ok(_) => result.items_removed += 1,
+                        Err(_) => result.skipped.push(format!("HKCU\\{}\\{}", path, name)),
+                    }
+                }
+            }
+        }
+        PrivacyTarget::ThumbnailCache => {
+            let explorer = local.join("Microsoft\\Windows\\Explorer");
+            if let Ok(entries) = std::fs::read_dir(&explorer) {
+                for entry in entries.flatten() {
+                    let name = entry.file_name().to_string_lossy().to_lowercase();
+                    if name.starts_with("thumbcache_") && name.ends_with(".db") {
+                        privacy_remove_file(&entry.path(), &mut result);
+                    }
+                }
+            }
+        }
+        PrivacyTarget::ChromeCache | PrivacyTarget::EdgeCache => {
+            let root = if target == PrivacyTarget::ChromeCache { &chromium_roots[0] } else { &chromium_roots[1] };
+            for profile in chromium_profiles(root) {
+                for cache in ["Cache", "Code Cache", "GPUCache"] {
+                    privacy_clean_dir(&profile.join(cache), &mut result);
+                }
+            }
+        }
+        PrivacyTarget::FirefoxCache => {
+            for profile in firefox_profiles(local) {
+                privacy_clean_dir(&profile.join("cache2"), &mut result);
+            }
+        }
+        PrivacyTarget::BrowserCookies => {
+            for root in &chromium_roots {
+                for profile in chromium_profiles(root) {
+                    for cookies in ["Network\\Cookies", "Network\\Cookies-journal", "Cookies", "Cookies-journal"] {
+                        let path = profile.join(cookies);
+                        if path.exists() {
+                            privacy_remove_file(&path, &mut result);
+                        }
+                    }
+                }
+            }
+            for profile in firefox_profiles(roaming) {
+                for cookies in ["cookies.sqlite", "cookies.sqlite-wal"] {
+                    let path = profile.join(cookies);
+                    if path.exists() {
+                        privacy_remove_file(&path, &mut result);
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Clean only the selected traces, with per-target counts. Saved passwords are never touched.
+#[cfg(windows)]
+pub fn clean_privacy_traces(targets: &[PrivacyTarget]) -> Result<Vec<PrivacyCleanResult>, String> {
+    let local = dirs::data_local_dir().ok_or_else(|| "Dossier LocalAppData introuvable".to_string())?;
+    let roaming = dirs::data_dir().ok_or_else(|| "Dossier AppData introuvable".to_string())?;
+
+    let mut seen: Vec<PrivacyTarget> = Vec::new();
+    let mut results = Vec::new();
+    for target in targets {
+        if seen.contains(target) {
+            continue;
+        }
+        seen.push(*target);
+        results.push(clean_privacy_target(*target, &local, &roaming));
+    }
+    Ok(results)
+}
+
+#[cfg(not(windows))]
+pub fn clean_privacy_traces(_targets: &[PrivacyTarget]) -> Result<Vec<PrivacyCleanResult>, String> {
+    Err(i18n::tr("common.unavailable_platform"))
+}
+
+// ============================================
+// ENVIRONMENT VARIABLES & PATH
+// ============================================
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EnvVar {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PathEntry {
+    pub raw: String,
+    pub expanded: String,
+    pub exists: bool,
+    pub duplicate: bool,
+    pub unresolved: bool,   // %VAR% unknown to the agent: state unknown, never removed
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EnvironmentInfo {
+    pub scope: String,
+    pub variables: Vec<EnvVar>,
+    pub path_entries: Vec<PathEntry>,
+    pub dead_count: usize,
+    pub duplicate_count: usize,
+}
+
+#[cfg(windows)]
+const PATH_SEPARATOR: char = ';';
+#[cfg(not(windows))]
+const PATH_SEPARATOR: char = ':';
+
+/// Nested references (%JAVA_HOME% -> %ProgramFiles%\Java) are followed this deep
+const ENV_EXPAND_MAX_DEPTH: usize = 4;
+
+/// Expand %VAR% references with `lookup`, None if any of them is unknown
+fn expand_env_vars<V: Fn(&str) -> Option<String>>(value: &str, lookup: &V) -> Option<String> {
+    expand_env_vars_at(value, lookup, 0)
+}
+
+fn expand_env_vars_at<V: Fn(&str) -> Option<String>>(value: &str, lookup: &V, depth: usize) -> Option<String> {
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('%') {
+            Some(end) => {
+                let name = &after[..end];
+                if name.is_empty() || depth >= ENV_EXPAND_MAX_DEPTH {
+                    return None;
+                }
+                result.push_str(&expand_env_vars_at(&lookup(name)?, lookup, depth + 1)?);
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push('%');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+fn normalize_path_entry(path: &str) -> String {
+    path.trim().trim_end_matches(&['\\', '/'][..]).to_lowercase()
+}
+
+/// Split a PATH value, flag dead directories and later duplicates (case-insensitive).
+/// Entries with a %VAR% `lookup` can't resolve are marked unresolved, not dead.
+fn analyze_path_entries<V, F>(raw_path: &str, lookup: V, dir_exists: F) -> Vec<PathEntry>
+where
+    V: Fn(&str) -> Option<String>,
+    F: Fn(&str) -> bool,
+{
+    let mut seen = std::collections::HashSet::new();
+    raw_path
+        .split(PATH_SEPARATOR)
+        .map(|e| e.trim())
+        .filter(|e| !e.is_empty())
+        .map(|raw| {
+            let (expanded, unresolved) = match expand_env_vars(raw, &lookup) {
+                Some(expanded) => (expanded, false),
+                None => (raw.to_string(), true),
+            };
+            let duplicate = !seen.insert(normalize_path_entry(&expanded));
+            PathEntry {
+                raw: raw.to_string(),
+                exists: !unresolved && dir_exists(&expanded),
+                expanded,
+                duplicate,
+                unresolved,
+            }
+        })
+        .collect()
+}
+
+/// Entries `repair_path` drops: dead or duplicate directories, never unresolved ones
+fn is_removable_path_entry(entry: &PathEntry) -> bool {
+    !entry.unresolved && (!entry.exists || entry.duplicate)
+}
+
+fn build_environment_info(scope: &str, mut variables: Vec<EnvVar>) -> EnvironmentInfo {
+    variables.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    let raw_path = variables.iter()
+        .find(|v| v.name.eq_ignore_ascii_case("path"))
+        .map(|v| v.value.clone())
+        .unwrap_or_default();
+    // Variables of the same scope first (the agent may predate them), then the agent's environment
+    let lookup = |name: &str| {
+        variables.iter()
+            .find(|v| v.name.eq_ignore_ascii_case(name) && !v.name.eq_ignore_ascii_case("path"))
+            .map(|v| v.value.clone())
+            .or_else(|| std::env::var(name).ok())
+    };
+    let path_entries = analyze_path_entries(&raw_path, lookup, |p| std::path::Path::new(p).is_dir());
+    let dead_count = path_entries.iter().filter(|e| !e.exists && !e.unresolved).count();
+    let duplicate_count = path_entries.iter().filter(|e| e.duplicate).count();
+
+    EnvironmentInfo {
+        scope: scope.to_string(),
+        variables,
+        path_entries,
+        dead_count,
+        duplicate_count,
+    }
+}
+
+#[cfg(windows)]
+fn environment_key(scope: &str) -> Option<(winreg::HKEY, &'static str)> {
+    match scope {
+        "user" => Some((HKEY_CURRENT_USER, "Environment")),
+        "machine" => Some((HKEY_LOCAL_MACHINE, r"SYSTEM\CurrentControlSet\Control\Session Manager\Environment")),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+fn to_reg_string(value: &str, vtype: winreg::enums::RegType) -> winreg::RegValue {
+    let bytes = value.encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(|c| c.to_le_bytes())
+        .collect();
+    winreg::RegValue { bytes, vtype }
+}
+
+/// An existing REG_EXPAND_SZ stays one even without '%' left; REG_SZ only becomes
+/// REG_EXPAND_SZ when %VAR% references appear, so they keep tracking their target
+#[cfg(windows)]
+fn env_value_type(existing: Option<winreg::enums::RegType>, value: &str) -> winreg::enums::RegType {
+    use winreg::enums::RegType::*;
+
+    match existing {
+        Some(REG_EXPAND_SZ) => REG_EXPAND_SZ,
+        _ if value.contains('%') => REG_EXPAND_SZ,
+        _ => REG_SZ,
+    }
+}
+
+/// Tell running apps (Explorer, new shells) that the environment changed
+#[cfg(windows)]
+fn broadcast_environment_change() {
+    use std::process::Command;
+
+    if !powershell::is_available() {
+        return;
+    }
+
+    let ps_script = r#"
+Add-Type -Namespace Win32 -Name Env -MemberDefinition @'
+[DllImport("user32.dll", SetLastError = true, CharSet = CharSet.Auto)]
+public static extern IntPtr SendMessageTimeout(IntPtr hWnd, uint Msg, UIntPtr wParam, string lParam, uint fuFlags, uint uTimeout, out UIntPtr lpdwResult);
+'@
+$result = [UIntPtr]::Zero
+[Win32.Env]::SendMessageTimeout([IntPtr]0xffff, 0x1A, [UIntPtr]::Zero, "Environment", 2, 5000, [ref]$result) | Out-Null
+"#;
+
+    let _ = Command::new(powershell::program())
+        .args(["-NoProfile", "-Command", ps_script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+}
+
+#[cfg(windows)]
+pub fn get_environment(scope: &str) -> Result<EnvironmentInfo, String> {
+    use winreg::types::FromRegValue;
+
+    let (root, path) = environment_key(scope).ok_or_else(|| format!("Portee inconnue: {}", scope))?;
+    let key = RegKey::predef(root)
+        .open_subkey_with_flags(path, KEY_READ)
+        .map_err(|e| format!("Accès refusé: {}", e))?;
+
+    let variables = key.enum_values()
+        .flatten()
+        .map(|(name, value)| EnvVar {
+            name,
+            value: String::from_reg_value(&value).unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(build_environment_info(scope, variables))
+}
+
 #[cfg(windows)]
-pub async fn activate_ghost_mode() -> TweakResult {
-    use std::process::Command;
+pub fn set_environment_var(scope: &str, name: &str, value: &str) -> TweakResult {
+    let (root, path) = match environment_key(scope) {
+        Some(k) => k,
+        None => return TweakResult {
+            success: false,
+            message: i18n::trf("env.unknown_scope", &[("scope", scope.to_string())]),
+            backup_path: None,
+        },
+    };
 
-    let mut results: Vec<String> = Vec::new();
+    let backup_result = create_reg_backup(&format!("env_{}", scope), root, path);
 
-    // 1. Clear clipboard
-    let _ = Command::new("cmd")
-        .args(["/C", "echo off | clip"])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output();
-    results.push("Presse-papier vidé".into());
+    let key = match RegKey::predef(root).open_subkey_with_flags(path, KEY_WRITE) {
+        Ok(key) => key,
+        Err(e) => return TweakResult {
+            success: false,
+            message: i18n::trf("common.access_denied", &[("error", e.to_string())]),
+            backup_path: None,
+        },
+    };
 
-    // 2. Clear DNS cache
-    let dns_result = Command::new("ipconfig")
-        .args(["/flushdns"])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output();
-    if dns_result.is_ok() {
-        results.push("Cache DNS vidé".into());
+    let existing = key.get_raw_value(name).ok().map(|v| v.vtype);
+    let write = key.set_raw_value(name, &to_reg_string(value, env_value_type(existing, value)));
+
+    match write {
+        Ok(_) => {
+            broadcast_environment_change();
+            TweakResult {
+                success: true,
+                message: i18n::trf("env.updated", &[("name", name.to_string())]),
+                backup_path: backup_result.ok(),
+            }
+        }
+        Err(e) => TweakResult {
+            success: false,
+            message: i18n::trf("common.error", &[("error", e.to_string())]),
+            backup_path: backup_result.ok(),
+        },
     }
+}
 
-    // 3. Clear recent files (Explorer)
-    let recent_path = dirs::data_local_dir()
-        .map(|p| p.join("Microsoft\\Windows\\Recent"))
-        .unwrap_or_default();
+#[cfg(windows)]
+pub fn repair_path(scope: &str) -> TweakResult {
+    let info = match get_environment(scope) {
+        Ok(info) => info,
+        Err(e) => return TweakResult { success: false, message: e, backup_path: None },
+    };
 
-    if recent_path.exists() {
-        let _ = std::fs::remove_dir_all(&recent_path);
-        let _ = std::fs::create_dir_all(&recent_path);
-        results.push("Fichiers récents supprimés".into());
+    let removed = info.path_entries.iter().filter(|e| is_removable_path_entry(e)).count();
+    if removed == 0 {
+        return TweakResult {
+            success: true,
+            message: i18n::tr("env.path_clean"),
+            backup_path: None,
+        };
     }
 
-    // 4. Clear temp files
-    if let Some(temp) = std::env::var_os("TEMP") {
-        let temp_path = std::path::PathBuf::from(temp);
-        let mut cleared = 0;
-        if let Ok(entries) = std::fs::read_dir(&temp_path) {
-            for entry in entries.flatten() {
-                let _ = std::fs::remove_file(entry.path()).or_else(|_| std::fs::remove_dir_all(entry.path()));
-                cleared += 1;
-            }
-        }
-        results.push(format!("{} fichiers temp supprimés", cleared));
+    let cleaned = info.path_entries.iter()
+        .filter(|e| !is_removable_path_entry(e))
+        .map(|e| e.raw.clone())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let mut result = set_environment_var(scope, "Path", &cleaned);
+    if result.success {
+        result.message = format!("{} entree(s) invalide(s) ou en double retiree(s) du PATH", removed);
     }
+    result
+}
 
-    // 5. Clear prefetch (requires admin)
-    let _ = Command::new("cmd")
-        .args(["/C", "del /q /s C:\\Windows\\Prefetch\\*.pf 2>nul"])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output();
-    results.push("Prefetch nettoyé".into());
+#[cfg(not(windows))]
+pub fn get_environment(scope: &str) -> Result<EnvironmentInfo, String> {
+    let variables = std::env::vars()
+        .map(|(name, value)| EnvVar { name, value })
+        .collect();
+    Ok(build_environment_info(scope, variables))
+}
 
+#[cfg(not(windows))]
+pub fn set_environment_var(_scope: &str, _name: &str, _value: &str) -> TweakResult {
     TweakResult {
-        success: true,
-        message: format!("Ghost Mode activé: {}", results.join(", ")),
+        success: false,
+        message: i18n::tr("common.unavailable_platform"),
         backup_path: None,
     }
 }
 
 #[cfg(not(windows))]
-pub async fn activate_ghost_mode() -> TweakResult {
+pub fn repair_path(_scope: &str) -> TweakResult {
     TweakResult {
         success: false,
-        message: "Non disponible sur cette plateforme".into(),
+        message: i18n::tr("common.unavailable_platform"),
         backup_path: None,
     }
 }
@@ -2179,7 +4656,7 @@ fn get_backup_dir() -> std::path::PathBuf {
 }
 
 #[cfg(windows)]
-fn create_reg_backup(name: &str, _root: winreg::HKEY, path: &str) -> Result<String, String> {
+fn create_reg_backup(name: &str, root: winreg::HKEY, path: &str) -> Result<String, String> {
     use std::process::Command;
 
     let backup_dir = get_backup_dir();
@@ -2187,7 +4664,8 @@ fn create_reg_backup(name: &str, _root: winreg::HKEY, path: &str) -> Result<Stri
     let filename = format!("{}_{}.reg", name, timestamp);
     let backup_path = backup_dir.join(&filename);
 
-    let full_key = format!("HKEY_LOCAL_MACHINE\\{}", path);
+    let root_name = if root == HKEY_CURRENT_USER { "HKEY_CURRENT_USER" } else { "HKEY_LOCAL_MACHINE" };
+    let full_key = format!("{}\\{}", root_name, path);
 
     let result = Command::new("reg")
         .args(["export", &full_key, backup_path.to_string_lossy().as_ref(), "/y"])
@@ -2201,6 +4679,119 @@ fn create_reg_backup(name: &str, _root: winreg::HKEY, path: &str) -> Result<Stri
     }
 }
 
+/// Full .reg export of the whole key instead of the single value (opt-in, less surgical undo)
+static FULL_KEY_BACKUPS: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+pub fn set_full_key_backups(enabled: bool) {
+    FULL_KEY_BACKUPS.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// One registry value as it was before a tweak (absent values are restored by deleting them)
+#[cfg(windows)]
+#[derive(Serialize, Deserialize)]
+struct RegValueBackup {
+    root: String,           // HKLM or HKCU
+    key_path: String,
+    value_name: String,
+    existed: bool,
+    value_type: Option<u32>,
+    data: Option<Vec<u8>>,
+    created_at: String,
+}
+
+#[cfg(windows)]
+fn reg_type_from_u32(value: u32) -> Option<winreg::enums::RegType> {
+    use winreg::enums::RegType::*;
+    match value {
+        0 => Some(REG_NONE),
+        1 => Some(REG_SZ),
+        2 => Some(REG_EXPAND_SZ),
+        3 => Some(REG_BINARY),
+        4 => Some(REG_DWORD),
+        5 => Some(REG_DWORD_BIG_ENDIAN),
+        7 => Some(REG_MULTI_SZ),
+        11 => Some(REG_QWORD),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+fn create_value_backup(name: &str, root: winreg::HKEY, path: &str, value_name: &str) -> Result<String, String> {
+    let current = RegKey::predef(root)
+        .open_subkey(path)
+        .and_then(|key| key.get_raw_value(value_name))
+        .ok();
+
+    let backup = RegValueBackup {
+        root: if root == HKEY_CURRENT_USER { "HKCU" } else { "HKLM" }.into(),
+        key_path: path.to_string(),
+        value_name: value_name.to_string(),
+        existed: current.is_some(),
+        value_type: current.as_ref().map(|v| v.vtype.clone() as u32),
+        data: current.map(|v| v.bytes),
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let backup_path = get_backup_dir().join(format!("{}_{}.json", name, timestamp));
+    let json = serde_json::to_string_pretty(&backup).map_err(|e| e.to_string())?;
+    std::fs::write(&backup_path, json).map_err(|e| e.to_string())?;
+    Ok(backup_path.to_string_lossy().to_string())
+}
+
+/// Value-level backup by default, whole key when full key backups are enabled
+#[cfg(windows)]
+fn backup_before_write(name: &str, root: winreg::HKEY, path: &str, value_name: &str) -> Result<String, String> {
+    if FULL_KEY_BACKUPS.load(std::sync::atomic::Ordering::Relaxed) {
+        create_reg_backup(name, root, path)
+    } else {
+        create_value_backup(name, root, path, value_name)
+    }
+}
+
+#[cfg(windows)]
+pub fn restore_value_backup(backup_path: &str) -> TweakResult {
+    use winreg::RegValue;
+
+    let fail = |message: String| TweakResult { success: false, message, backup_path: None };
+
+    let backup: RegValueBackup = match std::fs::read_to_string(backup_path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+    {
+        Ok(backup) => backup,
+        Err(e) => return fail(format!("Backup illisible: {}", e)),
+    };
+
+    let root = if backup.root == "HKCU" { HKEY_CURRENT_USER } else { HKEY_LOCAL_MACHINE };
+    let key = match RegKey::predef(root).create_subkey(&backup.key_path) {
+        Ok((key, _)) => key,
+        Err(e) => return fail(format!("Accès refusé: {}", e)),
+    };
+
+    let result = if backup.existed {
+        match (backup.value_type.and_then(reg_type_from_u32), backup.data) {
+            (Some(vtype), Some(bytes)) => key.set_raw_value(&backup.value_name, &RegValue { bytes, vtype }),
+            _ => return fail("Backup incomplet".into()),
+        }
+    } else {
+        // The value didn't exist before the tweak
+        match key.delete_value(&backup.value_name) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        }
+    };
+
+    match result {
+        Ok(_) => TweakResult {
+            success: true,
+            message: i18n::trf("backup.value_restored", &[("name", backup.value_name.clone())]),
+            backup_path: Some(backup_path.to_string()),
+        },
+        Err(e) => fail(i18n::trf("common.error", &[("error", e.to_string())])),
+    }
+}
+
 #[cfg(windows)]
 pub fn list_backups() -> Vec<RegBackup> {
     let backup_dir = get_backup_dir();
@@ -2209,22 +4800,26 @@ pub fn list_backups() -> Vec<RegBackup> {
     if let Ok(entries) = std::fs::read_dir(&backup_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.extension().map(|e| e == "reg").unwrap_or(false) {
-                if let Ok(metadata) = entry.metadata() {
-                    let created = metadata.modified()
-                        .map(|t| {
-                            let datetime: chrono::DateTime<chrono::Local> = t.into();
-                            datetime.format("%Y-%m-%d %H:%M:%S").to_string()
-                        })
-                        .unwrap_or_else(|_| "Unknown".into());
-
-                    backups.push(RegBackup {
-                        name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
-                        path: path.to_string_lossy().to_string(),
-                        created_at: created,
-                        size_bytes: metadata.len(),
-                    });
-                }
+            let kind = match path.extension().and_then(|e| e.to_str()) {
+                Some("reg") => "key",
+                Some("json") => "value",
+                _ => continue,
+            };
+            if let Ok(metadata) = entry.metadata() {
+                let created = metadata.modified()
+                    .map(|t| {
+                        let datetime: chrono::DateTime<chrono::Local> = t.into();
+                        datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+                    })
+                    .unwrap_or_else(|_| "Unknown".into());
+
+                backups.push(RegBackup {
+                    name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                    path: path.to_string_lossy().to_string(),
+                    created_at: created,
+                    size_bytes: metadata.len(),
+                    kind: kind.into(),
+                });
             }
         }
     }
@@ -2242,6 +4837,10 @@ pub fn list_backups() -> Vec<RegBackup> {
 pub fn restore_backup(backup_path: &str) -> TweakResult {
     use std::process::Command;
 
+    if backup_path.to_lowercase().ends_with(".json") {
+        return restore_value_backup(backup_path);
+    }
+
     let result = Command::new("reg")
         .args(["import", backup_path])
         .creation_flags(CREATE_NO_WINDOW)
@@ -2250,17 +4849,17 @@ pub fn restore_backup(backup_path: &str) -> TweakResult {
     match result {
         Ok(output) if output.status.success() => TweakResult {
             success: true,
-            message: "Backup restauré avec succès".into(),
+            message: i18n::tr("backup.restored"),
             backup_path: Some(backup_path.to_string()),
         },
         Ok(output) => TweakResult {
             success: false,
-            message: format!("Erreur: {}", String::from_utf8_lossy(&output.stderr)),
+            message: i18n::trf("common.error", &[("error", String::from_utf8_lossy(&output.stderr).to_string())]),
             backup_path: None,
         },
         Err(e) => TweakResult {
             success: false,
-            message: format!("Erreur: {}", e),
+            message: i18n::trf("common.error", &[("error", e.to_string())]),
             backup_path: None,
         },
     }
@@ -2270,11 +4869,123 @@ pub fn restore_backup(backup_path: &str) -> TweakResult {
 pub fn restore_backup(_backup_path: &str) -> TweakResult {
     TweakResult {
         success: false,
-        message: "Non disponible sur cette plateforme".into(),
+        message: i18n::tr("common.unavailable_platform"),
+        backup_path: None,
+    }
+}
+
+/// "tweak_telemetry_20250101_120000.json" -> ("tweak_telemetry", "20250101_120000")
+#[cfg(windows)]
+fn split_backup_name(file_name: &str) -> Option<(&str, &str)> {
+    let stem = file_name.strip_suffix(".reg").or_else(|| file_name.strip_suffix(".json"))?;
+    // Timestamp is "%Y%m%d_%H%M%S", 15 characters after the separator
+    let split = stem.len().checked_sub(16)?;
+    let (name, timestamp) = (stem.get(..split)?, stem.get(split + 1..)?);
+    let valid = stem.as_bytes()[split] == b'_'
+        && timestamp.len() == 15
+        && timestamp.bytes().enumerate().all(|(i, b)| if i == 8 { b == b'_' } else { b.is_ascii_digit() });
+    if valid { Some((name, timestamp)) } else { None }
+}
+
+/// Backup names are file names ("tweak_telemetry", "startup_OneDrive"), never paths
+#[cfg(windows)]
+fn is_valid_backup_prefix(prefix: &str) -> bool {
+    !prefix.is_empty() && !prefix.contains(['\\', '/', ':', '*', '?'])
+}
+
+/// Undo for a tweak or startup change: newest backup (.reg or value .json) named exactly `prefix`,
+/// so "tweak_tele" or "tweak" never restores another tweak's backup
+#[cfg(windows)]
+pub fn restore_latest_backup(prefix: &str) -> TweakResult {
+    let prefix = prefix.trim();
+    if !is_valid_backup_prefix(prefix) {
+        return TweakResult {
+            success: false,
+            message: i18n::trf("backup.invalid_prefix", &[("prefix", prefix.to_string())]),
+            backup_path: None,
+        };
+    }
+
+    let latest = list_backups()
+        .into_iter()
+        .filter_map(|b| {
+            let timestamp = split_backup_name(&b.name)
+                .filter(|(name, _)| *name == prefix)
+                .map(|(_, timestamp)| timestamp.to_string())?;
+            Some((timestamp, b))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0));
+
+    let backup = match latest {
+        Some((_, backup)) => backup,
+        None => {
+            return TweakResult {
+                success: false,
+                message: i18n::trf("backup.none_for_prefix", &[("prefix", prefix.to_string())]),
+                backup_path: None,
+            };
+        }
+    };
+
+    let mut result = restore_backup(&backup.path);
+    if result.success {
+        result.message = i18n::trf("backup.latest_restored", &[("name", backup.name)]);
+    }
+    result
+}
+
+#[cfg(not(windows))]
+pub fn restore_latest_backup(_prefix: &str) -> TweakResult {
+    TweakResult {
+        success: false,
+        message: i18n::tr("common.unavailable_platform"),
         backup_path: None,
     }
 }
 
+/// Deletes backups older than `keep_days`, always keeping the `keep_per_prefix` newest of
+/// each name so every tweak stays undoable. Files not named by the agent are left alone.
+#[cfg(windows)]
+pub fn cleanup_backups(keep_days: i32, keep_per_prefix: i32) -> BackupCleanup {
+    use std::collections::HashMap;
+
+    let cutoff = (chrono::Local::now() - chrono::Duration::days(keep_days.max(0) as i64))
+        .format("%Y%m%d_%H%M%S")
+        .to_string();
+
+    let mut by_name: HashMap<String, Vec<(String, RegBackup)>> = HashMap::new();
+    for backup in list_backups() {
+        if let Some((name, timestamp)) = split_backup_name(&backup.name) {
+            let (name, timestamp) = (name.to_string(), timestamp.to_string());
+            by_name.entry(name).or_default().push((timestamp, backup));
+        }
+    }
+
+    let mut cleanup = BackupCleanup::default();
+    for (_, mut backups) in by_name {
+        backups.sort_by(|a, b| b.0.cmp(&a.0));
+        for (timestamp, backup) in backups.into_iter().skip(keep_per_prefix.max(0) as usize) {
+            if timestamp >= cutoff {
+                continue;
+            }
+            if std::fs::remove_file(&backup.path).is_ok() {
+                cleanup.removed_files += 1;
+                cleanup.removed_bytes += backup.size_bytes;
+            }
+        }
+    }
+
+    if cleanup.removed_files > 0 {
+        println!("[Backup] Removed {} old backups ({} bytes)", cleanup.removed_files, cleanup.removed_bytes);
+    }
+    cleanup
+}
+
+#[cfg(not(windows))]
+pub fn cleanup_backups(_keep_days: i32, _keep_per_prefix: i32) -> BackupCleanup {
+    BackupCleanup::default()
+}
+
 // ============================================
 // RUSTDESK REMOTE SUPPORT
 // ============================================
@@ -2288,28 +4999,61 @@ pub struct RustDeskResult {
 
 const RUSTDESK_CONFIG: &str = "9JSPJl0dmNWWjJmbWJVbMFUU5oHNyVzM2pHVrUEMEZlMIlXS3IFRxR0VZlUOIJiOikXZrJCLiIiOikGchJCLiInZuMXdsBXLpRmcv5yazVGZ0NXdyJiOikXYsVmciwiIyZmLzVHbw1SakJ3bus2clRGdzVnciojI0N3boJye";
 
+#[derive(Serialize, Clone)]
+pub struct RustDeskStatus {
+    pub installed: bool,
+    pub running: bool,
+    pub id: Option<String>,
+    pub connected: bool,  // a remote session is open (connection manager running)
+}
+
 #[cfg(windows)]
-pub async fn install_rustdesk() -> RustDeskResult {
-    use std::process::Command;
+fn rustdesk_paths() -> Vec<std::path::PathBuf> {
     use std::path::PathBuf;
-    use std::fs;
-    use std::thread;
-    use std::time::Duration;
 
-    // 1. Check if already installed
-    let rustdesk_paths = vec![
+    vec![
         PathBuf::from(r"C:\Program Files\RustDesk\rustdesk.exe"),
         PathBuf::from(r"C:\Program Files (x86)\RustDesk\rustdesk.exe"),
         PathBuf::from(format!(r"{}\RustDesk\rustdesk.exe", std::env::var("LOCALAPPDATA").unwrap_or_default())),
+    ]
+}
+
+#[cfg(windows)]
+fn find_rustdesk_exe() -> Option<std::path::PathBuf> {
+    rustdesk_paths().into_iter().find(|p| p.exists())
+}
+
+/// `id = '123456789'` from the user config, or from the service profile when installed as a service
+#[cfg(windows)]
+fn read_rustdesk_id_from_config() -> Option<String> {
+    let appdata = std::env::var("APPDATA").unwrap_or_default();
+    let windir = std::env::var("WINDIR").unwrap_or_else(|_| r"C:\Windows".to_string());
+    let candidates = [
+        format!(r"{}\RustDesk\config\RustDesk.toml", appdata),
+        format!(r"{}\RustDesk\config\RustDesk2.toml", appdata),
+        format!(r"{}\ServiceProfiles\LocalService\AppData\Roaming\RustDesk\config\RustDesk.toml", windir),
     ];
 
-    let mut rustdesk_exe: Option<PathBuf> = None;
-    for path in &rustdesk_paths {
-        if path.exists() {
-            rustdesk_exe = Some(path.clone());
-            break;
-        }
-    }
+    candidates.iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .find_map(|content| {
+            content.lines()
+                .filter_map(|line| line.split_once('='))
+                .find(|(key, _)| key.trim() == "id")
+                .map(|(_, value)| value.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+                .filter(|id| !id.is_empty())
+        })
+}
+
+#[cfg(windows)]
+pub async fn install_rustdesk() -> RustDeskResult {
+    use std::process::Command;
+    use std::path::PathBuf;
+    use std::thread;
+    use std::time::Duration;
+
+    // 1. Check if already installed
+    let mut rustdesk_exe: Option<PathBuf> = find_rustdesk_exe();
 
     // 2. Install via winget if not found
     if rustdesk_exe.is_none() {
@@ -2328,24 +5072,19 @@ pub async fn install_rustdesk() -> RustDeskResult {
             Ok(output) if output.status.success() => {
                 thread::sleep(Duration::from_secs(3));
                 // Find exe after install
-                for path in &rustdesk_paths {
-                    if path.exists() {
-                        rustdesk_exe = Some(path.clone());
-                        break;
-                    }
-                }
+                rustdesk_exe = find_rustdesk_exe();
             }
             Ok(output) => {
                 return RustDeskResult {
                     success: false,
-                    message: format!("Echec installation: {}", String::from_utf8_lossy(&output.stderr)),
+                    message: i18n::trf("rustdesk.install_failed", &[("error", winget_failure(&output).describe())]),
                     rustdesk_id: None,
                 };
             }
             Err(e) => {
                 return RustDeskResult {
                     success: false,
-                    message: format!("Winget non disponible: {}", e),
+                    message: WingetError::missing(&e).describe(),
                     rustdesk_id: None,
                 };
             }
@@ -2357,7 +5096,7 @@ pub async fn install_rustdesk() -> RustDeskResult {
         None => {
             return RustDeskResult {
                 success: false,
-                message: "RustDesk introuvable apres installation".into(),
+                message: i18n::tr("rustdesk.missing_after_install"),
                 rustdesk_id: None,
             };
         }
@@ -2395,26 +5134,12 @@ pub async fn install_rustdesk() -> RustDeskResult {
 
     // Fallback: read from config file
     if rustdesk_id.is_none() {
-        let config_path = format!(r"{}\RustDesk\config\RustDesk2.toml",
-            std::env::var("APPDATA").unwrap_or_default());
-        if let Ok(content) = fs::read_to_string(&config_path) {
-            for line in content.lines() {
-                if line.starts_with("id") {
-                    if let Some(id) = line.split('=').nth(1) {
-                        let id = id.trim().trim_matches(|c| c == '"' || c == '\'');
-                        if !id.is_empty() {
-                            rustdesk_id = Some(id.to_string());
-                            break;
-                        }
-                    }
-                }
-            }
-        }
+        rustdesk_id = read_rustdesk_id_from_config();
     }
 
     RustDeskResult {
         success: true,
-        message: format!("RustDesk pret - Serveur: rustdesk.ordi-plus.fr"),
+        message: i18n::trf("rustdesk.ready", &[("server", "rustdesk.ordi-plus.fr".to_string())]),
         rustdesk_id,
     }
 }
@@ -2423,7 +5148,319 @@ pub async fn install_rustdesk() -> RustDeskResult {
 pub async fn install_rustdesk() -> RustDeskResult {
     RustDeskResult {
         success: false,
-        message: "RustDesk uniquement disponible sur Windows".into(),
+        message: i18n::trf("tool.windows_only", &[("tool", "RustDesk".to_string())]),
+        rustdesk_id: None,
+    }
+}
+
+/// Stops the service and processes, then runs the registered uninstaller (or `rustdesk.exe --uninstall`)
+#[cfg(windows)]
+pub fn uninstall_rustdesk() -> RustDeskResult {
+    use std::process::Command;
+    use std::thread;
+    use std::time::Duration;
+
+    let registered = get_installed_apps_native().into_iter()
+        .find(|app| app.name.to_lowercase().starts_with("rustdesk") && !app.uninstall_string.is_empty());
+    let exe = find_rustdesk_exe();
+    if registered.is_none() && exe.is_none() {
+        return RustDeskResult {
+            success: true,
+            message: i18n::trf("tool.not_installed", &[("tool", "RustDesk".to_string())]),
+            rustdesk_id: None,
+        };
+    }
+
+    // Service first, otherwise it respawns the process we kill
+    for args in [&["stop", "RustDesk"][..], &["delete", "RustDesk"][..]] {
+        let _ = Command::new("sc").args(args).creation_flags(CREATE_NO_WINDOW).output();
+    }
+    let _ = Command::new("taskkill")
+        .args(["/F", "/T", "/IM", "rustdesk.exe"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+    thread::sleep(Duration::from_secs(1));
+
+    let result = match registered {
+        Some(app) => uninstall_app(&app.uninstall_string, true),
+        None => match exe.map(|exe| Command::new(exe).arg("--uninstall").creation_flags(CREATE_NO_WINDOW).status()) {
+            Some(Err(e)) => TweakResult {
+                success: false,
+                message: i18n::trf("common.error", &[("error", e.to_string())]),
+                backup_path: None,
+            },
+            _ => TweakResult { success: true, message: String::new(), backup_path: None },
+        },
+    };
+    if !result.success {
+        return RustDeskResult { success: false, message: result.message, rustdesk_id: None };
+    }
+
+    // The uninstaller may return before the files are gone
+    for _ in 0..10 {
+        if find_rustdesk_exe().is_none() {
+            return RustDeskResult {
+                success: true,
+                message: i18n::tr("rustdesk.uninstalled"),
+                rustdesk_id: None,
+            };
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+    RustDeskResult {
+        success: false,
+        message: i18n::tr("rustdesk.still_present"),
+        rustdesk_id: None,
+    }
+}
+
+#[cfg(not(windows))]
+pub fn uninstall_rustdesk() -> RustDeskResult {
+    RustDeskResult {
+        success: false,
+        message: i18n::trf("tool.windows_only", &[("tool", "RustDesk".to_string())]),
         rustdesk_id: None,
     }
 }
+
+/// RustDesk starts a `--cm` connection manager process for the duration of an incoming session
+#[cfg(windows)]
+pub fn rustdesk_status() -> RustDeskStatus {
+    use sysinfo::{ProcessRefreshKind, System, UpdateKind};
+
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(ProcessRefreshKind::new().with_cmd(UpdateKind::Always));
+    let processes: Vec<_> = sys.processes().values()
+        .filter(|p| p.name().eq_ignore_ascii_case("rustdesk.exe"))
+        .collect();
+
+    let installed = find_rustdesk_exe().is_some();
+    RustDeskStatus {
+        installed,
+        running: !processes.is_empty(),
+        id: if installed { read_rustdesk_id_from_config() } else { None },
+        connected: processes.iter().any(|p| p.cmd().iter().any(|arg| arg == "--cm")),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn rustdesk_status() -> RustDeskStatus {
+    RustDeskStatus { installed: false, running: false, id: None, connected: false }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(windows)]
+    fn winget_row(name: &str, id: &str, version: &str, available: &str, source: &str) -> String {
+        format!("{:<37}{:<30}{:<15}{:<15}{}", name, id, version, available, source)
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn winget_upgrade_table_is_parsed_by_column() {
+        let header = winget_row("Name", "Id", "Version", "Available", "Source");
+        let stdout = [
+            format!("   - \r   \\ \r{}", header),
+            "-".repeat(header.len()),
+            winget_row("Microsoft Visual Studio Code", "Microsoft.VisualStudioCode", "1.89.1", "1.90.0", "winget"),
+            winget_row("Microsoft Visual C++ 2015-2022 Redis…", "Microsoft.VCRedist.2015+.x64", "14.38.33130.0", "14.40.33810.0", "winget"),
+            winget_row("Mozilla Firefox (x64 fr)", "Mozilla.Firefox.fr", "126.0", "126.0", "winget"),
+            "3 upgrades available.".to_string(),
+        ].join("\r\n");
+
+        let updates = parse_winget_upgrade_table(&stdout);
+
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].name, "Microsoft Visual Studio Code");
+        assert_eq!(updates[0].id, "Microsoft.VisualStudioCode");
+        assert_eq!(updates[0].current_version, "1.89.1");
+        assert_eq!(updates[0].available_version, "1.90.0");
+        assert_eq!(updates[1].name, "Microsoft Visual C++ 2015-2022 Redis…");
+        assert_eq!(updates[1].id, "Microsoft.VCRedist.2015+.x64");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn winget_upgrade_table_ignores_localized_headers_and_footer() {
+        let header = winget_row("Nom", "ID", "Version", "Disponible", "Source");
+        let stdout = [
+            header.clone(),
+            "-".repeat(header.len()),
+            winget_row("Microsoft Visual Studio Code", "Microsoft.VisualStudioCode", "1.89.1", "1.90.0", "winget"),
+            "1 mises à niveau disponibles.".to_string(),
+        ].join("\n");
+
+        let updates = parse_winget_upgrade_table(&stdout);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].available_version, "1.90.0");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn winget_errors_from_captured_output() {
+        let cases: &[(&str, Option<i32>, &str)] = &[
+            ("No package found matching input criteria.", Some(0x8A150014u32 as i32), "no_package"),
+            ("Aucun package trouvé correspondant aux critères d’entrée.", Some(1), "no_package"),
+            ("Installer failed with exit code: 1618\r\nAnother installation is already in progress. Try again later.", Some(0x8A150102u32 as i32), "install_in_progress"),
+            ("Une autre installation est déjà en cours. Réessayez plus tard.", Some(1), "install_in_progress"),
+            ("Failed when searching source: winget\r\nAn unexpected error occurred while executing the command:\r\n0x80072ee7 : The server name or address could not be resolved", Some(0x80072EE7u32 as i32), "network"),
+            ("Accès refusé.", Some(1), "needs_admin"),
+            ("Found Mozilla Firefox [Mozilla.Firefox]\r\nNo available upgrade found.\r\nNo newer package versions are available from the configured sources.", Some(0x8A15002Bu32 as i32), "already_installed"),
+            ("Aucune mise à niveau disponible.", Some(1), "already_installed"),
+        ];
+        for (stdout, exit_code, expected) in cases {
+            assert_eq!(parse_winget_error(stdout, "", *exit_code).code, *expected, "{}", stdout);
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn generic_failures_are_not_misclassified() {
+        // "failed", "error" or "network" in a package name or log line must not pick a category
+        let output = "Found Network Monitor Tool [Vendor.NetworkMonitor]\r\nInstaller failed with exit code: 2\r\nAn error occurred";
+        let error = parse_winget_error(output, "", Some(0x8A150109u32 as i32));
+        assert_eq!(error.code, "unknown");
+        assert_eq!(error.message, "Echec winget: An error occurred");
+    }
+
+    #[cfg(windows)]
+    fn write_file(path: &std::path::Path, size: usize) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![0u8; size]).unwrap();
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn privacy_targets_count_only_their_own_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("Local");
+        let roaming = dir.path().join("Roaming");
+        let recent = roaming.join("Microsoft\\Windows\\Recent");
+        write_file(&recent.join("rapport.docx.lnk"), 100);
+        write_file(&recent.join("photos.lnk"), 50);
+        write_file(&recent.join("AutomaticDestinations\\a.automaticDestinations-ms"), 1000);
+        write_file(&recent.join("CustomDestinations\\b.customDestinations-ms"), 500);
+        let chrome = local.join("Google\\Chrome\\User Data\\Default");
+        write_file(&chrome.join("Preferences"), 10);
+        write_file(&chrome.join("Cache\\Cache_Data\\f_000001"), 4096);
+        write_file(&chrome.join("Code Cache\\js\\index"), 2048);
+        write_file(&chrome.join("Network\\Cookies"), 300);
+        write_file(&local.join("Mozilla\\Firefox\\Profiles\\x.default\\cache2\\entries\\E1"), 700);
+
+        let recent_docs = clean_privacy_target(PrivacyTarget::RecentDocs, &local, &roaming);
+        assert_eq!(recent_docs.target, "recent_docs");
+        assert_eq!((recent_docs.items_removed, recent_docs.bytes_freed), (2, 150));
+
+        let jump_lists = clean_privacy_target(PrivacyTarget::JumpLists, &local, &roaming);
+        assert_eq!((jump_lists.items_removed, jump_lists.bytes_freed), (2, 1500));
+
+        let chrome_cache = clean_privacy_target(PrivacyTarget::ChromeCache, &local, &roaming);
+        assert_eq!((chrome_cache.items_removed, chrome_cache.bytes_freed), (2, 6144));
+        assert!(chrome.join("Preferences").exists());
+        assert!(chrome.join("Network\\Cookies").exists());
+
+        let firefox_cache = clean_privacy_target(PrivacyTarget::FirefoxCache, &local, &roaming);
+        assert_eq!((firefox_cache.items_removed, firefox_cache.bytes_freed), (1, 700));
+
+        let edge_cache = clean_privacy_target(PrivacyTarget::EdgeCache, &local, &roaming);
+        assert_eq!((edge_cache.items_removed, edge_cache.bytes_freed), (0, 0));
+        assert!(edge_cache.skipped.is_empty());
+    }
+
+    #[test]
+    fn path_entries_flag_dead_and_duplicate_directories() {
+        let raw = ["/opt/tools/bin", "/usr/bin/", "  ", "/missing/dir", "/USR/BIN", "/opt/tools/bin"]
+            .join(&PATH_SEPARATOR.to_string());
+        let existing = ["/opt/tools/bin", "/usr/bin/", "/USR/BIN"];
+
+        let entries = analyze_path_entries(&raw, |_| None, |p| existing.contains(&p));
+
+        let summary: Vec<(&str, bool, bool)> = entries.iter()
+            .map(|e| (e.raw.as_str(), e.exists, e.duplicate))
+            .collect();
+        assert_eq!(summary, vec![
+            ("/opt/tools/bin", true, false),
+            ("/usr/bin/", true, false),
+            ("/missing/dir", false, false),
+            ("/USR/BIN", true, true),
+            ("/opt/tools/bin", true, true),
+        ]);
+    }
+
+    #[test]
+    fn unresolved_path_entries_are_kept() {
+        let raw = ["%TOOLS%/bin", "%UNKNOWN_AGENT_VAR%/bin", "%JAVA_HOME%/bin", "/gone"]
+            .join(&PATH_SEPARATOR.to_string());
+        let lookup = |name: &str| match name {
+            "TOOLS" => Some("/opt/tools".to_string()),
+            "JAVA_HOME" => Some("%TOOLS%/java".to_string()),
+            _ => None,
+        };
+        let existing = ["/opt/tools/bin", "/opt/tools/java/bin"];
+
+        let entries = analyze_path_entries(&raw, lookup, |p| existing.contains(&p));
+
+        let summary: Vec<(&str, bool, bool, bool)> = entries.iter()
+            .map(|e| (e.expanded.as_str(), e.exists, e.unresolved, is_removable_path_entry(e)))
+            .collect();
+        assert_eq!(summary, vec![
+            ("/opt/tools/bin", true, false, false),
+            ("%UNKNOWN_AGENT_VAR%/bin", false, true, false),
+            ("/opt/tools/java/bin", true, false, false),
+            ("/gone", false, false, true),
+        ]);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn env_rewrite_keeps_the_value_type() {
+        use winreg::enums::RegType::*;
+
+        assert_eq!(env_value_type(Some(REG_EXPAND_SZ), r"C:\Tools"), REG_EXPAND_SZ);
+        assert_eq!(env_value_type(Some(REG_SZ), r"C:\Tools"), REG_SZ);
+        assert_eq!(env_value_type(Some(REG_SZ), r"%ProgramFiles%\Tools"), REG_EXPAND_SZ);
+        assert_eq!(env_value_type(None, r"C:\Tools"), REG_SZ);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn report_older_than_launch_is_stale() {
+        use std::time::{Duration, SystemTime};
+
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("DiskInfo.txt");
+        std::fs::write(&report, "previous run").unwrap();
+
+        let launched_at = SystemTime::now() + Duration::from_secs(3600);
+        assert!(!wait_for_file_newer_than(&report, launched_at, Duration::from_millis(300)));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn report_written_after_launch_is_fresh() {
+        use std::time::{Duration, SystemTime};
+
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("DiskInfo.txt");
+        let launched_at = SystemTime::now() - Duration::from_secs(1);
+        assert!(!wait_for_file_newer_than(&report, launched_at, Duration::from_millis(200)));
+
+        std::fs::write(&report, "new run").unwrap();
+        assert!(wait_for_file_newer_than(&report, launched_at, Duration::from_millis(200)));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn backup_names_split_into_exact_prefix_and_timestamp() {
+        assert_eq!(split_backup_name("tweak_telemetry_20250101_120000.json"), Some(("tweak_telemetry", "20250101_120000")));
+        assert_eq!(split_backup_name("startup_Microsoft Edge_20250101_120000.reg"), Some(("startup_Microsoft Edge", "20250101_120000")));
+        assert_eq!(split_backup_name("tweak_telemetry.json"), None);
+
+        assert!(is_valid_backup_prefix("startup_Microsoft Edge"));
+        assert!(!is_valid_backup_prefix(""));
+        assert!(!is_valid_backup_prefix(r"..\..\Windows\evil"));
+        assert!(!restore_latest_backup("   ").success);
+    }
+}
@@ -0,0 +1,102 @@
+// ============================================
+// MICRODIAG AGENT - File Hashing
+// Streaming SHA-256 for integrity checks
+// ============================================
+
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const HASH_CHUNK_SIZE: usize = 64 * 1024;
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-256 of a file, read in chunks so large installers never sit in memory
+pub fn hash_file_sha256(path: &Path) -> Result<String, String> {
+    hash_file_sha256_cancellable(path, &AtomicBool::new(false))
+}
+
+/// Same as `hash_file_sha256`, but stops as soon as `cancel` is set
+pub fn hash_file_sha256_cancellable(path: &Path, cancel: &AtomicBool) -> Result<String, String> {
+    let mut file = File::open(path)
+        .map_err(|e| format!("Impossible d'ouvrir {}: {}", path.display(), e))?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_CHUNK_SIZE];
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Calcul du hash annule".to_string());
+        }
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Erreur lecture {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// SHA-256 of in-memory content (script bodies, small payloads)
+pub fn hash_bytes_sha256(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+/// Case-insensitive digest comparison; an empty expected digest never matches
+pub fn digest_matches(actual: &str, expected: &str) -> bool {
+    let expected = expected.trim();
+    !expected.is_empty() && actual.trim().eq_ignore_ascii_case(expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[test]
+    fn hashes_known_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abc.txt");
+        std::fs::write(&path, b"abc").unwrap();
+
+        assert_eq!(hash_file_sha256(&path).unwrap(), ABC_SHA256);
+        assert_eq!(hash_bytes_sha256(b"abc"), ABC_SHA256);
+        assert!(digest_matches(ABC_SHA256, &ABC_SHA256.to_uppercase()));
+        assert!(!digest_matches(ABC_SHA256, ""));
+    }
+
+    #[test]
+    fn file_spanning_several_chunks_matches_in_memory_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.bin");
+        let content: Vec<u8> = (0..HASH_CHUNK_SIZE * 3 + 17).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &content).unwrap();
+
+        assert_eq!(hash_file_sha256(&path).unwrap(), hash_bytes_sha256(&content));
+    }
+
+    #[test]
+    fn cancelled_hash_returns_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abc.txt");
+        std::fs::write(&path, b"abc").unwrap();
+
+        assert!(hash_file_sha256_cancellable(&path, &AtomicBool::new(true)).is_err());
+    }
+    #[test]
+    fn missing_file_error_names_the_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("absent.msi");
+
+        let err = hash_file_sha256(&path).unwrap_err();
+        assert!(err.contains(&path.display().to_string()), "{}", err);
+    }
+}
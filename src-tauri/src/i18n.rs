@@ -0,0 +1,76 @@
+// ============================================
+// MICRODIAG AGENT - Localization
+// Embedded locale tables (locales/*.json), French by default
+// ============================================
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+pub const DEFAULT_LANGUAGE: &str = "fr";
+
+/// Flat "section.key" -> text tables, compiled into the binary
+const LOCALES: &[(&str, &str)] = &[
+    ("fr", include_str!("../locales/fr.json")),
+    ("en", include_str!("../locales/en.json")),
+];
+
+static TABLES: OnceLock<HashMap<&'static str, HashMap<String, String>>> = OnceLock::new();
+static LANGUAGE: RwLock<&'static str> = RwLock::new(DEFAULT_LANGUAGE);
+
+fn tables() -> &'static HashMap<&'static str, HashMap<String, String>> {
+    TABLES.get_or_init(|| {
+        LOCALES.iter()
+            .map(|(lang, json)| {
+                let table = serde_json::from_str(json).unwrap_or_else(|e| {
+                    eprintln!("[i18n] Locale {} invalide: {}", lang, e);
+                    HashMap::new()
+                });
+                (*lang, table)
+            })
+            .collect()
+    })
+}
+
+/// Shipped locale matching `lang` ("en", "en-US", "EN"...), French otherwise
+pub fn normalize(lang: &str) -> &'static str {
+    let code = lang.trim().split(['-', '_']).next().unwrap_or_default().to_lowercase();
+    LOCALES.iter()
+        .map(|(l, _)| *l)
+        .find(|l| *l == code)
+        .unwrap_or(DEFAULT_LANGUAGE)
+}
+
+pub fn language() -> &'static str {
+    *LANGUAGE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn set_language(lang: &str) {
+    *LANGUAGE.write().unwrap_or_else(|e| e.into_inner()) = normalize(lang);
+}
+
+/// Lookup with fallback to French, then to the key itself so a missing entry stays visible
+pub fn t(key: &str, lang: &str) -> String {
+    let tables = tables();
+    tables.get(normalize(lang))
+        .and_then(|table| table.get(key))
+        .or_else(|| tables.get(DEFAULT_LANGUAGE).and_then(|table| table.get(key)))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Same as `t`, replacing each `{name}` placeholder with its value
+pub fn tf(key: &str, lang: &str, args: &[(&str, String)]) -> String {
+    args.iter().fold(t(key, lang), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// `t` in the language from the settings table
+pub fn tr(key: &str) -> String {
+    t(key, language())
+}
+
+/// `tf` in the language from the settings table
+pub fn trf(key: &str, args: &[(&str, String)]) -> String {
+    tf(key, language(), args)
+}
@@ -14,16 +14,32 @@ mod sync;
 mod godmode;
 mod diagnostics;
 mod fixwin;
+mod agent_update;
+mod hashing;
+mod report;
+mod services;
+mod secure_store;
+mod eventlog;
+mod i18n;
+mod tools;
+mod error;
+#[cfg(windows)]
+mod powershell;
+#[cfg(windows)]
+mod wmi_cache;
 
 use config::*;
 use metrics::*;
 use security::*;
-use database::{Database, LocalScript, LocalMetrics, ChatMessage};
+use database::{Database, DbStorageStatus, LocalScript, LocalMetrics, ChatMessage, SmartHistoryEntry, HealthTrend, DbMaintenanceReport, TemperatureReading, DiagnosticHistoryEntry, ScriptExecution};
 use sync::*;
+use error::MicrodiagError;
 
 use serde::{Deserialize, Serialize};
 use sysinfo::System;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use std::path::PathBuf;
 use std::fs;
@@ -46,13 +62,53 @@ fn get_device_token_path() -> PathBuf {
     path
 }
 
+/// DPAPI-protected token (Windows); device_token.txt stays the plaintext fallback
+fn get_protected_token_path() -> PathBuf {
+    get_device_token_path().with_file_name("device_token.dat")
+}
+
+fn is_valid_token(token: &str) -> bool {
+    token.starts_with("mdiag_") && token.len() > 20
+}
+
+/// Writes the token encrypted when possible; returns true once the plaintext copy is no longer needed
+fn save_device_token(token: &str) -> bool {
+    if secure_store::is_supported() {
+        match secure_store::write_protected(&get_protected_token_path(), token) {
+            Ok(()) => return true,
+            Err(e) => println!("[Device] Warning: Could not encrypt token, keeping plaintext: {}", e),
+        }
+    }
+    match fs::write(get_device_token_path(), token) {
+        Ok(()) => false,
+        Err(e) => {
+            println!("[Device] Warning: Could not save token: {}", e);
+            false
+        }
+    }
+}
+
 fn load_or_create_device_token() -> String {
     let path = get_device_token_path();
 
-    // Try to load existing token
+    // Encrypted token first
+    if secure_store::is_supported() {
+        if let Ok(token) = secure_store::read_protected(&get_protected_token_path()) {
+            if is_valid_token(&token) {
+                println!("[Device] Token loaded: {}", &token[..20]);
+                return token;
+            }
+        }
+    }
+
+    // Plaintext token from older versions: migrate it once
     if let Ok(token) = fs::read_to_string(&path) {
         let token = token.trim().to_string();
-        if !token.is_empty() && token.starts_with("mdiag_") {
+        if is_valid_token(&token) {
+            if secure_store::is_supported() && save_device_token(&token) {
+                let _ = fs::remove_file(&path);
+                println!("[Device] Token migrated to encrypted storage");
+            }
             println!("[Device] Token loaded: {}", &token[..20]);
             return token;
         }
@@ -60,11 +116,8 @@ fn load_or_create_device_token() -> String {
 
     // Generate new persistent token
     let new_token = format!("mdiag_{}", uuid::Uuid::new_v4());
-    if let Err(e) = fs::write(&path, &new_token) {
-        println!("[Device] Warning: Could not save token: {}", e);
-    } else {
-        println!("[Device] New token created: {}", &new_token[..20]);
-    }
+    save_device_token(&new_token);
+    println!("[Device] New token created: {}", &new_token[..20]);
     new_token
 }
 
@@ -76,6 +129,9 @@ struct AppState {
     device_token: Mutex<String>,
     heartbeat_running: Mutex<bool>,
     db: Arc<Database>,
+    running_scripts: Mutex<HashMap<String, std::process::Child>>,
+    last_diagnostic: Mutex<Option<diagnostics::PremiumDiagnostic>>,
+    storage_scan_cancel: AtomicBool,
 }
 
 // ============================================
@@ -85,6 +141,8 @@ struct AppState {
 struct HeartbeatPayload {
     device_token: String,
     hostname: String,
+    // Friendly name set on the machine ("Reception-PC"), the dashboard still keys on device_token
+    device_label: Option<String>,
     os_type: String,
     os_version: String,
     status: String,
@@ -93,6 +151,8 @@ struct HeartbeatPayload {
     security: serde_json::Value,
     deep_health: serde_json::Value,
     agent_version: String,
+    // Sampling time, kept when the heartbeat is replayed from the offline queue
+    recorded_at: String,
 }
 
 #[derive(Deserialize, Debug)]
@@ -171,8 +231,9 @@ fn get_system_metrics(state: tauri::State<Arc<AppState>>) -> Result<SystemMetric
 #[tauri::command]
 fn get_health_score(state: tauri::State<Arc<AppState>>) -> Result<HealthScore, String> {
     // Simple health score based on metrics
+    let scoring = load_scoring_config(&state.db);
     let metrics = get_system_metrics(state)?;
-    Ok(metrics.calculate_health())
+    Ok(metrics.calculate_health(&scoring))
 }
 
 #[tauri::command]
@@ -180,6 +241,11 @@ fn get_security_status() -> Result<SecurityStatus, String> {
     Ok(SecurityStatus::check())
 }
 
+#[tauri::command]
+fn get_pending_reboot_status() -> security::PendingRebootStatus {
+    security::get_pending_reboot_status()
+}
+
 #[tauri::command]
 fn get_device_token(state: tauri::State<Arc<AppState>>) -> String {
     state.device_token.lock().unwrap().clone()
@@ -191,9 +257,89 @@ use std::os::windows::process::CommandExt;
 #[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+/// Collects a child pipe on its own thread so a chatty script can't fill the buffer and stall
+fn spawn_pipe_reader<R: std::io::Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+/// A process started by the script can outlive it and keep the pipes open
+const SCRIPT_PIPE_DRAIN_SECS: u64 = 5;
+
+/// Pipe contents once the reader is done, or nothing if the pipe is still held open after
+/// SCRIPT_PIPE_DRAIN_SECS: the thread is left detached rather than blocking the command
+async fn collect_pipe(reader: std::thread::JoinHandle<Vec<u8>>) -> Vec<u8> {
+    let deadline = std::time::Instant::now() + Duration::from_secs(SCRIPT_PIPE_DRAIN_SECS);
+    while !reader.is_finished() {
+        if std::time::Instant::now() >= deadline {
+            println!("[Script] Output pipe still open after exit, a child process kept it");
+            return Vec::new();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    reader.join().unwrap_or_default()
+}
+
+/// Kills the script and everything it started: killing powershell/cmd alone leaves
+/// grandchildren running with the inherited pipes
+fn kill_process_tree(child: &mut std::process::Child) -> std::io::Result<()> {
+    #[cfg(windows)]
+    {
+        let killed = std::process::Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &child.id().to_string()])
+            .creation_flags(CREATE_NO_WINDOW)
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false);
+        if killed {
+            let _ = child.wait();
+            return Ok(());
+        }
+    }
+    child.kill()?;
+    let _ = child.wait();
+    Ok(())
+}
+
+/// Same format as SQLite CURRENT_TIMESTAMP so rows compare with datetime('now')
+fn sql_timestamp_now() -> String {
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn record_script_execution(db: &Database, script_id: &str, source: &str, started_at: String, exit_code: Option<i32>, stdout: &str, stderr: &str) {
+    let entry = ScriptExecution {
+        id: None,
+        script_id: script_id.to_string(),
+        source: source.to_string(),
+        started_at,
+        finished_at: sql_timestamp_now(),
+        exit_code,
+        stdout_tail: stdout.to_string(),
+        stderr_tail: stderr.to_string(),
+    };
+    if let Err(e) = db.save_script_execution(&entry) {
+        println!("[Script] Failed to record execution of {}: {}", script_id, e);
+    }
+}
+
 #[tauri::command]
-async fn run_script(_script_id: String, code: String, language: String) -> Result<String, String> {
-    use std::process::Command;
+async fn run_script(
+    state: tauri::State<'_, Arc<AppState>>,
+    script_id: String,
+    code: String,
+    language: String,
+    timeout_secs: Option<u64>,
+    env: Option<HashMap<String, String>>,
+    cwd: Option<String>,
+    args: Option<Vec<String>>,
+    trigger: Option<String>,
+) -> Result<String, MicrodiagError> {
+    use std::process::{Command, Stdio};
     use std::fs;
     use std::env;
 
@@ -202,52 +348,169 @@ async fn run_script(_script_id: String, code: String, language: String) -> Resul
         "powershell" => ".ps1",
         "python" => ".py",
         "batch" => ".bat",
-        _ => return Err("Langage non supporté".to_string()),
+        _ => return Err(MicrodiagError::InvalidInput("Langage non supporté".to_string())),
     };
 
+    let cwd = cwd.map(|dir| dir.trim().to_string()).filter(|dir| !dir.is_empty());
+    if let Some(dir) = &cwd {
+        if !std::path::Path::new(dir).is_dir() {
+            return Err(MicrodiagError::NotFound(format!("Dossier de travail introuvable: {}", dir)));
+        }
+    }
+
     // Use system temp dir with simple filename (avoids encoding issues)
     let temp_dir = env::temp_dir();
     let filename = format!("mdiag_script_{}{}", uuid::Uuid::new_v4().to_string().replace("-", "")[..8].to_string(), ext);
     let path = temp_dir.join(&filename);
     let path_str = path.to_string_lossy().to_string();
 
-    // Write script content
-    fs::write(&path, code.as_bytes()).map_err(|e| format!("Erreur écriture: {}", e))?;
-
     #[cfg(windows)]
-    let output = match language.as_str() {
-        "powershell" => Command::new("powershell")
-            .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File", &path_str])
-            .creation_flags(CREATE_NO_WINDOW)
-            .output(),
-        "python" => Command::new("python")
-            .arg(&path_str)
-            .creation_flags(CREATE_NO_WINDOW)
-            .output(),
-        "batch" => Command::new("cmd")
-            .args(["/C", &path_str])
-            .creation_flags(CREATE_NO_WINDOW)
-            .output(),
-        _ => return Err("Langage non supporté".to_string()),
-    }.map_err(|e| format!("Erreur: {}", e))?;
+    let mut command = match language.as_str() {
+        "powershell" => {
+            let mut cmd = Command::new(powershell::require().map_err(MicrodiagError::NotFound)?);
+            cmd.args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File", &path_str]);
+            cmd
+        }
+        "python" => {
+            let mut cmd = Command::new("python");
+            cmd.arg(&path_str);
+            cmd
+        }
+        _ => {
+            let mut cmd = Command::new("cmd");
+            cmd.args(["/C", &path_str]);
+            cmd
+        }
+    };
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
 
     #[cfg(not(windows))]
-    let output = match language.as_str() {
-        "powershell" => Command::new("pwsh")
-            .args(["-NoProfile", "-File", &path_str])
-            .output(),
-        "python" => Command::new("python3").arg(&path_str).output(),
-        "batch" => Command::new("bash").arg(&path_str).output(),
-        _ => return Err("Langage non supporté".to_string()),
-    }.map_err(|e| format!("Erreur: {}", e))?;
+    let mut command = match language.as_str() {
+        "powershell" => {
+            let mut cmd = Command::new("pwsh");
+            cmd.args(["-NoProfile", "-File", &path_str]);
+            cmd
+        }
+        "python" => {
+            let mut cmd = Command::new("python3");
+            cmd.arg(&path_str);
+            cmd
+        }
+        _ => {
+            let mut cmd = Command::new("bash");
+            cmd.arg(&path_str);
+            cmd
+        }
+    };
+
+    // Script parameters go right after the script path (after -File for PowerShell)
+    command.args(args.unwrap_or_default());
+    command.envs(env.unwrap_or_default());
+    if let Some(dir) = &cwd {
+        command.current_dir(dir);
+    }
+
+    // Write script content
+    fs::write(&path, code.as_bytes()).map_err(MicrodiagError::from)?;
+
+    let source = if trigger.as_deref() == Some("remote") { "remote" } else { "local" };
+    let started_at = sql_timestamp_now();
+
+    // Register the child so cancel_script can reach it
+    let (stdout_reader, stderr_reader) = {
+        let mut running = state.running_scripts.lock().unwrap();
+        if running.contains_key(&script_id) {
+            let _ = fs::remove_file(&path);
+            return Err(MicrodiagError::InvalidInput("Ce script est deja en cours d'execution".to_string()));
+        }
+
+        let mut child = match command.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                let _ = fs::remove_file(&path);
+                let message = format!("Erreur: {}", e);
+                record_script_execution(&state.db, &script_id, source, started_at, None, "", &message);
+                return Err(MicrodiagError::from(e));
+            }
+        };
+        let readers = (spawn_pipe_reader(child.stdout.take()), spawn_pipe_reader(child.stderr.take()));
+        running.insert(script_id.clone(), child);
+        readers
+    };
+
+    let started = std::time::Instant::now();
+    let outcome: Result<std::process::ExitStatus, MicrodiagError> = loop {
+        {
+            let mut running = state.running_scripts.lock().unwrap();
+            let child = match running.get_mut(&script_id) {
+                Some(child) => child,
+                // Removed by cancel_script
+                None => break Err(MicrodiagError::Cancelled("Script annulé".to_string())),
+            };
+
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    running.remove(&script_id);
+                    break Ok(status);
+                }
+                Ok(None) => {
+                    if let Some(limit) = timeout_secs {
+                        if started.elapsed() >= Duration::from_secs(limit) {
+                            let _ = kill_process_tree(child);
+                            running.remove(&script_id);
+                            break Err(MicrodiagError::Timeout(format!("Script annulé après {} secondes", limit)));
+                        }
+                    }
+                }
+                Err(e) => {
+                    running.remove(&script_id);
+                    break Err(MicrodiagError::from(e));
+                }
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    };
 
     // Clean up temp file
     let _ = fs::remove_file(&path);
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    let stdout = String::from_utf8_lossy(&collect_pipe(stdout_reader).await).to_string();
+    let stderr = String::from_utf8_lossy(&collect_pipe(stderr_reader).await).to_string();
+
+    match outcome {
+        Ok(status) => {
+            record_script_execution(&state.db, &script_id, source, started_at, status.code(), &stdout, &stderr);
+            if status.success() {
+                Ok(stdout)
+            } else {
+                Err(MicrodiagError::ScriptFailed(stderr))
+            }
+        }
+        Err(error) => {
+            // Cancelled or timed out: keep the reason with whatever the script printed
+            let stderr = format!("{}\n{}", stderr, error);
+            record_script_execution(&state.db, &script_id, source, started_at, None, &stdout, stderr.trim());
+            Err(error)
+        }
+    }
+}
+
+#[tauri::command]
+fn db_get_script_executions(state: tauri::State<Arc<AppState>>, limit: i32) -> Result<Vec<ScriptExecution>, MicrodiagError> {
+    state.db.get_script_executions(limit).map_err(MicrodiagError::from)
+}
+
+#[tauri::command]
+fn cancel_script(state: tauri::State<Arc<AppState>>, script_id: String) -> Result<(), MicrodiagError> {
+    // Take it out of the map first: run_script sees it gone and reports the cancellation
+    let child = state.running_scripts.lock().unwrap().remove(&script_id);
+    match child {
+        Some(mut child) => {
+            kill_process_tree(&mut child).map_err(|e| MicrodiagError::Io(format!("Impossible d'arreter le script: {}", e)))?;
+            Ok(())
+        }
+        None => Err(MicrodiagError::NotFound(format!("Aucun script en cours: {}", script_id))),
     }
 }
 
@@ -262,7 +525,7 @@ fn send_notification(app: tauri::AppHandle, title: String, body: String) -> Resu
 }
 
 #[tauri::command]
-async fn run_security_scan(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+async fn run_security_scan(app: tauri::AppHandle) -> Result<security::SecurityScanReport, String> {
     use std::process::{Command, Stdio};
     use std::io::{BufRead, BufReader};
     use tauri::Emitter;
@@ -282,7 +545,7 @@ async fn run_security_scan(app: tauri::AppHandle) -> Result<serde_json::Value, S
     }));
 
     #[cfg(windows)]
-    let mut child = Command::new("powershell")
+    let mut child = Command::new(powershell::require()?)
         .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File", &script_path.to_string_lossy()])
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -301,22 +564,29 @@ async fn run_security_scan(app: tauri::AppHandle) -> Result<serde_json::Value, S
     let stdout = child.stdout.take().unwrap();
     let reader = BufReader::new(stdout);
 
+    // Drain stderr on its own thread so a chatty script can't block on a full pipe
+    let stderr = child.stderr.take().unwrap();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut text = String::new();
+        let _ = std::io::Read::read_to_string(&mut BufReader::new(stderr), &mut text);
+        text
+    });
+
     let mut output_lines = Vec::new();
     let mut current_step = 0;
-    let mut json_result: Option<serde_json::Value> = None;
+    let mut in_report = false;
 
     for line in reader.lines() {
         if let Ok(line) = line {
-            // Check if this is the JSON output
-            if line.trim().starts_with('{') && line.trim().ends_with('}') {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) {
-                    json_result = Some(json);
-                }
-                continue;
+            // Report lines are JSON, not progress
+            match line.trim() {
+                security::SCAN_JSON_START => in_report = true,
+                security::SCAN_JSON_END => in_report = false,
+                _ => {}
             }
 
             // Parse step progress from lines like "[1/8] Analyse des logs..."
-            if line.contains("[") && line.contains("/") && line.contains("]") {
+            if !in_report && line.contains("[") && line.contains("/") && line.contains("]") {
                 if let Some(start) = line.find('[') {
                     if let Some(slash) = line.find('/') {
                         if let Ok(step) = line[start+1..slash].parse::<u8>() {
@@ -341,6 +611,7 @@ async fn run_security_scan(app: tauri::AppHandle) -> Result<serde_json::Value, S
     // Wait for completion
     let _ = child.wait();
     let _ = std::fs::remove_file(&script_path);
+    let stderr_text = stderr_reader.join().unwrap_or_default();
 
     // Emit completion
     let _ = app.emit("scan-progress", serde_json::json!({
@@ -349,261 +620,1093 @@ async fn run_security_scan(app: tauri::AppHandle) -> Result<serde_json::Value, S
         "progress": 100
     }));
 
-    // Return the JSON result
-    json_result.ok_or_else(|| "Aucun resultat JSON trouve".to_string())
+    security::parse_scan_report(&output_lines.join("\n"), &stderr_text)
 }
 
 // ============================================
-// LOCAL-FIRST DATABASE COMMANDS
+// SUPPORT SUMMARY (copy/paste for support calls)
 // ============================================
+fn redact_device_token(token: &str) -> String {
+    let tail: String = token.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+    format!("mdiag_...{}", tail)
+}
 
-#[tauri::command]
-fn db_get_scripts(state: tauri::State<Arc<AppState>>) -> Result<Vec<LocalScript>, String> {
-    state.db.get_all_scripts().map_err(|e| e.to_string())
+fn build_support_summary(state: &AppState) -> Result<String, String> {
+    let (metrics, info) = {
+        let mut sys = state.system.lock().map_err(|_| "Failed to acquire system lock".to_string())?;
+        let metrics = SystemMetrics::collect(&mut sys);
+        let info = diagnostics::get_extended_system_info(&sys);
+        (metrics, info)
+    };
+    let health = metrics.calculate_health(&load_scoring_config(&state.db));
+    let token = state.device_token.lock().unwrap().clone();
+    Ok(format_support_summary(&metrics, &info, &health, &token))
+}
+
+fn format_support_summary(
+    metrics: &SystemMetrics,
+    info: &diagnostics::ExtendedSystemInfo,
+    health: &HealthScore,
+    token: &str,
+) -> String {
+    let disk_free = metrics.disks.iter()
+        .map(|d| format!("{} {:.0}/{:.0} GB", d.mount_point, d.free_gb, d.total_gb))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let top_issue = health.issues.first().cloned().unwrap_or_else(|| "Aucun".to_string());
+
+    format!(
+        "Microdiag Sentinel v{} | Appareil {}\n\
+         OS: {} | CPU: {} ({}c/{}t) | RAM: {:.1} GB ({:.0}% utilisee)\n\
+         Disques libres: {}\n\
+         Score sante: {}/100 ({}) | Probleme principal: {}",
+        AGENT_VERSION,
+        redact_device_token(token),
+        info.windows_version,
+        info.cpu_name.trim(),
+        info.cpu_cores,
+        info.cpu_threads,
+        info.ram_total_gb,
+        metrics.memory_percent,
+        if disk_free.is_empty() { "N/A".to_string() } else { disk_free },
+        health.score,
+        health.status,
+        top_issue,
+    )
+}
+
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    #[cfg(windows)]
+    let mut child = Command::new(powershell::require()?)
+        .args(["-NoProfile", "-Command", "$input | Set-Clipboard"])
+        .stdin(Stdio::piped())
+        .creation_flags(CREATE_NO_WINDOW)
+        .spawn()
+        .map_err(|e| format!("Erreur presse-papiers: {}", e))?;
+
+    #[cfg(target_os = "macos")]
+    let mut child = Command::new("pbcopy")
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Erreur presse-papiers: {}", e))?;
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let mut child = Command::new("xclip")
+        .args(["-selection", "clipboard"])
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Erreur presse-papiers: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).map_err(|e| format!("Erreur presse-papiers: {}", e))?;
+    }
+
+    let status = child.wait().map_err(|e| format!("Erreur presse-papiers: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("Impossible de copier dans le presse-papiers".to_string())
+    }
 }
 
 #[tauri::command]
-fn db_get_scripts_by_category(state: tauri::State<Arc<AppState>>, category: String) -> Result<Vec<LocalScript>, String> {
-    state.db.get_scripts_by_category(&category).map_err(|e| e.to_string())
+fn get_support_summary(state: tauri::State<Arc<AppState>>) -> Result<String, String> {
+    build_support_summary(&state)
 }
 
 #[tauri::command]
-fn db_get_scripts_count(state: tauri::State<Arc<AppState>>) -> Result<i32, String> {
-    state.db.get_scripts_count().map_err(|e| e.to_string())
+fn copy_support_summary(state: tauri::State<Arc<AppState>>) -> Result<String, String> {
+    let summary = build_support_summary(&state)?;
+    copy_to_clipboard(&summary)?;
+    Ok(summary)
 }
 
+// ============================================
+// LOCAL-FIRST DATABASE COMMANDS
+// ============================================
+
 #[tauri::command]
-fn db_save_metrics(state: tauri::State<Arc<AppState>>, metrics: LocalMetrics) -> Result<i64, String> {
-    state.db.save_metrics(&metrics).map_err(|e| e.to_string())
+fn db_get_scripts(state: tauri::State<Arc<AppState>>) -> Result<Vec<LocalScript>, MicrodiagError> {
+    state.db.get_all_scripts().map_err(MicrodiagError::from)
+}
+
+/// "Vider le cache DNS" -> "vider-le-cache-dns"
+fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
 }
 
+/// Saves a script written in the UI; it is flagged local so the Supabase sync keeps its hands off
 #[tauri::command]
-fn db_get_recent_metrics(state: tauri::State<Arc<AppState>>, limit: i32) -> Result<Vec<LocalMetrics>, String> {
-    state.db.get_recent_metrics(limit).map_err(|e| e.to_string())
+fn db_upsert_script(state: tauri::State<Arc<AppState>>, script: LocalScript) -> Result<LocalScript, MicrodiagError> {
+    let mut script = script;
+    script.name = script.name.trim().to_string();
+    script.language = script.language.trim().to_lowercase();
+
+    if script.name.is_empty() {
+        return Err(MicrodiagError::InvalidInput("Nom du script manquant".to_string()));
+    }
+    if script.code.trim().is_empty() {
+        return Err(MicrodiagError::InvalidInput("Code du script vide".to_string()));
+    }
+    if !database::SCRIPT_LANGUAGES.contains(&script.language.as_str()) {
+        return Err(MicrodiagError::InvalidInput(format!(
+            "Langage non supporte: {} ({})",
+            script.language,
+            database::SCRIPT_LANGUAGES.join(", ")
+        )));
+    }
+    if script.id.trim().is_empty() {
+        script.id = uuid::Uuid::new_v4().to_string();
+    } else if state.db.is_synced_script(&script.id)? {
+        // Overwriting would flip the catalog row to local and hide it from the next sync
+        return Err(MicrodiagError::InvalidInput("Script synchronise depuis le cloud, non modifiable".to_string()));
+    }
+    if script.category.trim().is_empty() {
+        script.category = "custom".to_string();
+    }
+
+    // INSERT OR REPLACE would silently delete whichever script already owns the slug
+    let script_id = script.id.clone();
+    let slug_taken = |slug: &str| -> Result<bool, MicrodiagError> {
+        let owner = state.db.script_id_for_slug(slug)?;
+        Ok(owner.map(|id| id != script_id).unwrap_or(false))
+    };
+    if script.slug.trim().is_empty() {
+        let base = slugify(&script.name);
+        let base = if base.is_empty() { "script".to_string() } else { base };
+        script.slug = if slug_taken(&base)? {
+            format!("{}-{}", base, &script.id[..script.id.len().min(8)])
+        } else {
+            base
+        };
+    } else {
+        script.slug = slugify(&script.slug);
+        if slug_taken(&script.slug)? {
+            return Err(MicrodiagError::InvalidInput(format!("Slug deja utilise: {}", script.slug)));
+        }
+    }
+
+    script.is_local = true;
+    state.db.upsert_script(&script)?;
+    Ok(script)
 }
 
+/// Only local scripts can be deleted, synced ones would come back with the next sync
 #[tauri::command]
-fn db_get_chat_history(state: tauri::State<Arc<AppState>>, limit: i32) -> Result<Vec<ChatMessage>, String> {
-    state.db.get_chat_history(limit).map_err(|e| e.to_string())
+fn db_delete_script(state: tauri::State<Arc<AppState>>, id: String) -> Result<(), MicrodiagError> {
+    match state.db.delete_local_script(&id)? {
+        0 => Err(MicrodiagError::NotFound("Script introuvable ou synchronise depuis le cloud".to_string())),
+        _ => Ok(()),
+    }
 }
 
 #[tauri::command]
-fn db_add_chat_message(state: tauri::State<Arc<AppState>>, role: String, content: String) -> Result<i64, String> {
-    state.db.add_chat_message(&role, &content).map_err(|e| e.to_string())
+fn db_get_scripts_by_category(state: tauri::State<Arc<AppState>>, category: String) -> Result<Vec<LocalScript>, MicrodiagError> {
+    state.db.get_scripts_by_category(&category).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-fn db_clear_chat(state: tauri::State<Arc<AppState>>) -> Result<(), String> {
-    state.db.clear_chat_history().map_err(|e| e.to_string())
+fn db_get_scripts_count(state: tauri::State<Arc<AppState>>) -> Result<i32, MicrodiagError> {
+    state.db.get_scripts_count().map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-fn db_get_setting(state: tauri::State<Arc<AppState>>, key: String) -> Result<Option<String>, String> {
-    state.db.get_setting(&key).map_err(|e| e.to_string())
+fn db_get_favorite_scripts(state: tauri::State<Arc<AppState>>) -> Result<Vec<LocalScript>, MicrodiagError> {
+    state.db.get_favorite_scripts().map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-fn db_set_setting(state: tauri::State<Arc<AppState>>, key: String, value: String) -> Result<(), String> {
-    state.db.set_setting(&key, &value).map_err(|e| e.to_string())
+fn db_set_script_favorite(state: tauri::State<Arc<AppState>>, id: String, favorite: bool) -> Result<(), MicrodiagError> {
+    state.db.set_script_favorite(&id, favorite).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-async fn db_sync_scripts(state: tauri::State<'_, Arc<AppState>>) -> Result<usize, String> {
-    sync_scripts_from_supabase(&state.db).await
+fn db_set_script_order(state: tauri::State<Arc<AppState>>, id: String, sort_order: i32) -> Result<(), MicrodiagError> {
+    state.db.set_script_order(&id, sort_order).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-async fn db_check_online() -> Result<bool, String> {
-    Ok(check_online_status().await)
+fn db_save_metrics(state: tauri::State<Arc<AppState>>, metrics: LocalMetrics) -> Result<i64, MicrodiagError> {
+    state.db.save_metrics(&metrics).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-async fn db_check_remote_executions(state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<RemoteExecution>, String> {
-    let device_token = state.device_token.lock().unwrap().clone();
-    check_remote_executions(&state.db, &device_token).await
+fn db_get_recent_metrics(state: tauri::State<Arc<AppState>>, limit: i32) -> Result<Vec<LocalMetrics>, MicrodiagError> {
+    state.db.get_recent_metrics(limit).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-async fn db_update_remote_execution(
-    id: String,
-    status: String,
-    output: Option<String>,
-    error: Option<String>,
-) -> Result<(), String> {
-    update_remote_execution(&id, &status, output.as_deref(), error.as_deref()).await
+async fn db_maintenance(state: tauri::State<'_, Arc<AppState>>) -> Result<DbMaintenanceReport, MicrodiagError> {
+    let db = state.db.clone();
+    Ok(tokio::task::spawn_blocking(move || db.run_maintenance()).await??)
 }
 
-// ============================================
-// GOD MODE COMMANDS (Native Performance)
-// ============================================
+/// Tells the UI when a database that could not be decrypted was set aside at startup
+#[tauri::command]
+fn db_get_storage_status(state: tauri::State<Arc<AppState>>) -> DbStorageStatus {
+    state.db.storage_status()
+}
 
 #[tauri::command]
-fn gm_get_installed_apps() -> Vec<godmode::InstalledApp> {
-    godmode::get_installed_apps_native()
+fn db_get_temperature_history(state: tauri::State<Arc<AppState>>, sensor_type: String, hours: i32) -> Result<Vec<TemperatureReading>, MicrodiagError> {
+    state.db.get_temperature_history(&sensor_type, hours).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-fn gm_get_deep_health() -> godmode::DeepHealth {
-    godmode::get_deep_health()
+fn db_get_health_trend(state: tauri::State<Arc<AppState>>, points: usize) -> Result<HealthTrend, MicrodiagError> {
+    state.db.get_health_trend(points).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-fn gm_get_startup_items() -> Vec<godmode::StartupItem> {
-    godmode::get_startup_items()
+fn db_get_chat_history(state: tauri::State<Arc<AppState>>, limit: i32) -> Result<Vec<ChatMessage>, MicrodiagError> {
+    state.db.get_chat_history(limit).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-fn gm_disable_startup_item(name: String, location: String) -> godmode::TweakResult {
-    godmode::disable_startup_item(&name, &location)
+fn db_get_chat_history_page(state: tauri::State<Arc<AppState>>, before_id: i64, limit: i32) -> Result<Vec<ChatMessage>, MicrodiagError> {
+    state.db.get_chat_history_page(before_id, limit).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-async fn gm_check_updates() -> Vec<godmode::OutdatedApp> {
-    godmode::check_winget_updates().await
+fn db_search_chat_history(state: tauri::State<Arc<AppState>>, query: String, limit: i32) -> Result<Vec<ChatMessage>, MicrodiagError> {
+    state.db.search_chat_history(&query, limit).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-async fn gm_install_apps(app_ids: Vec<String>) -> godmode::TweakResult {
-    godmode::install_winget_apps(app_ids).await
+fn db_add_chat_message(state: tauri::State<Arc<AppState>>, role: String, content: String) -> Result<i64, MicrodiagError> {
+    state.db.add_chat_message(&role, &content).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-async fn gm_update_all() -> godmode::TweakResult {
-    godmode::update_all_winget().await
+fn db_clear_chat(state: tauri::State<Arc<AppState>>) -> Result<(), MicrodiagError> {
+    state.db.clear_chat_history().map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-fn gm_apply_tweak(tweak_id: String, enable: bool) -> godmode::TweakResult {
-    godmode::apply_privacy_tweak(&tweak_id, enable)
+fn db_get_setting(state: tauri::State<Arc<AppState>>, key: String) -> Result<Option<String>, MicrodiagError> {
+    state.db.get_setting(&key).map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-async fn gm_ghost_mode() -> godmode::TweakResult {
-    godmode::activate_ghost_mode().await
+fn db_set_setting(state: tauri::State<Arc<AppState>>, key: String, value: String) -> Result<(), MicrodiagError> {
+    // Store the heartbeat interval already clamped, the loop picks it up on its next check
+    let value = if key == HEARTBEAT_INTERVAL_SETTING {
+        let secs: u64 = value.trim().parse().map_err(|_| MicrodiagError::InvalidInput(format!("Intervalle invalide: {}", value)))?;
+        secs.clamp(HEARTBEAT_INTERVAL_MIN_SECS, HEARTBEAT_INTERVAL_MAX_SECS).to_string()
+    } else if key == WMI_CACHE_TTL_SETTING {
+        let secs: u64 = value.trim().parse().map_err(|_| MicrodiagError::InvalidInput(format!("Duree invalide: {}", value)))?;
+        secs.min(WMI_CACHE_TTL_MAX_SECS).to_string()
+    } else {
+        value
+    };
+    #[cfg(windows)]
+    if key == WMI_CACHE_TTL_SETTING {
+        wmi_cache::set_ttl_secs(value.parse().unwrap_or(0));
+    }
+    if key == REGISTRY_FULL_BACKUP_SETTING {
+        godmode::set_full_key_backups(value == "true");
+    }
+    if key == LANGUAGE_SETTING {
+        i18n::set_language(&value);
+    }
+    state.db.set_setting(&key, &value).map_err(MicrodiagError::from)
+}
+
+/// Control characters stripped and whitespace trimmed, empty means no label
+fn clean_device_label(raw: &str) -> String {
+    raw.chars().filter(|c| !c.is_control()).collect::<String>().trim().to_string()
+}
+
+/// The setting can also arrive through db_set_setting or the settings sync, so it is cleaned again here
+fn load_device_label(db: &Database) -> Option<String> {
+    db.get_setting(DEVICE_LABEL_SETTING)
+        .ok()
+        .flatten()
+        .map(|raw| clean_device_label(&raw).chars().take(DEVICE_LABEL_MAX_CHARS).collect::<String>())
+        .filter(|label| !label.is_empty())
 }
 
 #[tauri::command]
-fn gm_list_backups() -> Vec<godmode::RegBackup> {
-    godmode::list_backups()
+fn get_device_label(state: tauri::State<Arc<AppState>>) -> Option<String> {
+    load_device_label(&state.db)
 }
 
+/// Saves the cleaned label and returns it, an empty label clears it
 #[tauri::command]
-fn gm_restore_backup(backup_path: String) -> godmode::TweakResult {
-    godmode::restore_backup(&backup_path)
+fn set_device_label(state: tauri::State<Arc<AppState>>, label: String) -> Result<Option<String>, MicrodiagError> {
+    let label = clean_device_label(&label);
+    if label.chars().count() > DEVICE_LABEL_MAX_CHARS {
+        return Err(MicrodiagError::InvalidInput(i18n::trf(
+            "device.label_too_long",
+            &[("max", DEVICE_LABEL_MAX_CHARS.to_string())],
+        )));
+    }
+    state.db.set_setting(DEVICE_LABEL_SETTING, &label)?;
+    Ok(if label.is_empty() { None } else { Some(label) })
 }
 
 #[tauri::command]
-async fn gm_install_rustdesk() -> godmode::RustDeskResult {
-    godmode::install_rustdesk().await
+async fn db_sync_scripts(state: tauri::State<'_, Arc<AppState>>) -> Result<usize, MicrodiagError> {
+    sync_scripts_from_supabase(&state.db).await
 }
 
 #[tauri::command]
-async fn gm_check_crystaldiskinfo() -> godmode::CrystalDiskInfoResult {
-    godmode::check_crystaldiskinfo().await
+async fn db_sync_settings(state: tauri::State<'_, Arc<AppState>>) -> Result<SettingsSyncReport, MicrodiagError> {
+    let device_token = state.device_token.lock().unwrap().clone();
+    sync_settings(&state.db, &device_token).await
 }
 
 #[tauri::command]
-async fn gm_install_crystaldiskinfo() -> godmode::TweakResult {
-    godmode::install_crystaldiskinfo().await
+async fn get_sync_status(state: tauri::State<'_, Arc<AppState>>) -> Result<SyncReport, MicrodiagError> {
+    Ok(get_sync_report(&state.db).await)
 }
 
 #[tauri::command]
-async fn gm_check_librehardwaremonitor() -> godmode::CrystalDiskInfoResult {
-    godmode::check_librehardwaremonitor().await
+fn reset_sync_queue_failures(state: tauri::State<Arc<AppState>>) -> Result<usize, MicrodiagError> {
+    state.db.reset_sync_queue_failures().map_err(MicrodiagError::from)
 }
 
 #[tauri::command]
-async fn gm_install_librehardwaremonitor() -> godmode::TweakResult {
-    godmode::install_librehardwaremonitor().await
+async fn db_check_online() -> Result<bool, MicrodiagError> {
+    Ok(check_online_status().await)
 }
 
 #[tauri::command]
-fn gm_get_all_temperatures() -> godmode::HardwareTemperatures {
-    godmode::get_all_temperatures()
+async fn db_check_remote_executions(state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<RemoteExecution>, MicrodiagError> {
+    let device_token = state.device_token.lock().unwrap().clone();
+    check_remote_executions(&state.db, &device_token).await
 }
 
 #[tauri::command]
-async fn gm_auto_setup_diagnostic_tools() -> godmode::DiagnosticToolsStatus {
-    godmode::auto_setup_diagnostic_tools().await
+async fn db_update_remote_execution(
+    id: String,
+    status: String,
+    output: Option<String>,
+    error: Option<String>,
+) -> Result<(), MicrodiagError> {
+    update_remote_execution(&id, &status, output.as_deref(), error.as_deref()).await
 }
 
 // ============================================
-// PREMIUM DIAGNOSTICS COMMANDS
+// AGENT SELF-UPDATE
 // ============================================
 
 #[tauri::command]
-fn run_premium_diagnostic(state: tauri::State<Arc<AppState>>) -> Result<diagnostics::PremiumDiagnostic, String> {
-    match state.system.lock() {
-        Ok(mut sys) => Ok(diagnostics::run_premium_diagnostic(&mut sys)),
-        Err(_) => Err("Failed to acquire system lock".to_string()),
-    }
+async fn check_agent_update() -> Result<agent_update::AgentUpdateInfo, String> {
+    agent_update::check_agent_update().await
 }
 
 #[tauri::command]
-fn get_temperatures() -> diagnostics::TemperatureInfo {
-    diagnostics::get_temperatures()
+async fn apply_agent_update() -> Result<String, String> {
+    let installer = agent_update::apply_agent_update().await?;
+    println!("[Update] Installer launched: {}", installer);
+
+    // Let the command return, then exit so the installer can replace the binary
+    std::thread::spawn(|| {
+        std::thread::sleep(Duration::from_millis(500));
+        std::process::exit(0);
+    });
+    Ok(installer)
 }
 
+// ============================================
+// GOD MODE COMMANDS (Native Performance)
+// ============================================
+
 #[tauri::command]
-fn get_process_analysis(state: tauri::State<Arc<AppState>>) -> Result<diagnostics::ProcessAnalysis, String> {
-    match state.system.lock() {
-        Ok(sys) => Ok(diagnostics::analyze_processes(&sys)),
-        Err(_) => Err("Failed to acquire system lock".to_string()),
-    }
+fn gm_get_installed_apps() -> Vec<godmode::InstalledApp> {
+    godmode::get_installed_apps_native()
 }
 
 #[tauri::command]
-fn get_network_analysis() -> diagnostics::NetworkAnalysis {
-    diagnostics::analyze_network()
+async fn gm_uninstall_app(uninstall_string: String, silent: bool) -> Result<godmode::TweakResult, String> {
+    tokio::task::spawn_blocking(move || godmode::uninstall_app(&uninstall_string, silent))
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-fn get_storage_analysis() -> diagnostics::StorageAnalysis {
-    diagnostics::analyze_storage()
+fn gm_get_deep_health(state: tauri::State<Arc<AppState>>) -> godmode::DeepHealth {
+    let health = godmode::get_deep_health();
+    record_smart_history(&state.db, &health.smart_disks);
+    health
 }
 
+/// Stores the current apps + startup entries as the reference for `gm_diff_installed_apps`
 #[tauri::command]
-async fn run_disk_benchmark(drive: String) -> diagnostics::DiskBenchmark {
-    // Run benchmark in a blocking task to avoid blocking the async runtime
-    tokio::task::spawn_blocking(move || {
-        diagnostics::run_disk_benchmark(&drive)
-    }).await.unwrap_or_else(|_| diagnostics::DiskBenchmark {
-        drive: "Error".into(),
-        seq_read_mbps: 0.0,
-        seq_write_mbps: 0.0,
-        rand_read_iops: 0,
-        rand_write_iops: 0,
-        rand_read_mbps: 0.0,
-        rand_write_mbps: 0.0,
-        latency_us: 0,
-        score: 0,
-        grade: "Error".into(),
-    })
+async fn gm_snapshot_installed_apps(state: tauri::State<'_, Arc<AppState>>) -> Result<godmode::AppsSnapshot, String> {
+    let snapshot = tokio::task::spawn_blocking(godmode::take_apps_snapshot)
+        .await
+        .map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(&snapshot).map_err(|e| e.to_string())?;
+    state.db.set_cache(APPS_SNAPSHOT_CACHE_KEY, &json, None).map_err(|e| e.to_string())?;
+    Ok(snapshot)
 }
 
 #[tauri::command]
-fn analyze_bsod() -> diagnostics::BsodAnalysis {
-    diagnostics::analyze_bsod_history()
+async fn gm_diff_installed_apps(state: tauri::State<'_, Arc<AppState>>) -> Result<godmode::AppsDiff, String> {
+    let json = state.db.get_cache(APPS_SNAPSHOT_CACHE_KEY)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Aucun instantane enregistre, prenez-en un d'abord".to_string())?;
+    let previous: godmode::AppsSnapshot = serde_json::from_str(&json)
+        .map_err(|e| format!("Instantane illisible: {}", e))?;
+    let current = tokio::task::spawn_blocking(godmode::take_apps_snapshot)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(godmode::diff_apps_snapshots(&previous, &current))
 }
 
 #[tauri::command]
-async fn run_speedtest() -> diagnostics::SpeedtestResult {
-    diagnostics::run_speedtest().await
+fn gm_get_startup_items() -> Vec<godmode::StartupItem> {
+    godmode::get_startup_items()
 }
 
 #[tauri::command]
-fn analyze_boot_time() -> diagnostics::BootAnalysis {
-    diagnostics::analyze_boot_time()
+fn gm_disable_startup_item(name: String, location: String) -> godmode::TweakResult {
+    godmode::disable_startup_item(&name, &location)
 }
 
 #[tauri::command]
-fn scan_cve() -> diagnostics::CveReport {
-    diagnostics::scan_cve_vulnerabilities()
+async fn gm_get_scheduled_startup_tasks() -> Result<Vec<godmode::ScheduledTask>, String> {
+    tokio::task::spawn_blocking(godmode::get_scheduled_startup_tasks)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn gm_disable_scheduled_task(path: String) -> godmode::TweakResult {
+    godmode::disable_scheduled_task(&path)
+}
+
+#[tauri::command]
+fn gm_enable_startup_item(name: String, command: String, location: String) -> godmode::TweakResult {
+    godmode::enable_startup_item(&name, &command, &location)
+}
+
+#[tauri::command]
+fn get_gpu_info() -> Option<godmode::GpuInfo> {
+    godmode::get_gpu_info()
+}
+
+#[tauri::command]
+async fn list_firewall_rules(direction: String) -> Result<Vec<FirewallRule>, String> {
+    tokio::task::spawn_blocking(move || security::list_firewall_rules(&direction))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_risky_open_ports() -> Result<Vec<RiskyFirewallRule>, String> {
+    tokio::task::spawn_blocking(security::get_risky_open_ports)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Autostart registry locations with a risk level (Run keys, Winlogon, IFEO...)
+#[tauri::command]
+async fn scan_persistence_points() -> Result<Vec<PersistenceEntry>, String> {
+    tokio::task::spawn_blocking(security::scan_persistence_points)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn generate_battery_report() -> Result<godmode::BatteryReport, String> {
+    tokio::task::spawn_blocking(godmode::generate_battery_report)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn list_power_plans() -> Result<Vec<godmode::PowerPlan>, String> {
+    tokio::task::spawn_blocking(godmode::list_power_plans)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_active_power_plan(guid: String) -> Result<godmode::TweakResult, String> {
+    tokio::task::spawn_blocking(move || godmode::set_active_power_plan(&guid))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// `mode`: safe_minimal, safe_network or normal; sticks for every boot until set back to normal
+#[tauri::command]
+async fn set_boot_mode(mode: String) -> Result<godmode::TweakResult, String> {
+    tokio::task::spawn_blocking(move || godmode::set_boot_mode(&mode))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_boot_logging(enabled: bool) -> Result<godmode::TweakResult, String> {
+    tokio::task::spawn_blocking(move || godmode::set_boot_logging(enabled))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn enable_ultimate_performance() -> Result<godmode::PowerPlan, String> {
+    tokio::task::spawn_blocking(godmode::enable_ultimate_performance)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn get_event_log(log_name: String, level: String, max_events: i32) -> Result<Vec<eventlog::EventEntry>, String> {
+    tokio::task::spawn_blocking(move || eventlog::get_event_log(&log_name, &level, max_events))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn list_services() -> Result<Vec<services::ServiceInfo>, MicrodiagError> {
+    tokio::task::spawn_blocking(services::list_services).await?
+}
+
+#[tauri::command]
+async fn set_service_state(name: String, action: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || services::set_service_state(&name, &action))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn set_service_start_type(name: String, mode: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || services::set_service_start_type(&name, &mode))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn gm_check_updates() -> Vec<godmode::OutdatedApp> {
+    godmode::check_winget_updates().await
+}
+
+#[tauri::command]
+async fn gm_install_apps(app_ids: Vec<String>) -> godmode::TweakResult {
+    godmode::install_winget_apps(app_ids).await
+}
+
+#[tauri::command]
+async fn gm_update_all() -> godmode::TweakResult {
+    godmode::update_all_winget().await
+}
+
+/// Same as gm_install_apps with each winget line sent as "winget-progress"
+#[tauri::command]
+async fn gm_install_apps_streaming(app: tauri::AppHandle, app_ids: Vec<String>) -> Result<godmode::TweakResult, String> {
+    use tauri::Emitter;
+
+    tokio::task::spawn_blocking(move || {
+        godmode::install_winget_apps_streaming(&app_ids, |progress| {
+            let _ = app.emit("winget-progress", &progress);
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Same as gm_update_all with each winget line sent as "winget-progress"
+#[tauri::command]
+async fn gm_update_all_streaming(app: tauri::AppHandle) -> Result<godmode::TweakResult, String> {
+    use tauri::Emitter;
+
+    tokio::task::spawn_blocking(move || {
+        godmode::update_all_winget_streaming(|progress| {
+            let _ = app.emit("winget-progress", &progress);
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn gm_apply_tweak(tweak_id: String, enable: bool) -> godmode::TweakResult {
+    godmode::apply_privacy_tweak(&tweak_id, enable)
+}
+
+#[tauri::command]
+fn gm_preview_tweak(tweak_id: String, enable: bool) -> Result<godmode::TweakPreview, String> {
+    godmode::preview_privacy_tweak(&tweak_id, enable)
+}
+
+#[tauri::command]
+fn gm_get_tweak_states() -> Vec<godmode::PrivacyTweakState> {
+    godmode::get_privacy_tweak_states()
+}
+
+#[tauri::command]
+async fn gm_ghost_mode(targets: Vec<String>) -> Vec<godmode::GhostModeResult> {
+    godmode::activate_ghost_mode(targets).await
+}
+
+#[tauri::command]
+async fn gm_clean_privacy_traces(targets: Vec<godmode::PrivacyTarget>) -> Result<Vec<godmode::PrivacyCleanResult>, String> {
+    tokio::task::spawn_blocking(move || godmode::clean_privacy_traces(&targets))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn gm_get_environment(scope: String) -> Result<godmode::EnvironmentInfo, String> {
+    godmode::get_environment(&scope)
+}
+
+#[tauri::command]
+fn gm_set_environment_var(scope: String, name: String, value: String) -> godmode::TweakResult {
+    godmode::set_environment_var(&scope, &name, &value)
+}
+
+#[tauri::command]
+fn gm_repair_path(scope: String) -> godmode::TweakResult {
+    godmode::repair_path(&scope)
+}
+
+#[tauri::command]
+fn gm_list_backups() -> Vec<godmode::RegBackup> {
+    godmode::list_backups()
+}
+
+#[tauri::command]
+fn gm_restore_backup(backup_path: String) -> godmode::TweakResult {
+    godmode::restore_backup(&backup_path)
+}
+
+#[tauri::command]
+fn gm_cleanup_backups(keep_days: i32, keep_per_prefix: i32) -> godmode::BackupCleanup {
+    godmode::cleanup_backups(keep_days, keep_per_prefix)
+}
+
+#[tauri::command]
+fn gm_restore_latest_backup(prefix: String) -> godmode::TweakResult {
+    godmode::restore_latest_backup(&prefix)
+}
+
+#[tauri::command]
+async fn gm_install_rustdesk() -> godmode::RustDeskResult {
+    godmode::install_rustdesk().await
+}
+
+#[tauri::command]
+async fn gm_uninstall_rustdesk() -> Result<godmode::RustDeskResult, String> {
+    tokio::task::spawn_blocking(godmode::uninstall_rustdesk)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn gm_rustdesk_status() -> godmode::RustDeskStatus {
+    godmode::rustdesk_status()
+}
+
+#[tauri::command]
+async fn gm_check_crystaldiskinfo() -> godmode::CrystalDiskInfoResult {
+    godmode::check_crystaldiskinfo().await
+}
+
+#[tauri::command]
+async fn gm_install_crystaldiskinfo() -> godmode::TweakResult {
+    godmode::install_crystaldiskinfo().await
+}
+
+#[tauri::command]
+async fn gm_check_librehardwaremonitor() -> godmode::CrystalDiskInfoResult {
+    godmode::check_librehardwaremonitor().await
+}
+
+#[tauri::command]
+async fn gm_install_librehardwaremonitor() -> godmode::TweakResult {
+    godmode::install_librehardwaremonitor().await
+}
+
+#[tauri::command]
+fn gm_get_all_temperatures() -> godmode::HardwareTemperatures {
+    godmode::get_all_temperatures()
+}
+
+#[tauri::command]
+fn gm_get_all_sensors() -> Vec<godmode::HardwareSensor> {
+    godmode::get_all_sensors()
+}
+
+#[tauri::command]
+async fn gm_auto_setup_diagnostic_tools() -> godmode::DiagnosticToolsStatus {
+    godmode::auto_setup_diagnostic_tools().await
+}
+
+#[tauri::command]
+async fn gm_list_tools(check_updates: bool) -> Result<Vec<tools::ToolStatus>, String> {
+    tokio::task::spawn_blocking(move || tools::list_tools(check_updates))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn gm_ensure_tool(id: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || tools::ensure_tool(&id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn gm_get_tool_version(id: String) -> Option<String> {
+    tools::tool_version(&id)
+}
+
+// ============================================
+// PREMIUM DIAGNOSTICS COMMANDS
+// ============================================
+
+fn load_suspicion_config(db: &Database) -> diagnostics::SuspicionConfig {
+    diagnostics::SuspicionConfig::from_setting(
+        db.get_setting(diagnostics::SuspicionConfig::SETTINGS_KEY).ok().flatten()
+    )
+}
+
+#[tauri::command]
+fn get_suspicion_config(state: tauri::State<Arc<AppState>>) -> diagnostics::SuspicionConfig {
+    load_suspicion_config(&state.db)
+}
+
+#[tauri::command]
+fn set_suspicion_config(state: tauri::State<Arc<AppState>>, config: diagnostics::SuspicionConfig) -> Result<(), String> {
+    let json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    state.db.set_setting(diagnostics::SuspicionConfig::SETTINGS_KEY, &json).map_err(|e| e.to_string())
+}
+
+fn load_scoring_config(db: &Database) -> ScoringConfig {
+    ScoringConfig::from_setting(db.get_setting(ScoringConfig::SETTINGS_KEY).ok().flatten())
+}
+
+#[tauri::command]
+fn get_scoring_config(state: tauri::State<Arc<AppState>>) -> ScoringConfig {
+    load_scoring_config(&state.db)
+}
+
+#[tauri::command]
+fn set_scoring_config(state: tauri::State<Arc<AppState>>, config: ScoringConfig) -> Result<(), String> {
+    let json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    state.db.set_setting(ScoringConfig::SETTINGS_KEY, &json).map_err(|e| e.to_string())
+}
+
+fn load_latency_config(db: &Database) -> diagnostics::LatencyConfig {
+    diagnostics::LatencyConfig::from_setting(
+        db.get_setting(diagnostics::LatencyConfig::SETTINGS_KEY).ok().flatten()
+    )
+}
+
+#[tauri::command]
+fn get_latency_config(state: tauri::State<Arc<AppState>>) -> diagnostics::LatencyConfig {
+    load_latency_config(&state.db)
+}
+
+#[tauri::command]
+fn set_latency_config(state: tauri::State<Arc<AppState>>, config: diagnostics::LatencyConfig) -> Result<(), String> {
+    let json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    state.db.set_setting(diagnostics::LatencyConfig::SETTINGS_KEY, &json).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn run_premium_diagnostic(state: tauri::State<'_, Arc<AppState>>) -> Result<diagnostics::PremiumDiagnostic, String> {
+    // Network lookup first, the system lock must not be held across an await
+    let public_info = diagnostics::fetch_public_ip_info().await;
+    // The scan blocks for seconds (WMI, PowerShell), keep it off the async runtime
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let suspicion = load_suspicion_config(&state.db);
+        let scoring = load_scoring_config(&state.db);
+        let latency = load_latency_config(&state.db);
+        let mut diagnostic = {
+            let mut sys = state.system.lock().map_err(|_| "Failed to acquire system lock".to_string())?;
+            diagnostics::run_premium_diagnostic(&mut sys, &suspicion, &scoring, &latency)
+        };
+        diagnostics::apply_public_ip_info(&mut diagnostic.network, public_info);
+        let scores = diagnostics::compute_scores(&diagnostic.temperatures, &diagnostic.processes, &diagnostic.network, &diagnostic.storage, &scoring);
+        record_diagnostic_history(&state.db, "manual", &scores);
+        if let Ok(mut last) = state.last_diagnostic.lock() {
+            *last = Some(diagnostic.clone());
+        }
+        Ok(diagnostic)
+    }).await.map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Lightweight scan for the dashboard; not recorded in the history or kept as last diagnostic
+#[tauri::command]
+fn run_quick_diagnostic(state: tauri::State<Arc<AppState>>) -> Result<diagnostics::QuickDiagnostic, String> {
+    let suspicion = load_suspicion_config(&state.db);
+    let scoring = load_scoring_config(&state.db);
+    match state.system.lock() {
+        Ok(mut sys) => Ok(diagnostics::run_quick_diagnostic(&mut sys, &suspicion, &scoring)),
+        Err(_) => Err("Failed to acquire system lock".to_string()),
+    }
+}
+
+#[tauri::command]
+fn db_get_diagnostic_history(state: tauri::State<Arc<AppState>>, days: i32) -> Result<Vec<DiagnosticHistoryEntry>, String> {
+    state.db.get_diagnostic_history(days).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn export_diagnostic_report(state: tauri::State<'_, Arc<AppState>>, format: String) -> Result<String, String> {
+    let cached = state.last_diagnostic.lock().ok().and_then(|last| last.clone());
+    let diagnostic = match cached {
+        Some(diagnostic) => diagnostic,
+        None => run_premium_diagnostic(state).await?,
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let report = report::DiagnosticReport::new(
+            diagnostic,
+            godmode::get_deep_health(),
+            diagnostics::analyze_bsod_history(),
+        );
+        report::export_report(&report, &format)
+    }).await.map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+fn get_temperatures() -> diagnostics::TemperatureInfo {
+    diagnostics::get_temperatures()
+}
+
+#[tauri::command]
+fn get_process_analysis(state: tauri::State<Arc<AppState>>) -> Result<diagnostics::ProcessAnalysis, String> {
+    let suspicion = load_suspicion_config(&state.db);
+    match state.system.lock() {
+        Ok(sys) => Ok(diagnostics::analyze_processes(&sys, &suspicion)),
+        Err(_) => Err("Failed to acquire system lock".to_string()),
+    }
+}
+
+#[tauri::command]
+fn kill_process(state: tauri::State<Arc<AppState>>, pid: u32) -> Result<(), String> {
+    let mut sys = state.system.lock().map_err(|_| "Failed to acquire system lock".to_string())?;
+    diagnostics::kill_process(&mut sys, pid)
+}
+
+#[tauri::command]
+fn suspend_process(state: tauri::State<Arc<AppState>>, pid: u32) -> Result<(), String> {
+    let mut sys = state.system.lock().map_err(|_| "Failed to acquire system lock".to_string())?;
+    diagnostics::suspend_process(&mut sys, pid)
+}
+
+#[tauri::command]
+fn resume_process(state: tauri::State<Arc<AppState>>, pid: u32) -> Result<(), String> {
+    let mut sys = state.system.lock().map_err(|_| "Failed to acquire system lock".to_string())?;
+    diagnostics::resume_process(&mut sys, pid)
+}
+
+#[tauri::command]
+fn set_process_priority(state: tauri::State<Arc<AppState>>, pid: u32, priority: String) -> Result<String, String> {
+    let mut sys = state.system.lock().map_err(|_| "Failed to acquire system lock".to_string())?;
+    diagnostics::set_process_priority(&mut sys, pid, &priority)
+}
+
+#[tauri::command]
+async fn get_network_analysis(state: tauri::State<'_, Arc<AppState>>) -> Result<diagnostics::NetworkAnalysis, String> {
+    let latency = load_latency_config(&state.db);
+    let public_info = diagnostics::fetch_public_ip_info().await;
+    let mut network = tokio::task::spawn_blocking(move || diagnostics::analyze_network(&latency))
+        .await
+        .map_err(|e| e.to_string())?;
+    diagnostics::apply_public_ip_info(&mut network, public_info);
+    Ok(network)
+}
+
+/// Live throughput per interface over `duration_ms` (default 1s)
+#[tauri::command]
+async fn measure_bandwidth(duration_ms: Option<u64>) -> Result<Vec<diagnostics::BandwidthSample>, String> {
+    let duration_ms = duration_ms.unwrap_or(1000);
+    tokio::task::spawn_blocking(move || diagnostics::measure_bandwidth(duration_ms))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Per-core usage and frequency, sampled over ~200ms
+#[tauri::command]
+async fn get_cpu_cores(state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<CoreInfo>, String> {
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let mut sys = state.system.lock().map_err(|_| "Failed to acquire system lock".to_string())?;
+        Ok(metrics::get_cpu_cores(&mut sys))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Which process talks to the network (established, non-system connections first)
+#[tauri::command]
+async fn get_network_connections(state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<diagnostics::NetworkConnection>, String> {
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        // The PowerShell query takes seconds, only the PID lookup needs the lock
+        let connections = diagnostics::list_network_connections();
+        let mut sys = state.system.lock().map_err(|_| "Failed to acquire system lock".to_string())?;
+        Ok(diagnostics::resolve_connection_processes(&mut sys, connections))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// `probe_speed` adds a short read/write test per local drive
+#[tauri::command]
+async fn get_storage_analysis(probe_speed: Option<bool>) -> Result<diagnostics::StorageAnalysis, String> {
+    let probe_speed = probe_speed.unwrap_or(false);
+    tokio::task::spawn_blocking(move || diagnostics::analyze_storage(probe_speed))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn scan_largest_files(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<AppState>>,
+    root: String,
+    top_n: usize,
+) -> Result<Vec<diagnostics::LargeFile>, String> {
+    use tauri::Emitter;
+
+    let state = state.inner().clone();
+    state.storage_scan_cancel.store(false, Ordering::SeqCst);
+
+    tokio::task::spawn_blocking(move || {
+        diagnostics::scan_largest_files(&root, top_n.clamp(1, 500), &state.storage_scan_cancel, |files_scanned, current| {
+            let _ = app.emit("storage-scan-progress", serde_json::json!({
+                "files_scanned": files_scanned,
+                "current": current,
+            }));
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn cancel_storage_scan(state: tauri::State<Arc<AppState>>) {
+    state.storage_scan_cancel.store(true, Ordering::SeqCst);
+}
+
+#[tauri::command]
+async fn run_disk_benchmark(drive: String) -> diagnostics::DiskBenchmark {
+    // Run benchmark in a blocking task to avoid blocking the async runtime
+    tokio::task::spawn_blocking(move || {
+        diagnostics::run_disk_benchmark(&drive)
+    }).await.unwrap_or_else(|_| diagnostics::DiskBenchmark {
+        drive: "Error".into(),
+        seq_read_mbps: 0.0,
+        seq_write_mbps: 0.0,
+        rand_read_iops: 0,
+        rand_write_iops: 0,
+        rand_read_mbps: 0.0,
+        rand_write_mbps: 0.0,
+        queue_depth: 0,
+        rand_read_iops_qd: 0,
+        rand_write_iops_qd: 0,
+        rand_read_mbps_qd: 0.0,
+        rand_write_mbps_qd: 0.0,
+        latency_us: 0,
+        score: 0,
+        grade: "Error".into(),
+    })
+}
+
+#[tauri::command]
+async fn run_disk_benchmark_compare(drive_a: String, drive_b: String, size_mb: Option<u32>) -> Result<diagnostics::BenchmarkComparison, String> {
+    tokio::task::spawn_blocking(move || {
+        diagnostics::run_disk_benchmark_compare(&drive_a, &drive_b, size_mb)
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn analyze_bsod() -> diagnostics::BsodAnalysis {
+    diagnostics::analyze_bsod_history()
+}
+
+#[tauri::command]
+fn get_reliability_index() -> diagnostics::ReliabilityIndex {
+    diagnostics::get_reliability_index()
+}
+
+#[tauri::command]
+fn get_reliability_events(days: u32) -> Vec<diagnostics::ReliabilityEvent> {
+    diagnostics::get_reliability_events(days)
+}
+
+#[tauri::command]
+async fn run_speedtest(streams: Option<u32>) -> diagnostics::SpeedtestResult {
+    diagnostics::run_speedtest(streams.unwrap_or(diagnostics::SPEEDTEST_DEFAULT_STREAMS)).await
+}
+
+#[tauri::command]
+fn analyze_boot_time() -> diagnostics::BootAnalysis {
+    diagnostics::analyze_boot_time()
+}
+
+#[tauri::command]
+fn scan_cve() -> diagnostics::CveReport {
+    diagnostics::scan_cve_vulnerabilities()
+}
+
+#[tauri::command]
+async fn scan_cve_online(state: tauri::State<'_, Arc<AppState>>) -> Result<diagnostics::CveReport, String> {
+    Ok(diagnostics::scan_cve_online(&state.db).await)
+}
+
+#[tauri::command]
+fn predict_failures(state: tauri::State<Arc<AppState>>) -> diagnostics::FailurePrediction {
+    let trends: Vec<diagnostics::SmartTrend> = state.db.get_smart_serials()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|serial| {
+            let points = state.db.get_smart_history(&serial, 30).unwrap_or_default();
+            diagnostics::analyze_smart_trend(&serial, points)
+        })
+        .collect();
+    diagnostics::predict_failures(&trends)
+}
+
+#[tauri::command]
+async fn schedule_memory_test(reboot_now: bool) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || diagnostics::schedule_memory_test(reboot_now))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn get_memory_test_result() -> Result<diagnostics::MemoryTestResult, String> {
+    tokio::task::spawn_blocking(diagnostics::get_memory_test_result)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn run_smart_self_test(device_id: String, kind: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || godmode::run_smart_self_test(&device_id, &kind))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn get_smart_self_test_status(device_id: String) -> Result<godmode::SmartSelfTestStatus, String> {
+    tokio::task::spawn_blocking(move || godmode::get_smart_self_test_status(&device_id))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
-fn predict_failures() -> diagnostics::FailurePrediction {
-    diagnostics::predict_failures()
+fn get_smart_trend(state: tauri::State<Arc<AppState>>, serial: String) -> Result<diagnostics::SmartTrend, String> {
+    let points = state.db.get_smart_history(&serial, 30).map_err(|e| e.to_string())?;
+    Ok(diagnostics::analyze_smart_trend(&serial, points))
 }
 
 // ============================================
@@ -641,20 +1744,407 @@ async fn fw_execute_fix(app: tauri::AppHandle, fix_id: String) -> Result<fixwin:
     Ok(result)
 }
 
+/// Fixes in order with "fix-step-start"/"fix-step-done" per id, then one combined report
+#[tauri::command]
+async fn fw_execute_fixes(app: tauri::AppHandle, fix_ids: Vec<String>) -> Result<fixwin::FixResult, String> {
+    use tauri::Emitter;
+
+    let emitter = app.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        fixwin::execute_fixes(&fix_ids, |event| match event {
+            fixwin::BatchEvent::StepStart { fix_id, index, total } => {
+                let _ = emitter.emit("fix-step-start", serde_json::json!({
+                    "fix_id": fix_id,
+                    "index": index,
+                    "total": total,
+                }));
+            }
+            fixwin::BatchEvent::Output { fix_id, output } => {
+                let _ = emitter.emit("fixwin-output", serde_json::json!({
+                    "fix_id": fix_id,
+                    "line": output.line,
+                    "line_type": output.line_type,
+                    "progress": output.progress,
+                }));
+            }
+            fixwin::BatchEvent::StepDone { fix_id, index, total, result } => {
+                let _ = emitter.emit("fix-step-done", serde_json::json!({
+                    "fix_id": fix_id,
+                    "index": index,
+                    "total": total,
+                    "success": result.success,
+                    "message": &result.message,
+                    "requires_reboot": result.requires_reboot,
+                }));
+            }
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let _ = app.emit("fixwin-complete", serde_json::json!({
+        "fix_id": "batch",
+        "success": result.success,
+        "message": &result.message,
+        "requires_reboot": result.requires_reboot,
+    }));
+
+    Ok(result)
+}
+
+/// TRIM or defrag of one drive, streamed as the "optimize_drive" fix
+#[tauri::command]
+async fn fw_optimize_drive(app: tauri::AppHandle, letter: String) -> Result<fixwin::FixResult, String> {
+    use tauri::Emitter;
+
+    let emitter = app.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        fixwin::optimize_drive(&letter, |output| {
+            let _ = emitter.emit("fixwin-output", serde_json::json!({
+                "fix_id": "optimize_drive",
+                "line": output.line,
+                "line_type": output.line_type,
+                "progress": output.progress,
+            }));
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let _ = app.emit("fixwin-complete", serde_json::json!({
+        "fix_id": "optimize_drive",
+        "success": result.success,
+        "message": &result.message,
+        "requires_reboot": result.requires_reboot,
+    }));
+
+    Ok(result)
+}
+
+/// Bounce one adapter, streamed as the "reset_network_adapter" fix
+#[tauri::command]
+async fn fw_cycle_network_adapter(app: tauri::AppHandle, name: String) -> Result<fixwin::FixResult, String> {
+    use tauri::Emitter;
+
+    let emitter = app.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        fixwin::cycle_network_adapter(&name, |output| {
+            let _ = emitter.emit("fixwin-output", serde_json::json!({
+                "fix_id": "reset_network_adapter",
+                "line": output.line,
+                "line_type": output.line_type,
+                "progress": output.progress,
+            }));
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let _ = app.emit("fixwin-complete", serde_json::json!({
+        "fix_id": "reset_network_adapter",
+        "success": result.success,
+        "message": &result.message,
+        "requires_reboot": result.requires_reboot,
+    }));
+
+    Ok(result)
+}
+
+/// Safe fixes in sequence, combined progress via "fixwin-maintenance-progress"
+#[tauri::command]
+async fn fw_run_auto_maintenance(app: tauri::AppHandle) -> Result<fixwin::FixResult, String> {
+    use tauri::Emitter;
+
+    let emitter = app.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        fixwin::run_auto_maintenance(|progress| {
+            let _ = emitter.emit("fixwin-maintenance-progress", &progress);
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let _ = app.emit("fixwin-complete", serde_json::json!({
+        "fix_id": "auto_maintenance",
+        "success": result.success,
+        "message": &result.message,
+        "requires_reboot": result.requires_reboot,
+    }));
+
+    Ok(result)
+}
+
+#[tauri::command]
+fn fw_get_repair_log_summary() -> fixwin::RepairLogSummary {
+    fixwin::get_repair_log_summary()
+}
+
 #[tauri::command]
 fn fw_create_restore_point() -> fixwin::FixResult {
     fixwin::fix_create_restore_point(|_| {})
 }
 
+#[tauri::command]
+async fn fw_list_restore_points() -> Result<Vec<fixwin::RestorePoint>, String> {
+    tokio::task::spawn_blocking(fixwin::list_restore_points)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn fw_restore_to_point(sequence: u32) -> Result<fixwin::FixResult, String> {
+    tokio::task::spawn_blocking(move || fixwin::restore_to_point(sequence))
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ============================================
-// HEARTBEAT
+// SMART HISTORY
 // ============================================
-async fn send_heartbeat(device_token: &str, metrics: &SystemMetrics, health: &HealthScore, security: &SecurityStatus, deep_health: &godmode::DeepHealth) -> Result<(), String> {
-    let client = reqwest::Client::new();
+fn smart_snapshot(disk: &godmode::SmartDiskInfo) -> Option<SmartHistoryEntry> {
+    // Serial is the stable key; fall back to model when the firmware hides it
+    let serial = if disk.serial.is_empty() { disk.model.clone() } else { disk.serial.clone() };
+    if serial.is_empty() {
+        return None;
+    }
+    Some(SmartHistoryEntry {
+        id: None,
+        serial,
+        model: Some(disk.model.clone()),
+        timestamp: String::new(),
+        reallocated_sectors: disk.reallocated_sectors.map(|v| v as i64),
+        pending_sectors: disk.pending_sectors.map(|v| v as i64),
+        uncorrectable_sectors: disk.uncorrectable_errors.map(|v| v as i64),
+        temperature_c: disk.temperature_c.map(|v| v as i64),
+        power_on_hours: disk.power_on_hours.map(|v| v as i64),
+        percentage_used: disk.percentage_used.map(|v| v as i64),
+    })
+}
+
+fn record_smart_history(db: &Database, disks: &[godmode::SmartDiskInfo]) {
+    for entry in disks.iter().filter_map(smart_snapshot) {
+        if let Err(e) = db.save_smart_snapshot(&entry) {
+            println!("[SMART] Failed to record history: {}", e);
+        }
+    }
+}
+
+/// A drive whose reallocated or pending sector count went up since the previous heartbeat
+struct SmartDegradation {
+    serial: String,
+    model: String,
+    reallocated: (i64, i64),
+    pending: (i64, i64),
+}
+
+/// Counts the heartbeat last compared per drive. Kept in memory because the deep health
+/// command also records snapshots: comparing against the newest row would miss an increase
+/// it stored between two heartbeats. Seeded from smart_history after a restart.
+#[derive(Default)]
+struct SmartAlertTracker {
+    last: HashMap<String, SmartHistoryEntry>,
+}
+
+impl SmartAlertTracker {
+    /// Call before recording the new snapshots so the seed is the previous sample
+    fn check(&mut self, db: &Database, disks: &[godmode::SmartDiskInfo]) -> Vec<SmartDegradation> {
+        let mut degradations = Vec::new();
+
+        for current in disks.iter().filter_map(smart_snapshot) {
+            let previous = match self.last.get(&current.serial) {
+                Some(entry) => Some(entry.clone()),
+                None => db.get_last_smart_snapshot(&current.serial).ok().flatten(),
+            };
+
+            if let Some(previous) = previous {
+                let counts = |get: fn(&SmartHistoryEntry) -> Option<i64>| match (get(&previous), get(&current)) {
+                    (Some(before), Some(now)) => (before, now),
+                    _ => (0, 0),
+                };
+                let reallocated = counts(|e| e.reallocated_sectors);
+                let pending = counts(|e| e.pending_sectors);
+                if reallocated.1 > reallocated.0 || pending.1 > pending.0 {
+                    degradations.push(SmartDegradation {
+                        serial: current.serial.clone(),
+                        model: current.model.clone().unwrap_or_default(),
+                        reallocated,
+                        pending,
+                    });
+                }
+            }
+
+            self.last.insert(current.serial.clone(), current);
+        }
+
+        degradations
+    }
+}
+
+async fn send_smart_alert(app: &AppHandle, device_token: &str, degradation: &SmartDegradation) {
+    let args = [
+        ("model", degradation.model.clone()),
+        ("reallocated", degradation.reallocated.1.to_string()),
+        ("pending", degradation.pending.1.to_string()),
+    ];
+    let body = i18n::trf("alert.smart.body", &args);
+    if let Err(e) = send_notification(app.clone(), i18n::tr("alert.smart.title"), body.clone()) {
+        println!("[SMART] Notification failed: {}", e);
+    }
+
+    let log = SecurityLog {
+        severity: "critical".to_string(),
+        category: "smart".to_string(),
+        message: body,
+        details: serde_json::json!({
+            "serial": degradation.serial,
+            "model": degradation.model,
+            "reallocated_before": degradation.reallocated.0,
+            "reallocated_now": degradation.reallocated.1,
+            "pending_before": degradation.pending.0,
+            "pending_now": degradation.pending.1,
+        }),
+    };
+    let _ = send_security_log(device_token, &log).await;
+}
+
+// ============================================
+// TEMPERATURE HISTORY
+// ============================================
+fn record_temperature_history(db: &Database, temps: &godmode::HardwareTemperatures) {
+    let reading = |name: &str, sensor_type: &str, value: f32| TemperatureReading {
+        id: None,
+        timestamp: String::new(),
+        sensor_name: name.to_string(),
+        sensor_type: sensor_type.to_string(),
+        value,
+    };
+
+    let mut readings: Vec<TemperatureReading> = temps.sensors.iter()
+        .map(|s| reading(&s.name, &s.sensor_type, s.value))
+        .collect();
+
+    // Native WMI fallback only fills the summary fields
+    if readings.is_empty() {
+        readings.extend(temps.cpu_temp.map(|v| reading("CPU", "CPU", v)));
+        readings.extend(temps.gpu_temp.map(|v| reading("GPU", "GPU", v)));
+        readings.extend(temps.disk_temps.iter().map(|(name, v)| reading(name, "Disk", *v)));
+    }
+
+    if readings.is_empty() {
+        return;
+    }
+    if let Err(e) = db.save_temperature_readings(&readings) {
+        println!("[Temp] Failed to record history: {}", e);
+    }
+}
+
+// ============================================
+// THERMAL ALERTS
+// ============================================
+struct ThermalThresholds {
+    cpu: f32,
+    gpu: f32,
+    disk: f32,
+}
+
+fn load_thermal_thresholds(db: &Database) -> ThermalThresholds {
+    let read = |key: &str, default: f32| db.get_setting(key)
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<f32>().ok())
+        .filter(|v| *v > 0.0)
+        .unwrap_or(default);
+
+    ThermalThresholds {
+        cpu: read(THERMAL_CPU_THRESHOLD_SETTING, THERMAL_CPU_THRESHOLD_C),
+        gpu: read(THERMAL_GPU_THRESHOLD_SETTING, THERMAL_GPU_THRESHOLD_C),
+        disk: read(THERMAL_DISK_THRESHOLD_SETTING, THERMAL_DISK_THRESHOLD_C),
+    }
+}
+
+#[derive(Default)]
+struct SensorAlertState {
+    consecutive: u32,
+    last_alert: Option<std::time::Instant>,
+}
+
+/// Per-sensor debounce: a sensor alerts once it stayed above its threshold for
+/// THERMAL_ALERT_CONSECUTIVE_SAMPLES heartbeats, then not again before the cooldown
+#[derive(Default)]
+struct ThermalAlertTracker {
+    sensors: HashMap<String, SensorAlertState>,
+}
+
+impl ThermalAlertTracker {
+    /// Records one sample, true when it should raise an alert
+    fn observe(&mut self, sensor: &str, value: f32, threshold: f32, now: std::time::Instant) -> bool {
+        let state = self.sensors.entry(sensor.to_string()).or_default();
+        if value < threshold {
+            state.consecutive = 0;
+            return false;
+        }
 
+        state.consecutive += 1;
+        let cooled_down = state.last_alert
+            .map(|t| now.duration_since(t) >= Duration::from_secs(THERMAL_ALERT_COOLDOWN_SECS))
+            .unwrap_or(true);
+        if state.consecutive >= THERMAL_ALERT_CONSECUTIVE_SAMPLES && cooled_down {
+            state.last_alert = Some(now);
+            return true;
+        }
+        false
+    }
+}
+
+/// (sensor, value, threshold) for every sampled temperature that must be reported now
+fn check_thermal_alerts(
+    tracker: &mut ThermalAlertTracker,
+    temps: &godmode::HardwareTemperatures,
+    thresholds: &ThermalThresholds,
+) -> Vec<(String, f32, f32)> {
+    let mut samples: Vec<(String, f32, f32)> = Vec::new();
+    samples.extend(temps.cpu_temp.map(|v| ("CPU".to_string(), v, thresholds.cpu)));
+    samples.extend(temps.gpu_temp.map(|v| ("GPU".to_string(), v, thresholds.gpu)));
+    samples.extend(temps.disk_temps.iter().map(|(name, v)| (name.clone(), *v, thresholds.disk)));
+
+    let now = std::time::Instant::now();
+    samples.into_iter()
+        .filter(|(sensor, value, threshold)| tracker.observe(sensor, *value, *threshold, now))
+        .collect()
+}
+
+async fn send_thermal_alert(app: &AppHandle, device_token: &str, sensor: &str, value: f32, threshold: f32) {
+    let args = [
+        ("sensor", sensor.to_string()),
+        ("value", format!("{:.0}", value)),
+        ("threshold", format!("{:.0}", threshold)),
+    ];
+    let body = i18n::trf("alert.thermal.body", &args);
+    if let Err(e) = send_notification(app.clone(), i18n::tr("alert.thermal.title"), body.clone()) {
+        println!("[Thermal] Notification failed: {}", e);
+    }
+
+    let log = SecurityLog {
+        severity: "warning".to_string(),
+        category: "thermal".to_string(),
+        message: body,
+        details: serde_json::json!({
+            "sensor": sensor,
+            "temperature_c": value,
+            "threshold_c": threshold,
+        }),
+    };
+    let _ = send_security_log(device_token, &log).await;
+}
+
+// ============================================
+// HEARTBEAT
+// ============================================
+async fn send_heartbeat(db: &Database, device_token: &str, metrics: &SystemMetrics, health: &HealthScore, security: &SecurityStatus, deep_health: &godmode::DeepHealth) -> Result<(), String> {
     let payload = HeartbeatPayload {
         device_token: device_token.to_string(),
         hostname: metrics.hostname.clone(),
+        device_label: load_device_label(db),
         os_type: "windows".to_string(),
         os_version: metrics.os_version.clone(),
         status: health.status.clone(),
@@ -673,6 +2163,8 @@ async fn send_heartbeat(device_token: &str, metrics: &SystemMetrics, health: &He
             "realtime": security.realtime_protection,
             "firewall": security.firewall_enabled,
             "issues": security.issues,
+            "pending_reboot": security.pending_reboot.pending,
+            "reboot_reasons": security.pending_reboot.reasons,
         }),
         deep_health: serde_json::json!({
             "disk_model": deep_health.disk_model,
@@ -688,24 +2180,26 @@ async fn send_heartbeat(device_token: &str, metrics: &SystemMetrics, health: &He
             }
         }),
         agent_version: AGENT_VERSION.to_string(),
+        recorded_at: chrono::Utc::now().to_rfc3339(),
     };
+    let payload = serde_json::to_value(&payload).map_err(|e| e.to_string())?;
 
-    let response = client
-        .post(format!("{}/functions/v1/heartbeat", SUPABASE_URL))
-        .header("Authorization", format!("Bearer {}", SUPABASE_ANON_KEY))
-        .header("Content-Type", "application/json")
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| format!("Network error: {}", e))?;
-
-    if response.status().is_success() {
-        println!("[Heartbeat] OK");
-        Ok(())
-    } else {
-        let err = response.text().await.unwrap_or_default();
-        println!("[Heartbeat] Error: {}", err);
-        Err(err)
+    match post_heartbeat(&payload).await {
+        Ok(()) => {
+            println!("[Heartbeat] OK");
+            Ok(())
+        }
+        Err(HeartbeatError::Offline(err)) => {
+            // Keep the tick for later, the sync loop replays it once back online
+            println!("[Heartbeat] Offline, queued: {}", err);
+            db.add_to_sync_queue(HEARTBEAT_QUEUE_TABLE, "insert", &payload.to_string())
+                .map_err(|e| e.to_string())?;
+            Err(err)
+        }
+        Err(HeartbeatError::Rejected(err)) => {
+            println!("[Heartbeat] Error: {}", err);
+            Err(err)
+        }
     }
 }
 
@@ -755,12 +2249,42 @@ async fn check_pending_commands(device_token: &str) -> Vec<AgentCommand> {
 // ============================================
 // BACKGROUND LOOPS
 // ============================================
+/// Heartbeat interval from settings, clamped; the compile-time default when missing or invalid
+fn load_heartbeat_interval(db: &Database) -> u64 {
+    db.get_setting(HEARTBEAT_INTERVAL_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(|secs| secs.clamp(HEARTBEAT_INTERVAL_MIN_SECS, HEARTBEAT_INTERVAL_MAX_SECS))
+        .unwrap_or(HEARTBEAT_INTERVAL_SECS)
+}
+
+/// Sleeps until the next heartbeat is due, re-reading the interval so a new value applies right away
+async fn wait_for_next_heartbeat(db: &Database) {
+    let started = std::time::Instant::now();
+    loop {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        if started.elapsed() >= Duration::from_secs(load_heartbeat_interval(db)) {
+            return;
+        }
+    }
+}
+
 fn start_heartbeat_loop(app_handle: AppHandle, state: Arc<AppState>) {
     tauri::async_runtime::spawn(async move {
-        let mut ticker = interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
+        println!("[Heartbeat] Interval: {}s", load_heartbeat_interval(&state.db));
+        let mut first_tick = true;
+        let mut last_firewall_check: Option<std::time::Instant> = None;
+        let mut thermal_alerts = ThermalAlertTracker::default();
+        let mut reported_firewall = ReportedFindings::default();
+        let mut reported_persistence = ReportedFindings::default();
+        let mut smart_alerts = SmartAlertTracker::default();
 
         loop {
-            ticker.tick().await;
+            if !first_tick {
+                wait_for_next_heartbeat(&state.db).await;
+            }
+            first_tick = false;
 
             let running = *state.heartbeat_running.lock().unwrap();
             if !running { continue; }
@@ -770,19 +2294,51 @@ fn start_heartbeat_loop(app_handle: AppHandle, state: Arc<AppState>) {
                 let mut sys = state.system.lock().unwrap();
                 SystemMetrics::collect(&mut sys)
             };
-            let health = metrics.calculate_health();
+            let health = metrics.calculate_health(&load_scoring_config(&state.db));
             let security = SecurityStatus::check();
             let deep_health = godmode::get_deep_health();
+            let smart_degradations = smart_alerts.check(&state.db, &deep_health.smart_disks);
+            record_smart_history(&state.db, &deep_health.smart_disks);
+            let temperatures = godmode::get_all_temperatures();
+            record_temperature_history(&state.db, &temperatures);
             let device_token = state.device_token.lock().unwrap().clone();
 
+            let thresholds = load_thermal_thresholds(&state.db);
+            for (sensor, value, threshold) in check_thermal_alerts(&mut thermal_alerts, &temperatures, &thresholds) {
+                send_thermal_alert(&app_handle, &device_token, &sensor, value, threshold).await;
+            }
+            for degradation in &smart_degradations {
+                println!("[SMART] {} degrading: reallocated {:?}, pending {:?}", degradation.serial, degradation.reallocated, degradation.pending);
+                send_smart_alert(&app_handle, &device_token, degradation).await;
+            }
+
             // Send heartbeat with deep health info
-            let _ = send_heartbeat(&device_token, &metrics, &health, &security, &deep_health).await;
+            let _ = send_heartbeat(&state.db, &device_token, &metrics, &health, &security, &deep_health).await;
 
             // Log security issues
             if let Some(log) = SecurityLog::from_status(&security) {
                 let _ = send_security_log(&device_token, &log).await;
             }
 
+            // Firewall rules are slow to enumerate, check them a few times a day
+            let firewall_due = last_firewall_check
+                .map(|t| t.elapsed() >= Duration::from_secs(FIREWALL_CHECK_INTERVAL_SECS))
+                .unwrap_or(true);
+            if firewall_due {
+                last_firewall_check = Some(std::time::Instant::now());
+                let risky = tokio::task::spawn_blocking(security::get_risky_open_ports).await.unwrap_or_default();
+                let risky = reported_firewall.fresh(risky, RiskyFirewallRule::report_key);
+                for log in SecurityLog::from_firewall_rules(&risky) {
+                    let _ = send_security_log(&device_token, &log).await;
+                }
+
+                let persistence = tokio::task::spawn_blocking(security::scan_persistence_points).await.unwrap_or_default();
+                let persistence = reported_persistence.fresh(persistence, PersistenceEntry::report_key);
+                for log in SecurityLog::from_persistence_points(&persistence) {
+                    let _ = send_security_log(&device_token, &log).await;
+                }
+            }
+
             // Emit critical events
             if health.status == "critical" || security.is_critical() {
                 if let Some(window) = app_handle.get_webview_window("main") {
@@ -796,6 +2352,177 @@ fn start_heartbeat_loop(app_handle: AppHandle, state: Arc<AppState>) {
     });
 }
 
+fn record_diagnostic_history(db: &Database, source: &str, scores: &diagnostics::DiagnosticScores) {
+    let entry = DiagnosticHistoryEntry {
+        id: None,
+        timestamp: String::new(),
+        source: source.to_string(),
+        overall_score: scores.overall as i64,
+        temperature_score: scores.temperature as i64,
+        process_score: scores.processes as i64,
+        storage_score: scores.storage as i64,
+        network_score: scores.network as i64,
+        score_delta: None,
+        is_regression: false,
+    };
+    if let Err(e) = db.save_diagnostic_history(&entry) {
+        println!("[Diagnostic] Failed to record history: {}", e);
+    }
+}
+
+/// Scheduled diagnostic interval in hours, 0 when disabled
+fn load_diagnostic_interval(db: &Database) -> u64 {
+    db.get_setting(DIAGNOSTIC_INTERVAL_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(|hours| hours.min(DIAGNOSTIC_INTERVAL_MAX_HOURS))
+        .unwrap_or(DIAGNOSTIC_INTERVAL_HOURS)
+}
+
+/// Why a due scheduled run must wait, None when it can start now
+fn diagnostic_blocker() -> Option<&'static str> {
+    if diagnostics::heavy_task_running() {
+        return Some("benchmark running");
+    }
+    match godmode::get_power_status() {
+        Some((true, charge)) if charge < DIAGNOSTIC_MIN_BATTERY_PERCENT => Some("battery low"),
+        _ => None,
+    }
+}
+
+fn start_diagnostic_schedule_loop(state: Arc<AppState>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(DIAGNOSTIC_CHECK_INTERVAL_SECS));
+        // Leave startup alone, the first check happens one interval later
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+
+            let hours = load_diagnostic_interval(&state.db);
+            if hours == 0 {
+                continue;
+            }
+            let due = state.db.get_last_diagnostic_age_secs("scheduled")
+                .ok()
+                .flatten()
+                .map(|age| age >= (hours * 3600) as i64)
+                .unwrap_or(true);
+            if !due {
+                continue;
+            }
+            if let Some(reason) = diagnostic_blocker() {
+                println!("[Diagnostic] Scheduled run postponed: {}", reason);
+                continue;
+            }
+
+            let suspicion = load_suspicion_config(&state.db);
+            let scoring = load_scoring_config(&state.db);
+            let latency = load_latency_config(&state.db);
+            let run_state = Arc::clone(&state);
+            let scores = tokio::task::spawn_blocking(move || {
+                run_state.system.lock().ok().map(|mut sys| diagnostics::run_light_diagnostic(&mut sys, &suspicion, &scoring, &latency))
+            }).await.ok().flatten();
+
+            if let Some(scores) = scores {
+                println!("[Diagnostic] Scheduled run: {}/100", scores.overall);
+                record_diagnostic_history(&state.db, "scheduled", &scores);
+            }
+        }
+    });
+}
+
+// ============================================
+// TRAY STATUS
+// ============================================
+
+/// Tray menu entries whose label follows the agent state
+struct TrayItems {
+    status: MenuItem<tauri::Wry>,
+    heartbeat_toggle: MenuItem<tauri::Wry>,
+    last_report: Mutex<Option<SyncReport>>,
+}
+
+fn tray_status_label(report: Option<&SyncReport>, heartbeat_running: bool) -> String {
+    let mut label = match report {
+        Some(r) if r.online => "Status: En ligne".to_string(),
+        Some(_) => "Status: Hors ligne".to_string(),
+        None => "Status: Verification...".to_string(),
+    };
+    if !heartbeat_running {
+        label.push_str(" - heartbeat en pause");
+    }
+    if let Some(r) = report.filter(|r| r.queue_depth > 0) {
+        label.push_str(&format!(" - {} envoi(s) en attente", r.queue_depth));
+    }
+    label
+}
+
+fn heartbeat_toggle_label(heartbeat_running: bool) -> &'static str {
+    if heartbeat_running { "Suspendre le heartbeat" } else { "Reprendre le heartbeat" }
+}
+
+fn refresh_tray(app: &AppHandle) {
+    let (items, state) = match (app.try_state::<TrayItems>(), app.try_state::<Arc<AppState>>()) {
+        (Some(items), Some(state)) => (items, state),
+        _ => return,
+    };
+    let running = *state.heartbeat_running.lock().unwrap();
+    let report = items.last_report.lock().unwrap().clone();
+    let _ = items.status.set_text(tray_status_label(report.as_ref(), running));
+    let _ = items.heartbeat_toggle.set_text(heartbeat_toggle_label(running));
+}
+
+fn toggle_heartbeat(app: &AppHandle) {
+    if let Some(state) = app.try_state::<Arc<AppState>>() {
+        let mut running = state.heartbeat_running.lock().unwrap();
+        *running = !*running;
+        println!("[Heartbeat] {}", if *running { "Resumed from tray" } else { "Paused from tray" });
+    }
+    refresh_tray(app);
+}
+
+/// Emits `sync-status-changed` and updates the tray when the sync state moves (offline, pending, synced...)
+fn start_sync_status_watch(app_handle: AppHandle, db: Arc<Database>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(SYNC_STATUS_CHECK_INTERVAL_SECS));
+        let mut previous: Option<SyncReport> = None;
+
+        loop {
+            ticker.tick().await;
+
+            let report = get_sync_report(&db).await;
+            if previous.as_ref().map(|p| p.same_state(&report)).unwrap_or(false) {
+                continue;
+            }
+            println!("[Sync] Status: online={}, queue={}", report.online, report.queue_depth);
+            let _ = app_handle.emit("sync-status-changed", &report);
+            if let Some(items) = app_handle.try_state::<TrayItems>() {
+                *items.last_report.lock().unwrap() = Some(report.clone());
+            }
+            refresh_tray(&app_handle);
+            previous = Some(report);
+        }
+    });
+}
+
+/// Emits `update-available` once after startup when a newer release is published; nothing is downloaded
+fn start_update_check(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(UPDATE_CHECK_DELAY_SECS)).await;
+
+        match agent_update::check_agent_update().await {
+            Ok(info) if info.update_available => {
+                println!("[Update] Version {} available (mandatory: {})", info.latest_version, info.mandatory);
+                let _ = app_handle.emit("update-available", &info);
+            }
+            Ok(_) => {}
+            Err(e) => println!("[Update] Release check failed: {}", e),
+        }
+    });
+}
+
 fn start_command_loop(state: Arc<AppState>) {
     tauri::async_runtime::spawn(async move {
         let mut ticker = interval(Duration::from_secs(COMMAND_POLL_INTERVAL_SECS));
@@ -821,9 +2548,20 @@ fn main() {
     // Initialize Local-First SQLite database
     let db = Arc::new(Database::new().expect("Failed to initialize database"));
     println!("[Microdiag] SQLite database initialized");
+    godmode::set_full_key_backups(
+        db.get_setting(REGISTRY_FULL_BACKUP_SETTING).ok().flatten().as_deref() == Some("true")
+    );
+    if let Some(lang) = db.get_setting(LANGUAGE_SETTING).ok().flatten() {
+        i18n::set_language(&lang);
+    }
+    #[cfg(windows)]
+    if let Some(secs) = db.get_setting(WMI_CACHE_TTL_SETTING).ok().flatten().and_then(|v| v.trim().parse::<u64>().ok()) {
+        wmi_cache::set_ttl_secs(secs.min(WMI_CACHE_TTL_MAX_SECS));
+    }
 
     // Load or create persistent device token (ONCE)
     let device_token = load_or_create_device_token();
+    let token_for_sync = device_token.clone();
 
     // Initialize sysinfo - fast startup, metrics refresh on first call
     let system = System::new();
@@ -835,6 +2573,9 @@ fn main() {
         device_token: Mutex::new(device_token),
         heartbeat_running: Mutex::new(true),
         db: Arc::clone(&db),
+        running_scripts: Mutex::new(HashMap::new()),
+        last_diagnostic: Mutex::new(None),
+        storage_scan_cancel: AtomicBool::new(false),
     });
 
     let state_for_manage = Arc::clone(&state);
@@ -853,12 +2594,20 @@ fn main() {
             let handle = app.handle().clone();
 
             // Build tray menu
+            let status_item = MenuItem::with_id(app, "status", tray_status_label(None, true), false, None::<&str>)?;
+            let heartbeat_item = MenuItem::with_id(app, "heartbeat_toggle", heartbeat_toggle_label(true), true, None::<&str>)?;
             let menu = Menu::with_items(app, &[
                 &MenuItem::with_id(app, "dashboard", "Ouvrir Dashboard", true, None::<&str>)?,
                 &MenuItem::with_id(app, "scan", "Lancer Scan", true, None::<&str>)?,
-                &MenuItem::with_id(app, "status", "Status: En ligne", false, None::<&str>)?,
+                &status_item,
+                &heartbeat_item,
                 &MenuItem::with_id(app, "quit", "Quitter", true, None::<&str>)?,
             ])?;
+            app.manage(TrayItems {
+                status: status_item,
+                heartbeat_toggle: heartbeat_item,
+                last_report: Mutex::new(None),
+            });
 
             // Build tray icon
             let _tray = TrayIconBuilder::new()
@@ -890,6 +2639,7 @@ fn main() {
                                 let _ = w.emit("run-scan", ());
                             }
                         }
+                        "heartbeat_toggle" => toggle_heartbeat(app),
                         "quit" => std::process::exit(0),
                         _ => {}
                     }
@@ -899,11 +2649,15 @@ fn main() {
             // Start background loops with shared state
             start_heartbeat_loop(handle.clone(), Arc::clone(&state_heartbeat));
             start_command_loop(Arc::clone(&state_commands));
+            start_diagnostic_schedule_loop(Arc::clone(&state_commands));
 
             // Start background sync with Supabase (delayed)
-            start_sync_loop(Arc::clone(&db_for_sync));
+            start_sync_loop(Arc::clone(&db_for_sync), token_for_sync);
+            start_sync_status_watch(handle.clone(), Arc::clone(&db_for_sync));
             println!("[Microdiag] Background sync started");
 
+            start_update_check(handle.clone());
+
             // Force window to front after startup
             if let Some(window) = app.get_webview_window("main") {
                 std::thread::spawn(move || {
@@ -922,64 +2676,297 @@ fn main() {
             get_system_metrics,
             get_health_score,
             get_security_status,
+            get_pending_reboot_status,
             get_device_token,
             run_script,
+            cancel_script,
+            db_get_script_executions,
             send_notification,
             run_security_scan,
+            get_support_summary,
+            copy_support_summary,
             // Local-First database commands
             db_get_scripts,
             db_get_scripts_by_category,
+            db_upsert_script,
+            db_delete_script,
             db_get_scripts_count,
+            db_get_favorite_scripts,
+            db_set_script_favorite,
+            db_set_script_order,
             db_save_metrics,
             db_get_recent_metrics,
+            db_get_health_trend,
+            db_get_temperature_history,
+            db_maintenance,
+            db_get_storage_status,
             db_get_chat_history,
+            db_get_chat_history_page,
+            db_search_chat_history,
             db_add_chat_message,
             db_clear_chat,
             db_get_setting,
             db_set_setting,
+            get_device_label,
+            set_device_label,
             db_sync_scripts,
+            db_sync_settings,
+            get_sync_status,
+            reset_sync_queue_failures,
             db_check_online,
             db_check_remote_executions,
             db_update_remote_execution,
+            // Agent self-update
+            check_agent_update,
+            apply_agent_update,
             // God Mode commands (Native Performance)
             gm_get_installed_apps,
+            gm_uninstall_app,
             gm_get_deep_health,
             gm_get_startup_items,
+            gm_snapshot_installed_apps,
+            gm_diff_installed_apps,
             gm_disable_startup_item,
+            gm_enable_startup_item,
+            gm_get_scheduled_startup_tasks,
+            gm_disable_scheduled_task,
+            get_gpu_info,
+            generate_battery_report,
+            list_firewall_rules,
+            get_risky_open_ports,
+            scan_persistence_points,
+            list_power_plans,
+            set_active_power_plan,
+            enable_ultimate_performance,
+            set_boot_mode,
+            set_boot_logging,
+            get_event_log,
+            list_services,
+            set_service_state,
+            set_service_start_type,
             gm_check_updates,
             gm_install_apps,
             gm_update_all,
+            gm_install_apps_streaming,
+            gm_update_all_streaming,
             gm_apply_tweak,
+            gm_preview_tweak,
+            gm_get_tweak_states,
             gm_ghost_mode,
+            gm_clean_privacy_traces,
+            gm_get_environment,
+            gm_set_environment_var,
+            gm_repair_path,
             gm_list_backups,
             gm_restore_backup,
+            gm_restore_latest_backup,
+            gm_cleanup_backups,
             gm_install_rustdesk,
+            gm_uninstall_rustdesk,
+            gm_rustdesk_status,
             gm_check_crystaldiskinfo,
             gm_install_crystaldiskinfo,
             gm_check_librehardwaremonitor,
             gm_install_librehardwaremonitor,
             gm_get_all_temperatures,
+            gm_get_all_sensors,
             gm_auto_setup_diagnostic_tools,
+            gm_list_tools,
+            gm_ensure_tool,
+            gm_get_tool_version,
             // Premium Diagnostics commands
             run_premium_diagnostic,
+            run_quick_diagnostic,
+            export_diagnostic_report,
+            db_get_diagnostic_history,
             get_temperatures,
             get_process_analysis,
+            kill_process,
+            suspend_process,
+            resume_process,
+            set_process_priority,
+            get_suspicion_config,
+            set_suspicion_config,
+            get_scoring_config,
+            set_scoring_config,
+            get_latency_config,
+            set_latency_config,
             get_network_analysis,
+            get_network_connections,
+            measure_bandwidth,
+            get_cpu_cores,
             get_storage_analysis,
+            scan_largest_files,
+            cancel_storage_scan,
             // v3.2.0 - Benchmark & BSOD Analysis
             run_disk_benchmark,
+            run_disk_benchmark_compare,
             analyze_bsod,
+            get_reliability_index,
+            get_reliability_events,
             // v3.3.0 - Speedtest & Boot Analysis
             run_speedtest,
             analyze_boot_time,
             // v3.4.0 - CVE Scanner & Failure Prediction
             scan_cve,
+            scan_cve_online,
             predict_failures,
+            get_smart_trend,
+            run_smart_self_test,
+            get_smart_self_test_status,
+            schedule_memory_test,
+            get_memory_test_result,
             // v3.12.0 - FixWin System Repair Tools
             fw_get_categories,
             fw_execute_fix,
             fw_create_restore_point,
+            fw_list_restore_points,
+            fw_restore_to_point,
+            fw_execute_fixes,
+            fw_run_auto_maintenance,
+            fw_cycle_network_adapter,
+            fw_optimize_drive,
+            fw_get_repair_log_summary,
         ])
         .run(tauri::generate_context!())
         .expect("Error starting application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_system_info() -> diagnostics::ExtendedSystemInfo {
+        diagnostics::ExtendedSystemInfo {
+            cpu_name: "Intel(R) Core(TM) i5-10400 ".to_string(),
+            cpu_cores: 6,
+            cpu_threads: 12,
+            cpu_frequency_mhz: 2900,
+            ram_total_gb: 16.0,
+            ram_slots_used: "2/4".to_string(),
+            gpu_name: "Intel UHD 630".to_string(),
+            gpu_memory_mb: 1024,
+            motherboard: "ASUS PRIME B460".to_string(),
+            bios_version: "1401".to_string(),
+            windows_version: "Windows 11 Pro".to_string(),
+            windows_build: "22631".to_string(),
+            install_date: "2024-01-10".to_string(),
+            last_boot: "2024-06-01".to_string(),
+            uptime_hours: 5,
+        }
+    }
+
+    #[test]
+    fn support_summary_has_expected_fields_and_redacts_token() {
+        let metrics = SystemMetrics {
+            cpu_usage: 12.0,
+            memory_total: 16 * 1024 * 1024 * 1024,
+            memory_used: 8 * 1024 * 1024 * 1024,
+            memory_percent: 50.0,
+            disks: vec![DiskInfo {
+                name: "OS".to_string(),
+                mount_point: "C:\\".to_string(),
+                total_gb: 476.0,
+                used_gb: 356.0,
+                free_gb: 120.0,
+                percent: 74.8,
+            }],
+            hostname: "PC-ACCUEIL".to_string(),
+            os_version: "11".to_string(),
+        };
+        let health = HealthScore { score: 85, status: "Bon".to_string(), issues: vec!["Disque presque plein".to_string()] };
+        let token = "mdiag_0f3c9a2e-7b41-4d8e-9c55-1a2b3c4dabcd";
+
+        let summary = format_support_summary(&metrics, &sample_system_info(), &health, token);
+
+        assert!(summary.contains(&format!("Microdiag Sentinel v{}", AGENT_VERSION)));
+        assert!(summary.contains("Appareil mdiag_...abcd"));
+        assert!(!summary.contains(token));
+        assert!(summary.contains("OS: Windows 11 Pro"));
+        assert!(summary.contains("CPU: Intel(R) Core(TM) i5-10400 (6c/12t)"));
+        assert!(summary.contains("RAM: 16.0 GB (50% utilisee)"));
+        assert!(summary.contains("Disques libres: C:\\ 120/476 GB"));
+        assert!(summary.contains("Score sante: 85/100 (Bon) | Probleme principal: Disque presque plein"));
+    }
+
+    #[test]
+    fn thermal_alert_needs_consecutive_samples_then_cools_down() {
+        let mut tracker = ThermalAlertTracker::default();
+        let start = std::time::Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        for i in 1..THERMAL_ALERT_CONSECUTIVE_SAMPLES {
+            assert!(!tracker.observe("CPU", 95.0, 90.0, at(i as u64 * 300)), "alerted after {} samples", i);
+        }
+        let first_alert = THERMAL_ALERT_CONSECUTIVE_SAMPLES as u64 * 300;
+        assert!(tracker.observe("CPU", 95.0, 90.0, at(first_alert)));
+
+        // Still hot, or hot again after a dip: quiet until the cooldown is over
+        assert!(!tracker.observe("CPU", 96.0, 90.0, at(first_alert + 300)));
+        assert!(!tracker.observe("CPU", 80.0, 90.0, at(first_alert + 600)));
+        for i in 1..=THERMAL_ALERT_CONSECUTIVE_SAMPLES as u64 {
+            assert!(!tracker.observe("CPU", 95.0, 90.0, at(first_alert + 600 + i * 300)));
+        }
+        assert!(tracker.observe("CPU", 95.0, 90.0, at(first_alert + THERMAL_ALERT_COOLDOWN_SECS)));
+    }
+
+    #[test]
+    fn thermal_alert_dip_resets_the_count() {
+        let mut tracker = ThermalAlertTracker::default();
+        let now = std::time::Instant::now();
+
+        for _ in 1..THERMAL_ALERT_CONSECUTIVE_SAMPLES {
+            assert!(!tracker.observe("CPU", 95.0, 90.0, now));
+        }
+        assert!(!tracker.observe("CPU", 85.0, 90.0, now));
+        for _ in 1..THERMAL_ALERT_CONSECUTIVE_SAMPLES {
+            assert!(!tracker.observe("CPU", 95.0, 90.0, now));
+        }
+        assert!(tracker.observe("CPU", 95.0, 90.0, now));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn killed_script_does_not_wait_forever_on_a_grandchild_pipe() {
+        use std::process::{Command, Stdio};
+
+        // The background sleep inherits stdout and outlives its parent
+        let mut child = Command::new("sh")
+            .args(["-c", "sleep 30 & sleep 30"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let reader = spawn_pipe_reader(child.stdout.take());
+
+        kill_process_tree(&mut child).unwrap();
+        let started = std::time::Instant::now();
+        let output = tauri::async_runtime::block_on(collect_pipe(reader));
+
+        assert!(output.is_empty());
+        assert!(started.elapsed() < Duration::from_secs(SCRIPT_PIPE_DRAIN_SECS + 5));
+    }
+
+    #[test]
+    fn persistence_entries_are_reported_once_until_they_change() {
+        let entry = |value: &str| PersistenceEntry {
+            location: r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run".to_string(),
+            name: "Updater".to_string(),
+            value: value.to_string(),
+            risk: "high".to_string(),
+            reason: "Lancement via mshta".to_string(),
+        };
+        let mut reported = ReportedFindings::default();
+
+        let first = reported.fresh(vec![entry("mshta a.hta"), entry("mshta a.hta")], PersistenceEntry::report_key);
+        assert_eq!(first.len(), 1);
+        assert!(reported.fresh(vec![entry("mshta a.hta")], PersistenceEntry::report_key).is_empty());
+
+        let changed = reported.fresh(vec![entry("mshta b.hta")], PersistenceEntry::report_key);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].value, "mshta b.hta");
+
+        // Removed then restored: reported again
+        assert!(reported.fresh(Vec::new(), PersistenceEntry::report_key).is_empty());
+        assert_eq!(reported.fresh(vec![entry("mshta b.hta")], PersistenceEntry::report_key).len(), 1);
+    }
+}
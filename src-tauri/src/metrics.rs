@@ -26,6 +26,13 @@ pub struct DiskInfo {
     pub percent: f32,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CoreInfo {
+    pub index: usize,
+    pub usage_percent: f32,
+    pub frequency_mhz: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct HealthScore {
     pub score: u8,
@@ -33,6 +40,73 @@ pub struct HealthScore {
     pub issues: Vec<String>,
 }
 
+/// Thresholds and deductions behind the health scores (basic and premium diagnostic).
+/// Stored as JSON in settings so managed deployments can tune them.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ScoringConfig {
+    // Basic health score (heartbeat, dashboard)
+    pub cpu_threshold: f32,
+    pub cpu_penalty: u8,
+    pub memory_threshold: f32,
+    pub memory_penalty: u8,
+    pub disk_full_threshold: f32,
+    pub disk_full_penalty: u8,
+    // Premium diagnostic
+    pub temp_warning_c: f32,
+    pub temp_warning_penalty: u8,
+    pub temp_critical_c: f32,
+    pub temp_critical_penalty: u8,
+    pub suspicious_process_penalty: u8,
+    pub top_cpu_threshold: f32,
+    pub top_cpu_penalty: u8,
+    pub disk_warning_percent: f32,
+    pub disk_warning_penalty: u8,
+    pub disk_critical_percent: f32,
+    pub disk_critical_penalty: u8,
+    pub offline_penalty: u8,
+    pub latency_threshold_ms: u32,
+    pub latency_penalty: u8,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        ScoringConfig {
+            cpu_threshold: 80.0,
+            cpu_penalty: 15,
+            memory_threshold: 85.0,
+            memory_penalty: 20,
+            disk_full_threshold: 90.0,
+            disk_full_penalty: 25,
+            temp_warning_c: 75.0,
+            temp_warning_penalty: 10,
+            temp_critical_c: 85.0,
+            temp_critical_penalty: 20,
+            suspicious_process_penalty: 15,
+            top_cpu_threshold: 90.0,
+            top_cpu_penalty: 10,
+            disk_warning_percent: 85.0,
+            disk_warning_penalty: 10,
+            disk_critical_percent: 95.0,
+            disk_critical_penalty: 25,
+            offline_penalty: 15,
+            latency_threshold_ms: 100,
+            latency_penalty: 5,
+        }
+    }
+}
+
+impl ScoringConfig {
+    pub const SETTINGS_KEY: &'static str = "scoring_config";
+
+    /// Parse the JSON stored in settings, falling back to defaults
+    pub fn from_setting(value: Option<String>) -> Self {
+        value
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+}
+
 impl SystemMetrics {
     pub fn collect(sys: &mut System) -> Self {
         // Targeted refresh - faster than refresh_all()
@@ -76,23 +150,23 @@ impl SystemMetrics {
         }
     }
 
-    pub fn calculate_health(&self) -> HealthScore {
+    pub fn calculate_health(&self, scoring: &ScoringConfig) -> HealthScore {
         let mut score: u8 = 100;
         let mut issues: Vec<String> = Vec::new();
 
-        if self.cpu_usage > 80.0 {
-            score = score.saturating_sub(15);
+        if self.cpu_usage > scoring.cpu_threshold {
+            score = score.saturating_sub(scoring.cpu_penalty);
             issues.push("CPU élevé".to_string());
         }
 
-        if self.memory_percent > 85.0 {
-            score = score.saturating_sub(20);
+        if self.memory_percent > scoring.memory_threshold {
+            score = score.saturating_sub(scoring.memory_penalty);
             issues.push("Mémoire faible".to_string());
         }
 
         for disk in &self.disks {
-            if disk.percent > 90.0 {
-                score = score.saturating_sub(25);
+            if disk.percent > scoring.disk_full_threshold {
+                score = score.saturating_sub(scoring.disk_full_penalty);
                 issues.push(format!("Disque {} plein", disk.mount_point));
             }
         }
@@ -108,3 +182,54 @@ impl SystemMetrics {
         HealthScore { score, status, issues }
     }
 }
+
+/// Per logical core, from the last two CPU refreshes
+pub fn cpu_cores_snapshot(sys: &System) -> Vec<CoreInfo> {
+    sys.cpus().iter().enumerate()
+        .map(|(index, cpu)| CoreInfo {
+            index,
+            usage_percent: (cpu.cpu_usage() * 10.0).round() / 10.0,
+            frequency_mhz: cpu.frequency(),
+        })
+        .collect()
+}
+
+/// Usage is a delta between two refreshes: sample twice, sysinfo's minimum interval apart
+pub fn get_cpu_cores(sys: &mut System) -> Vec<CoreInfo> {
+    sys.refresh_cpu();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    sys.refresh_cpu();
+    cpu_cores_snapshot(sys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_scoring_config_is_pinned() {
+        let c = ScoringConfig::default();
+        assert_eq!((c.cpu_threshold, c.cpu_penalty), (80.0, 15));
+        assert_eq!((c.memory_threshold, c.memory_penalty), (85.0, 20));
+        assert_eq!((c.disk_full_threshold, c.disk_full_penalty), (90.0, 25));
+        assert_eq!((c.temp_warning_c, c.temp_warning_penalty), (75.0, 10));
+        assert_eq!((c.temp_critical_c, c.temp_critical_penalty), (85.0, 20));
+        assert_eq!(c.suspicious_process_penalty, 15);
+        assert_eq!((c.top_cpu_threshold, c.top_cpu_penalty), (90.0, 10));
+        assert_eq!((c.disk_warning_percent, c.disk_warning_penalty), (85.0, 10));
+        assert_eq!((c.disk_critical_percent, c.disk_critical_penalty), (95.0, 25));
+        assert_eq!(c.offline_penalty, 15);
+        assert_eq!((c.latency_threshold_ms, c.latency_penalty), (100, 5));
+    }
+
+    #[test]
+    fn partial_or_invalid_setting_falls_back_to_defaults() {
+        let partial = ScoringConfig::from_setting(Some(r#"{"cpu_threshold": 70.0}"#.to_string()));
+        assert_eq!(partial.cpu_threshold, 70.0);
+        assert_eq!(partial.memory_penalty, 20);
+
+        let invalid = ScoringConfig::from_setting(Some("not json".to_string()));
+        assert_eq!(invalid.cpu_threshold, 80.0);
+        assert_eq!(ScoringConfig::from_setting(None).latency_threshold_ms, 100);
+    }
+}
@@ -0,0 +1,109 @@
+// ============================================
+// MICRODIAG AGENT - PowerShell Detection
+// One-time lookup of powershell/pwsh, cached for the whole session
+// ============================================
+
+use std::os::windows::process::CommandExt;
+use std::process::Command;
+use std::sync::OnceLock;
+
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+pub const UNAVAILABLE_MESSAGE: &str = "PowerShell indisponible sur ce poste (absent ou bloque par une strategie)";
+
+static DETECTED: OnceLock<Option<String>> = OnceLock::new();
+
+fn candidates() -> Vec<String> {
+    let mut list = vec!["powershell".to_string()];
+    // PATH may have been stripped, try the system location directly
+    if let Ok(root) = std::env::var("SystemRoot") {
+        list.push(format!("{}\\System32\\WindowsPowerShell\\v1.0\\powershell.exe", root));
+    }
+    list.push("pwsh".to_string());
+    list
+}
+
+/// Runs a trivial command: catches missing binaries as well as policy blocks
+fn probe(program: &str) -> bool {
+    Command::new(program)
+        .args(["-NoProfile", "-NonInteractive", "-Command", "$PSVersionTable.PSVersion.Major"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map(|out| out.status.success() && !String::from_utf8_lossy(&out.stdout).trim().is_empty())
+        .unwrap_or(false)
+}
+
+fn first_usable(candidates: Vec<String>, probe: impl Fn(&str) -> bool) -> Option<String> {
+    candidates.into_iter().find(|c| probe(c))
+}
+
+fn detect_cached(cache: &'static OnceLock<Option<String>>, detect: impl FnOnce() -> Option<String>) -> Option<&'static str> {
+    cache
+        .get_or_init(|| {
+            let found = detect();
+            if found.is_none() {
+                println!("[PowerShell] Not available, PowerShell-based checks disabled");
+            }
+            found
+        })
+        .as_deref()
+}
+
+/// Usable PowerShell executable, probed once then cached
+pub fn detect_powershell() -> Option<&'static str> {
+    detect_cached(&DETECTED, || first_usable(candidates(), probe))
+}
+
+pub fn is_available() -> bool {
+    detect_powershell().is_some()
+}
+
+/// Executable to launch; still "powershell" when undetected so spawn errors stay explicit
+pub fn program() -> &'static str {
+    detect_powershell().unwrap_or("powershell")
+}
+
+pub fn require() -> Result<&'static str, String> {
+    require_detected(detect_powershell())
+}
+
+fn require_detected(detected: Option<&'static str>) -> Result<&'static str, String> {
+    detected.ok_or_else(|| UNAVAILABLE_MESSAGE.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn first_candidate_that_runs_wins() {
+        let candidates = vec!["powershell".to_string(), "C:\\Windows\\powershell.exe".to_string(), "pwsh".to_string()];
+        assert_eq!(first_usable(candidates.clone(), |c| c == "pwsh").as_deref(), Some("pwsh"));
+        assert_eq!(first_usable(candidates, |_| false), None);
+    }
+
+    #[test]
+    fn detection_runs_once_then_is_cached() {
+        static CACHE: OnceLock<Option<String>> = OnceLock::new();
+        let probes = AtomicUsize::new(0);
+        let detect = || {
+            probes.fetch_add(1, Ordering::SeqCst);
+            Some("pwsh".to_string())
+        };
+
+        assert_eq!(detect_cached(&CACHE, detect), Some("pwsh"));
+        assert_eq!(detect_cached(&CACHE, detect), Some("pwsh"));
+        assert_eq!(probes.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn missing_powershell_degrades_to_a_clear_error() {
+        static CACHE: OnceLock<Option<String>> = OnceLock::new();
+
+        let detected = detect_cached(&CACHE, || first_usable(vec!["powershell".to_string()], |_| false));
+        assert_eq!(detected, None);
+        assert_eq!(require_detected(detected), Err(UNAVAILABLE_MESSAGE.to_string()));
+        assert_eq!(require_detected(Some("powershell")), Ok("powershell"));
+    }
+}
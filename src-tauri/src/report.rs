@@ -0,0 +1,250 @@
+// ============================================
+// MICRODIAG AGENT - Diagnostic Report Export
+// JSON dump or paginated PDF handed to the customer
+// ============================================
+
+use crate::config::AGENT_VERSION;
+use crate::diagnostics::{BsodAnalysis, PremiumDiagnostic};
+use crate::godmode::DeepHealth;
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+use serde::Serialize;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+pub struct DiagnosticReport {
+    pub hostname: String,
+    pub generated_at: String,
+    pub agent_version: String,
+    pub diagnostic: PremiumDiagnostic,
+    pub deep_health: DeepHealth,
+    pub bsod: BsodAnalysis,
+}
+
+impl DiagnosticReport {
+    pub fn new(diagnostic: PremiumDiagnostic, deep_health: DeepHealth, bsod: BsodAnalysis) -> Self {
+        DiagnosticReport {
+            hostname: sysinfo::System::host_name().unwrap_or_else(|| "PC".to_string()),
+            generated_at: chrono::Local::now().format("%d/%m/%Y %H:%M").to_string(),
+            agent_version: AGENT_VERSION.to_string(),
+            diagnostic,
+            deep_health,
+            bsod,
+        }
+    }
+}
+
+pub fn get_reports_dir() -> PathBuf {
+    let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("Microdiag");
+    path.push("reports");
+    let _ = std::fs::create_dir_all(&path);
+    path
+}
+
+/// microdiag_report_<hostname>_<timestamp>.<ext>, hostname reduced to filename-safe chars
+fn report_path(hostname: &str, ext: &str) -> PathBuf {
+    let host: String = hostname
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    get_reports_dir().join(format!("microdiag_report_{}_{}.{}", host, stamp, ext))
+}
+
+/// Writes the report and returns the saved file path
+pub fn export_report(report: &DiagnosticReport, format: &str) -> Result<String, String> {
+    let path = match format.to_lowercase().as_str() {
+        "json" => {
+            let path = report_path(&report.hostname, "json");
+            let json = serde_json::to_string_pretty(report).map_err(|e| format!("JSON error: {}", e))?;
+            std::fs::write(&path, json).map_err(|e| format!("Erreur ecriture: {}", e))?;
+            path
+        }
+        "pdf" => {
+            let path = report_path(&report.hostname, "pdf");
+            write_pdf(report, &path)?;
+            path
+        }
+        other => return Err(format!("Format inconnu: {} (json ou pdf)", other)),
+    };
+    Ok(path.to_string_lossy().to_string())
+}
+
+// ============================================
+// PDF RENDERING
+// ============================================
+
+const PAGE_WIDTH_MM: f32 = 210.0;
+const PAGE_HEIGHT_MM: f32 = 297.0;
+const MARGIN_MM: f32 = 18.0;
+const LINE_HEIGHT_MM: f32 = 5.5;
+/// Rough Helvetica 10pt capacity for the printable width
+const WRAP_CHARS: usize = 95;
+
+/// Text cursor that opens a new page when it reaches the bottom margin
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    font: IndirectFontRef,
+    bold: IndirectFontRef,
+    layer: PdfLayerReference,
+    y: f32,
+    page: u32,
+}
+
+impl PdfWriter {
+    fn new(title: &str) -> Result<Self, String> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Calque 1");
+        let font = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| format!("PDF error: {}", e))?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(|e| format!("PDF error: {}", e))?;
+        let layer = doc.get_page(page).get_layer(layer);
+        Ok(PdfWriter { doc, font, bold, layer, y: PAGE_HEIGHT_MM - MARGIN_MM, page: 1 })
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height >= MARGIN_MM {
+            return;
+        }
+        self.page += 1;
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), format!("Page {}", self.page));
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT_MM - MARGIN_MM;
+    }
+
+    fn write(&mut self, text: &str, size: f32, bold: bool, indent: f32) {
+        for line in wrap(text, WRAP_CHARS) {
+            self.ensure_space(LINE_HEIGHT_MM);
+            let font = if bold { &self.bold } else { &self.font };
+            self.layer.use_text(line, size, Mm(MARGIN_MM + indent), Mm(self.y), font);
+            self.y -= LINE_HEIGHT_MM * size / 10.0;
+        }
+    }
+
+    fn title(&mut self, text: &str) {
+        self.ensure_space(LINE_HEIGHT_MM * 3.0);
+        self.y -= LINE_HEIGHT_MM / 2.0;
+        self.write(text, 14.0, true, 0.0);
+    }
+
+    fn line(&mut self, text: &str) {
+        self.write(text, 10.0, false, 0.0);
+    }
+
+    /// Fixed-width columns, good enough with short cell values
+    fn table_row(&mut self, cells: &[String], widths: &[f32], bold: bool) {
+        self.ensure_space(LINE_HEIGHT_MM);
+        let font = if bold { &self.bold } else { &self.font };
+        let mut x = MARGIN_MM;
+        for (cell, width) in cells.iter().zip(widths) {
+            let max_chars = (*width / 1.9) as usize;
+            let text: String = cell.chars().take(max_chars).collect();
+            self.layer.use_text(text, 9.0, Mm(x), Mm(self.y), font);
+            x += width;
+        }
+        self.y -= LINE_HEIGHT_MM;
+    }
+
+    fn save(self, path: &Path) -> Result<(), String> {
+        let file = std::fs::File::create(path).map_err(|e| format!("Erreur ecriture: {}", e))?;
+        self.doc.save(&mut BufWriter::new(file)).map_err(|e| format!("PDF error: {}", e))
+    }
+}
+
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+fn opt<T: std::fmt::Display>(value: Option<T>, unit: &str) -> String {
+    value.map(|v| format!("{}{}", v, unit)).unwrap_or_else(|| "-".to_string())
+}
+
+fn write_pdf(report: &DiagnosticReport, path: &Path) -> Result<(), String> {
+    let diag = &report.diagnostic;
+    let mut pdf = PdfWriter::new("Rapport de diagnostic Microdiag")?;
+
+    pdf.write("Rapport de diagnostic Microdiag", 18.0, true, 0.0);
+    pdf.line(&format!("Poste: {}   -   Genere le {}   -   Agent v{}", report.hostname, report.generated_at, report.agent_version));
+
+    // Health score
+    pdf.title("Score de sante");
+    pdf.write(&format!("{}/100 - {}", diag.overall_score, diag.overall_status), 12.0, true, 0.0);
+    let info = &diag.system_info;
+    pdf.line(&format!("Processeur: {} ({} coeurs / {} threads)", info.cpu_name, info.cpu_cores, info.cpu_threads));
+    pdf.line(&format!("Memoire: {:.1} Go   -   GPU: {}", info.ram_total_gb, info.gpu_name));
+    pdf.line(&format!("Windows: {} (build {})", info.windows_version, info.windows_build));
+
+    // Recommendations
+    pdf.title("Recommandations");
+    if diag.recommendations.is_empty() {
+        pdf.line("Aucune action necessaire.");
+    }
+    for rec in &diag.recommendations {
+        pdf.write(&format!("[{}] {}", rec.priority.to_uppercase(), rec.title), 10.0, true, 0.0);
+        pdf.write(&rec.description, 10.0, false, 4.0);
+    }
+
+    // Temperatures
+    pdf.title("Temperatures");
+    pdf.line(&format!(
+        "CPU: {}   GPU: {}   Disque: {}",
+        opt(diag.temperatures.cpu_temp.map(|t| format!("{:.0}", t)), " C"),
+        opt(diag.temperatures.gpu_temp.map(|t| format!("{:.0}", t)), " C"),
+        opt(diag.temperatures.disk_temp.map(|t| format!("{:.0}", t)), " C"),
+    ));
+    if !diag.temperatures.components.is_empty() {
+        let widths = [100.0, 25.0, 25.0, 25.0];
+        pdf.table_row(&["Composant".into(), "Temp.".into(), "Max".into(), "Etat".into()], &widths, true);
+        for c in &diag.temperatures.components {
+            pdf.table_row(&[
+                c.name.clone(),
+                format!("{:.0} C", c.temp),
+                format!("{:.0} C", c.max_temp),
+                c.status.clone(),
+            ], &widths, false);
+        }
+    }
+
+    // SMART
+    pdf.title("Disques (SMART)");
+    if report.deep_health.smart_disks.is_empty() {
+        pdf.line(&format!("{} - {}", report.deep_health.disk_model, report.deep_health.disk_smart_status));
+    } else {
+        let widths = [62.0, 18.0, 22.0, 18.0, 18.0, 30.0];
+        pdf.table_row(&[
+            "Modele".into(), "Type".into(), "Etat".into(), "Sante".into(), "Temp.".into(), "Heures".into(),
+        ], &widths, true);
+        for disk in &report.deep_health.smart_disks {
+            pdf.table_row(&[
+                disk.model.clone(),
+                disk.media_type.clone(),
+                disk.health_status.clone(),
+                format!("{}%", disk.health_percent),
+                opt(disk.temperature_c, " C"),
+                opt(disk.power_on_hours, " h"),
+            ], &widths, false);
+        }
+    }
+
+    // Crashes
+    pdf.title("Ecrans bleus");
+    pdf.line(&format!("{} crash(s) - {}", report.bsod.total_crashes, report.bsod.recommendation));
+    for crash in report.bsod.crashes.iter().take(10) {
+        pdf.write(&format!("{} {} - {} ({})", crash.date, crash.time, crash.bug_check_name, crash.bug_check_code), 10.0, false, 4.0);
+    }
+
+    pdf.save(path)
+}
@@ -0,0 +1,148 @@
+// ============================================
+// MICRODIAG AGENT - Secrets at Rest
+// User-bound encryption (Windows DPAPI) for the device token and DB key
+//
+// Fallback: other platforms have no equivalent machine key store, so
+// `is_supported()` is false, the token stays in device_token.txt and the
+// database is opened without a key. Both then rely on the user profile
+// permissions only.
+// ============================================
+
+use std::path::Path;
+
+/// Extra entropy so other apps on the machine can't unprotect our blobs by accident
+#[cfg(windows)]
+const ENTROPY: &[u8] = b"microdiag-sentinel";
+
+#[cfg(windows)]
+mod dpapi {
+    use std::ffi::c_void;
+
+    pub const CRYPTPROTECT_UI_FORBIDDEN: u32 = 0x1;
+
+    #[repr(C)]
+    pub struct DataBlob {
+        pub cb_data: u32,
+        pub pb_data: *mut u8,
+    }
+
+    #[link(name = "crypt32")]
+    extern "system" {
+        pub fn CryptProtectData(
+            data_in: *const DataBlob,
+            description: *const u16,
+            entropy: *const DataBlob,
+            reserved: *mut c_void,
+            prompt: *const c_void,
+            flags: u32,
+            data_out: *mut DataBlob,
+        ) -> i32;
+
+        pub fn CryptUnprotectData(
+            data_in: *const DataBlob,
+            description: *mut *mut u16,
+            entropy: *const DataBlob,
+            reserved: *mut c_void,
+            prompt: *const c_void,
+            flags: u32,
+            data_out: *mut DataBlob,
+        ) -> i32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn LocalFree(mem: *mut c_void) -> *mut c_void;
+    }
+
+    pub fn blob(data: &[u8]) -> DataBlob {
+        DataBlob { cb_data: data.len() as u32, pb_data: data.as_ptr() as *mut u8 }
+    }
+
+    /// Copies the output blob and releases the buffer DPAPI allocated
+    pub unsafe fn take(out: DataBlob) -> Vec<u8> {
+        let bytes = std::slice::from_raw_parts(out.pb_data, out.cb_data as usize).to_vec();
+        LocalFree(out.pb_data as *mut c_void);
+        bytes
+    }
+}
+
+pub fn is_supported() -> bool {
+    cfg!(windows)
+}
+
+#[cfg(windows)]
+pub fn protect(data: &[u8]) -> Result<Vec<u8>, String> {
+    use dpapi::*;
+
+    let input = blob(data);
+    let entropy = blob(ENTROPY);
+    let mut out = DataBlob { cb_data: 0, pb_data: std::ptr::null_mut() };
+    let ok = unsafe {
+        CryptProtectData(
+            &input,
+            std::ptr::null(),
+            &entropy,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            // No LOCAL_MACHINE flag: only this Windows account can unprotect the blob
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut out,
+        )
+    };
+    if ok == 0 {
+        return Err(format!("Chiffrement DPAPI impossible: {}", std::io::Error::last_os_error()));
+    }
+    Ok(unsafe { take(out) })
+}
+
+#[cfg(not(windows))]
+pub fn protect(_data: &[u8]) -> Result<Vec<u8>, String> {
+    Err("Chiffrement disponible uniquement sur Windows".to_string())
+}
+
+#[cfg(windows)]
+pub fn unprotect(data: &[u8]) -> Result<Vec<u8>, String> {
+    use dpapi::*;
+
+    let input = blob(data);
+    let entropy = blob(ENTROPY);
+    let mut out = DataBlob { cb_data: 0, pb_data: std::ptr::null_mut() };
+    let ok = unsafe {
+        CryptUnprotectData(
+            &input,
+            std::ptr::null_mut(),
+            &entropy,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut out,
+        )
+    };
+    if ok == 0 {
+        return Err(format!("Dechiffrement DPAPI impossible: {}", std::io::Error::last_os_error()));
+    }
+    Ok(unsafe { take(out) })
+}
+
+#[cfg(not(windows))]
+pub fn unprotect(_data: &[u8]) -> Result<Vec<u8>, String> {
+    Err("Chiffrement disponible uniquement sur Windows".to_string())
+}
+
+/// Written next to the target then renamed, so an existing secret is never left half-written
+pub fn write_protected(path: &Path, secret: &str) -> Result<(), String> {
+    let blob = protect(secret.as_bytes())?;
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, blob)
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            format!("Erreur ecriture: {}", e)
+        })
+}
+
+pub fn read_protected(path: &Path) -> Result<String, String> {
+    let blob = std::fs::read(path).map_err(|e| format!("Erreur lecture: {}", e))?;
+    let plain = unprotect(&blob)?;
+    String::from_utf8(plain).map_err(|_| "Secret illisible".to_string())
+}
@@ -9,6 +9,10 @@ use serde::{Deserialize, Serialize};
 use winreg::enums::*;
 #[cfg(windows)]
 use winreg::RegKey;
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SecurityStatus {
@@ -18,6 +22,41 @@ pub struct SecurityStatus {
     pub last_scan_days: i32,
     pub definitions_age_days: i32,
     pub issues: Vec<String>,
+    #[serde(default)]
+    pub pending_reboot: PendingRebootStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PendingRebootStatus {
+    pub pending: bool,
+    pub reasons: Vec<String>,
+}
+
+/// Standard pending reboot indicators (servicing stack, Windows Update, file renames)
+#[cfg(windows)]
+pub fn get_pending_reboot_status() -> PendingRebootStatus {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let mut reasons = Vec::new();
+
+    if hklm.open_subkey("SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Component Based Servicing\\RebootPending").is_ok() {
+        reasons.push("Installation de composants Windows a finaliser".to_string());
+    }
+    if hklm.open_subkey("SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\WindowsUpdate\\Auto Update\\RebootRequired").is_ok() {
+        reasons.push("Mises a jour Windows en attente de redemarrage".to_string());
+    }
+    let renames = hklm.open_subkey("SYSTEM\\CurrentControlSet\\Control\\Session Manager")
+        .and_then(|key| key.get_value::<Vec<String>, _>("PendingFileRenameOperations"))
+        .unwrap_or_default();
+    if renames.iter().any(|op| !op.is_empty()) {
+        reasons.push("Fichiers a remplacer au prochain demarrage".to_string());
+    }
+
+    PendingRebootStatus { pending: !reasons.is_empty(), reasons }
+}
+
+#[cfg(not(windows))]
+pub fn get_pending_reboot_status() -> PendingRebootStatus {
+    PendingRebootStatus::default()
 }
 
 impl SecurityStatus {
@@ -30,6 +69,7 @@ impl SecurityStatus {
             last_scan_days: 0,
             definitions_age_days: 0,
             issues: Vec::new(),
+            pending_reboot: get_pending_reboot_status(),
         };
 
         // Check Windows Defender via Registry (FAST - ~1ms)
@@ -99,6 +139,7 @@ impl SecurityStatus {
             last_scan_days: 0,
             definitions_age_days: 0,
             issues: Vec::new(),
+            pending_reboot: PendingRebootStatus::default(),
         }
     }
 
@@ -136,3 +177,495 @@ impl SecurityLog {
         })
     }
 }
+
+// ============================================
+// FIREWALL RULES (NetSecurity cmdlets)
+// ============================================
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FirewallRule {
+    pub name: String,
+    pub enabled: bool,
+    pub direction: String,        // inbound, outbound
+    pub action: String,           // allow, block
+    pub local_port: String,       // "Any", "3389", "5000-5010,8080"...
+    pub remote_address: String,
+    pub program: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RiskyFirewallRule {
+    pub rule: FirewallRule,
+    pub reason: String,
+}
+
+/// Inbound ports that should never be open to everyone on a workstation
+const RISKY_PORTS: &[(u16, &str)] = &[(3389, "RDP"), (445, "SMB")];
+
+/// True if a Get-NetFirewallPortFilter LocalPort list ("80,443", "3000-4000") covers the port
+fn port_list_contains(ports: &str, port: u16) -> bool {
+    ports.split(',').map(str::trim).any(|p| match p.split_once('-') {
+        Some((lo, hi)) => matches!((lo.parse::<u16>(), hi.parse::<u16>()), (Ok(lo), Ok(hi)) if (lo..=hi).contains(&port)),
+        None => p.parse::<u16>() == Ok(port),
+    })
+}
+
+fn firewall_rule_risk(rule: &FirewallRule) -> Option<String> {
+    if !rule.enabled || rule.direction != "inbound" || rule.action != "allow" {
+        return None;
+    }
+    let any = |v: &str| v.is_empty() || v.eq_ignore_ascii_case("any");
+
+    if any(&rule.local_port) && any(&rule.remote_address) && any(&rule.program) {
+        return Some("Tous les ports ouverts a toutes les adresses".into());
+    }
+    RISKY_PORTS.iter()
+        .find(|(port, _)| port_list_contains(&rule.local_port, *port))
+        .map(|(port, service)| format!("Port {} ({}) ouvert en entree", port, service))
+}
+
+/// direction: inbound, outbound or all
+#[cfg(windows)]
+pub fn list_firewall_rules(direction: &str) -> Vec<FirewallRule> {
+    use std::process::Command;
+
+    let filter = match direction.to_lowercase().as_str() {
+        "inbound" => "-Direction Inbound",
+        "outbound" => "-Direction Outbound",
+        _ => "",
+    };
+    // Filters come back in pipeline order, so index i matches rule i
+    let script = format!(
+        r#"
+[Console]::OutputEncoding = [System.Text.Encoding]::UTF8
+try {{
+    $rules = @(Get-NetFirewallRule {filter} -ErrorAction Stop)
+    $ports = @($rules | Get-NetFirewallPortFilter)
+    $addrs = @($rules | Get-NetFirewallAddressFilter)
+    $apps = @($rules | Get-NetFirewallApplicationFilter)
+    @(for ($i = 0; $i -lt $rules.Count; $i++) {{
+        [PSCustomObject]@{{
+            Name = $rules[$i].DisplayName
+            Enabled = ("$($rules[$i].Enabled)" -eq 'True')
+            Direction = "$($rules[$i].Direction)".ToLower()
+            Action = "$($rules[$i].Action)".ToLower()
+            LocalPort = ($ports[$i].LocalPort -join ',')
+            RemoteAddress = ($addrs[$i].RemoteAddress -join ',')
+            Program = "$($apps[$i].Program)"
+        }}
+    }}) | ConvertTo-Json -Compress
+}} catch {{ '[]' }}
+"#,
+        filter = filter
+    );
+
+    let output = match crate::powershell::require().and_then(|ps| Command::new(ps)
+        .args(["-NoProfile", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| e.to_string()))
+    {
+        Ok(out) => out,
+        Err(_) => return Vec::new(),
+    };
+
+    let items = match serde_json::from_str::<serde_json::Value>(String::from_utf8_lossy(&output.stdout).trim()) {
+        Ok(serde_json::Value::Array(items)) => items,
+        Ok(serde_json::Value::Null) | Err(_) => Vec::new(),
+        Ok(single) => vec![single],
+    };
+
+    let text = |v: &serde_json::Value| v.as_str().unwrap_or_default().to_string();
+    items.iter()
+        .map(|r| FirewallRule {
+            name: text(&r["Name"]),
+            enabled: r["Enabled"].as_bool().unwrap_or(false),
+            direction: text(&r["Direction"]),
+            action: text(&r["Action"]),
+            local_port: text(&r["LocalPort"]),
+            remote_address: text(&r["RemoteAddress"]),
+            program: text(&r["Program"]),
+        })
+        .collect()
+}
+
+#[cfg(not(windows))]
+pub fn list_firewall_rules(_direction: &str) -> Vec<FirewallRule> {
+    Vec::new()
+}
+
+/// Enabled inbound allow rules exposing RDP, SMB or every port
+pub fn get_risky_open_ports() -> Vec<RiskyFirewallRule> {
+    list_firewall_rules("inbound")
+        .into_iter()
+        .filter_map(|rule| firewall_rule_risk(&rule).map(|reason| RiskyFirewallRule { rule, reason }))
+        .collect()
+}
+
+impl RiskyFirewallRule {
+    /// Rules have no stable id here, a rule edited to open something else is a new finding
+    pub fn report_key(&self) -> String {
+        format!("{}|{}|{}|{}", self.rule.name, self.rule.local_port, self.rule.remote_address, self.rule.program)
+    }
+}
+
+impl SecurityLog {
+    /// Pass the output of `ReportedFindings::fresh` so a rule is logged when it opens, not at every check
+    pub fn from_firewall_rules(rules: &[RiskyFirewallRule]) -> Vec<Self> {
+        rules.iter()
+            .map(|r| SecurityLog {
+                severity: "warning".to_string(),
+                category: "firewall".to_string(),
+                message: format!("{}: {}", r.rule.name, r.reason),
+                details: serde_json::json!({
+                    "rule": r.rule.name,
+                    "local_port": r.rule.local_port,
+                    "remote_address": r.rule.remote_address,
+                    "program": r.rule.program,
+                }),
+            })
+            .collect()
+    }
+}
+
+// ============================================
+// PERSISTENCE POINTS (autostart registry locations beyond the Run keys)
+// ============================================
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PersistenceEntry {
+    pub location: String,   // HKLM\...\Winlogon, HKCU\...\RunOnce...
+    pub name: String,
+    pub value: String,
+    pub risk: String,       // low, medium, high
+    pub reason: String,
+}
+
+#[cfg(windows)]
+const RUN_KEYS: &[(&str, &str)] = &[
+    ("HKLM", r"SOFTWARE\Microsoft\Windows\CurrentVersion\Run"),
+    ("HKLM", r"SOFTWARE\Microsoft\Windows\CurrentVersion\RunOnce"),
+    ("HKLM", r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Run"),
+    ("HKLM", r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\RunOnce"),
+    ("HKCU", r"Software\Microsoft\Windows\CurrentVersion\Run"),
+    ("HKCU", r"Software\Microsoft\Windows\CurrentVersion\RunOnce"),
+];
+
+#[cfg(windows)]
+const WINLOGON_KEY: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\Winlogon";
+#[cfg(windows)]
+const IFEO_KEY: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\Image File Execution Options";
+#[cfg(windows)]
+const WINDOWS_KEY: &str = r"Software\Microsoft\Windows NT\CurrentVersion\Windows";
+
+/// Interpreters and LOLBins that malware launches its payload through
+#[cfg(windows)]
+const SCRIPT_HOSTS: &[&str] = &["powershell", "pwsh", "mshta", "wscript", "cscript", "regsvr32", "rundll32", "cmd.exe /c", "bitsadmin", "certutil"];
+#[cfg(windows)]
+const RISKY_DIRS: &[&str] = &[r"\temp\", r"\appdata\local\temp", r"\users\public\", r"\downloads\", r"\programdata\"];
+
+/// Risk of an autostart command line found in a Run/RunOnce key
+#[cfg(windows)]
+fn autostart_command_risk(command: &str) -> (&'static str, String) {
+    let lower = command.to_lowercase();
+    if let Some(host) = SCRIPT_HOSTS.iter().find(|h| lower.contains(*h)) {
+        return ("high", format!("Lancement via {}", host.trim_end_matches(" /c")));
+    }
+    if RISKY_DIRS.iter().any(|dir| lower.contains(dir)) {
+        return ("medium", "Executable dans un dossier temporaire ou public".to_string());
+    }
+    ("low", String::new())
+}
+
+/// Executable of a command line: the quoted part, or everything up to ".exe"
+#[cfg(windows)]
+fn command_executable(command: &str) -> String {
+    let command = command.trim();
+    let path = match command.strip_prefix('"') {
+        Some(rest) => rest.split('"').next().unwrap_or_default(),
+        None => match command.to_ascii_lowercase().find(".exe") {
+            Some(end) => &command[..end + 4],
+            None => command.split_whitespace().next().unwrap_or_default(),
+        },
+    };
+    let windir = std::env::var("SystemRoot").unwrap_or_else(|_| r"C:\Windows".to_string());
+    let lower = path.to_ascii_lowercase();
+    for var in ["%systemroot%", "%windir%"] {
+        if let Some(rest) = lower.strip_prefix(var) {
+            return format!("{}{}", windir, &path[path.len() - rest.len()..]);
+        }
+    }
+    path.to_string()
+}
+
+/// Binary under the Windows directory (System32, SysWOW64...)
+#[cfg(windows)]
+fn is_system_path(path: &str) -> bool {
+    let windir = std::env::var("SystemRoot").unwrap_or_else(|_| r"C:\Windows".to_string());
+    path.to_lowercase().starts_with(&format!(r"{}\", windir.to_lowercase()))
+}
+
+/// Script hosts are Windows binaries: a signed one from the Windows directory that
+/// loads nothing from a temp or public folder is most often a vendor helper
+#[cfg(windows)]
+fn downgrade_signed_system_commands(entries: &mut [PersistenceEntry]) {
+    let is_candidate = |e: &PersistenceEntry| {
+        e.risk == "high"
+            && e.reason.starts_with("Lancement via")
+            && !RISKY_DIRS.iter().any(|dir| e.value.to_lowercase().contains(dir))
+            && is_system_path(&command_executable(&e.value))
+    };
+    let candidates: Vec<String> = entries.iter()
+        .filter(|e| is_candidate(e))
+        .map(|e| command_executable(&e.value))
+        .collect();
+    if candidates.is_empty() {
+        return;
+    }
+
+    let signatures = crate::diagnostics::get_signatures(&candidates);
+    for entry in entries.iter_mut().filter(|e| is_candidate(e)) {
+        let signed = signatures.get(&command_executable(&entry.value)).map(|s| s.is_signed).unwrap_or(false);
+        if signed {
+            entry.risk = "medium".to_string();
+            entry.reason = format!("{} (binaire systeme signe)", entry.reason);
+        }
+    }
+}
+
+/// "explorer.exe" or a full path to it
+#[cfg(windows)]
+fn is_default_shell(value: &str) -> bool {
+    let lower = value.trim().to_lowercase();
+    lower == "explorer.exe" || lower.ends_with(r"\windows\explorer.exe")
+}
+
+/// "C:\Windows\system32\userinit.exe," with or without the trailing comma
+#[cfg(windows)]
+fn is_default_userinit(value: &str) -> bool {
+    let programs: Vec<String> = value.split(',')
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect();
+    programs.len() == 1 && (programs[0] == "userinit.exe" || programs[0].ends_with(r"\system32\userinit.exe"))
+}
+
+#[cfg(windows)]
+fn hive(name: &str) -> RegKey {
+    match name {
+        "HKLM" => RegKey::predef(HKEY_LOCAL_MACHINE),
+        _ => RegKey::predef(HKEY_CURRENT_USER),
+    }
+}
+
+/// String values of a key, skipping the unnamed default value
+#[cfg(windows)]
+fn string_values(key: &RegKey) -> Vec<(String, String)> {
+    key.enum_values()
+        .filter_map(|v| v.ok())
+        .filter(|(name, _)| !name.is_empty())
+        .filter_map(|(name, _)| key.get_value::<String, _>(&name).ok().map(|value| (name, value)))
+        .collect()
+}
+
+/// Run/RunOnce, Winlogon Shell/Userinit, IFEO debuggers and the legacy Load/Run/AppInit_DLLs values.
+/// Run entries are listed with a risk level; the other locations only when they differ from Windows defaults.
+#[cfg(windows)]
+pub fn scan_persistence_points() -> Vec<PersistenceEntry> {
+    let mut entries: Vec<PersistenceEntry> = Vec::new();
+    let mut push = |location: String, name: &str, value: &str, risk: &str, reason: String| {
+        // The same value can be reached twice (e.g. a RunOnce entry mirrored in Run)
+        if entries.iter().any(|e| e.location == location && e.name == name && e.value == value) {
+            return;
+        }
+        entries.push(PersistenceEntry {
+            location,
+            name: name.to_string(),
+            value: value.to_string(),
+            risk: risk.to_string(),
+            reason,
+        });
+    };
+
+    for (root, path) in RUN_KEYS {
+        if let Ok(key) = hive(root).open_subkey(path) {
+            for (name, value) in string_values(&key) {
+                let (risk, reason) = autostart_command_risk(&value);
+                push(format!(r"{}\{}", root, path), &name, &value, risk, reason);
+            }
+        }
+    }
+
+    // Winlogon: Shell and Userinit run at every logon; HKCU normally has neither
+    for root in ["HKLM", "HKCU"] {
+        let key = match hive(root).open_subkey(WINLOGON_KEY) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+        let location = format!(r"{}\{}", root, WINLOGON_KEY);
+        if let Ok(shell) = key.get_value::<String, _>("Shell") {
+            if root == "HKCU" || !is_default_shell(&shell) {
+                push(location.clone(), "Shell", &shell, "high", "Shell de session different de explorer.exe".to_string());
+            }
+        }
+        if let Ok(userinit) = key.get_value::<String, _>("Userinit") {
+            if root == "HKCU" || !is_default_userinit(&userinit) {
+                push(location, "Userinit", &userinit, "high", "Userinit ne pointe pas uniquement vers userinit.exe".to_string());
+            }
+        }
+    }
+
+    // Image File Execution Options: a Debugger value hijacks every launch of that exe
+    if let Ok(ifeo) = hive("HKLM").open_subkey(IFEO_KEY) {
+        for exe in ifeo.enum_keys().filter_map(|k| k.ok()) {
+            let debugger = ifeo.open_subkey(&exe).and_then(|k| k.get_value::<String, _>("Debugger"));
+            if let Ok(debugger) = debugger {
+                if !debugger.trim().is_empty() {
+                    push(
+                        format!(r"HKLM\{}\{}", IFEO_KEY, exe),
+                        "Debugger",
+                        &debugger,
+                        "high",
+                        format!("Detournement du lancement de {}", exe),
+                    );
+                }
+            }
+        }
+    }
+
+    // Legacy Windows key: Load/Run (per user) and AppInit_DLLs (machine-wide, injected in every GUI process)
+    for (root, names) in [("HKCU", &["Load", "Run"][..]), ("HKLM", &["AppInit_DLLs"][..])] {
+        if let Ok(key) = hive(root).open_subkey(WINDOWS_KEY) {
+            for name in names {
+                if let Ok(value) = key.get_value::<String, _>(*name) {
+                    if !value.trim().is_empty() {
+                        push(format!(r"{}\{}", root, WINDOWS_KEY), name, &value, "high", format!("Valeur {} non vide", name));
+                    }
+                }
+            }
+        }
+    }
+
+    downgrade_signed_system_commands(&mut entries);
+    entries
+}
+
+#[cfg(not(windows))]
+pub fn scan_persistence_points() -> Vec<PersistenceEntry> {
+    Vec::new()
+}
+
+impl SecurityLog {
+    /// High-risk persistence points only, the rest is normal autostart software.
+    /// Pass the output of `ReportedFindings::fresh` so each entry is reported once.
+    pub fn from_persistence_points(entries: &[PersistenceEntry]) -> Vec<Self> {
+        entries.iter()
+            .filter(|e| e.risk == "high")
+            .map(|e| SecurityLog {
+                severity: "warning".to_string(),
+                category: "persistence".to_string(),
+                message: format!("{} ({}): {}", e.name, e.reason, e.value),
+                details: serde_json::json!({
+                    "location": e.location,
+                    "name": e.name,
+                    "value": e.value,
+                }),
+            })
+            .collect()
+    }
+}
+
+/// Findings sent in the previous report, so a periodic scan only logs what appeared or changed
+#[derive(Default)]
+pub struct ReportedFindings {
+    keys: std::collections::HashSet<String>,
+}
+
+impl ReportedFindings {
+    /// Items whose key was not in the previous report. The current keys replace the old
+    /// ones, so a finding that disappears and comes back is reported again.
+    pub fn fresh<T>(&mut self, items: Vec<T>, key: impl Fn(&T) -> String) -> Vec<T> {
+        let previous = std::mem::take(&mut self.keys);
+        let mut fresh = Vec::new();
+        for item in items {
+            let key = key(&item);
+            // insert() is false for a duplicate within the same scan
+            if self.keys.insert(key.clone()) && !previous.contains(&key) {
+                fresh.push(item);
+            }
+        }
+        fresh
+    }
+}
+
+impl PersistenceEntry {
+    /// A changed value counts as a new finding
+    pub fn report_key(&self) -> String {
+        format!("{}|{}|{}", self.location, self.name, self.value)
+    }
+}
+
+// ============================================
+// FULL SECURITY SCAN REPORT (scripts/full_security_scan.ps1)
+// ============================================
+
+/// The scan script prints its JSON report between these two lines
+pub const SCAN_JSON_START: &str = "---MDIAG-JSON-START---";
+pub const SCAN_JSON_END: &str = "---MDIAG-JSON-END---";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SecurityScanReport {
+    pub timestamp: String,
+    pub hostname: String,
+    pub username: String,
+    pub os_version: String,
+    pub score: u8,
+    pub status: String,     // ok, warning, critical
+    pub message: String,
+    pub advice: String,
+    pub summary: SecurityScanSummary,
+    pub sections: Vec<SecurityScanSection>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SecurityScanSummary {
+    pub critical: u32,
+    pub warning: u32,
+    pub info: u32,
+    pub ok: u32,
+    pub total: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SecurityScanSection {
+    pub title: String,
+    pub icon: String,
+    pub status: String,     // ok, info, warning, critical
+    pub explanation: String,
+    pub action: String,
+    /// Free-form per section: always a summary, plus details specific to the check
+    pub items: serde_json::Value,
+}
+
+/// Text between the sentinel lines, None if the script never reached its output step
+pub fn extract_scan_json(stdout: &str) -> Option<String> {
+    let start = stdout.find(SCAN_JSON_START)? + SCAN_JSON_START.len();
+    let end = start + stdout[start..].find(SCAN_JSON_END)?;
+    Some(stdout[start..end].trim().to_string())
+}
+
+pub fn parse_scan_report(stdout: &str, stderr: &str) -> Result<SecurityScanReport, String> {
+    let json = extract_scan_json(stdout).ok_or_else(|| {
+        let stderr = stderr.trim();
+        if stderr.is_empty() {
+            "Rapport d'analyse absent de la sortie du script".to_string()
+        } else {
+            format!("Rapport d'analyse absent de la sortie du script. Erreurs PowerShell:\n{}", stderr)
+        }
+    })?;
+
+    serde_json::from_str(&json).map_err(|e| format!("Rapport d'analyse invalide: {}", e))
+}
@@ -0,0 +1,209 @@
+// ============================================
+// MICRODIAG AGENT - Windows Services
+// List / start / stop / startup type through the Service Control Manager
+// ============================================
+
+use crate::error::MicrodiagError;
+use serde::Serialize;
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ServiceInfo {
+    pub name: String,
+    pub display_name: String,
+    pub status: String,      // running, stopped, start_pending, stop_pending, paused...
+    pub start_type: String,  // auto, manual, disabled
+}
+
+/// Time allowed for a service to reach the requested state
+#[cfg(windows)]
+const STATE_TIMEOUT_SECS: u64 = 30;
+
+// ============================================
+// LIST
+// ============================================
+
+#[cfg(windows)]
+pub fn list_services() -> Result<Vec<ServiceInfo>, MicrodiagError> {
+    use std::collections::HashMap;
+    use wmi::{COMLibrary, Variant, WMIConnection};
+
+    let wmi_con = COMLibrary::new()
+        .and_then(WMIConnection::new)
+        .map_err(|e| MicrodiagError::wmi(format!("Connexion WMI impossible: {}", e)))?;
+
+    let rows: Vec<HashMap<String, Variant>> = crate::wmi_cache::cached_query(&wmi_con, "SELECT Name, DisplayName, State, StartMode FROM Win32_Service")
+        .map_err(|e| MicrodiagError::wmi(format!("Requete Win32_Service: {}", e)))?;
+
+    let text = |row: &HashMap<String, Variant>, key: &str| match row.get(key) {
+        Some(Variant::String(s)) => s.clone(),
+        _ => String::new(),
+    };
+
+    let mut services: Vec<ServiceInfo> = rows.iter()
+        .map(|row| ServiceInfo {
+            name: text(row, "Name"),
+            display_name: text(row, "DisplayName"),
+            status: text(row, "State").to_lowercase().replace(' ', "_"),
+            start_type: match text(row, "StartMode").as_str() {
+                "Auto" => "auto",
+                "Manual" => "manual",
+                "Disabled" => "disabled",
+                "Boot" | "System" => "system",
+                _ => "unknown",
+            }.to_string(),
+        })
+        .filter(|s| !s.name.is_empty())
+        .collect();
+
+    services.sort_by_key(|s| s.display_name.to_lowercase());
+    Ok(services)
+}
+
+#[cfg(not(windows))]
+pub fn list_services() -> Result<Vec<ServiceInfo>, MicrodiagError> {
+    Err(MicrodiagError::wmi("Services Windows disponibles uniquement sur Windows"))
+}
+
+// ============================================
+// CONTROL (sc.exe)
+// ============================================
+
+#[cfg(windows)]
+fn sc(args: &[&str]) -> Result<String, String> {
+    use std::process::Command;
+
+    let output = Command::new("sc")
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Impossible de lancer sc: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        // sc prints "[SC] OpenService FAILED 5:" style messages on stdout
+        let detail = stdout.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Err(format!("Erreur service (code {}): {}", output.status.code().unwrap_or(-1), detail))
+    }
+}
+
+#[cfg(windows)]
+fn wmi_connection() -> Result<wmi::WMIConnection, String> {
+    wmi::COMLibrary::new()
+        .and_then(wmi::WMIConnection::new)
+        .map_err(|e| format!("Connexion WMI impossible: {}", e))
+}
+
+/// Current state from Win32_Service, same form as ServiceInfo::status ("running", "stopped").
+/// Uncached and language-independent, unlike the text printed by `sc query`.
+#[cfg(windows)]
+fn query_state(wmi_con: &wmi::WMIConnection, name: &str) -> Option<String> {
+    use std::collections::HashMap;
+    use wmi::Variant;
+
+    let query = format!("SELECT State FROM Win32_Service WHERE Name = '{}'", name.replace('\'', "\\'"));
+    let rows: Vec<HashMap<String, Variant>> = wmi_con.raw_query(&query).ok()?;
+    match rows.first().and_then(|row| row.get("State")) {
+        Some(Variant::String(state)) => Some(state.to_lowercase().replace(' ', "_")),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+fn wait_for_state(wmi_con: &wmi::WMIConnection, name: &str, wanted: &str) -> Result<(), String> {
+    use std::time::{Duration, Instant};
+
+    let deadline = Instant::now() + Duration::from_secs(STATE_TIMEOUT_SECS);
+    while Instant::now() < deadline {
+        if query_state(wmi_con, name).as_deref() == Some(wanted) {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+    Err(format!("Le service {} n'a pas atteint l'etat {} apres {}s", name, wanted, STATE_TIMEOUT_SECS))
+}
+
+fn validate_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\', '"']) {
+        return Err(format!("Nom de service invalide: {}", name));
+    }
+    Ok(name)
+}
+
+/// action: start, stop or restart. Waits until the service reaches the target state.
+#[cfg(windows)]
+pub fn set_service_state(name: &str, action: &str) -> Result<String, String> {
+    let name = validate_name(name)?;
+    let wmi_con = wmi_connection()?;
+    let already = |state: &str| query_state(&wmi_con, name).as_deref() == Some(state);
+
+    let result = match action {
+        "start" => {
+            if !already("running") {
+                sc(&["start", name])?;
+                wait_for_state(&wmi_con, name, "running")?;
+            }
+            Ok(format!("Service {} demarre", name))
+        }
+        "stop" => {
+            if !already("stopped") {
+                sc(&["stop", name])?;
+                wait_for_state(&wmi_con, name, "stopped")?;
+            }
+            Ok(format!("Service {} arrete", name))
+        }
+        "restart" => {
+            if !already("stopped") {
+                sc(&["stop", name])?;
+                wait_for_state(&wmi_con, name, "stopped")?;
+            }
+            sc(&["start", name])?;
+            wait_for_state(&wmi_con, name, "running")?;
+            Ok(format!("Service {} redemarre", name))
+        }
+        other => Err(format!("Action inconnue: {} (start, stop ou restart)", other)),
+    };
+    // The next list_services must show the new state, not the cached one
+    crate::wmi_cache::invalidate_wmi_cache();
+    result
+}
+
+#[cfg(not(windows))]
+pub fn set_service_state(name: &str, _action: &str) -> Result<String, String> {
+    validate_name(name)?;
+    Err("Gestion des services disponible uniquement sur Windows".to_string())
+}
+
+/// mode: auto, manual or disabled
+#[cfg(windows)]
+pub fn set_service_start_type(name: &str, mode: &str) -> Result<String, String> {
+    let name = validate_name(name)?;
+    let sc_mode = match mode {
+        "auto" => "auto",
+        "manual" => "demand",
+        "disabled" => "disabled",
+        other => return Err(format!("Mode inconnu: {} (auto, manual ou disabled)", other)),
+    };
+
+    // sc expects "start=" and the value as two separate arguments
+    sc(&["config", name, "start=", sc_mode])?;
+    crate::wmi_cache::invalidate_wmi_cache();
+    Ok(format!("Demarrage de {} regle sur {}", name, mode))
+}
+
+#[cfg(not(windows))]
+pub fn set_service_start_type(name: &str, _mode: &str) -> Result<String, String> {
+    validate_name(name)?;
+    Err("Gestion des services disponible uniquement sur Windows".to_string())
+}
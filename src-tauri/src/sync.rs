@@ -4,14 +4,17 @@
 // ============================================
 
 use crate::config::*;
-use crate::database::{Database, LocalScript};
-use std::sync::Arc;
+use crate::database::{Database, LocalScript, SettingEntry};
+use crate::error::MicrodiagError;
+use crate::hashing;
+use std::sync::{Arc, Mutex};
 use tokio::time::{interval, Duration};
 
 // ============================================
 // SYNC STATUS
 // ============================================
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(tag = "state", content = "detail", rename_all = "snake_case")]
 pub enum SyncStatus {
     Synced,
     Syncing,
@@ -20,10 +23,77 @@ pub enum SyncStatus {
     Error(String),
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SyncReport {
+    pub scripts: SyncStatus,
+    pub metrics: SyncStatus,          // Heartbeats queued while offline
+    pub queue_depth: usize,
+    pub last_success: Option<String>, // Last settings sync, UTC
+    pub online: bool,
+}
+
+impl SyncReport {
+    /// Same state for the UI, whatever the timestamps say
+    pub fn same_state(&self, other: &SyncReport) -> bool {
+        self.scripts == other.scripts
+            && self.metrics == other.metrics
+            && self.queue_depth == other.queue_depth
+            && self.online == other.online
+    }
+}
+
+/// Error of the last scripts sync, None once one succeeded
+static SCRIPTS_SYNC_ERROR: Mutex<Option<String>> = Mutex::new(None);
+/// False until the first scripts sync finished, either way
+static SCRIPTS_SYNC_RAN: Mutex<bool> = Mutex::new(false);
+
+pub async fn get_sync_report(db: &Database) -> SyncReport {
+    let online = check_online_status().await;
+    let (queue_depth, abandoned) = db.count_sync_queue().unwrap_or((0, 0));
+
+    let scripts_error = SCRIPTS_SYNC_ERROR.lock().map(|e| e.clone()).unwrap_or(None);
+    let scripts_ran = SCRIPTS_SYNC_RAN.lock().map(|r| *r).unwrap_or(false);
+    let scripts = match scripts_error {
+        _ if !online => SyncStatus::Offline,
+        Some(e) => SyncStatus::Error(e),
+        None if !scripts_ran => SyncStatus::Syncing,
+        None => SyncStatus::Synced,
+    };
+
+    let metrics = if abandoned > 0 {
+        SyncStatus::Error(format!("{} envoi(s) abandonne(s) apres {} essais", abandoned, crate::database::SYNC_MAX_RETRIES))
+    } else if queue_depth > 0 {
+        SyncStatus::Pending(queue_depth)
+    } else if !online {
+        SyncStatus::Offline
+    } else {
+        SyncStatus::Synced
+    };
+
+    SyncReport {
+        scripts,
+        metrics,
+        queue_depth,
+        last_success: db.get_setting(SETTINGS_LAST_SYNC_KEY).ok().flatten(),
+        online,
+    }
+}
+
 // ============================================
 // SCRIPTS SYNC
 // ============================================
-pub async fn sync_scripts_from_supabase(db: &Arc<Database>) -> Result<usize, String> {
+pub async fn sync_scripts_from_supabase(db: &Arc<Database>) -> Result<usize, MicrodiagError> {
+    let result = fetch_scripts_from_supabase(db).await;
+    if let Ok(mut error) = SCRIPTS_SYNC_ERROR.lock() {
+        *error = result.as_ref().err().map(|e| e.to_string());
+    }
+    if let Ok(mut ran) = SCRIPTS_SYNC_RAN.lock() {
+        *ran = true;
+    }
+    result
+}
+
+async fn fetch_scripts_from_supabase(db: &Arc<Database>) -> Result<usize, MicrodiagError> {
     println!("[Sync] Starting scripts sync from Supabase...");
     let client = reqwest::Client::new();
 
@@ -38,12 +108,12 @@ pub async fn sync_scripts_from_supabase(db: &Arc<Database>) -> Result<usize, Str
         .await
         .map_err(|e| {
             println!("[Sync] Network error: {}", e);
-            format!("Network error: {}", e)
+            MicrodiagError::from(e)
         })?;
 
     println!("[Sync] Response status: {}", response.status());
     if !response.status().is_success() {
-        return Err(format!("API error: {}", response.status()));
+        return Err(MicrodiagError::from_status(response.status()));
     }
 
     let scripts: Vec<serde_json::Value> = response
@@ -51,7 +121,7 @@ pub async fn sync_scripts_from_supabase(db: &Arc<Database>) -> Result<usize, Str
         .await
         .map_err(|e| {
             println!("[Sync] JSON parse error: {}", e);
-            format!("JSON error: {}", e)
+            MicrodiagError::Parse(format!("JSON error: {}", e))
         })?;
 
     println!("[Sync] Received {} scripts from API", scripts.len());
@@ -71,8 +141,25 @@ pub async fn sync_scripts_from_supabase(db: &Arc<Database>) -> Result<usize, Str
             requires_admin: script["requires_admin"].as_bool().unwrap_or(false),
             estimated_time: script["estimated_time"].as_str().map(|s| s.to_string()),
             success_message: script["success_message"].as_str().map(|s| s.to_string()),
+            is_favorite: false,
+            sort_order: 0,
+            is_local: false,
         };
 
+        // Reject scripts whose code doesn't match the published checksum
+        if let Some(expected) = script["checksum"].as_str() {
+            if !hashing::digest_matches(&hashing::hash_bytes_sha256(local_script.code.as_bytes()), expected) {
+                println!("[Sync] Checksum mismatch for script {}, skipped", local_script.slug);
+                continue;
+            }
+        }
+
+        // Scripts written on this machine win over the cloud copy
+        if db.is_local_script(&local_script.id, &local_script.slug).unwrap_or(false) {
+            println!("[Sync] Script {} is local, skipped", local_script.slug);
+            continue;
+        }
+
         if !local_script.slug.is_empty() && !local_script.code.is_empty() {
             if let Err(e) = db.upsert_script(&local_script) {
                 println!("[Sync] Error saving script {}: {}", local_script.slug, e);
@@ -86,10 +173,125 @@ pub async fn sync_scripts_from_supabase(db: &Arc<Database>) -> Result<usize, Str
     Ok(count)
 }
 
+// ============================================
+// SETTINGS SYNC (last write wins)
+// ============================================
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SettingsSyncReport {
+    pub pushed: usize,
+    pub pulled: usize,
+    pub synced_at: String,
+}
+
+const SQLITE_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Supabase returns RFC 3339, SQLite stores "YYYY-MM-DD HH:MM:SS" UTC: compare in the SQLite form
+fn remote_to_sqlite_timestamp(ts: &str) -> Option<String> {
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|dt| dt.with_timezone(&chrono::Utc).format(SQLITE_TIMESTAMP_FORMAT).to_string())
+}
+
+fn sqlite_to_remote_timestamp(ts: &str) -> String {
+    chrono::NaiveDateTime::parse_from_str(ts, SQLITE_TIMESTAMP_FORMAT)
+        .map(|dt| dt.and_utc().to_rfc3339())
+        .unwrap_or_else(|_| chrono::Utc::now().to_rfc3339())
+}
+
+async fn fetch_remote_settings(client: &reqwest::Client, device_token: &str) -> Result<Vec<SettingEntry>, MicrodiagError> {
+    let response = client
+        .get(format!(
+            "{}/rest/v1/device_settings?device_token=eq.{}&select=key,value,updated_at",
+            SUPABASE_URL, device_token
+        ))
+        .header("Authorization", format!("Bearer {}", SUPABASE_ANON_KEY))
+        .header("apikey", SUPABASE_ANON_KEY)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(MicrodiagError::from_status(response.status()));
+    }
+
+    let rows: Vec<serde_json::Value> = response.json().await?;
+    Ok(rows.iter()
+        .filter_map(|row| Some(SettingEntry {
+            key: row["key"].as_str()?.to_string(),
+            value: row["value"].as_str()?.to_string(),
+            updated_at: remote_to_sqlite_timestamp(row["updated_at"].as_str()?)?,
+        }))
+        .collect())
+}
+
+/// Pushes settings changed since the last sync and pulls newer remote ones.
+/// When both sides changed the same key, the most recent `updated_at` wins.
+pub async fn sync_settings(db: &Arc<Database>, device_token: &str) -> Result<SettingsSyncReport, MicrodiagError> {
+    let client = reqwest::Client::new();
+    let last_synced = db.get_setting(SETTINGS_LAST_SYNC_KEY)?;
+
+    let remote = fetch_remote_settings(&client, device_token).await?;
+    let local: Vec<SettingEntry> = db.get_settings_with_timestamps()?
+        .into_iter()
+        .filter(|s| s.key != SETTINGS_LAST_SYNC_KEY)
+        .collect();
+
+    // Pull: remote value differs and is newer than ours (or we don't have it)
+    let mut pulled = 0;
+    for entry in &remote {
+        let newer = match local.iter().find(|l| l.key == entry.key) {
+            Some(l) => l.value != entry.value && entry.updated_at > l.updated_at,
+            None => true,
+        };
+        if newer && entry.key != SETTINGS_LAST_SYNC_KEY {
+            db.set_setting_at(&entry.key, &entry.value, &entry.updated_at)?;
+            pulled += 1;
+        }
+    }
+
+    // Push: changed locally since the last sync and not older than the cloud copy
+    let to_push: Vec<serde_json::Value> = local.iter()
+        .filter(|l| last_synced.as_deref().map(|last| l.updated_at.as_str() > last).unwrap_or(true))
+        .filter(|l| match remote.iter().find(|r| r.key == l.key) {
+            Some(r) => r.value != l.value && l.updated_at >= r.updated_at,
+            None => true,
+        })
+        .map(|l| serde_json::json!({
+            "device_token": device_token,
+            "key": l.key,
+            "value": l.value,
+            "updated_at": sqlite_to_remote_timestamp(&l.updated_at),
+        }))
+        .collect();
+
+    if !to_push.is_empty() {
+        let response = client
+            .post(format!("{}/rest/v1/device_settings?on_conflict=device_token,key", SUPABASE_URL))
+            .header("Authorization", format!("Bearer {}", SUPABASE_ANON_KEY))
+            .header("apikey", SUPABASE_ANON_KEY)
+            .header("Content-Type", "application/json")
+            .header("Prefer", "resolution=merge-duplicates,return=minimal")
+            .json(&to_push)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(MicrodiagError::from_status(response.status()));
+        }
+    }
+
+    let synced_at = chrono::Utc::now().format(SQLITE_TIMESTAMP_FORMAT).to_string();
+    db.set_setting(SETTINGS_LAST_SYNC_KEY, &synced_at)?;
+
+    if pulled > 0 || !to_push.is_empty() {
+        println!("[Sync] Settings: {} pushed, {} pulled", to_push.len(), pulled);
+    }
+    Ok(SettingsSyncReport { pushed: to_push.len(), pulled, synced_at })
+}
+
 // ============================================
 // DEVICE ID CACHE
 // ============================================
-pub async fn get_or_fetch_device_id(db: &Arc<Database>, device_token: &str) -> Result<String, String> {
+pub async fn get_or_fetch_device_id(db: &Arc<Database>, device_token: &str) -> Result<String, MicrodiagError> {
     // Check cache first
     if let Ok(Some(cached_id)) = db.get_cache("device_id") {
         return Ok(cached_id);
@@ -105,13 +307,9 @@ pub async fn get_or_fetch_device_id(db: &Arc<Database>, device_token: &str) -> R
         .header("Authorization", format!("Bearer {}", SUPABASE_ANON_KEY))
         .header("apikey", SUPABASE_ANON_KEY)
         .send()
-        .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .await?;
 
-    let devices: Vec<serde_json::Value> = response
-        .json()
-        .await
-        .map_err(|e| format!("JSON error: {}", e))?;
+    let devices: Vec<serde_json::Value> = response.json().await?;
 
     if let Some(device) = devices.first() {
         if let Some(id) = device["id"].as_str() {
@@ -121,7 +319,7 @@ pub async fn get_or_fetch_device_id(db: &Arc<Database>, device_token: &str) -> R
         }
     }
 
-    Err("Device not found".to_string())
+    Err(MicrodiagError::NotFound("Device not found".to_string()))
 }
 
 // ============================================
@@ -138,7 +336,7 @@ pub struct RemoteExecution {
     pub status: String,
 }
 
-pub async fn check_remote_executions(db: &Arc<Database>, device_token: &str) -> Result<Vec<RemoteExecution>, String> {
+pub async fn check_remote_executions(db: &Arc<Database>, device_token: &str) -> Result<Vec<RemoteExecution>, MicrodiagError> {
     // Get cached device ID
     let device_id = match get_or_fetch_device_id(db, device_token).await {
         Ok(id) => id,
@@ -154,8 +352,7 @@ pub async fn check_remote_executions(db: &Arc<Database>, device_token: &str) ->
         .header("Authorization", format!("Bearer {}", SUPABASE_ANON_KEY))
         .header("apikey", SUPABASE_ANON_KEY)
         .send()
-        .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .await?;
 
     if !response.status().is_success() {
         return Ok(vec![]);
@@ -193,7 +390,7 @@ pub async fn update_remote_execution(
     status: &str,
     output: Option<&str>,
     error: Option<&str>,
-) -> Result<(), String> {
+) -> Result<(), MicrodiagError> {
     let client = reqwest::Client::new();
 
     let mut payload = serde_json::json!({
@@ -218,20 +415,86 @@ pub async fn update_remote_execution(
         .header("Prefer", "return=minimal")
         .json(&payload)
         .send()
+        .await?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(MicrodiagError::from_status(response.status()))
+    }
+}
+
+// ============================================
+// HEARTBEAT DELIVERY + OFFLINE QUEUE
+// ============================================
+pub const HEARTBEAT_QUEUE_TABLE: &str = "heartbeat";
+
+#[derive(Debug)]
+pub enum HeartbeatError {
+    /// Request never reached the server: worth queueing
+    Offline(String),
+    /// Server answered with an error: retrying the same payload won't help much
+    Rejected(String),
+}
+
+pub async fn post_heartbeat(payload: &serde_json::Value) -> Result<(), HeartbeatError> {
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/functions/v1/heartbeat", SUPABASE_URL))
+        .header("Authorization", format!("Bearer {}", SUPABASE_ANON_KEY))
+        .header("Content-Type", "application/json")
+        .json(payload)
+        .send()
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(|e| HeartbeatError::Offline(format!("Network error: {}", e)))?;
 
     if response.status().is_success() {
         Ok(())
     } else {
-        Err(format!("API error: {}", response.status()))
+        Err(HeartbeatError::Rejected(response.text().await.unwrap_or_default()))
+    }
+}
+
+/// Replays queued heartbeats oldest first; stops at the first network failure
+pub async fn flush_queued_heartbeats(db: &Arc<Database>) -> Result<usize, String> {
+    let items = db.get_pending_sync_items(100).map_err(|e| e.to_string())?;
+    let mut sent = 0;
+
+    for item in items.into_iter().filter(|i| i.table_name == HEARTBEAT_QUEUE_TABLE) {
+        let payload: serde_json::Value = match serde_json::from_str(&item.data) {
+            Ok(p) => p,
+            Err(e) => {
+                let _ = db.mark_sync_failed(item.id, &format!("JSON error: {}", e));
+                continue;
+            }
+        };
+
+        match post_heartbeat(&payload).await {
+            Ok(()) => {
+                let _ = db.mark_sync_success(item.id);
+                sent += 1;
+            }
+            Err(HeartbeatError::Offline(e)) => {
+                let _ = db.mark_sync_failed(item.id, &e);
+                break;
+            }
+            Err(HeartbeatError::Rejected(e)) => {
+                let _ = db.mark_sync_failed(item.id, &e);
+            }
+        }
     }
+
+    if sent > 0 {
+        println!("[Sync] Replayed {} queued heartbeats", sent);
+    }
+    Ok(sent)
 }
 
 // ============================================
 // BACKGROUND SYNC LOOP
 // ============================================
-pub fn start_sync_loop(db: Arc<Database>) {
+pub fn start_sync_loop(db: Arc<Database>, device_token: String) {
     tauri::async_runtime::spawn(async move {
         // Initial sync after 5 seconds
         tokio::time::sleep(Duration::from_secs(5)).await;
@@ -241,6 +504,11 @@ pub fn start_sync_loop(db: Arc<Database>) {
             println!("[Sync] Initial scripts sync failed: {}", e);
         }
 
+        // Restores the cloud config right away after a reinstall
+        if let Err(e) = sync_settings(&db, &device_token).await {
+            println!("[Sync] Initial settings sync failed: {}", e);
+        }
+
         // Periodic sync every 5 minutes
         let mut ticker = interval(Duration::from_secs(300));
 
@@ -252,6 +520,16 @@ pub fn start_sync_loop(db: Arc<Database>) {
                 println!("[Sync] Scripts sync failed: {}", e);
             }
 
+            // Heartbeats missed while offline, then settings both ways
+            if check_online_status().await {
+                if let Err(e) = flush_queued_heartbeats(&db).await {
+                    println!("[Sync] Heartbeat replay failed: {}", e);
+                }
+                if let Err(e) = sync_settings(&db, &device_token).await {
+                    println!("[Sync] Settings sync failed: {}", e);
+                }
+            }
+
             // Cleanup old data
             if let Err(e) = db.cleanup_old_metrics() {
                 println!("[Sync] Metrics cleanup failed: {}", e);
@@ -259,6 +537,10 @@ pub fn start_sync_loop(db: Arc<Database>) {
             if let Err(e) = db.cleanup_expired_cache() {
                 println!("[Sync] Cache cleanup failed: {}", e);
             }
+            if let Err(e) = db.cleanup_old_temperatures() {
+                println!("[Sync] Temperature cleanup failed: {}", e);
+            }
+            crate::godmode::cleanup_backups(BACKUP_RETENTION_DAYS, BACKUP_KEEP_PER_PREFIX);
         }
     });
 }
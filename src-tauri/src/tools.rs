@@ -0,0 +1,300 @@
+// ============================================
+// MICRODIAG AGENT - External Tools
+// Third-party executables the agent drives (SMART, sensors...):
+// portable copy under tools\ first, then system installs, then PATH
+// ============================================
+
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+pub const CRYSTALDISKINFO: &str = "crystaldiskinfo";
+pub const LIBREHARDWAREMONITOR: &str = "librehardwaremonitor";
+pub const SMARTCTL: &str = "smartctl";
+
+pub enum VersionProbe {
+    /// Version resource of the executable, for GUI tools without a CLI
+    FileVersion,
+    /// Run the executable with these arguments and read the first version-like token
+    Command(&'static [&'static str]),
+}
+
+pub struct ExternalTool {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Folder under tools\ next to the agent holding the portable copy
+    pub bundled_dir: &'static str,
+    /// Executable names, preferred first
+    pub exe_names: &'static [&'static str],
+    /// System install folders: (environment variable, path below it)
+    pub install_dirs: &'static [(&'static str, &'static str)],
+    pub winget_id: &'static str,
+    pub version_probe: VersionProbe,
+}
+
+pub const TOOL_REGISTRY: &[ExternalTool] = &[
+    ExternalTool {
+        id: CRYSTALDISKINFO,
+        name: "CrystalDiskInfo",
+        bundled_dir: "CrystalDiskInfo",
+        exe_names: &["DiskInfo64.exe", "DiskInfo32.exe"],
+        install_dirs: &[
+            ("ProgramFiles", "CrystalDiskInfo"),
+            ("ProgramFiles(x86)", "CrystalDiskInfo"),
+            ("LOCALAPPDATA", r"Microsoft\WinGet\Packages\CrystalDewWorld.CrystalDiskInfo_Microsoft.Winget.Source_8wekyb3d8bbwe"),
+            ("LOCALAPPDATA", r"Programs\CrystalDiskInfo"),
+            ("LOCALAPPDATA", "CrystalDiskInfo"),
+        ],
+        winget_id: "CrystalDewWorld.CrystalDiskInfo",
+        version_probe: VersionProbe::FileVersion,
+    },
+    ExternalTool {
+        id: LIBREHARDWAREMONITOR,
+        name: "LibreHardwareMonitor",
+        bundled_dir: "LibreHardwareMonitor",
+        exe_names: &["LibreHardwareMonitor.exe"],
+        install_dirs: &[
+            ("ProgramFiles", "LibreHardwareMonitor"),
+            ("ProgramFiles(x86)", "LibreHardwareMonitor"),
+            ("LOCALAPPDATA", r"Microsoft\WinGet\Packages\LibreHardwareMonitor.LibreHardwareMonitor_Microsoft.Winget.Source_8wekyb3d8bbwe"),
+            ("LOCALAPPDATA", r"Programs\LibreHardwareMonitor"),
+            ("LOCALAPPDATA", "LibreHardwareMonitor"),
+        ],
+        winget_id: "LibreHardwareMonitor.LibreHardwareMonitor",
+        version_probe: VersionProbe::FileVersion,
+    },
+    ExternalTool {
+        id: SMARTCTL,
+        name: "smartctl",
+        bundled_dir: r"smartmontools\bin",
+        exe_names: &["smartctl.exe"],
+        install_dirs: &[("ProgramFiles", r"smartmontools\bin")],
+        winget_id: "smartmontools.smartmontools",
+        version_probe: VersionProbe::Command(&["--version"]),
+    },
+];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ToolStatus {
+    pub id: String,
+    pub name: String,
+    pub winget_id: String,
+    pub installed: bool,
+    pub source: Option<String>,             // bundled, installed, path
+    pub path: Option<String>,
+    pub version: Option<String>,
+    pub available_version: Option<String>,  // Newer winget release, only checked for system installs
+}
+
+pub fn get_tool(id: &str) -> Option<&'static ExternalTool> {
+    TOOL_REGISTRY.iter().find(|t| t.id == id)
+}
+
+fn env_root(var: &str) -> Option<PathBuf> {
+    match std::env::var(var) {
+        Ok(value) if !value.is_empty() => Some(PathBuf::from(value)),
+        _ => match var {
+            "ProgramFiles" => Some(PathBuf::from(r"C:\Program Files")),
+            "ProgramFiles(x86)" => Some(PathBuf::from(r"C:\Program Files (x86)")),
+            _ => None,
+        },
+    }
+}
+
+/// First existing executable among the tool's names in `dirs`
+fn probe_dirs(tool: &ExternalTool, dirs: &[PathBuf]) -> Option<PathBuf> {
+    dirs.iter()
+        .flat_map(|dir| tool.exe_names.iter().map(move |exe| dir.join(exe)))
+        .find(|path| path.exists())
+}
+
+#[cfg(windows)]
+fn find_in_path(tool: &ExternalTool) -> Option<PathBuf> {
+    tool.exe_names.iter().find_map(|exe| {
+        Command::new("where")
+            .arg(exe)
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8_lossy(&o.stdout).lines().next().map(|l| PathBuf::from(l.trim())))
+            .filter(|path| path.exists())
+    })
+}
+
+#[cfg(not(windows))]
+fn find_in_path(_tool: &ExternalTool) -> Option<PathBuf> {
+    None
+}
+
+/// Executable and where it was found: "bundled", "installed" or "path"
+fn locate(tool: &ExternalTool) -> Option<(PathBuf, &'static str)> {
+    // PRIORITY 1: Portable copy shipped with the agent (zero friction)
+    if let Some(tools_dir) = std::env::current_exe().ok().and_then(|p| p.parent().map(|d| d.join("tools"))) {
+        let bundled = [tools_dir.join(tool.bundled_dir), tools_dir];
+        if let Some(path) = probe_dirs(tool, &bundled) {
+            return Some((path, "bundled"));
+        }
+    }
+
+    // PRIORITY 2: System installs (winget, installer, portable extracted by the user)
+    let installed: Vec<PathBuf> = tool.install_dirs.iter()
+        .filter_map(|(var, sub)| env_root(var).map(|root| root.join(sub)))
+        .collect();
+    if let Some(path) = probe_dirs(tool, &installed) {
+        return Some((path, "installed"));
+    }
+
+    // PRIORITY 3: Anywhere on PATH
+    find_in_path(tool).map(|path| (path, "path"))
+}
+
+pub fn find_tool(id: &str) -> Option<PathBuf> {
+    get_tool(id).and_then(locate).map(|(path, _)| path)
+}
+
+/// Installs the tool through winget when it can't be found, then returns its path
+#[cfg(windows)]
+pub fn ensure_tool(id: &str) -> Result<PathBuf, String> {
+    use crate::godmode::{winget_failure, WingetError};
+
+    let tool = get_tool(id).ok_or_else(|| format!("Outil inconnu: {}", id))?;
+    if let Some((path, _)) = locate(tool) {
+        return Ok(path);
+    }
+
+    println!("[Tools] Installing {} via winget", tool.name);
+    let output = Command::new("winget")
+        .args([
+            "install",
+            "--id", tool.winget_id,
+            "-e",
+            "--silent",
+            "--accept-package-agreements",
+            "--accept-source-agreements",
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| WingetError::missing(&e).describe())?;
+
+    if !output.status.success() {
+        return Err(format!("{} install: {}", tool.name, winget_failure(&output).describe()));
+    }
+
+    // The installer may still be unpacking when winget returns
+    std::thread::sleep(std::time::Duration::from_secs(3));
+    locate(tool)
+        .map(|(path, _)| path)
+        .ok_or_else(|| format!("{}: winget OK mais exe non trouve", tool.name))
+}
+
+#[cfg(not(windows))]
+pub fn ensure_tool(id: &str) -> Result<PathBuf, String> {
+    let tool = get_tool(id).ok_or_else(|| format!("Outil inconnu: {}", id))?;
+    locate(tool)
+        .map(|(path, _)| path)
+        .ok_or_else(|| format!("Installation de {} disponible uniquement sur Windows", tool.name))
+}
+
+#[cfg(windows)]
+fn file_version(path: &std::path::Path) -> Option<String> {
+    use crate::powershell;
+
+    let script = format!(
+        "(Get-Item -LiteralPath '{}').VersionInfo.ProductVersion",
+        path.to_string_lossy().replace('\'', "''")
+    );
+    let output = Command::new(powershell::detect_powershell()?)
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if version.is_empty() { None } else { Some(version) }
+}
+
+#[cfg(not(windows))]
+fn file_version(_path: &std::path::Path) -> Option<String> {
+    None
+}
+
+/// "smartctl 7.4 2023-08-01 r5530 [...]" -> "7.4"
+fn command_version(path: &std::path::Path, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(path);
+    command.args(args);
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+
+    let output = command.output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let first_line = stdout.lines().find(|l| !l.trim().is_empty())?;
+    first_line.split_whitespace()
+        .map(|token| token.trim_start_matches(['v', 'V']))
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()) && token.contains('.'))
+        .map(|token| token.to_string())
+}
+
+fn probe_version(tool: &ExternalTool, path: &std::path::Path) -> Option<String> {
+    match tool.version_probe {
+        VersionProbe::FileVersion => file_version(path),
+        VersionProbe::Command(args) => command_version(path, args),
+    }
+}
+
+pub fn tool_version(id: &str) -> Option<String> {
+    let tool = get_tool(id)?;
+    let (path, _) = locate(tool)?;
+    probe_version(tool, &path)
+}
+
+/// Newer release in the winget source. Bundled copies are updated with the agent itself.
+#[cfg(windows)]
+fn winget_available_version(tool: &ExternalTool) -> Option<String> {
+    use crate::godmode::parse_winget_upgrade_table;
+
+    let output = Command::new("winget")
+        .args(["upgrade", "--id", tool.winget_id, "-e", "--accept-source-agreements"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    parse_winget_upgrade_table(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .find(|app| app.id.eq_ignore_ascii_case(tool.winget_id))
+        .map(|app| app.available_version)
+}
+
+#[cfg(not(windows))]
+fn winget_available_version(_tool: &ExternalTool) -> Option<String> {
+    None
+}
+
+pub fn tool_status(tool: &ExternalTool, check_updates: bool) -> ToolStatus {
+    let location = locate(tool);
+    let version = location.as_ref().and_then(|(path, _)| probe_version(tool, path));
+    let available_version = match &location {
+        Some((_, "installed")) if check_updates => winget_available_version(tool),
+        _ => None,
+    };
+
+    ToolStatus {
+        id: tool.id.to_string(),
+        name: tool.name.to_string(),
+        winget_id: tool.winget_id.to_string(),
+        installed: location.is_some(),
+        source: location.as_ref().map(|(_, source)| source.to_string()),
+        path: location.map(|(path, _)| path.to_string_lossy().to_string()),
+        version,
+        available_version,
+    }
+}
+
+pub fn list_tools(check_updates: bool) -> Vec<ToolStatus> {
+    TOOL_REGISTRY.iter()
+        .map(|tool| tool_status(tool, check_updates))
+        .collect()
+}
@@ -0,0 +1,79 @@
+// ============================================
+// MICRODIAG AGENT - WMI Cache
+// Short-lived results of repeated root\cimv2 queries, shared by every thread
+// so a dashboard refresh doesn't re-run the same WMI class several times
+// ============================================
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use wmi::{Variant, WMIConnection, WMIResult};
+
+pub type WmiRow = HashMap<String, Variant>;
+
+const DEFAULT_TTL_SECS: u64 = 5;
+
+static TTL_SECS: AtomicU64 = AtomicU64::new(DEFAULT_TTL_SECS);
+static CACHE: OnceLock<Mutex<HashMap<String, (Instant, DetachedRows)>>> = OnceLock::new();
+
+/// Rows without the COM-backed variants (Object, Unknown): those belong to the thread
+/// and connection that produced them, everything else is plain owned data
+struct DetachedRows(Vec<WmiRow>);
+
+// SAFETY: `detach` drops every variant holding a COM pointer before rows are stored
+unsafe impl Send for DetachedRows {}
+
+fn detach(value: &Variant) -> Option<Variant> {
+    match value {
+        Variant::Object(_) | Variant::Unknown(_) => None,
+        Variant::Array(items) => Some(Variant::Array(items.iter().filter_map(detach).collect())),
+        other => Some(other.clone()),
+    }
+}
+
+fn detach_rows(rows: &[WmiRow]) -> Vec<WmiRow> {
+    rows.iter()
+        .map(|row| row.iter().filter_map(|(k, v)| detach(v).map(|v| (k.clone(), v))).collect())
+        .collect()
+}
+
+fn cache() -> &'static Mutex<HashMap<String, (Instant, DetachedRows)>> {
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 0 disables the cache
+pub fn set_ttl_secs(secs: u64) {
+    TTL_SECS.store(secs, Ordering::Relaxed);
+}
+
+/// `raw_query` reusing a result younger than the TTL. The key is the query text only,
+/// so other namespaces (LibreHardwareMonitor...) must keep querying their connection directly.
+/// Each caller still owns its COMLibrary, COM stays initialized per thread.
+/// Rows come back detached on a miss too, so a result never depends on the cache being warm.
+pub fn cached_query(wmi_con: &WMIConnection, query: &str) -> WMIResult<Vec<WmiRow>> {
+    let ttl = Duration::from_secs(TTL_SECS.load(Ordering::Relaxed));
+    {
+        let cache = cache().lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((at, rows)) = cache.get(query) {
+            if at.elapsed() < ttl {
+                return Ok(rows.0.clone());
+            }
+        }
+    }
+
+    // Not locked while querying: WMI can take seconds, a concurrent miss just queries twice
+    let raw: Vec<WmiRow> = wmi_con.raw_query(query)?;
+    let rows = detach_rows(&raw);
+    if !ttl.is_zero() {
+        let mut cache = cache().lock().unwrap_or_else(|e| e.into_inner());
+        cache.retain(|_, (at, _)| at.elapsed() < ttl);
+        cache.insert(query.to_string(), (Instant::now(), DetachedRows(rows.clone())));
+    }
+    Ok(rows)
+}
+
+/// Drops every cached result, for callers that just changed what WMI reports
+pub fn invalidate_wmi_cache() {
+    cache().lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
@@ -263,7 +263,7 @@ function App() {
     } catch (error) {
       clearInterval(stepInterval);
 
-      const errorMsg = String(error);
+      const errorMsg = localDb.errorMessage(error);
       setExecTerminalOutput(prev => [...prev, '', `[ERREUR] ${errorMsg}`]);
       setExecSteps(prev => prev.map((s, i) => ({
         ...s,
@@ -314,7 +314,7 @@ function App() {
         toast.success(lastLine.length > 60 ? `${name} terminé !` : lastLine);
         fetchData();
       } catch (error) {
-        toast.error(`Erreur: ${error}`);
+        toast.error(`Erreur: ${localDb.errorMessage(error)}`);
       } finally {
         setActionRunning(null);
       }
@@ -390,6 +390,7 @@ function App() {
             scriptId: script.slug,
             code: script.code,
             language: 'powershell',
+            trigger: 'remote',
           });
 
           // Update status to completed
@@ -428,7 +429,7 @@ function App() {
               body: JSON.stringify({
                 status: 'failed',
                 completed_at: new Date().toISOString(),
-                error: String(error),
+                error: localDb.errorMessage(error),
               }),
             }
           );
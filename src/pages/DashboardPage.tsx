@@ -290,6 +290,13 @@ export function DashboardPage({
         status: security.firewall_enabled ? 'ok' : 'critical',
         detail: security.firewall_enabled ? 'Actif' : 'Desactive',
       });
+      if (security.pending_reboot?.pending) {
+        items.push({
+          label: 'Redemarrage en attente',
+          status: 'warning',
+          detail: security.pending_reboot.reasons.join(', '),
+        });
+      }
     }
 
     // Disk health from SMART
@@ -191,12 +191,13 @@ export function GodModePage({ metrics }: GodModePageProps) {
   const handleGhostMode = async () => {
     toast.info('Activation Ghost Mode...');
     try {
-      const result = await godmode.activateGhostMode();
-      if (result.success) {
-        toast.success(result.message);
-      } else {
-        toast.error(result.message);
+      const results = await godmode.activateGhostMode();
+      const failed = results.filter(r => !r.success);
+      const done = results.filter(r => r.success).map(r => r.message);
+      if (done.length > 0) {
+        toast.success(`Ghost Mode activé: ${done.join(', ')}`);
       }
+      failed.forEach(r => toast.error(r.message));
     } catch (e) {
       toast.error('Erreur: ' + e);
     }
@@ -15,11 +15,33 @@ export interface PremiumDiagnostic {
   network: NetworkAnalysis;
   storage: StorageAnalysis;
   system_info: ExtendedSystemInfo;
+  cpu_cores: CoreInfo[];
   recommendations: Recommendation[];
   overall_score: number;
   overall_status: 'excellent' | 'good' | 'warning' | 'critical';
 }
 
+export interface CoreInfo {
+  index: number;
+  usage_percent: number;
+  frequency_mhz: number;
+}
+
+export interface DiagnosticScores {
+  overall: number;
+  temperature: number;
+  processes: number;
+  storage: number;
+  network: number;
+}
+
+export interface QuickDiagnostic {
+  scores: DiagnosticScores;
+  overall_status: 'excellent' | 'good' | 'warning' | 'critical';
+  top_processes: ProcessInfo[];
+  drives: DriveAnalysis[];
+}
+
 export interface TemperatureInfo {
   cpu_temp: number | null;
   gpu_temp: number | null;
@@ -67,7 +89,9 @@ export interface NetworkAnalysis {
   is_connected: boolean;
   latency_ms: number | null;
   latency_status: string;
+  latency: LatencyResult;
   dns_status: string;
+  dns: DnsHealth;
   interfaces: NetworkInterface[];
   download_speed: number | null;
   upload_speed: number | null;
@@ -75,6 +99,29 @@ export interface NetworkAnalysis {
   summary: string;
 }
 
+export interface LatencyResult {
+  host: string;
+  method: 'icmp' | 'tcp' | 'none';
+  sent: number;
+  received: number;
+  min_ms: number | null;
+  avg_ms: number | null;
+  max_ms: number | null;
+  jitter_ms: number | null;
+  loss_percent: number;
+}
+
+export interface LatencyConfig {
+  host: string;
+  count: number;
+}
+
+export interface DnsHealth {
+  status: 'ok' | 'slow' | 'failing' | 'untested';
+  resolver_ip: string | null;
+  avg_ms: number | null;
+}
+
 export interface NetworkInterface {
   name: string;
   ip: string;
@@ -84,6 +131,23 @@ export interface NetworkInterface {
   is_up: boolean;
 }
 
+export interface BandwidthSample {
+  interface: string;
+  rx_mbps: number;
+  tx_mbps: number;
+}
+
+export interface NetworkConnection {
+  pid: number;
+  process_name: string;
+  category: string;
+  protocol: 'TCP' | 'UDP';
+  local_addr: string;
+  remote_addr: string;
+  state: string;
+  highlighted: boolean;
+}
+
 export interface StorageAnalysis {
   drives: DriveAnalysis[];
   total_space_gb: number;
@@ -186,10 +250,13 @@ export interface SpeedtestResult {
   upload_mbps: number;
   ping_ms: number;
   jitter_ms: number;
+  packet_loss_percent: number;
   server: string;
+  upload_server: string;
   isp: string;
   grade: string;
   status: string;
+  streams: number;
 }
 
 export interface BootAnalysis {
@@ -282,6 +349,14 @@ export async function runPremiumDiagnostic(): Promise<PremiumDiagnostic> {
   return invoke<PremiumDiagnostic>('run_premium_diagnostic');
 }
 
+/**
+ * Run lightweight diagnostic (cached system state, no ping or PowerShell probes)
+ * Score, top 3 processes and disk fullness - for the dashboard on load
+ */
+export async function runQuickDiagnostic(): Promise<QuickDiagnostic> {
+  return invoke<QuickDiagnostic>('run_quick_diagnostic');
+}
+
 /**
  * Get temperature readings
  */
@@ -303,11 +378,44 @@ export async function getNetworkAnalysis(): Promise<NetworkAnalysis> {
   return invoke<NetworkAnalysis>('get_network_analysis');
 }
 
+/**
+ * Latency target used by the network analysis (default 1.1.1.1, 4 pings)
+ */
+export async function getLatencyConfig(): Promise<LatencyConfig> {
+  return invoke<LatencyConfig>('get_latency_config');
+}
+
+export async function setLatencyConfig(config: LatencyConfig): Promise<void> {
+  return invoke('set_latency_config', { config });
+}
+
+/**
+ * Per-core CPU usage and frequency (~200ms sample)
+ */
+export async function getCpuCores(): Promise<CoreInfo[]> {
+  return invoke<CoreInfo[]>('get_cpu_cores');
+}
+
+/**
+ * Connections per process (highlighted = established by a non-system process, listed first)
+ */
+export async function getNetworkConnections(): Promise<NetworkConnection[]> {
+  return invoke<NetworkConnection[]>('get_network_connections');
+}
+
+/**
+ * Live throughput per interface, sampled over durationMs (250-10000, busiest first)
+ */
+export async function measureBandwidth(durationMs: number = 1000): Promise<BandwidthSample[]> {
+  return invoke<BandwidthSample[]>('measure_bandwidth', { durationMs });
+}
+
 /**
  * Get storage analysis
+ * @param probeSpeed Quick 5MB read/write test per local drive (fills read_speed/write_speed)
  */
-export async function getStorageAnalysis(): Promise<StorageAnalysis> {
-  return invoke<StorageAnalysis>('get_storage_analysis');
+export async function getStorageAnalysis(probeSpeed: boolean = false): Promise<StorageAnalysis> {
+  return invoke<StorageAnalysis>('get_storage_analysis', { probeSpeed });
 }
 
 /**
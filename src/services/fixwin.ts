@@ -12,6 +12,18 @@ export interface FixResult {
   message: string;
   output: string[];
   requires_reboot: boolean;
+  repair_outcome?: RepairOutcome | null;
+}
+
+export type SfcOutcome = 'no_violations' | 'repaired' | 'unrepairable' | 'could_not_run';
+
+export type DismOutcome =
+  | { status: 'success' }
+  | { status: 'failed'; error_code: string };
+
+export interface RepairOutcome {
+  sfc: SfcOutcome | null;
+  dism: DismOutcome | null;
 }
 
 export interface FixCategory {
@@ -46,6 +58,28 @@ export interface FixComplete {
   requires_reboot: boolean;
 }
 
+export interface FixStepEvent {
+  fix_id: string;
+  index: number;
+  total: number;
+}
+
+export interface FixStepDone extends FixStepEvent {
+  success: boolean;
+  message: string;
+  requires_reboot: boolean;
+}
+
+export interface MaintenanceProgress {
+  fix_id: string;
+  step_name: string;
+  step_index: number;
+  step_count: number;
+  line: string;
+  line_type: 'info' | 'progress' | 'success' | 'error' | 'warning';
+  percent: number;
+}
+
 // Risk level colors and labels
 export const RISK_LEVELS = {
   low: {
@@ -114,6 +148,76 @@ export async function executeFix(fixId: string): Promise<FixResult> {
   }
 }
 
+/**
+ * Execute several fixes in order with one combined report
+ * A failing high-risk fix stops the remaining ones
+ */
+export async function executeFixes(fixIds: string[]): Promise<FixResult> {
+  try {
+    return await invoke<FixResult>('fw_execute_fixes', { fixIds });
+  } catch (error) {
+    console.error('Failed to execute fixes:', error);
+    return {
+      success: false,
+      message: `Erreur: ${error}`,
+      output: [],
+      requires_reboot: false
+    };
+  }
+}
+
+/**
+ * TRIM (SSD) or defragment (HDD) a drive, e.g. "D:"
+ */
+export async function optimizeDrive(letter: string): Promise<FixResult> {
+  try {
+    return await invoke<FixResult>('fw_optimize_drive', { letter });
+  } catch (error) {
+    console.error('Failed to optimize drive:', error);
+    return {
+      success: false,
+      message: `Erreur: ${error}`,
+      output: [],
+      requires_reboot: false
+    };
+  }
+}
+
+/**
+ * Disable then re-enable a network adapter (name from the reset_network_adapter listing)
+ */
+export async function cycleNetworkAdapter(name: string): Promise<FixResult> {
+  try {
+    return await invoke<FixResult>('fw_cycle_network_adapter', { name });
+  } catch (error) {
+    console.error('Failed to cycle network adapter:', error);
+    return {
+      success: false,
+      message: `Erreur: ${error}`,
+      output: [],
+      requires_reboot: false
+    };
+  }
+}
+
+/**
+ * Run the safe fixes in one go (flush DNS, temp files, icon cache, explorer)
+ * Progress via onMaintenanceProgress, completion via onFixComplete ('auto_maintenance')
+ */
+export async function runAutoMaintenance(): Promise<FixResult> {
+  try {
+    return await invoke<FixResult>('fw_run_auto_maintenance');
+  } catch (error) {
+    console.error('Failed to run auto maintenance:', error);
+    return {
+      success: false,
+      message: `Erreur: ${error}`,
+      output: [],
+      requires_reboot: false
+    };
+  }
+}
+
 /**
  * Create a system restore point
  */
@@ -157,6 +261,39 @@ export async function onFixComplete(
   });
 }
 
+/**
+ * Listen to the start of each fix in a batch
+ */
+export async function onFixStepStart(
+  callback: (step: FixStepEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<FixStepEvent>('fix-step-start', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Listen to the result of each fix in a batch
+ */
+export async function onFixStepDone(
+  callback: (step: FixStepDone) => void
+): Promise<UnlistenFn> {
+  return await listen<FixStepDone>('fix-step-done', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Listen to combined progress of the auto maintenance
+ */
+export async function onMaintenanceProgress(
+  callback: (progress: MaintenanceProgress) => void
+): Promise<UnlistenFn> {
+  return await listen<MaintenanceProgress>('fixwin-maintenance-progress', (event) => {
+    callback(event.payload);
+  });
+}
+
 // ============================================
 // HELPER FUNCTIONS
 // ============================================
@@ -4,6 +4,7 @@
 // ============================================
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
 
 // ============================================
 // TYPES
@@ -46,6 +47,10 @@ export interface DriverInfo {
   manufacturer: string;
   driver_date: string;
   status: string;
+  driver_age_days: number | null;
+  is_outdated: boolean;
+  gpu_vendor: string | null;    // nvidia, amd, intel (GPU only)
+  download_url: string | null;
 }
 
 // CrystalDisk-style SMART info
@@ -92,11 +97,28 @@ export interface TweakResult {
   backup_path: string | null;
 }
 
+export type GhostModeTarget = 'clipboard' | 'dns' | 'recent' | 'temp' | 'prefetch' | 'browser_cache';
+
+export interface GhostModeResult {
+  target: GhostModeTarget;
+  success: boolean;
+  message: string;
+  items_cleared: number;
+}
+
+export const DEFAULT_GHOST_MODE_TARGETS: GhostModeTarget[] = ['clipboard', 'dns', 'recent', 'temp', 'prefetch'];
+
 export interface RegBackup {
   name: string;
   path: string;
   created_at: string;
   size_bytes: number;
+  kind: 'key' | 'value';
+}
+
+export interface BackupCleanup {
+  removed_files: number;
+  removed_bytes: number;
 }
 
 export interface PrivacyTweak {
@@ -238,6 +260,40 @@ export async function updateAllApps(): Promise<TweakResult> {
   return invoke<TweakResult>('gm_update_all');
 }
 
+export interface WingetProgress {
+  app_id: string | null;
+  index: number;
+  total: number;
+  line: string;
+  line_type: 'info' | 'progress' | 'success' | 'error' | 'warning';
+  percent: number | null;
+}
+
+/**
+ * Install multiple apps via Winget, progress via onWingetProgress
+ */
+export async function installAppsStreaming(appIds: string[]): Promise<TweakResult> {
+  return invoke<TweakResult>('gm_install_apps_streaming', { appIds });
+}
+
+/**
+ * Update all apps via Winget, progress via onWingetProgress
+ */
+export async function updateAllAppsStreaming(): Promise<TweakResult> {
+  return invoke<TweakResult>('gm_update_all_streaming');
+}
+
+/**
+ * Listen to winget output lines ("Installation 2/5: ...", download percentage)
+ */
+export async function onWingetProgress(
+  callback: (progress: WingetProgress) => void
+): Promise<UnlistenFn> {
+  return await listen<WingetProgress>('winget-progress', (event) => {
+    callback(event.payload);
+  });
+}
+
 /**
  * Apply a privacy tweak
  */
@@ -246,10 +302,12 @@ export async function applyTweak(tweakId: string, enable: boolean): Promise<Twea
 }
 
 /**
- * Activate Ghost Mode (clear traces)
+ * Activate Ghost Mode (clear only the selected traces)
  */
-export async function activateGhostMode(): Promise<TweakResult> {
-  return invoke<TweakResult>('gm_ghost_mode');
+export async function activateGhostMode(
+  targets: GhostModeTarget[] = DEFAULT_GHOST_MODE_TARGETS
+): Promise<GhostModeResult[]> {
+  return invoke<GhostModeResult[]>('gm_ghost_mode', { targets });
 }
 
 /**
@@ -266,6 +324,40 @@ export async function restoreBackup(backupPath: string): Promise<TweakResult> {
   return invoke<TweakResult>('gm_restore_backup', { backupPath });
 }
 
+/**
+ * Delete backups older than keepDays, keeping the keepPerPrefix newest of each tweak
+ */
+export async function cleanupBackups(keepDays: number, keepPerPrefix: number): Promise<BackupCleanup> {
+  return invoke<BackupCleanup>('gm_cleanup_backups', { keepDays, keepPerPrefix });
+}
+
+/**
+ * Undo a tweak: restore the newest backup named exactly prefix plus its timestamp (e.g. "tweak_telemetry")
+ */
+export async function restoreLatestBackup(prefix: string): Promise<TweakResult> {
+  return invoke<TweakResult>('gm_restore_latest_backup', { prefix });
+}
+
+// ============================================
+// BOOT MODE
+// ============================================
+
+export type BootMode = 'safe_minimal' | 'safe_network' | 'normal';
+
+/**
+ * Next boots go into the selected mode until set back to 'normal' (admin, BCD exported to backup_path)
+ */
+export async function setBootMode(mode: BootMode): Promise<TweakResult> {
+  return invoke<TweakResult>('set_boot_mode', { mode });
+}
+
+/**
+ * Boot logging to %SystemRoot%\ntbtlog.txt (admin, BCD exported to backup_path)
+ */
+export async function setBootLogging(enabled: boolean): Promise<TweakResult> {
+  return invoke<TweakResult>('set_boot_logging', { enabled });
+}
+
 // ============================================
 // CRYSTALDISKINFO INTEGRATION
 // ============================================
@@ -334,6 +426,17 @@ export async function getAllTemperatures(): Promise<HardwareTemperatures> {
 // AUTO-SETUP DIAGNOSTIC TOOLS
 // ============================================
 
+export interface ToolStatus {
+  id: string;
+  name: string;
+  winget_id: string;
+  installed: boolean;
+  source: 'bundled' | 'installed' | 'path' | null;
+  path: string | null;
+  version: string | null;
+  available_version: string | null;
+}
+
 export interface DiagnosticToolsStatus {
   crystaldiskinfo_installed: boolean;
   crystaldiskinfo_installing: boolean;
@@ -345,6 +448,7 @@ export interface DiagnosticToolsStatus {
   needs_admin: boolean;
   message: string;
   errors: string[];
+  tools: ToolStatus[];
 }
 
 /**
@@ -355,6 +459,24 @@ export async function autoSetupDiagnosticTools(): Promise<DiagnosticToolsStatus>
   return invoke<DiagnosticToolsStatus>('gm_auto_setup_diagnostic_tools');
 }
 
+/**
+ * List external tools with version (and winget update when checkUpdates)
+ */
+export async function listTools(checkUpdates: boolean = false): Promise<ToolStatus[]> {
+  return invoke<ToolStatus[]>('gm_list_tools', { checkUpdates });
+}
+
+/**
+ * Install a registered tool via winget if missing, returns its path
+ */
+export async function ensureTool(id: string): Promise<string> {
+  return invoke<string>('gm_ensure_tool', { id });
+}
+
+export async function getToolVersion(id: string): Promise<string | null> {
+  return invoke<string | null>('gm_get_tool_version', { id });
+}
+
 // ============================================
 // HEALTH SCORE CALCULATOR
 // ============================================
@@ -30,6 +30,9 @@ export interface LocalScript {
   requires_admin: boolean;
   estimated_time: string | null;
   success_message: string | null;
+  is_favorite?: boolean;
+  sort_order?: number;
+  is_local?: boolean;
 }
 
 export interface LocalMetrics {
@@ -50,6 +53,57 @@ export interface ChatMessage {
   timestamp?: string;
 }
 
+export interface ScriptExecution {
+  id?: number;
+  script_id: string;
+  source: 'local' | 'remote';
+  started_at: string;
+  finished_at: string;
+  exit_code: number | null;
+  stdout_tail: string;
+  stderr_tail: string;
+}
+
+export type SyncStatus =
+  | { state: 'synced' }
+  | { state: 'syncing' }
+  | { state: 'pending'; detail: number }
+  | { state: 'offline' }
+  | { state: 'error'; detail: string };
+
+export interface SyncReport {
+  scripts: SyncStatus;
+  metrics: SyncStatus;
+  queue_depth: number;
+  last_success: string | null;
+  online: boolean;
+}
+
+/** Encryption state; locked_copy is set when an undecryptable database was set aside at startup */
+export interface DbStorageStatus {
+  encrypted: boolean;
+  locked_copy: string | null;
+}
+
+/** Error returned by run_script, db_* and sync commands */
+export interface MicrodiagError {
+  code:
+    | 'network_error'
+    | 'permission_denied'
+    | 'not_found'
+    | 'wmi_error'
+    | 'io'
+    | 'parse'
+    | 'not_supported_on_platform'
+    | 'database'
+    | 'invalid_input'
+    | 'script_failed'
+    | 'cancelled'
+    | 'timeout'
+    | 'internal';
+  message: string;
+}
+
 export interface RemoteExecution {
   id: string;
   script_id: string;
@@ -100,6 +154,20 @@ export async function getScriptsCount(): Promise<number> {
   }
 }
 
+/**
+ * Enregistre un script local (slug et id generes si vides, jamais ecrase par la sync)
+ */
+export async function upsertScript(script: LocalScript): Promise<LocalScript> {
+  return await invoke<LocalScript>('db_upsert_script', { script });
+}
+
+/**
+ * Supprime un script local (les scripts synchronises ne peuvent pas etre supprimes)
+ */
+export async function deleteScript(id: string): Promise<void> {
+  await invoke('db_delete_script', { id });
+}
+
 /**
  * Force la synchronisation des scripts depuis Supabase
  */
@@ -112,6 +180,18 @@ export async function syncScripts(): Promise<number> {
   }
 }
 
+/**
+ * Journal d'audit des executions de scripts (plus recentes en premier)
+ */
+export async function getScriptExecutions(limit: number = 50): Promise<ScriptExecution[]> {
+  try {
+    return await invoke<ScriptExecution[]>('db_get_script_executions', { limit });
+  } catch (error) {
+    console.error('[LocalDB] Error getting script executions:', error);
+    return [];
+  }
+}
+
 // ============================================
 // METRICS API
 // ============================================
@@ -156,6 +236,30 @@ export async function getChatHistory(limit: number = 50): Promise<ChatMessage[]>
   }
 }
 
+/**
+ * Page de messages plus anciens que beforeId (0 = les plus recents)
+ */
+export async function getChatHistoryPage(beforeId: number, limit: number = 50): Promise<ChatMessage[]> {
+  try {
+    return await invoke<ChatMessage[]>('db_get_chat_history_page', { beforeId, limit });
+  } catch (error) {
+    console.error('[LocalDB] Error getting chat page:', error);
+    return [];
+  }
+}
+
+/**
+ * Recherche plein texte dans l'historique de chat
+ */
+export async function searchChatHistory(query: string, limit: number = 20): Promise<ChatMessage[]> {
+  try {
+    return await invoke<ChatMessage[]>('db_search_chat_history', { query, limit });
+  } catch (error) {
+    console.error('[LocalDB] Error searching chat:', error);
+    return [];
+  }
+}
+
 /**
  * Ajoute un message au chat local
  */
@@ -179,6 +283,22 @@ export async function clearChatHistory(): Promise<void> {
   }
 }
 
+// ============================================
+// STORAGE STATUS
+// ============================================
+
+/**
+ * Indique si la base a ete remplacee au demarrage (cle illisible pour cet utilisateur)
+ */
+export async function getStorageStatus(): Promise<DbStorageStatus | null> {
+  try {
+    return await invoke<DbStorageStatus>('db_get_storage_status');
+  } catch (error) {
+    console.error('[LocalDB] Error getting storage status:', error);
+    return null;
+  }
+}
+
 // ============================================
 // SETTINGS API
 // ============================================
@@ -206,6 +326,25 @@ export async function setSetting(key: string, value: string): Promise<void> {
   }
 }
 
+/**
+ * Nom convivial du poste ("Accueil-PC"), envoye avec le hostname Windows
+ */
+export async function getDeviceLabel(): Promise<string | null> {
+  try {
+    return await invoke<string | null>('get_device_label');
+  } catch (error) {
+    console.error('[LocalDB] Error getting device label:', error);
+    return null;
+  }
+}
+
+/**
+ * Enregistre le nom convivial (64 caracteres max, vide = supprime)
+ */
+export async function setDeviceLabel(label: string): Promise<string | null> {
+  return await invoke<string | null>('set_device_label', { label });
+}
+
 // ============================================
 // ONLINE STATUS
 // ============================================
@@ -222,6 +361,18 @@ export async function checkOnlineStatus(): Promise<boolean> {
   }
 }
 
+/**
+ * Etat de synchronisation par categorie (aussi emis via 'sync-status-changed')
+ */
+export async function getSyncStatus(): Promise<SyncReport | null> {
+  try {
+    return await invoke<SyncReport>('get_sync_status');
+  } catch (error) {
+    console.error('[LocalDB] Error getting sync status:', error);
+    return null;
+  }
+}
+
 // ============================================
 // REMOTE EXECUTION API
 // ============================================
@@ -258,6 +409,17 @@ export async function updateRemoteExecution(
 // UTILITY FUNCTIONS
 // ============================================
 
+export function isMicrodiagError(error: unknown): error is MicrodiagError {
+  return typeof error === 'object' && error !== null && 'code' in error && 'message' in error;
+}
+
+/**
+ * Message affichable, que la commande renvoie une MicrodiagError ou une chaine
+ */
+export function errorMessage(error: unknown): string {
+  return isMicrodiagError(error) ? error.message : String(error);
+}
+
 /**
  * Hook-friendly wrapper pour charger les scripts avec fallback Supabase
  */
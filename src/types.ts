@@ -33,6 +33,12 @@ export interface SecurityStatus {
   realtime_protection: boolean;
   firewall_enabled: boolean;
   issues: string[];
+  pending_reboot?: PendingRebootStatus;
+}
+
+export interface PendingRebootStatus {
+  pending: boolean;
+  reasons: string[];
 }
 
 export interface Script {